@@ -1,17 +1,319 @@
-use crate::blocks::columns;
+use crate::antenna::Netlist;
 use crate::blocks::sram::{Sram, SramParams};
-use crate::Result;
+use crate::{anyhow, bus_bit, Result};
 use lef21::{
     LefDecimal, LefForeign, LefGeometry, LefLayerGeometries, LefLibrary, LefMacro, LefMacroClass,
     LefPin, LefPinAntennaAttr, LefPinDirection, LefPinUse, LefPoint, LefPort, LefShape,
     LefSymmetry,
 };
+use std::collections::HashMap;
 use std::path::Path;
 use subgeom::bbox::BoundBox;
-use subgeom::{Corner, Point};
+use subgeom::{Corner, Point, Rect};
+use substrate::component::Component;
 use substrate::data::SubstrateCtx;
 use substrate::layout::cell::{Port, PortId};
 use substrate::layout::layers::selector::Selector;
+use substrate::layout::layers::LayerKey;
+use substrate::schematic::circuit::Direction;
+
+/// Metal layers included in abstracts, with their LEF names and the spacing
+/// kept between obstructions and other shapes on the same layer.
+const ABSTRACT_LAYERS: [(usize, &str, i64); 4] = [
+    (1, "met1", 140),
+    (2, "met2", 140),
+    (3, "met3", 300),
+    (4, "met4", 300),
+];
+
+/// Via layers between consecutive layers of [`ABSTRACT_LAYERS`].
+const ABSTRACT_VIAS: [&str; 3] = ["via", "via2", "via3"];
+
+/// Pitch of the grid on which shapes are merged into obstructions.
+const OBS_GRID: i64 = 500;
+
+/// Size of the square regions in which shapes are compared when tracing nets.
+const TRACE_BUCKET: i64 = 2_000;
+
+fn lef_rect(rect: Rect) -> LefGeometry {
+    LefGeometry::Shape(LefShape::Rect(
+        None,
+        LefPoint::new(LefDecimal::new(rect.p0.x, 3), LefDecimal::new(rect.p0.y, 3)),
+        LefPoint::new(LefDecimal::new(rect.p1.x, 3), LefDecimal::new(rect.p1.y, 3)),
+    ))
+}
+
+/// Converts an area in square microns to a LEF value.
+fn lef_area(area: f64) -> LefDecimal {
+    LefDecimal::new((area * 1e6).round() as i64, 6)
+}
+
+fn layer_geometries(layer_name: &str, rects: impl IntoIterator<Item = Rect>) -> LefLayerGeometries {
+    LefLayerGeometries {
+        layer_name: layer_name.to_string(),
+        geometries: rects.into_iter().map(lef_rect).collect(),
+        vias: Vec::new(),
+        except_pg_net: None,
+        spacing: None,
+        width: None,
+    }
+}
+
+/// Removes `hole` from `rect`, returning the remaining pieces.
+fn subtract(rect: Rect, hole: Rect) -> Vec<Rect> {
+    let (x0, y0) = (hole.p0.x.max(rect.p0.x), hole.p0.y.max(rect.p0.y));
+    let (x1, y1) = (hole.p1.x.min(rect.p1.x), hole.p1.y.min(rect.p1.y));
+    if x0 >= x1 || y0 >= y1 {
+        return vec![rect];
+    }
+    [
+        (rect.p0.x, rect.p0.y, rect.p1.x, y0),
+        (rect.p0.x, y1, rect.p1.x, rect.p1.y),
+        (rect.p0.x, y0, x0, y1),
+        (x1, y0, rect.p1.x, y1),
+    ]
+    .into_iter()
+    .filter(|&(x0, y0, x1, y1)| x0 < x1 && y0 < y1)
+    .map(|(x0, y0, x1, y1)| Rect::new(Point::new(x0, y0), Point::new(x1, y1)))
+    .collect()
+}
+
+/// Merges `shapes` into a small set of obstructions that cover every shape
+/// expanded by `spacing`, do not overlap any of `pins`, and leave regions
+/// without shapes open for routing.
+///
+/// Shapes are snapped outward to a grid of pitch [`OBS_GRID`] anchored at `bbox`.
+fn obstructions(
+    shapes: impl IntoIterator<Item = Rect>,
+    bbox: Rect,
+    spacing: i64,
+    pins: &[Rect],
+) -> Vec<Rect> {
+    let nx = ((bbox.p1.x - bbox.p0.x) as usize).div_ceil(OBS_GRID as usize);
+    let ny = ((bbox.p1.y - bbox.p0.y) as usize).div_ceil(OBS_GRID as usize);
+    let mut blocked = vec![false; nx * ny];
+    let cell =
+        |v: i64, origin: i64, n: usize| ((v - origin).max(0) / OBS_GRID).min(n as i64) as usize;
+    for shape in shapes {
+        let x0 = cell(shape.p0.x - spacing, bbox.p0.x, nx);
+        let y0 = cell(shape.p0.y - spacing, bbox.p0.y, ny);
+        let x1 = cell(shape.p1.x + spacing + OBS_GRID - 1, bbox.p0.x, nx);
+        let y1 = cell(shape.p1.y + spacing + OBS_GRID - 1, bbox.p0.y, ny);
+        for y in y0..y1 {
+            blocked[y * nx + x0..y * nx + x1].fill(true);
+        }
+    }
+
+    // Merge runs of blocked cells in each row, then merge identical runs across rows.
+    let to_rect = |(x0, x1): (usize, usize), y0: usize, y1: usize| {
+        let p0 = Point::new(
+            bbox.p0.x + x0 as i64 * OBS_GRID,
+            bbox.p0.y + y0 as i64 * OBS_GRID,
+        );
+        let p1 = Point::new(
+            (bbox.p0.x + x1 as i64 * OBS_GRID).min(bbox.p1.x),
+            (bbox.p0.y + y1 as i64 * OBS_GRID).min(bbox.p1.y),
+        );
+        Rect::new(p0, p1)
+    };
+    let mut rects = Vec::new();
+    let mut active: HashMap<(usize, usize), usize> = HashMap::new();
+    for y in 0..=ny {
+        let mut runs = Vec::new();
+        if y < ny {
+            let row = &blocked[y * nx..(y + 1) * nx];
+            let mut x = 0;
+            while x < nx {
+                if row[x] {
+                    let start = x;
+                    while x < nx && row[x] {
+                        x += 1;
+                    }
+                    runs.push((start, x));
+                } else {
+                    x += 1;
+                }
+            }
+        }
+        let mut next = HashMap::with_capacity(runs.len());
+        for run in runs {
+            next.insert(run, active.remove(&run).unwrap_or(y));
+        }
+        let mut done = active.drain().collect::<Vec<_>>();
+        done.sort();
+        rects.extend(done.into_iter().map(|(run, y0)| to_rect(run, y0, y)));
+        active = next;
+    }
+
+    for pin in pins {
+        rects = rects
+            .into_iter()
+            .flat_map(|rect| subtract(rect, *pin))
+            .collect();
+    }
+    rects
+}
+
+/// Returns true if `a` and `b` overlap or share an edge.
+fn touches(a: Rect, b: Rect) -> bool {
+    a.p0.x <= b.p1.x && b.p0.x <= a.p1.x && a.p0.y <= b.p1.y && b.p0.y <= a.p1.y
+}
+
+/// The [`TRACE_BUCKET`] regions covered by `rect`.
+fn buckets(rect: Rect) -> impl Iterator<Item = (i64, i64)> {
+    let bucket = |v: i64| v.div_euclid(TRACE_BUCKET);
+    let xs = bucket(rect.p0.x)..=bucket(rect.p1.x);
+    (bucket(rect.p0.y)..=bucket(rect.p1.y)).flat_map(move |y| xs.clone().map(move |x| (x, y)))
+}
+
+/// The shapes on one layer, indexed by the [`TRACE_BUCKET`] regions they cover.
+struct LayerShapes {
+    rects: Vec<Rect>,
+    buckets: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl LayerShapes {
+    fn new(mut rects: Vec<Rect>) -> Self {
+        rects.sort_by_key(|r| (r.p0.x, r.p0.y, r.p1.x, r.p1.y));
+        rects.dedup();
+        let mut buckets: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (i, &rect) in rects.iter().enumerate() {
+            for bucket in self::buckets(rect) {
+                buckets.entry(bucket).or_default().push(i);
+            }
+        }
+        Self { rects, buckets }
+    }
+
+    /// The indices of the shapes that touch `rect`.
+    fn touching(&self, rect: Rect) -> Vec<usize> {
+        let mut touching = buckets(rect)
+            .filter_map(|bucket| self.buckets.get(&bucket))
+            .flatten()
+            .copied()
+            .filter(|&i| touches(self.rects[i], rect))
+            .collect::<Vec<_>>();
+        touching.sort_unstable();
+        touching.dedup();
+        touching
+    }
+}
+
+/// The metal shapes of a layout, grouped into electrically connected nets.
+struct MetalNets {
+    /// Shapes on each layer of [`ABSTRACT_LAYERS`].
+    layers: Vec<LayerShapes>,
+    /// The net of each shape, indexed by layer and then by shape.
+    nets: Vec<Vec<usize>>,
+    /// The metal area of each net on each layer in square nanometers,
+    /// indexed by net and then by layer.
+    ///
+    /// Overlapping shapes are counted more than once, which overestimates the area.
+    areas: Vec<i64>,
+}
+
+impl MetalNets {
+    /// Traces nets through `metals`, the shapes on each layer of [`ABSTRACT_LAYERS`],
+    /// and `vias`, the shapes on each layer of [`ABSTRACT_VIAS`].
+    fn new(metals: Vec<Vec<Rect>>, vias: Vec<Vec<Rect>>) -> Self {
+        let layers = metals.into_iter().map(LayerShapes::new).collect::<Vec<_>>();
+        let offsets = layers
+            .iter()
+            .scan(0, |offset, layer| {
+                let start = *offset;
+                *offset += layer.rects.len();
+                Some(start)
+            })
+            .collect::<Vec<_>>();
+        let mut parent =
+            (0..layers.iter().map(|layer| layer.rects.len()).sum()).collect::<Vec<_>>();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        fn union(parent: &mut [usize], a: usize, b: usize) {
+            let (a, b) = (find(parent, a), find(parent, b));
+            parent[a.max(b)] = a.min(b);
+        }
+
+        for (layer, &offset) in layers.iter().zip(offsets.iter()) {
+            for shapes in layer.buckets.values() {
+                for (k, &i) in shapes.iter().enumerate() {
+                    for &j in &shapes[k + 1..] {
+                        if touches(layer.rects[i], layer.rects[j]) {
+                            union(&mut parent, offset + i, offset + j);
+                        }
+                    }
+                }
+            }
+        }
+        for (below, vias) in vias.into_iter().enumerate() {
+            for via in vias {
+                let lower = layers[below].touching(via);
+                let upper = layers[below + 1].touching(via);
+                let mut shapes = lower
+                    .into_iter()
+                    .map(|i| offsets[below] + i)
+                    .chain(upper.into_iter().map(|i| offsets[below + 1] + i));
+                if let Some(first) = shapes.next() {
+                    for shape in shapes {
+                        union(&mut parent, first, shape);
+                    }
+                }
+            }
+        }
+
+        let mut areas = vec![0; parent.len() * layers.len()];
+        let nets = layers
+            .iter()
+            .zip(offsets.iter())
+            .enumerate()
+            .map(|(l, (layer, &offset))| {
+                layer
+                    .rects
+                    .iter()
+                    .enumerate()
+                    .map(|(i, rect)| {
+                        let net = find(&mut parent, offset + i);
+                        areas[net * layers.len() + l] += rect.area();
+                        net
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            layers,
+            nets,
+            areas,
+        }
+    }
+
+    /// The metal area on each layer of the nets that touch `pins`,
+    /// given as pairs of a layer index and a pin shape.
+    fn pin_areas(&self, pins: impl IntoIterator<Item = (usize, Rect)>) -> Vec<i64> {
+        let mut nets = pins
+            .into_iter()
+            .flat_map(|(layer, rect)| {
+                self.layers[layer]
+                    .touching(rect)
+                    .into_iter()
+                    .map(move |i| self.nets[layer][i])
+            })
+            .collect::<Vec<_>>();
+        nets.sort_unstable();
+        nets.dedup();
+        (0..self.layers.len())
+            .map(|layer| {
+                nets.iter()
+                    .map(|&net| self.areas[net * self.layers.len() + layer])
+                    .sum()
+            })
+            .collect()
+    }
+}
 
 #[cfg(feature = "commercial")]
 pub fn run_abstract(
@@ -34,26 +336,51 @@ pub fn run_abstract(
     Ok(())
 }
 
+/// Writes a LEF abstract of an SRAM.
 pub fn write_abstract(
     ctx: &SubstrateCtx,
     params: &SramParams,
     path: impl AsRef<Path>,
 ) -> Result<()> {
-    let sram = ctx.instantiate_layout::<Sram>(params)?;
-    let m1 = ctx.layers().get(Selector::Metal(1))?;
-    let m2 = ctx.layers().get(Selector::Metal(2))?;
+    write_component_abstract::<Sram>(ctx, params, path)
+}
+
+/// Writes a LEF abstract of any component with both a schematic and a layout.
+///
+/// Every schematic port must have a layout port of the same name.
+/// Pin directions are taken from the schematic. The antenna metal area of each pin
+/// is the metal traced from its shapes through the layout, and its gate and diffusion
+/// areas are taken from the netlist.
+///
+/// [`CoarseTdc`](crate::blocks::coarse_tdc::CoarseTdc) and
+/// [`NaiveDelayLine`](crate::blocks::delay_line::NaiveDelayLine) do not have a top-level
+/// layout yet, so their abstracts cannot be generated.
+pub fn write_component_abstract<T: Component>(
+    ctx: &SubstrateCtx,
+    params: &T::Params,
+    path: impl AsRef<Path>,
+) -> Result<()> {
+    let layout = ctx.instantiate_layout::<T>(params)?;
+    let schematic = ctx.instantiate_schematic::<T>(params)?;
+    let name = layout.cell().name().to_string();
+    let layers = ABSTRACT_LAYERS
+        .into_iter()
+        .map(|(metal, name, spacing)| {
+            Ok((ctx.layers().get(Selector::Metal(metal))?, name, spacing))
+        })
+        .collect::<Result<Vec<(LayerKey, &str, i64)>>>()?;
     let mut lef = LefLibrary::new();
     lef.version = Some(LefDecimal::new(58, 1));
     lef.bus_bit_chars = Some(('[', ']'));
     lef.divider_char = Some('/');
-    let mut lef_macro = LefMacro::new(params.name().as_str());
-    let brect = sram.brect();
+    let mut lef_macro = LefMacro::new(name.as_str());
+    let brect = layout.brect();
     let ll = brect.corner(Corner::LowerLeft);
     lef_macro.class = Some(LefMacroClass::Block { tp: None });
     lef_macro.origin = (ll != Point::zero())
         .then(|| LefPoint::new(LefDecimal::new(-ll.x, 3), LefDecimal::new(-ll.y, 3)));
     lef_macro.foreign = Some(LefForeign {
-        cell_name: params.name().as_str().to_string(),
+        cell_name: name.clone(),
         pt: (ll != Point::zero())
             .then(|| LefPoint::new(LefDecimal::new(ll.x, 3), LefDecimal::new(ll.y, 3))),
         orient: None,
@@ -63,260 +390,110 @@ pub fn write_abstract(
         LefDecimal::new(brect.height(), 3),
     ));
     lef_macro.symmetry = Some(vec![LefSymmetry::X, LefSymmetry::Y, LefSymmetry::R90]);
-    for layer_name in ["met1", "met2"] {
-        lef_macro.obs.push(LefLayerGeometries {
-            layer_name: layer_name.to_string(),
-            geometries: vec![LefGeometry::Shape(LefShape::Rect(
-                None,
-                LefPoint::new(
-                    LefDecimal::new(brect.p0.x, 3),
-                    LefDecimal::new(brect.p0.y, 3),
-                ),
-                LefPoint::new(
-                    LefDecimal::new(brect.p1.x, 3),
-                    LefDecimal::new(brect.p1.y, 3),
-                ),
-            ))],
-            vias: Vec::new(),
-            except_pg_net: None,
-            spacing: None,
-            width: None,
-        });
-    }
+    // The antenna gate and diffusion areas of each pin come from the netlist.
+    let netlist_dir = tempfile::tempdir()?;
+    let netlist_path = netlist_dir.path().join(format!("{name}.spice"));
+    ctx.write_schematic_to_file::<T>(params, &netlist_path)?;
+    let mut netlist = Netlist::parse(&std::fs::read_to_string(&netlist_path)?)?;
+    netlist.include_stdcells()?;
 
-    let get_layer_name = |layer_key| {
-        if layer_key == m1 {
-            "met1"
-        } else if layer_key == m2 {
-            "met2"
-        } else {
-            unimplemented!()
-        }
-    };
+    // The antenna metal area of each pin comes from the layout.
+    let metal_rects = layers
+        .iter()
+        .map(|&(layer, _, _)| {
+            layout
+                .shapes_on(layer)
+                .map(|shape| shape.brect())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let via_rects = ABSTRACT_VIAS
+        .into_iter()
+        .map(|via| {
+            let layer = ctx.layers().get(Selector::Name(via))?;
+            Ok(layout
+                .shapes_on(layer)
+                .map(|shape| shape.brect())
+                .collect::<Vec<_>>())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let metal_nets = MetalNets::new(metal_rects.clone(), via_rects);
 
-    for (pin, layer, width, direction) in [
-        (
-            "dout",
-            m1,
-            params.data_width(),
-            LefPinDirection::Output { tristate: false },
-        ),
-        ("din", m1, params.data_width(), LefPinDirection::Input),
-        ("wmask", m1, params.wmask_width(), LefPinDirection::Input),
-        ("addr", m1, params.addr_width(), LefPinDirection::Input),
-        ("we", m1, 1, LefPinDirection::Input),
-        ("ce", m1, 1, LefPinDirection::Input),
-        ("clk", m1, 1, LefPinDirection::Input),
-        ("rstb", m1, 1, LefPinDirection::Input),
-        ("vdd", m2, 1, LefPinDirection::Inout),
-        ("vss", m2, 1, LefPinDirection::Inout),
-    ] {
+    // Pin shapes on each layer, which are left out of the obstructions.
+    let mut pin_rects: HashMap<LayerKey, Vec<Rect>> = HashMap::new();
+    for schematic_port in schematic.ports()? {
+        let pin = schematic_port.name().to_string();
+        let width = schematic_port.width();
+        let use_ = match pin.as_str() {
+            "vdd" | "vpwr" => Some(LefPinUse::Power),
+            "vss" | "vgnd" => Some(LefPinUse::Ground),
+            _ => None,
+        };
+        let direction = match schematic_port.direction() {
+            Direction::Input => LefPinDirection::Input,
+            Direction::Output => LefPinDirection::Output { tristate: false },
+            Direction::InOut => LefPinDirection::Inout,
+        };
         for i in 0..width {
+            let name = if width > 1 {
+                bus_bit(&pin, i)
+            } else {
+                pin.clone()
+            };
+            let port = layout
+                .port(PortId::new(pin.as_str(), i))
+                .map_err(|_| anyhow!("layout of {} has no port {name}", lef_macro.name))?;
+            let mut port_layers = Vec::new();
+            let mut port_rects = Vec::new();
+            for (l, &(layer, layer_name, _)) in layers.iter().enumerate() {
+                let rects = port
+                    .shapes(layer)
+                    .map(|shape| shape.brect())
+                    .collect::<Vec<_>>();
+                if rects.is_empty() {
+                    continue;
+                }
+                port_rects.extend(rects.iter().map(|&rect| (l, rect)));
+                pin_rects
+                    .entry(layer)
+                    .or_default()
+                    .extend(rects.iter().copied());
+                port_layers.push(layer_geometries(layer_name, rects));
+            }
+            let mut antenna_attrs = Vec::new();
+            if use_.is_none() {
+                let metal_areas = metal_nets.pin_areas(port_rects);
+                for (&(_, layer_name, _), metal_area) in layers.iter().zip(metal_areas) {
+                    if metal_area > 0 {
+                        antenna_attrs.push(LefPinAntennaAttr {
+                            key: "ANTENNAPARTIALMETALAREA".to_string(),
+                            val: LefDecimal::new(metal_area, 6),
+                            layer: Some(layer_name.to_string()),
+                        });
+                    }
+                }
+                let areas = netlist.port_areas(&name)?;
+                for (key, area) in [
+                    ("ANTENNAGATEAREA", areas.gate_area),
+                    ("ANTENNADIFFAREA", areas.diff_area),
+                ] {
+                    antenna_attrs.push(LefPinAntennaAttr {
+                        key: key.to_string(),
+                        val: lef_area(area),
+                        layer: None,
+                    });
+                }
+            }
             lef_macro.pins.push(LefPin {
-                name: if width > 1 {
-                    format!("{pin}[{i}]")
-                } else {
-                    pin.to_string()
-                },
+                name,
                 ports: vec![LefPort {
                     class: None,
-                    layers: vec![LefLayerGeometries {
-                        layer_name: get_layer_name(layer).to_string(),
-                        geometries: sram
-                            .port(PortId::new(pin, i))?
-                            .shapes(layer)
-                            .filter_map(|shape| shape.as_rect())
-                            .map(|rect| match pin {
-                                "vdd" | "vss" => LefGeometry::Shape(LefShape::Rect(
-                                    None,
-                                    LefPoint::new(
-                                        LefDecimal::new(rect.left(), 3),
-                                        LefDecimal::new(rect.bottom(), 3),
-                                    ),
-                                    LefPoint::new(
-                                        LefDecimal::new(rect.right(), 3),
-                                        LefDecimal::new(rect.top(), 3),
-                                    ),
-                                )),
-                                _ => LefGeometry::Shape(LefShape::Rect(
-                                    None,
-                                    LefPoint::new(
-                                        LefDecimal::new(rect.left(), 3),
-                                        LefDecimal::new(rect.bottom(), 3),
-                                    ),
-                                    LefPoint::new(
-                                        LefDecimal::new(rect.right(), 3),
-                                        LefDecimal::new(rect.bottom() + rect.width(), 3),
-                                    ),
-                                )),
-                            })
-                            .collect(),
-                        vias: Vec::new(),
-                        except_pg_net: None,
-                        spacing: None,
-                        width: None,
-                    }],
+                    layers: port_layers,
                 }],
                 direction: Some(direction.clone()),
-                use_: match pin {
-                    "vdd" => Some(LefPinUse::Power),
-                    "vss" => Some(LefPinUse::Ground),
-                    _ => None,
-                },
+                use_: use_.clone(),
                 antenna_model: None,
-                antenna_attrs: match pin {
-                    "addr" | "ce" | "we" => vec![
-                        LefPinAntennaAttr {
-                            key: "ANTENNAGATEAREA".to_string(),
-                            val: LefDecimal::new(126_000, 6),
-                            layer: Some("met1".to_string()),
-                        },
-                        LefPinAntennaAttr {
-                            key: "ANTENNAPARTIALMETALAREA".to_string(),
-                            // Conservative estimate of extra m1
-                            val: LefDecimal::new(
-                                149_500 + sram.port(PortId::new(pin, i))?.largest_rect(m1)?.area(),
-                                6,
-                            ),
-                            // due to via.
-                            layer: Some("met1".to_string()),
-                        },
-                    ],
-                    "wmask" => vec![
-                        LefPinAntennaAttr {
-                            key: "ANTENNAGATEAREA".to_string(),
-                            // Conservative estimate of extra m1
-                            val: LefDecimal::new(126_000, 6),
-                            // due to via.
-                            layer: Some("met2".to_string()),
-                        },
-                        LefPinAntennaAttr {
-                            key: "ANTENNAPARTIALMETALAREA".to_string(),
-                            // Conservative estimate of extra m1
-                            val: LefDecimal::new(
-                                149_500 + sram.port(PortId::new(pin, i))?.largest_rect(m1)?.area(),
-                                6,
-                            ),
-                            // due to via.
-                            layer: Some("met1".to_string()),
-                        },
-                        LefPinAntennaAttr {
-                            key: "ANTENNAPARTIALMETALAREA".to_string(),
-                            // Conservative estimate of extra m2 from vias.
-                            val: LefDecimal::new(800 * 140 + 2 * 83_200, 6),
-                            // due to via.
-                            layer: Some("met2".to_string()),
-                        },
-                    ],
-                    "din" => vec![
-                        LefPinAntennaAttr {
-                            key: "ANTENNAGATEAREA".to_string(),
-                            val: LefDecimal::new(126_000, 6),
-                            layer: Some("met2".to_string()),
-                        },
-                        LefPinAntennaAttr {
-                            key: "ANTENNAPARTIALMETALAREA".to_string(),
-                            // Conservative estimate of extra m1 due to via.
-                            val: LefDecimal::new(
-                                149_500 + sram.port(PortId::new(pin, i))?.largest_rect(m1)?.area(),
-                                6,
-                            ),
-                            layer: Some("met1".to_string()),
-                        },
-                        LefPinAntennaAttr {
-                            key: "ANTENNAPARTIALMETALAREA".to_string(),
-                            val: LefDecimal::new(
-                                sram.cell()
-                                    .get_metadata::<columns::layout::Metadata>()
-                                    .dout_din_m2_area,
-                                6,
-                            ),
-                            layer: Some("met2".to_string()),
-                        },
-                    ],
-                    "dout" => vec![
-                        LefPinAntennaAttr {
-                            key: "ANTENNADIFFAREA".to_string(),
-                            val: LefDecimal::new(
-                                sram.cell()
-                                    .get_metadata::<columns::layout::Metadata>()
-                                    .dout_diff_area,
-                                6,
-                            ),
-                            layer: Some("met2".to_string()),
-                        },
-                        LefPinAntennaAttr {
-                            key: "ANTENNAPARTIALMETALAREA".to_string(),
-                            // Conservative estimate of extra m1 due to via.
-                            val: LefDecimal::new(
-                                149_500 + sram.port(PortId::new(pin, i))?.largest_rect(m1)?.area(),
-                                6,
-                            ),
-                            layer: Some("met1".to_string()),
-                        },
-                        LefPinAntennaAttr {
-                            key: "ANTENNAPARTIALMETALAREA".to_string(),
-                            val: LefDecimal::new(
-                                sram.cell()
-                                    .get_metadata::<columns::layout::Metadata>()
-                                    .dout_din_m2_area
-                                    + 800 * 140
-                                    + 2 * 83_200,
-                                6,
-                            ),
-                            layer: Some("met2".to_string()),
-                        },
-                    ],
-                    "clk" => vec![
-                        LefPinAntennaAttr {
-                            key: "ANTENNAGATEAREA".to_string(),
-                            val: LefDecimal::new(
-                                279_000
-                                    * (params.addr_width()
-                                        + 2
-                                        + 2 * params.data_width()
-                                        + params.wmask_width())
-                                        as i64
-                                    + 558_000,
-                                6,
-                            ),
-                            layer: Some("met2".to_string()),
-                        },
-                        // LefPinAntennaAttr {
-                        //     key: "ANTENNAPARTIALMETALAREA".to_string(),
-                        //     val: LefDecimal::new(
-                        //         sram.port(PortId::new(pin, i))?.largest_rect(m1)?.area(),
-                        //         6,
-                        //     ), // TODO: Correctly calculate this area.
-                        //     layer: Some("met1".to_string()),
-                        // },
-                    ],
-                    "rstb" => vec![
-                        LefPinAntennaAttr {
-                            key: "ANTENNAGATEAREA".to_string(),
-                            val: LefDecimal::new(
-                                279_000
-                                    * (params.addr_width()
-                                        + 2
-                                        + 2 * params.data_width()
-                                        + params.wmask_width())
-                                        as i64
-                                    + 4_464_000,
-                                6,
-                            ),
-                            layer: Some("met2".to_string()),
-                        },
-                        // LefPinAntennaAttr {
-                        //     key: "ANTENNAPARTIALMETALAREA".to_string(),
-                        //     val: LefDecimal::new(
-                        //         sram.port(PortId::new(pin, i))?.largest_rect(m1)?.area(),
-                        //         6,
-                        //     ), // TODO: Correctly calculate this area.
-                        //     layer: Some("met1".to_string()),
-                        // },
-                    ],
-                    _ => vec![],
-                },
+                antenna_attrs,
                 shape: None,
                 taper_rule: None,
                 net_expr: None,
@@ -327,8 +504,76 @@ pub fn write_abstract(
             });
         }
     }
+
+    for (&(layer, layer_name, spacing), shapes) in layers.iter().zip(metal_rects) {
+        let pins = pin_rects.remove(&layer).unwrap_or_default();
+        let shapes = shapes.into_iter().filter(|rect| !pins.contains(rect));
+        let obs = obstructions(shapes, brect, spacing, &pins);
+        if !obs.is_empty() {
+            lef_macro.obs.push(layer_geometries(layer_name, obs));
+        }
+    }
+
     lef.macros.push(lef_macro);
     lef.save(path)
         .map_err(|err| anyhow::anyhow!("Failed to save LEF {err:?}"))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: i64, y0: i64, x1: i64, y1: i64) -> Rect {
+        Rect::new(Point::new(x0, y0), Point::new(x1, y1))
+    }
+
+    #[test]
+    fn test_obstructions() {
+        let bbox = rect(0, 0, 10_000, 10_000);
+        // Two stacked shapes merge into one obstruction; the upper half stays open.
+        let obs = obstructions(
+            [
+                rect(1_000, 1_000, 3_000, 2_000),
+                rect(1_000, 2_000, 3_000, 4_000),
+            ],
+            bbox,
+            0,
+            &[],
+        );
+        assert_eq!(obs, vec![rect(1_000, 1_000, 3_000, 4_000)]);
+
+        // Pins are cut out of the obstructions.
+        let obs = obstructions([bbox], bbox, 0, &[rect(0, 0, 1_000, 500)]);
+        assert_eq!(
+            obs,
+            vec![rect(0, 500, 10_000, 10_000), rect(1_000, 0, 10_000, 500)]
+        );
+    }
+
+    #[test]
+    fn test_metal_nets() {
+        // A met1 pin extended on met1 and connected by a via to a met2 wire,
+        // next to an unrelated met1 wire.
+        let metals = vec![
+            vec![
+                rect(0, 0, 1_000, 1_000),
+                rect(1_000, 0, 5_000, 200),
+                rect(0, 2_000, 5_000, 2_200),
+            ],
+            vec![rect(4_800, 0, 5_000, 10_000)],
+            vec![],
+            vec![],
+        ];
+        let vias = vec![vec![rect(4_850, 50, 4_950, 150)], vec![], vec![]];
+        let nets = MetalNets::new(metals, vias);
+        assert_eq!(
+            nets.pin_areas([(0, rect(0, 0, 1_000, 1_000))]),
+            vec![1_000_000 + 800_000, 2_000_000, 0, 0]
+        );
+        assert_eq!(
+            nets.pin_areas([(0, rect(0, 2_000, 100, 2_200))]),
+            vec![1_000_000, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Antenna areas of macro pins, extracted from SPICE netlists.
+//!
+//! Device dimensions are read in microns, as in the sky130 netlists
+//! written by Substrate, the hand-drawn cells in `tech/sky130/spice`
+//! and the standard cell netlists of the PDK.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{anyhow, Result, SKY130_OPEN_PDK_ROOT};
+
+/// Length of the source/drain diffusion of a transistor in microns,
+/// from the gate to the end of the contacted diffusion.
+const SD_LENGTH: f64 = 0.29;
+
+/// Gate and diffusion areas connected to a net, in square microns.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct AntennaAreas {
+    pub gate_area: f64,
+    pub diff_area: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Subckt {
+    ports: Vec<String>,
+    instances: Vec<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct Instance {
+    nets: Vec<String>,
+    cell: String,
+    params: HashMap<String, f64>,
+}
+
+/// A hierarchical SPICE netlist.
+///
+/// Names are case-insensitive and stored in lowercase.
+#[derive(Debug, Clone)]
+pub struct Netlist {
+    subckts: HashMap<String, Subckt>,
+    /// The last subcircuit defined, which is the top cell of netlists written by Substrate.
+    top: Option<String>,
+}
+
+/// Parses a SPICE number with an optional scale suffix, such as `1.12` or `150000u`.
+fn parse_number(s: &str) -> Option<f64> {
+    let s = s.to_lowercase();
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e')))
+        .unwrap_or(s.len());
+    let (num, suffix) = s.split_at(end);
+    let value = num.parse::<f64>().ok()?;
+    let scale = if suffix.starts_with("meg") {
+        1e6
+    } else {
+        match suffix.chars().next() {
+            None => 1.,
+            Some('t') => 1e12,
+            Some('g') => 1e9,
+            Some('k') => 1e3,
+            Some('m') => 1e-3,
+            Some('u') => 1e-6,
+            Some('n') => 1e-9,
+            Some('p') => 1e-12,
+            Some('f') => 1e-15,
+            Some(_) => return None,
+        }
+    };
+    Some(value * scale)
+}
+
+/// Parses the subcircuits of a SPICE netlist, along with the name of the last one defined.
+fn parse_subckts(src: &str) -> Result<(HashMap<String, Subckt>, Option<String>)> {
+    // Join continuation lines and drop comments.
+    let mut lines: Vec<String> = Vec::new();
+    for line in src.lines() {
+        let line = line.split('$').next().unwrap().trim().to_lowercase();
+        if line.is_empty() || line.starts_with('*') {
+            continue;
+        }
+        match (line.strip_prefix('+'), lines.last_mut()) {
+            (Some(rest), Some(last)) => {
+                last.push(' ');
+                last.push_str(rest);
+            }
+            _ => lines.push(line),
+        }
+    }
+
+    let mut subckts = HashMap::new();
+    let mut top = None;
+    let mut current: Option<(String, Subckt)> = None;
+    for line in lines {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        match tokens[0] {
+            ".subckt" => {
+                let name = tokens
+                    .get(1)
+                    .ok_or_else(|| anyhow!("unnamed subcircuit: {line}"))?;
+                let ports = tokens[2..]
+                    .iter()
+                    .take_while(|t| !t.contains('='))
+                    .map(|t| t.to_string())
+                    .collect();
+                current = Some((
+                    name.to_string(),
+                    Subckt {
+                        ports,
+                        instances: Vec::new(),
+                    },
+                ));
+            }
+            ".ends" => {
+                let (name, subckt) = current.take().ok_or_else(|| anyhow!("unmatched .ends"))?;
+                top = Some(name.clone());
+                subckts.insert(name, subckt);
+            }
+            _ => {
+                let Some((_, subckt)) = current.as_mut() else {
+                    continue;
+                };
+                let (params, terms): (Vec<&str>, Vec<&str>) =
+                    tokens[1..].iter().partition(|t| t.contains('='));
+                let Some((cell, nets)) = terms.split_last() else {
+                    continue;
+                };
+                let params = params
+                    .into_iter()
+                    .filter_map(|p| {
+                        let (key, value) = p.split_once('=')?;
+                        Some((key.to_string(), parse_number(value)?))
+                    })
+                    .collect();
+                let nets = nets.iter().map(|n| n.to_string()).collect();
+                match tokens[0].chars().next() {
+                    Some('x') | Some('m') => subckt.instances.push(Instance {
+                        nets,
+                        cell: cell.to_string(),
+                        params,
+                    }),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok((subckts, top))
+}
+
+/// The SPICE netlist of the standard cell `cell`, such as `sky130_fd_sc_hs__inv_2`.
+///
+/// Returns [`None`] if `cell` is not named like a sky130 standard cell.
+pub fn stdcell_spice_path(cell: &str) -> Option<PathBuf> {
+    let (lib, name) = cell.split_once("__")?;
+    if !lib.starts_with("sky130_fd_sc_") {
+        return None;
+    }
+    // Every drive strength of a cell shares a directory, such as `cells/inv` for `inv_2`.
+    let base = name.rsplit_once('_').map_or(name, |(base, _)| base);
+    Some(
+        PathBuf::from(SKY130_OPEN_PDK_ROOT)
+            .join(format!("libraries/{lib}/latest/cells/{base}/{cell}.spice")),
+    )
+}
+
+impl Netlist {
+    pub fn parse(src: &str) -> Result<Self> {
+        let (subckts, top) = parse_subckts(src)?;
+        Ok(Self { subckts, top })
+    }
+
+    /// Adds the subcircuits defined in `src`, keeping the current top cell.
+    pub fn include(&mut self, src: &str) -> Result<()> {
+        let (subckts, _) = parse_subckts(src)?;
+        self.subckts.extend(subckts);
+        Ok(())
+    }
+
+    /// Reads the definitions of standard cells that are instantiated
+    /// but not defined in the netlist from [`stdcell_spice_path`].
+    pub fn include_stdcells(&mut self) -> Result<()> {
+        loop {
+            let missing = self
+                .subckts
+                .values()
+                .flat_map(|subckt| subckt.instances.iter())
+                .filter(|inst| !self.subckts.contains_key(&inst.cell))
+                .filter_map(|inst| Some((inst.cell.clone(), stdcell_spice_path(&inst.cell)?)))
+                .collect::<HashMap<_, _>>();
+            if missing.is_empty() {
+                return Ok(());
+            }
+            for (cell, path) in missing {
+                let src = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow!("failed to read netlist of {cell} at {path:?}: {e}"))?;
+                self.include(&src)?;
+                if !self.subckts.contains_key(&cell) {
+                    return Err(anyhow!("{path:?} does not define {cell}"));
+                }
+            }
+        }
+    }
+
+    /// The gate and diffusion areas connected to `port` of the top cell.
+    pub fn port_areas(&self, port: &str) -> Result<AntennaAreas> {
+        let top = self
+            .top
+            .as_ref()
+            .ok_or_else(|| anyhow!("netlist contains no subcircuits"))?;
+        let subckt = &self.subckts[top];
+        let port = port.to_lowercase();
+        if !subckt.ports.contains(&port) {
+            return Err(anyhow!("subcircuit {top} has no port {port}"));
+        }
+        let mut areas = AntennaAreas::default();
+        self.net_areas(top, &port, &mut areas)?;
+        Ok(areas)
+    }
+
+    /// Walks the hierarchy below `net` of `cell`, stopping at transistor terminals.
+    ///
+    /// Fails if `net` reaches an instance of a cell that is neither defined
+    /// in the netlist nor a sky130 device.
+    fn net_areas(&self, cell: &str, net: &str, areas: &mut AntennaAreas) -> Result<()> {
+        let subckt = &self.subckts[cell];
+        for inst in subckt.instances.iter() {
+            if let Some(child) = self.subckts.get(&inst.cell) {
+                for (port, inst_net) in child.ports.iter().zip(inst.nets.iter()) {
+                    if inst_net == net {
+                        self.net_areas(&inst.cell, port, areas)?;
+                    }
+                }
+            } else if !inst.nets.iter().any(|inst_net| inst_net == net) {
+                continue;
+            } else if !inst.cell.starts_with("sky130_fd_pr__") {
+                return Err(anyhow!(
+                    "cannot resolve antenna areas of {net} in {cell}: cell {} is not defined",
+                    inst.cell
+                ));
+            } else if inst.cell.contains("fet") && inst.nets.len() >= 4 {
+                let param = |key: &str, default: f64| *inst.params.get(key).unwrap_or(&default);
+                let mult = param("mult", 1.) * param("m", 1.);
+                let w = param("w", 0.) * param("nf", 1.) * mult;
+                let l = param("l", 0.);
+                // Terminals are ordered drain, gate, source, body.
+                if inst.nets[1] == net {
+                    areas.gate_area += w * l;
+                }
+                for sd in [&inst.nets[0], &inst.nets[2]] {
+                    if sd == net {
+                        areas.diff_area += w * SD_LENGTH;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    const NETLIST: &str = "
+.subckt inv a y vdd vss
+X0 y a vdd vdd sky130_fd_pr__pfet_01v8 w=2 l=150000u
+X1 y a vss vss sky130_fd_pr__nfet_01v8 w=1
++ l=0.15 nf=2
+.ends
+.subckt top in out vdd vss
+Xinv0 in mid vdd vss inv
+Xinv1 mid out vdd vss inv
+Xinv2 in out vdd vss inv
+.ends
+";
+
+    #[test]
+    fn test_port_areas() {
+        let netlist = Netlist::parse(NETLIST).unwrap();
+        let input = netlist.port_areas("IN").unwrap();
+        assert_relative_eq!(input.gate_area, 2. * 4. * 0.15);
+        assert_relative_eq!(input.diff_area, 0.);
+        let output = netlist.port_areas("out").unwrap();
+        assert_relative_eq!(output.gate_area, 0.);
+        assert_relative_eq!(output.diff_area, 2. * 4. * SD_LENGTH);
+        assert!(netlist.port_areas("clk").is_err());
+    }
+
+    #[test]
+    fn test_unresolved_cell() {
+        let mut netlist = Netlist::parse(
+            "
+.subckt top in out vdd vss
+Xbuf in out vdd vss sky130_fd_sc_hs__buf_2
+.ends
+",
+        )
+        .unwrap();
+        assert!(netlist.port_areas("in").is_err());
+        assert_eq!(
+            stdcell_spice_path("sky130_fd_sc_hs__buf_2").unwrap(),
+            PathBuf::from(SKY130_OPEN_PDK_ROOT)
+                .join("libraries/sky130_fd_sc_hs/latest/cells/buf/sky130_fd_sc_hs__buf_2.spice")
+        );
+        assert!(stdcell_spice_path("inv").is_none());
+
+        netlist
+            .include(
+                "
+.subckt sky130_fd_sc_hs__buf_2 a x vpwr vgnd
+X0 x a vgnd vgnd sky130_fd_pr__nfet_01v8 w=1 l=0.15
+.ends
+",
+            )
+            .unwrap();
+        let input = netlist.port_areas("in").unwrap();
+        assert_relative_eq!(input.gate_area, 0.15);
+    }
+}
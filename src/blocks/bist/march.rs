@@ -0,0 +1,123 @@
+//! March test algorithms.
+//!
+//! These tables drive both the simulated tests in
+//! [`testbench`](crate::blocks::sram::testbench) and the BIST controller hardware,
+//! so the two always run the same sequence.
+
+use serde::{Deserialize, Serialize};
+
+/// The order in which a march element visits addresses.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AddrOrder {
+    /// From address 0 to the last address.
+    Up,
+    /// From the last address to address 0.
+    Down,
+}
+
+/// An operation performed on every bit of a word.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum MarchOp {
+    /// Read, expecting all bits to equal the given value.
+    Read(bool),
+    /// Write the given value to all bits.
+    Write(bool),
+}
+
+/// A sequence of operations applied to each address in turn.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MarchElement {
+    pub order: AddrOrder,
+    pub ops: &'static [MarchOp],
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarchAlgorithm {
+    /// March C-: `{⇕(w0); ⇑(r0,w1); ⇑(r1,w0); ⇓(r0,w1); ⇓(r1,w0); ⇓(r0)}`.
+    MarchCm,
+    /// MATS+: `{⇕(w0); ⇑(r0,w1); ⇓(r1,w0)}`.
+    MatsPlus,
+}
+
+use AddrOrder::*;
+use MarchOp::*;
+
+pub const MARCH_CM: &[MarchElement] = &[
+    MarchElement {
+        order: Up,
+        ops: &[Write(false)],
+    },
+    MarchElement {
+        order: Up,
+        ops: &[Read(false), Write(true)],
+    },
+    MarchElement {
+        order: Up,
+        ops: &[Read(true), Write(false)],
+    },
+    MarchElement {
+        order: Down,
+        ops: &[Read(false), Write(true)],
+    },
+    MarchElement {
+        order: Down,
+        ops: &[Read(true), Write(false)],
+    },
+    MarchElement {
+        order: Down,
+        ops: &[Read(false)],
+    },
+];
+
+pub const MATS_PLUS: &[MarchElement] = &[
+    MarchElement {
+        order: Up,
+        ops: &[Write(false)],
+    },
+    MarchElement {
+        order: Up,
+        ops: &[Read(false), Write(true)],
+    },
+    MarchElement {
+        order: Down,
+        ops: &[Read(true), Write(false)],
+    },
+];
+
+impl MarchAlgorithm {
+    /// The name of the algorithm in configuration files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarchAlgorithm::MarchCm => "march_cm",
+            MarchAlgorithm::MatsPlus => "mats_plus",
+        }
+    }
+
+    pub fn elements(&self) -> &'static [MarchElement] {
+        match self {
+            MarchAlgorithm::MarchCm => MARCH_CM,
+            MarchAlgorithm::MatsPlus => MATS_PLUS,
+        }
+    }
+}
+
+impl MarchOp {
+    #[inline]
+    pub fn is_read(&self) -> bool {
+        matches!(self, Read(_))
+    }
+
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        matches!(self, Write(_))
+    }
+
+    /// The value read or written.
+    #[inline]
+    pub fn value(&self) -> bool {
+        match *self {
+            Read(value) | Write(value) => value,
+        }
+    }
+}
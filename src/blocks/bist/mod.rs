@@ -0,0 +1,603 @@
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+use subgeom::bbox::BoundBox;
+use subgeom::{Rect, Span};
+use substrate::component::{error, Component};
+use substrate::data::SubstrateCtx;
+use substrate::error::Result;
+use substrate::layout::cell::{Instance, Port, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::placement::align::AlignRect;
+use substrate::schematic::circuit::Direction;
+use substrate::schematic::context::SchematicCtx;
+
+use crate::blocks::logic::channel::{strap_supplies, Channel, STRAP_SPACE};
+use crate::blocks::logic::{LogicBlock, LogicBlockParams, LogicBuilder, LogicCell};
+use crate::blocks::sram::{PortStyle, Sram, SramParams};
+
+use self::march::{AddrOrder, MarchAlgorithm, MarchOp};
+
+pub mod march;
+
+/// An [`Sram`] with a built-in self-test controller.
+///
+/// When `bist_en` is high, the SRAM inputs are driven by the march test controller
+/// of [`bist_logic`] instead of the macro pins.
+/// The controller logic sits to the right of the SRAM.
+///
+/// The controller is a [`LogicBlock`] of standard cells rather than a
+/// [`DffArray`](crate::blocks::columns::layout::DffArray) and the gates of
+/// [`blocks::gate`](crate::blocks::gate). Those gates are drawn at the decoder pitch with
+/// heights that depend on the gate, so they cannot share rows, and the pins of a `DffArray`
+/// are not on the bottom edge where the routing [`Channel`] can reach them.
+pub struct SramBist {
+    params: SramBistParams,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct SramBistParams {
+    pub sram: SramParams,
+    pub algorithm: MarchAlgorithm,
+}
+
+impl SramBistParams {
+    pub const fn new(sram: SramParams, algorithm: MarchAlgorithm) -> Self {
+        Self { sram, algorithm }
+    }
+
+    /// The name of the SRAM with BIST cell with these parameters.
+    pub fn name(&self) -> ArcStr {
+        arcstr::format!("{}_bist_{}", self.sram.name(), self.algorithm.name())
+    }
+}
+
+/// A single operation of a march test, flattened across elements.
+pub(crate) struct Step {
+    pub(crate) order: AddrOrder,
+    pub(crate) op: MarchOp,
+    /// The index of the first step of this step's element.
+    pub(crate) first: usize,
+    /// The index of the last step of this step's element.
+    pub(crate) last: usize,
+}
+
+pub(crate) fn steps(algorithm: MarchAlgorithm) -> Vec<Step> {
+    let mut steps = Vec::new();
+    for element in algorithm.elements() {
+        let first = steps.len();
+        let last = first + element.ops.len() - 1;
+        for &op in element.ops {
+            steps.push(Step {
+                order: element.order,
+                op,
+                first,
+                last,
+            });
+        }
+    }
+    steps
+}
+
+/// The march test controller and input mux of an [`SramBist`].
+///
+/// While `bist_en` is low, the controller is held in reset
+/// and the SRAM inputs are driven by the macro pins.
+/// Once `bist_en` goes high, the controller issues one operation per clock cycle
+/// and compares each read against the expected value on the following cycle.
+/// `bist_done` goes high once the last read has been checked,
+/// and `bist_fail` goes high if any read returned unexpected data.
+pub fn bist_logic(params: &SramBistParams) -> LogicBlockParams {
+    let aw = params.sram.addr_width();
+    let dw = params.sram.data_width();
+    let mw = params.sram.wmask_width();
+    let steps = steps(params.algorithm);
+    let n = steps.len();
+
+    let mut logic = LogicBuilder::new(arcstr::format!("{}_logic", params.name()));
+    let [clk, rstb, bist_en, we, ce] =
+        ["clk", "rstb", "bist_en", "we", "ce"].map(|name| logic.input(name, 1)[0]);
+    let addr = logic.input("addr", aw);
+    let wmask = logic.input("wmask", mw);
+    let din = logic.input("din", dw);
+    let dout = logic.input("dout", dw);
+    let [sram_we, sram_ce] = ["sram_we", "sram_ce"].map(|name| logic.output(name, 1)[0]);
+    let sram_addr = logic.output("sram_addr", aw);
+    let sram_wmask = logic.output("sram_wmask", mw);
+    let sram_din = logic.output("sram_din", dw);
+    let [bist_done, bist_fail] = ["bist_done", "bist_fail"].map(|name| logic.output(name, 1)[0]);
+
+    let rb = logic.and2(rstb, bist_en);
+
+    // One flop per step, plus one indicating that the test has finished.
+    // The first step is stored inverted so that it is active out of reset.
+    let state = (0..=n).map(|_| logic.net()).collect::<Vec<_>>();
+    let count = (0..aw).map(|_| logic.net()).collect::<Vec<_>>();
+    let [chk, exp, fail] = [(); 3].map(|_| logic.net());
+
+    let mut s = state[..n].to_vec();
+    s[0] = logic.inv(state[0]);
+    let finished = state[n];
+
+    // The address counter wraps to 0 at the end of each element. It never exceeds
+    // the last address, so the last address is the only count with all of its
+    // set bits also set.
+    let last_addr = params.sram.num_words() - 1;
+    let last_bits = (0..aw)
+        .filter(|&i| (last_addr >> i) & 1 != 0)
+        .map(|i| count[i])
+        .collect::<Vec<_>>();
+    let term = logic.and_all(&last_bits);
+    let term_b = logic.inv(term);
+
+    // Next state.
+    let mut state_d = Vec::with_capacity(n + 1);
+    for k in 0..n {
+        let mut sources = Vec::new();
+        if k > 0 {
+            if steps[k - 1].last == k - 1 {
+                sources.push(logic.and2(s[k - 1], term));
+            } else {
+                sources.push(s[k - 1]);
+            }
+        }
+        if steps[k].first == k {
+            sources.push(logic.and2(s[steps[k].last], term_b));
+        }
+        let next = logic.or_all(&sources);
+        state_d.push(if k == 0 { logic.inv(next) } else { next });
+    }
+    let end = logic.and2(s[n - 1], term);
+    state_d.push(logic.or2(finished, end));
+
+    // Advance the address after the last operation of each element.
+    let last_steps = (0..n)
+        .filter(|&k| steps[k].last == k)
+        .map(|k| s[k])
+        .collect::<Vec<_>>();
+    let advance = logic.or_all(&last_steps);
+    // Unless the last address is all ones, incrementing it does not wrap to 0.
+    let wrap_b = if (last_addr + 1).is_power_of_two() {
+        None
+    } else {
+        let wrap = logic.and2(advance, term);
+        Some(logic.inv(wrap))
+    };
+    let mut carry = advance;
+    let mut count_d = Vec::with_capacity(aw);
+    for (i, &bit) in count.iter().enumerate() {
+        let sum = logic.xor2(bit, carry);
+        count_d.push(match wrap_b {
+            Some(wrap_b) => logic.and2(sum, wrap_b),
+            None => sum,
+        });
+        if i + 1 < aw {
+            carry = logic.and2(bit, carry);
+        }
+    }
+
+    // Operation outputs.
+    let select = |f: &dyn Fn(&Step) -> bool| {
+        (0..n)
+            .filter(|&k| f(&steps[k]))
+            .map(|k| s[k])
+            .collect::<Vec<_>>()
+    };
+    let down = logic.or_all(&select(&|step| step.order == AddrOrder::Down));
+    // Descending elements visit `last_addr - count`. Each bit of the difference is
+    // the count bit, flipped if the last address bit differs from the borrow into it.
+    let mut borrow = None;
+    let mut bist_addr = Vec::with_capacity(aw);
+    for (i, &bit) in count.iter().enumerate() {
+        let flip = if (last_addr >> i) & 1 != 0 {
+            let flip = match borrow {
+                Some(b) => {
+                    let b_b = logic.inv(b);
+                    logic.and2(down, b_b)
+                }
+                None => down,
+            };
+            borrow = borrow.map(|b| logic.and2(bit, b));
+            Some(flip)
+        } else {
+            let flip = borrow.map(|b| logic.and2(down, b));
+            borrow = Some(borrow.map_or(bit, |b| logic.or2(bit, b)));
+            flip
+        };
+        bist_addr.push(match flip {
+            Some(flip) => logic.xor2(bit, flip),
+            None => bit,
+        });
+    }
+    let bist_ce = logic.or_all(&s);
+    let bist_we = logic.or_all(&select(&|step| step.op.is_write()));
+    let bist_data = logic.or_all(&select(&|step| step.op == MarchOp::Write(true)));
+
+    // Read data is available the cycle after a read is issued.
+    let chk_d = logic.or_all(&select(&|step| step.op.is_read()));
+    let exp_d = logic.or_all(&select(&|step| step.op == MarchOp::Read(true)));
+    let mismatches = dout
+        .iter()
+        .map(|&bit| logic.xor2(bit, exp))
+        .collect::<Vec<_>>();
+    let mismatch = logic.or_all(&mismatches);
+    let error = logic.and2(chk, mismatch);
+    let fail_d = logic.or2(fail, error);
+
+    for (q, d) in state
+        .into_iter()
+        .chain(count)
+        .zip(state_d.into_iter().chain(count_d))
+        .chain([(chk, chk_d), (exp, exp_d), (fail, fail_d)])
+    {
+        logic.gate_to(LogicCell::Dff, &[clk, rb, d], q);
+    }
+    let done = logic.dff(clk, rb, finished);
+    logic.buf(done, bist_done);
+    logic.buf(fail, bist_fail);
+
+    // Input mux. Every bit of a word is written during the test.
+    logic.gate_to(LogicCell::Mux2, &[bist_en, we, bist_we], sram_we);
+    logic.gate_to(LogicCell::Mux2, &[bist_en, ce, bist_ce], sram_ce);
+    for ((&a0, &a1), &y) in addr.iter().zip(&bist_addr).zip(&sram_addr) {
+        logic.gate_to(LogicCell::Mux2, &[bist_en, a0, a1], y);
+    }
+    for (&a, &y) in wmask.iter().zip(&sram_wmask) {
+        logic.gate_to(LogicCell::Or2, &[a, bist_en], y);
+    }
+    for (&a0, &y) in din.iter().zip(&sram_din) {
+        logic.gate_to(LogicCell::Mux2, &[bist_en, a0, bist_data], y);
+    }
+
+    logic.build()
+}
+
+impl Component for SramBist {
+    type Params = SramBistParams;
+
+    fn new(params: &Self::Params, _ctx: &SubstrateCtx) -> Result<Self> {
+        // The controller drives `ce` and `we`, has no sleep, retain or trim outputs,
+        // and compares each read on the following cycle.
+        if params.sram.port_style() != PortStyle::CeWe
+            || params.sram.read_latency() != 1
+            || params.sram.power_gating()
+            || params.sram.timing_trim().is_some()
+        {
+            return Err(error::Error::InvalidParams.into());
+        }
+        Ok(Self { params: *params })
+    }
+
+    fn name(&self) -> ArcStr {
+        self.params.name()
+    }
+
+    fn schematic(&self, ctx: &mut SchematicCtx) -> Result<()> {
+        let sram = &self.params.sram;
+        let aw = sram.addr_width();
+        let dw = sram.data_width();
+        let mw = sram.wmask_width();
+
+        let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+        let [clk, we, ce, rstb, bist_en] =
+            ctx.ports(["clk", "we", "ce", "rstb", "bist_en"], Direction::Input);
+        let addr = ctx.bus_port("addr", aw, Direction::Input);
+        let wmask = ctx.bus_port("wmask", mw, Direction::Input);
+        let din = ctx.bus_port("din", dw, Direction::Input);
+        let dout = ctx.bus_port("dout", dw, Direction::Output);
+        let [bist_done, bist_fail] = ctx.ports(["bist_done", "bist_fail"], Direction::Output);
+
+        let [sram_we, sram_ce] = ctx.signals(["sram_we", "sram_ce"]);
+        let sram_addr = ctx.bus("sram_addr", aw);
+        let sram_wmask = ctx.bus("sram_wmask", mw);
+        let sram_din = ctx.bus("sram_din", dw);
+
+        ctx.instantiate::<LogicBlock>(&bist_logic(&self.params))?
+            .with_connections([
+                ("vdd", vdd),
+                ("vss", vss),
+                ("clk", clk),
+                ("rstb", rstb),
+                ("bist_en", bist_en),
+                ("we", we),
+                ("ce", ce),
+                ("addr", addr),
+                ("wmask", wmask),
+                ("din", din),
+                ("dout", dout),
+                ("sram_we", sram_we),
+                ("sram_ce", sram_ce),
+                ("sram_addr", sram_addr),
+                ("sram_wmask", sram_wmask),
+                ("sram_din", sram_din),
+                ("bist_done", bist_done),
+                ("bist_fail", bist_fail),
+            ])
+            .named("bist_logic")
+            .add_to(ctx);
+
+        ctx.instantiate::<Sram>(sram)?
+            .with_connections([
+                ("vdd", vdd),
+                ("vss", vss),
+                ("clk", clk),
+                ("we", sram_we),
+                ("ce", sram_ce),
+                ("rstb", rstb),
+                ("addr", sram_addr),
+                ("wmask", sram_wmask),
+                ("din", sram_din),
+                ("dout", dout),
+            ])
+            .named("sram")
+            .add_to(ctx);
+
+        Ok(())
+    }
+
+    fn layout(&self, ctx: &mut LayoutCtx) -> Result<()> {
+        let sram_params = &self.params.sram;
+        let aw = sram_params.addr_width();
+        let dw = sram_params.data_width();
+        let mw = sram_params.wmask_width();
+        let m1 = ctx.layers().get(Selector::Metal(1))?;
+
+        // The BIST logic sits to the right of the SRAM, with its pins level with those of the SRAM.
+        let sram = ctx.instantiate::<Sram>(sram_params)?;
+        let mut logic = ctx.instantiate::<LogicBlock>(&bist_logic(&self.params))?;
+        logic.align_to_the_right_of(sram.bbox(), STRAP_SPACE);
+        logic.align_bottom(sram.bbox());
+
+        // The `vdd` rails of the BIST logic also reach the outer supply ring.
+        for net in ["vdd", "vss"] {
+            ctx.add_port(sram.port(net)?.into_cell_port())?;
+        }
+        let ring_vdd = sram
+            .port("vdd")?
+            .shapes(m1)
+            .filter_map(|shape| shape.as_rect())
+            .max_by_key(|rect| rect.right())
+            .unwrap();
+        strap_supplies(ctx, &logic, Some(ring_vdd.left()))?;
+
+        // Everything else is connected in a channel beneath the SRAM and the BIST logic,
+        // which has a track for every net that may cross it.
+        let mut channel = Channel::new(
+            ctx,
+            Span::new(sram.brect().left(), logic.brect().right()),
+            sram.brect().bottom(),
+            3 * dw + 2 * (aw + mw) + 16,
+        )?;
+        let pin = |inst: &Instance, port: &str, i: usize| -> Result<Rect> {
+            inst.port(PortId::new(port, i))?.largest_rect(m1)
+        };
+
+        // The clock, reset and read data are shared by the SRAM, the BIST logic and the macro pins.
+        for (port, width) in [("clk", 1), ("rstb", 1), ("dout", dw)] {
+            for i in 0..width {
+                let net = format!("{port}_{i}");
+                let src = channel.pin(ctx, pin(&sram, port, i)?, &net)?;
+                let dst = channel.pin(ctx, pin(&logic, port, i)?, &net)?;
+                let macro_pin = channel.macro_pin(ctx, PortId::new(port, i), src, &net)?;
+                channel.connect(macro_pin, src, &net);
+                channel.connect(macro_pin, dst, &net);
+            }
+        }
+        for (port, width) in [
+            ("bist_en", 1),
+            ("we", 1),
+            ("ce", 1),
+            ("addr", aw),
+            ("wmask", mw),
+            ("din", dw),
+        ] {
+            for i in 0..width {
+                let net = format!("{port}_{i}");
+                let dst = channel.pin(ctx, pin(&logic, port, i)?, &net)?;
+                let src = channel.macro_pin(ctx, PortId::new(port, i), dst, &net)?;
+                channel.connect(src, dst, &net);
+            }
+        }
+        for (port, width) in [
+            ("we", 1),
+            ("ce", 1),
+            ("addr", aw),
+            ("wmask", mw),
+            ("din", dw),
+        ] {
+            for i in 0..width {
+                let net = format!("sram_{port}_{i}");
+                let src = channel.pin(ctx, pin(&logic, &format!("sram_{port}"), i)?, &net)?;
+                let dst = channel.pin(ctx, pin(&sram, port, i)?, &net)?;
+                channel.connect(src, dst, &net);
+            }
+        }
+        for port in ["bist_done", "bist_fail"] {
+            let net = format!("{port}_0");
+            let src = channel.pin(ctx, pin(&logic, port, 0)?, &net)?;
+            let dst = channel.macro_pin(ctx, PortId::new(port, 0), src, &net)?;
+            channel.connect(src, dst, &net);
+        }
+        channel.draw(ctx)?;
+
+        ctx.draw(sram)?;
+        ctx.draw(logic)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use substrate::verification::simulation::bits::BitSignal;
+
+    use crate::blocks::logic::{Net, VDD, VSS};
+    use crate::blocks::sram::testbench::{march_test, Op};
+    use crate::blocks::sram::MuxRatio;
+    use crate::paths::{out_gds, out_lef, out_spice, out_verilog};
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+    use crate::verilog::save_bist_verilog;
+
+    use super::*;
+
+    const SRAM22_64X32M4W8_BIST: SramBistParams = SramBistParams::new(
+        SramParams::new(8, MuxRatio::M4, 64, 32),
+        MarchAlgorithm::MarchCm,
+    );
+
+    /// Runs the BIST logic of `params` cycle by cycle against a model of the SRAM
+    /// whose bit `stuck` of every word always reads as 0, if given.
+    ///
+    /// Returns the operations issued to the SRAM and the value of `bist_fail`
+    /// once `bist_done` goes high.
+    fn run_bist(params: &SramBistParams, stuck: Option<usize>) -> (Vec<Op>, bool) {
+        let logic = bist_logic(params);
+        let ports = logic
+            .ports()
+            .map(|(name, nets, _)| (name.to_string(), nets.to_vec()))
+            .collect::<HashMap<_, _>>();
+        let aw = params.sram.addr_width();
+        let dw = params.sram.data_width();
+
+        let mut values = HashMap::from([(VSS, false), (VDD, true)]);
+        let set = |values: &mut HashMap<Net, bool>, port: &str, bits: u64| {
+            for (i, &net) in ports[port].iter().enumerate() {
+                values.insert(net, (bits >> i) & 1 != 0);
+            }
+        };
+        let get = |values: &HashMap<Net, bool>, port: &str| {
+            ports[port]
+                .iter()
+                .enumerate()
+                .map(|(i, net)| (values[net] as u64) << i)
+                .sum::<u64>()
+        };
+        // Settles the combinational gates, which are built after the nets they read
+        // except for flop outputs.
+        let settle = |values: &mut HashMap<Net, bool>| {
+            for gate in logic.gates() {
+                if gate.cell == LogicCell::Dff {
+                    continue;
+                }
+                let x = gate
+                    .inputs
+                    .iter()
+                    .map(|net| values[net])
+                    .collect::<Vec<_>>();
+                let y = match gate.cell {
+                    LogicCell::Inv => !x[0],
+                    LogicCell::Buf => x[0],
+                    LogicCell::Nand2 => !(x[0] && x[1]),
+                    LogicCell::Nor2 => !(x[0] || x[1]),
+                    LogicCell::And2 => x[0] && x[1],
+                    LogicCell::Or2 => x[0] || x[1],
+                    LogicCell::Xor2 => x[0] ^ x[1],
+                    LogicCell::Mux2 => x[if x[0] { 2 } else { 1 }],
+                    LogicCell::Dff => unreachable!(),
+                };
+                values.insert(gate.output, y);
+            }
+        };
+
+        // Flops start out reset, as they are while `bist_en` is low.
+        for gate in logic.gates() {
+            if gate.cell == LogicCell::Dff {
+                values.insert(gate.output, false);
+            }
+        }
+        for port in ["clk", "rstb", "we", "ce", "addr", "wmask", "din", "dout"] {
+            set(&mut values, port, 0);
+        }
+        set(&mut values, "rstb", 1);
+        set(&mut values, "bist_en", 1);
+
+        let mask = (1 << dw) - 1;
+        let mut mem = vec![0u64; params.sram.num_words()];
+        let mut ops = Vec::new();
+        for _ in 0..1 << (aw + 4) {
+            settle(&mut values);
+            if get(&values, "bist_done") == 1 {
+                return (ops, get(&values, "bist_fail") == 1);
+            }
+            let d = logic
+                .gates()
+                .iter()
+                .filter(|gate| gate.cell == LogicCell::Dff)
+                .map(|gate| (gate.output, values[&gate.inputs[2]]))
+                .collect::<Vec<_>>();
+
+            if get(&values, "sram_ce") == 1 {
+                let addr = get(&values, "sram_addr");
+                let word = &mut mem[addr as usize];
+                if get(&values, "sram_we") == 1 {
+                    assert_eq!(
+                        get(&values, "sram_wmask"),
+                        (1 << params.sram.wmask_width()) - 1
+                    );
+                    let data = get(&values, "sram_din");
+                    *word = data;
+                    ops.push(Op::Write {
+                        addr: BitSignal::from_u64(addr, aw),
+                        data: if data == mask {
+                            BitSignal::ones(dw)
+                        } else {
+                            BitSignal::zeros(dw)
+                        },
+                    });
+                } else {
+                    let data = stuck.map_or(*word, |bit| *word & !(1 << bit));
+                    set(&mut values, "dout", data);
+                    ops.push(Op::Read {
+                        addr: BitSignal::from_u64(addr, aw),
+                    });
+                }
+            }
+            values.extend(d);
+        }
+        panic!("BIST did not finish");
+    }
+
+    #[test]
+    fn test_bist_logic() {
+        for algorithm in [MarchAlgorithm::MarchCm, MarchAlgorithm::MatsPlus] {
+            let params = SramBistParams::new(SRAM22_64X32M4W8_BIST.sram, algorithm);
+            let (ops, fail) = run_bist(&params, None);
+            assert_eq!(ops, march_test(params.sram, algorithm.elements()));
+            assert!(!fail);
+
+            // A bit stuck at 0 is caught by the first read of a 1.
+            let (_, fail) = run_bist(&params, Some(5));
+            assert!(fail);
+
+            // The address counter stops at the last word.
+            let params = SramBistParams::new(SramParams::new(8, MuxRatio::M4, 96, 32), algorithm);
+            let (ops, fail) = run_bist(&params, None);
+            assert_eq!(ops, march_test(params.sram, algorithm.elements()));
+            assert!(!fail);
+        }
+    }
+
+    #[test]
+    fn test_sram_bist() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sram_bist");
+        let name = SRAM22_64X32M4W8_BIST.name();
+        ctx.write_schematic_to_file::<SramBist>(
+            &SRAM22_64X32M4W8_BIST,
+            out_spice(&work_dir, "schematic"),
+        )
+        .expect("failed to write schematic");
+        save_bist_verilog(out_verilog(&work_dir, &*name), &SRAM22_64X32M4W8_BIST)
+            .expect("failed to write behavioral model");
+        ctx.write_layout::<SramBist>(&SRAM22_64X32M4W8_BIST, out_gds(&work_dir, &*name))
+            .expect("failed to write layout");
+        crate::abs::write_component_abstract::<SramBist>(
+            &ctx,
+            &SRAM22_64X32M4W8_BIST,
+            out_lef(&work_dir, &*name),
+        )
+        .expect("failed to write abstract");
+    }
+}
@@ -0,0 +1,160 @@
+use arcstr::ArcStr;
+use grid::Grid;
+use serde::{Deserialize, Serialize};
+use subgeom::orientation::Named;
+use substrate::component::{Component, NoParams};
+use substrate::index::IndexOwned;
+use substrate::into_vec;
+use substrate::layout::cell::{CellPort, PortConflictStrategy};
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::placement::grid::GridTiler;
+use substrate::layout::placement::tile::{LayerBbox, OptionTile};
+use substrate::schematic::circuit::Direction;
+
+use crate::blocks::macros::DpCell;
+
+/// An array of dual-port bitcells.
+///
+/// Port 0 accesses the array through `wl0`, `bl0` and `br0`;
+/// port 1 accesses it through `wl1`, `bl1` and `br1`.
+pub struct DpCellArray {
+    params: DpCellArrayParams,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct DpCellArrayParams {
+    pub rows: usize,
+    pub cols: usize,
+    pub mux_ratio: usize,
+}
+
+impl Component for DpCellArray {
+    type Params = DpCellArrayParams;
+
+    fn new(
+        params: &Self::Params,
+        _ctx: &substrate::data::SubstrateCtx,
+    ) -> substrate::error::Result<Self> {
+        if params.rows % 2 != 0
+            || params.cols % params.mux_ratio != 0
+            || params.rows == 0
+            || params.cols == 0
+        {
+            return Err(substrate::component::error::Error::InvalidParams.into());
+        }
+        Ok(Self { params: *params })
+    }
+
+    fn name(&self) -> ArcStr {
+        arcstr::literal!("dp_cell_array")
+    }
+
+    fn schematic(
+        &self,
+        ctx: &mut substrate::schematic::context::SchematicCtx,
+    ) -> substrate::error::Result<()> {
+        let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+        let bl0 = ctx.bus_port("bl0", self.params.cols, Direction::InOut);
+        let br0 = ctx.bus_port("br0", self.params.cols, Direction::InOut);
+        let bl1 = ctx.bus_port("bl1", self.params.cols, Direction::InOut);
+        let br1 = ctx.bus_port("br1", self.params.cols, Direction::InOut);
+        let wl0 = ctx.bus_port("wl0", self.params.rows, Direction::Input);
+        let wl1 = ctx.bus_port("wl1", self.params.rows, Direction::Input);
+
+        for i in 0..self.params.rows {
+            for j in 0..self.params.cols {
+                let mut cell = ctx.instantiate::<DpCell>(&NoParams)?;
+                cell.connect_all([
+                    ("BL0", bl0.index(j)),
+                    ("BR0", br0.index(j)),
+                    ("BL1", bl1.index(j)),
+                    ("BR1", br1.index(j)),
+                    ("WL0", wl0.index(i)),
+                    ("WL1", wl1.index(i)),
+                    ("VDD", vdd),
+                    ("GND", vss),
+                ]);
+                cell.set_name(arcstr::format!("cell_{i}_{j}"));
+                ctx.add_instance(cell);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+    ) -> substrate::error::Result<()> {
+        let outline = ctx.layers().get(Selector::Name("outline"))?;
+        let cell = ctx.instantiate::<DpCell>(&NoParams)?;
+        let cell_flip_h = cell.with_orientation(Named::ReflectHoriz);
+        let cell_flip_v = cell.with_orientation(Named::ReflectVert);
+        let cell_r180 = cell.with_orientation(Named::R180);
+
+        // Adjacent cells are mirrored so that they share supply and bitline contacts.
+        // The cell draws shared geometry past its edges, so it is tiled on its outline.
+        let mut row_a: Vec<OptionTile> = Vec::with_capacity(self.params.cols);
+        let mut row_b: Vec<OptionTile> = Vec::with_capacity(self.params.cols);
+        for _ in 0..self.params.cols / 2 {
+            row_a.extend(into_vec![
+                LayerBbox::new(cell.clone(), outline),
+                LayerBbox::new(cell_flip_h.clone(), outline)
+            ]);
+            row_b.extend(into_vec![
+                LayerBbox::new(cell_flip_v.clone(), outline),
+                LayerBbox::new(cell_r180.clone(), outline)
+            ]);
+        }
+
+        let mut grid = Grid::new(0, 0);
+        for i in 0..self.params.rows {
+            if i % 2 == 0 {
+                grid.push_row(row_a.clone());
+            } else {
+                grid.push_row(row_b.clone());
+            }
+        }
+
+        let mut grid_tiler = GridTiler::new(grid);
+        grid_tiler.expose_ports(
+            |port: CellPort, (i, j)| match port.name().as_str() {
+                "bl0" | "br0" | "bl1" | "br1" => Some(port.with_index(j)),
+                "wl0" | "wl1" => Some(port.with_index(i)),
+                "vdd" => Some(port),
+                "gnd" => Some(port.named("vss")),
+                _ => None,
+            },
+            PortConflictStrategy::Merge,
+        )?;
+        ctx.add_ports(grid_tiler.ports().cloned())?;
+        ctx.draw(grid_tiler)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::paths::{out_gds, out_spice};
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+
+    use super::*;
+
+    #[test]
+    fn test_dp_cell_array() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_dp_cell_array");
+        let params = DpCellArrayParams {
+            rows: 16,
+            cols: 16,
+            mux_ratio: 4,
+        };
+        ctx.write_layout::<DpCellArray>(&params, out_gds(&work_dir, "layout"))
+            .expect("failed to write layout");
+
+        ctx.write_schematic_to_file::<DpCellArray>(&params, out_spice(&work_dir, "schematic"))
+            .expect("failed to write schematic");
+    }
+}
@@ -6,7 +6,8 @@ use substrate::component::{Component, NoParams};
 
 use subgeom::orientation::Named;
 use subgeom::Shape;
-use substrate::layout::cell::{CellPort, PortConflictStrategy, PortId};
+use substrate::layout::cell::{CellPort, Instance, PortConflictStrategy, PortId};
+use substrate::layout::context::LayoutCtx;
 use substrate::layout::layers::selector::Selector;
 use substrate::layout::layers::LayerKey;
 use substrate::layout::placement::grid::GridTiler;
@@ -19,9 +20,27 @@ use crate::blocks::macros::{
     SpHorizWlstrapP, SpHstrap, SpRowend, SpRowendHstrap, SpRowenda, SpWlstrapP, SpWlstrapaP,
 };
 
-use super::SpCellArray;
+use super::{BitcellType, SpCellArray};
+
+/// Instantiates `T`, a cell of the unmirrored rows of the array,
+/// or its opt1a counterpart `A` if the array only uses opt1a cells.
+pub(crate) fn row_cell<T, A>(
+    ctx: &mut LayoutCtx,
+    bitcell: BitcellType,
+) -> substrate::error::Result<Instance>
+where
+    T: Component<Params = NoParams>,
+    A: Component<Params = NoParams>,
+{
+    match bitcell {
+        BitcellType::Sp => ctx.instantiate::<T>(&NoParams),
+        BitcellType::SpOpt1a => ctx.instantiate::<A>(&NoParams),
+    }
+}
 
-pub struct SpCellArrayCornerUl;
+pub struct SpCellArrayCornerUl {
+    bitcell: BitcellType,
+}
 
 fn corner_port_map_fn(
     port: CellPort,
@@ -58,13 +77,13 @@ fn corner_port_map_fn(
 }
 
 impl Component for SpCellArrayCornerUl {
-    type Params = NoParams;
+    type Params = BitcellType;
 
     fn new(
-        _params: &Self::Params,
+        params: &Self::Params,
         _ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self> {
-        Ok(Self)
+        Ok(Self { bitcell: *params })
     }
 
     fn name(&self) -> ArcStr {
@@ -77,10 +96,10 @@ impl Component for SpCellArrayCornerUl {
     ) -> substrate::error::Result<()> {
         let vmetal = ctx.layers().get(Selector::Metal(1))?;
         let hmetal = ctx.layers().get(Selector::Metal(2))?;
-        let colend = ctx.instantiate::<SpColend>(&NoParams)?;
-        let corner = ctx.instantiate::<SpCorner>(&NoParams)?;
-        let rowend = ctx.instantiate::<SpRowend>(&NoParams)?;
-        let cell = ctx.instantiate::<SpCell>(&NoParams)?;
+        let colend = row_cell::<SpColend, SpColenda>(ctx, self.bitcell)?;
+        let corner = row_cell::<SpCorner, SpCornera>(ctx, self.bitcell)?;
+        let rowend = row_cell::<SpRowend, SpRowenda>(ctx, self.bitcell)?;
+        let cell = row_cell::<SpCell, SpCellOpt1a>(ctx, self.bitcell)?;
 
         let mut grid_tiler = GridTiler::new(into_grid![[corner, colend][rowend, cell]]);
         grid_tiler.expose_ports(
@@ -94,16 +113,18 @@ impl Component for SpCellArrayCornerUl {
     }
 }
 
-pub struct SpCellArrayCornerUr;
+pub struct SpCellArrayCornerUr {
+    bitcell: BitcellType,
+}
 
 impl Component for SpCellArrayCornerUr {
-    type Params = NoParams;
+    type Params = BitcellType;
 
     fn new(
-        _params: &Self::Params,
+        params: &Self::Params,
         _ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self> {
-        Ok(Self)
+        Ok(Self { bitcell: *params })
     }
 
     fn name(&self) -> ArcStr {
@@ -116,19 +137,15 @@ impl Component for SpCellArrayCornerUr {
     ) -> substrate::error::Result<()> {
         let vmetal = ctx.layers().get(Selector::Metal(1))?;
         let hmetal = ctx.layers().get(Selector::Metal(2))?;
-        let colend = ctx
-            .instantiate::<SpColend>(&NoParams)?
+        let colend = row_cell::<SpColend, SpColenda>(ctx, self.bitcell)?
             .with_orientation(Named::ReflectHoriz);
-        let corner = ctx
-            .instantiate::<SpCorner>(&NoParams)?
+        let corner = row_cell::<SpCorner, SpCornera>(ctx, self.bitcell)?
             .with_orientation(Named::ReflectHoriz);
-        let rowend = ctx
-            .instantiate::<SpRowend>(&NoParams)?
+        let rowend = row_cell::<SpRowend, SpRowenda>(ctx, self.bitcell)?
             .with_orientation(Named::ReflectHoriz);
-        let colend_p_cent = ctx.instantiate::<SpColendPCent>(&NoParams)?;
-        let wlstrap_p = ctx.instantiate::<SpWlstrapP>(&NoParams)?;
-        let cell = ctx
-            .instantiate::<SpCell>(&NoParams)?
+        let colend_p_cent = row_cell::<SpColendPCent, SpColendaPCent>(ctx, self.bitcell)?;
+        let wlstrap_p = row_cell::<SpWlstrapP, SpWlstrapaP>(ctx, self.bitcell)?;
+        let cell = row_cell::<SpCell, SpCellOpt1a>(ctx, self.bitcell)?
             .with_orientation(Named::ReflectHoriz);
 
         let mut grid_tiler =
@@ -275,6 +292,7 @@ pub struct SpCellArrayLeft {
 pub struct TapRatio {
     pub mux_ratio: usize,
     pub hstrap_ratio: usize,
+    pub bitcell: BitcellType,
 }
 
 impl Component for SpCellArrayLeft {
@@ -296,10 +314,11 @@ impl Component for SpCellArrayLeft {
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
         let hmetal = ctx.layers().get(Selector::Metal(2))?;
-        let rowend_replica = ctx.instantiate::<SpRowend>(&NoParams)?;
+        let bitcell = self.params.bitcell;
+        let rowend_replica = row_cell::<SpRowend, SpRowenda>(ctx, bitcell)?;
         let mut rowenda_replica = ctx.instantiate::<SpRowenda>(&NoParams)?;
         let mut rowend_hstrap = ctx.instantiate::<SpRowendHstrap>(&NoParams)?;
-        let cell = ctx.instantiate::<SpCell>(&NoParams)?;
+        let cell = row_cell::<SpCell, SpCellOpt1a>(ctx, bitcell)?;
         let mut cell_opt1a = ctx.instantiate::<SpCellOpt1a>(&NoParams)?;
         let mut hstrap = ctx.instantiate::<SpHstrap>(&NoParams)?;
         rowenda_replica.set_orientation(Named::ReflectVert);
@@ -403,12 +422,13 @@ impl Component for SpCellArrayTop {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
-        let colend_1 = ctx.instantiate::<SpColend>(&NoParams)?;
-        let mut colend_2 = ctx.instantiate::<SpColend>(&NoParams)?;
-        let cell_1 = ctx.instantiate::<SpCell>(&NoParams)?;
-        let mut cell_2 = ctx.instantiate::<SpCell>(&NoParams)?;
-        let wlstrap_p = ctx.instantiate::<SpWlstrapP>(&NoParams)?;
-        let colend_p_cent = ctx.instantiate::<SpColendPCent>(&NoParams)?;
+        let bitcell = self.params.bitcell;
+        let colend_1 = row_cell::<SpColend, SpColenda>(ctx, bitcell)?;
+        let mut colend_2 = row_cell::<SpColend, SpColenda>(ctx, bitcell)?;
+        let cell_1 = row_cell::<SpCell, SpCellOpt1a>(ctx, bitcell)?;
+        let mut cell_2 = row_cell::<SpCell, SpCellOpt1a>(ctx, bitcell)?;
+        let wlstrap_p = row_cell::<SpWlstrapP, SpWlstrapaP>(ctx, bitcell)?;
+        let colend_p_cent = row_cell::<SpColendPCent, SpColendaPCent>(ctx, bitcell)?;
         colend_2.set_orientation(Named::ReflectHoriz);
         cell_2.set_orientation(Named::ReflectHoriz);
 
@@ -472,11 +492,12 @@ impl Component for SpCellArrayCenter {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
-        let cell_1 = ctx.instantiate::<SpCell>(&NoParams)?;
-        let mut cell_2 = ctx.instantiate::<SpCell>(&NoParams)?;
+        let bitcell = self.params.bitcell;
+        let cell_1 = row_cell::<SpCell, SpCellOpt1a>(ctx, bitcell)?;
+        let mut cell_2 = row_cell::<SpCell, SpCellOpt1a>(ctx, bitcell)?;
         let mut cell_opt1a_1 = ctx.instantiate::<SpCellOpt1a>(&NoParams)?;
         let mut cell_opt1a_2 = ctx.instantiate::<SpCellOpt1a>(&NoParams)?;
-        let wlstrap_p = ctx.instantiate::<SpWlstrapP>(&NoParams)?;
+        let wlstrap_p = row_cell::<SpWlstrapP, SpWlstrapaP>(ctx, bitcell)?;
         let mut wlstrapa_p = ctx.instantiate::<SpWlstrapaP>(&NoParams)?;
         let mut hstrap_1 = ctx.instantiate::<SpHstrap>(&NoParams)?;
         let hstrap_2 = ctx.instantiate::<SpHstrap>(&NoParams)?;
@@ -627,14 +648,15 @@ impl Component for SpCellArrayRight {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
-        let mut rowend = ctx.instantiate::<SpRowend>(&NoParams)?;
+        let bitcell = self.params.bitcell;
+        let mut rowend = row_cell::<SpRowend, SpRowenda>(ctx, bitcell)?;
         let mut rowenda = ctx.instantiate::<SpRowenda>(&NoParams)?;
         let mut rowend_hstrap = ctx.instantiate::<SpRowendHstrap>(&NoParams)?;
-        let mut cell = ctx.instantiate::<SpCell>(&NoParams)?;
+        let mut cell = row_cell::<SpCell, SpCellOpt1a>(ctx, bitcell)?;
         let mut cell_opt1a = ctx.instantiate::<SpCellOpt1a>(&NoParams)?;
         let hstrap = ctx.instantiate::<SpHstrap>(&NoParams)?;
         let horiz_wlstrap_p = ctx.instantiate::<SpHorizWlstrapP>(&NoParams)?;
-        let wlstrap_p = ctx.instantiate::<SpWlstrapP>(&NoParams)?;
+        let wlstrap_p = row_cell::<SpWlstrapP, SpWlstrapaP>(ctx, bitcell)?;
         let wlstrapa_p = ctx
             .instantiate::<SpWlstrapaP>(&NoParams)?
             .with_orientation(Named::ReflectVert);
@@ -701,8 +723,9 @@ impl SpCellArray {
         let tap_ratio = TapRatio {
             mux_ratio: self.params.mux_ratio,
             hstrap_ratio,
+            bitcell: self.params.bitcell,
         };
-        let corner_ul = ctx.instantiate::<SpCellArrayCornerUl>(&NoParams)?;
+        let corner_ul = ctx.instantiate::<SpCellArrayCornerUl>(&self.params.bitcell)?;
         let left = ctx.instantiate::<SpCellArrayLeft>(&tap_ratio)?;
         let corner_ll = ctx.instantiate::<SpCellArrayCornerLl>(&NoParams)?;
 
@@ -712,7 +735,7 @@ impl SpCellArray {
         let bot = ctx.instantiate::<SpCellArrayBottom>(&tap_ratio)?;
         let bot = LayerBbox::new(bot, outline);
 
-        let corner_ur = ctx.instantiate::<SpCellArrayCornerUr>(&NoParams)?;
+        let corner_ur = ctx.instantiate::<SpCellArrayCornerUr>(&self.params.bitcell)?;
         let right = ctx.instantiate::<SpCellArrayRight>(&tap_ratio)?;
         let right = LayerBbox::new(right, outline);
         let corner_lr = ctx.instantiate::<SpCellArrayCornerLr>(&NoParams)?;
@@ -816,7 +839,7 @@ mod tests {
     use crate::setup_ctx;
     use crate::tests::test_work_dir;
 
-    use super::{SpCellArrayBottom, TapRatio};
+    use super::{BitcellType, SpCellArrayBottom, TapRatio};
 
     #[test]
     #[ignore]
@@ -826,6 +849,7 @@ mod tests {
         let tap_ratio = TapRatio {
             mux_ratio: 4,
             hstrap_ratio: 4,
+            bitcell: BitcellType::Sp,
         };
         ctx.write_layout::<SpCellArrayBottom>(&tap_ratio, out_gds(work_dir, "layout"))
             .expect("failed to write layout");
@@ -9,7 +9,10 @@ use substrate::layout::layers::selector::Selector;
 use substrate::layout::layers::LayerBoundBox;
 
 use crate::blocks::guard_ring::{GuardRingWrapper, WrapperParams};
+use crate::blocks::sram::{BITLINE_CAP_PER_CELL, WORDLINE_CAP_PER_CELL};
+use crate::tech::{BITCELL_HEIGHT, BITCELL_OPT1A_HEIGHT, BITCELL_OPT1A_WIDTH, BITCELL_WIDTH};
 
+pub mod dual_port;
 pub mod layout;
 pub mod replica;
 pub mod schematic;
@@ -18,11 +21,64 @@ pub struct SpCellArray {
     params: SpCellArrayParams,
 }
 
+/// The foundry single-port bitcell used throughout an array.
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BitcellType {
+    /// Rows alternate between `sram_sp_cell_opt1` and mirrored `sram_sp_cell_opt1a` cells.
+    #[default]
+    Sp,
+    /// Every row uses `sram_sp_cell_opt1a` and its end, strap and replica cells.
+    SpOpt1a,
+}
+
+impl BitcellType {
+    /// The height of a bitcell in nm.
+    pub fn height(&self) -> isize {
+        match self {
+            Self::Sp => BITCELL_HEIGHT,
+            Self::SpOpt1a => BITCELL_OPT1A_HEIGHT,
+        }
+    }
+
+    /// The width of a bitcell in nm.
+    pub fn width(&self) -> isize {
+        match self {
+            Self::Sp => BITCELL_WIDTH,
+            Self::SpOpt1a => BITCELL_OPT1A_WIDTH,
+        }
+    }
+
+    /// The wordline capacitance contributed by each bitcell.
+    ///
+    /// The opt1 and opt1a cells have the same shapes on every layer and only differ in
+    /// where the wordline is labeled, so an opt1a array extracts to the same values as an
+    /// opt1 array. `test_opt1a_cell_geometry` checks that this remains the case.
+    pub fn wordline_cap_per_cell(&self) -> f64 {
+        match self {
+            Self::Sp | Self::SpOpt1a => WORDLINE_CAP_PER_CELL,
+        }
+    }
+
+    /// The bitline capacitance contributed by each bitcell.
+    ///
+    /// See [`BitcellType::wordline_cap_per_cell`].
+    pub fn bitline_cap_per_cell(&self) -> f64 {
+        match self {
+            Self::Sp | Self::SpOpt1a => BITLINE_CAP_PER_CELL,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct SpCellArrayParams {
     pub rows: usize,
     pub cols: usize,
     pub mux_ratio: usize,
+    #[serde(default)]
+    pub bitcell: BitcellType,
 }
 
 impl Component for SpCellArray {
@@ -312,6 +368,8 @@ mod tests {
     use crate::setup_ctx;
     use crate::tests::test_work_dir;
 
+    use crate::blocks::macros::{SpCell, SpCellOpt1a};
+
     use super::layout::{
         SpCellArrayBottom, SpCellArrayCenter, SpCellArrayCornerLl, SpCellArrayCornerLr,
         SpCellArrayCornerUl, SpCellArrayCornerUr, TapRatio,
@@ -326,6 +384,7 @@ mod tests {
             rows: 32,
             cols: 32,
             mux_ratio: 4,
+            bitcell: BitcellType::Sp,
         };
         ctx.write_layout::<SpCellArray>(&params, out_gds(&work_dir, "layout"))
             .expect("failed to write layout");
@@ -334,6 +393,60 @@ mod tests {
             .expect("failed to write schematic");
     }
 
+    #[test]
+    fn test_sp_cell_array_opt1a() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sp_cell_array_opt1a");
+        let params = SpCellArrayParams {
+            rows: 32,
+            cols: 32,
+            mux_ratio: 4,
+            bitcell: BitcellType::SpOpt1a,
+        };
+        ctx.write_layout::<SpCellArray>(&params, out_gds(&work_dir, "layout"))
+            .expect("failed to write layout");
+
+        let spice_path = out_spice(&work_dir, "schematic");
+        ctx.write_schematic_to_file::<SpCellArray>(&params, &spice_path)
+            .expect("failed to write schematic");
+        let netlist = std::fs::read_to_string(spice_path).expect("failed to read netlist");
+        assert!(netlist.contains("sky130_fd_bd_sram__sram_sp_cell_opt1a"));
+    }
+
+    #[test]
+    fn test_opt1a_cell_geometry() {
+        let ctx = setup_ctx();
+        let opt1 = ctx
+            .instantiate_layout::<SpCell>(&NoParams)
+            .expect("failed to generate layout");
+        let opt1a = ctx
+            .instantiate_layout::<SpCellOpt1a>(&NoParams)
+            .expect("failed to generate layout");
+        let layers = ctx.layers();
+        for selector in [
+            Selector::Name("diff"),
+            Selector::Name("poly"),
+            Selector::Metal(0),
+            Selector::Metal(1),
+            Selector::Metal(2),
+        ] {
+            let layer = layers.get(selector).expect("failed to get layer");
+            let [opt1, opt1a] = [&opt1, &opt1a].map(|cell| {
+                let mut rects = cell
+                    .shapes_on(layer)
+                    .map(|shape| {
+                        let r = shape.brect();
+                        (r.left(), r.bottom(), r.right(), r.top())
+                    })
+                    .collect::<Vec<_>>();
+                rects.sort();
+                rects
+            });
+            assert!(!opt1.is_empty());
+            assert_eq!(opt1, opt1a);
+        }
+    }
+
     #[test]
     fn test_sp_cell_array_with_guard_ring() -> substrate::error::Result<()> {
         let ctx = setup_ctx();
@@ -344,6 +457,7 @@ mod tests {
                 rows: 16,
                 cols: 16,
                 mux_ratio: 4,
+                bitcell: BitcellType::Sp,
             },
             h_width: 1_360,
             v_width: 1_360,
@@ -385,11 +499,18 @@ mod tests {
         let tap_ratio = TapRatio {
             mux_ratio: 4,
             hstrap_ratio: 4,
+            bitcell: BitcellType::Sp,
         };
-        ctx.write_layout::<SpCellArrayCornerUl>(&NoParams, out_gds(&work_dir, "corner_ul"))
-            .expect("failed to write layout");
-        ctx.write_layout::<SpCellArrayCornerUr>(&NoParams, out_gds(&work_dir, "corner_ur"))
-            .expect("failed to write layout");
+        ctx.write_layout::<SpCellArrayCornerUl>(
+            &tap_ratio.bitcell,
+            out_gds(&work_dir, "corner_ul"),
+        )
+        .expect("failed to write layout");
+        ctx.write_layout::<SpCellArrayCornerUr>(
+            &tap_ratio.bitcell,
+            out_gds(&work_dir, "corner_ur"),
+        )
+        .expect("failed to write layout");
         ctx.write_layout::<SpCellArrayCornerLr>(&NoParams, out_gds(&work_dir, "corner_lr"))
             .expect("failed to write layout");
         ctx.write_layout::<SpCellArrayCornerLl>(&NoParams, out_gds(&work_dir, "corner_ll"))
@@ -417,6 +538,7 @@ mod tests {
                 rows: 128,
                 cols: 8,
                 mux_ratio: 4,
+                bitcell: BitcellType::Sp,
             },
             h_width: 1_360,
             v_width: 1_360,
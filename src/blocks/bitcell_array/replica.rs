@@ -18,6 +18,9 @@ use substrate::layout::placement::nine_patch::{NpTiler, Region};
 use substrate::layout::placement::tile::{LayerBbox, RectBbox};
 use substrate::schematic::circuit::Direction;
 
+use super::layout::row_cell;
+use super::BitcellType;
+
 pub struct ReplicaCellArray {
     params: ReplicaCellArrayParams,
 }
@@ -26,6 +29,8 @@ pub struct ReplicaCellArray {
 pub struct ReplicaCellArrayParams {
     pub rows: usize,
     pub cols: usize,
+    #[serde(default)]
+    pub bitcell: BitcellType,
 }
 
 pub struct RowTapEndRowendHstrap;
@@ -83,16 +88,18 @@ impl Component for RowTapEndRowendHstrap {
     }
 }
 
-pub struct Center;
+pub struct Center {
+    bitcell: BitcellType,
+}
 
 impl Component for Center {
-    type Params = NoParams;
+    type Params = BitcellType;
 
     fn new(
-        _params: &Self::Params,
+        params: &Self::Params,
         _ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self> {
-        Ok(Self)
+        Ok(Self { bitcell: *params })
     }
 
     fn name(&self) -> ArcStr {
@@ -103,7 +110,7 @@ impl Component for Center {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
-        let replica = ctx.instantiate::<SpCellReplica>(&NoParams)?;
+        let replica = row_cell::<SpCellReplica, SpCellOpt1aReplica>(ctx, self.bitcell)?;
         let replica_a = ctx
             .instantiate::<SpCellOpt1aReplica>(&NoParams)?
             .with_orientation(Named::ReflectVert);
@@ -138,16 +145,18 @@ impl Component for Center {
     }
 }
 
-pub struct Top;
+pub struct Top {
+    bitcell: BitcellType,
+}
 
 impl Component for Top {
-    type Params = NoParams;
+    type Params = BitcellType;
 
     fn new(
-        _params: &Self::Params,
+        params: &Self::Params,
         _ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self> {
-        Ok(Self)
+        Ok(Self { bitcell: *params })
     }
 
     fn name(&self) -> ArcStr {
@@ -158,10 +167,10 @@ impl Component for Top {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
-        let colend = ctx.instantiate::<SpColend>(&NoParams)?;
+        let colend = row_cell::<SpColend, SpColenda>(ctx, self.bitcell)?;
         let colend_flip = colend.with_orientation(Named::ReflectHoriz);
 
-        let replica = ctx.instantiate::<SpCellReplica>(&NoParams)?;
+        let replica = row_cell::<SpCellReplica, SpCellOpt1aReplica>(ctx, self.bitcell)?;
         let replica_a = ctx
             .instantiate::<SpCellOpt1aReplica>(&NoParams)?
             .with_orientation(Named::ReflectVert);
@@ -284,16 +293,18 @@ impl Component for Bot {
     }
 }
 
-pub struct LeftRight;
+pub struct LeftRight {
+    bitcell: BitcellType,
+}
 
 impl Component for LeftRight {
-    type Params = NoParams;
+    type Params = BitcellType;
 
     fn new(
-        _params: &Self::Params,
+        params: &Self::Params,
         _ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self> {
-        Ok(Self)
+        Ok(Self { bitcell: *params })
     }
 
     fn name(&self) -> ArcStr {
@@ -304,7 +315,7 @@ impl Component for LeftRight {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
-        let rowend = ctx.instantiate::<SpRowendReplica>(&NoParams)?;
+        let rowend = row_cell::<SpRowendReplica, SpRowendaReplica>(ctx, self.bitcell)?;
         let rowenda = ctx
             .instantiate::<SpRowendaReplica>(&NoParams)?
             .with_orientation(Named::ReflectVert);
@@ -345,16 +356,18 @@ impl Component for LeftRight {
     }
 }
 
-pub struct CornerTop;
+pub struct CornerTop {
+    bitcell: BitcellType,
+}
 
 impl Component for CornerTop {
-    type Params = NoParams;
+    type Params = BitcellType;
 
     fn new(
-        _params: &Self::Params,
+        params: &Self::Params,
         _ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self> {
-        Ok(Self)
+        Ok(Self { bitcell: *params })
     }
 
     fn name(&self) -> ArcStr {
@@ -365,8 +378,8 @@ impl Component for CornerTop {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
-        let rowend = ctx.instantiate::<SpRowendReplica>(&NoParams)?;
-        let corner = ctx.instantiate::<SpCorner>(&NoParams)?;
+        let rowend = row_cell::<SpRowendReplica, SpRowendaReplica>(ctx, self.bitcell)?;
+        let corner = row_cell::<SpCorner, SpCornera>(ctx, self.bitcell)?;
 
         let grid = into_grid![[corner][rowend]];
         let mut grid_tiler = GridTiler::new(grid);
@@ -474,18 +487,21 @@ impl Component for ReplicaCellArray {
         for i in 0..rows {
             for j in 0..self.params.cols {
                 let wl = if i == 0 { wl } else { vss };
-                ctx.instantiate::<SpCellReplica>(&NoParams)?
-                    .with_connections([
-                        ("BL", bl),
-                        ("BR", br),
-                        ("VSS", vss),
-                        ("VDD", vdd),
-                        ("VPB", vdd),
-                        ("VNB", vss),
-                        ("WL", wl),
-                    ])
-                    .named(format!("cell_{i}_{j}"))
-                    .add_to(ctx);
+                let cell = match self.params.bitcell {
+                    BitcellType::Sp => ctx.instantiate::<SpCellReplica>(&NoParams)?,
+                    BitcellType::SpOpt1a => ctx.instantiate::<SpCellOpt1aReplica>(&NoParams)?,
+                };
+                cell.with_connections([
+                    ("BL", bl),
+                    ("BR", br),
+                    ("VSS", vss),
+                    ("VDD", vdd),
+                    ("VPB", vdd),
+                    ("VNB", vss),
+                    ("WL", wl),
+                ])
+                .named(format!("cell_{i}_{j}"))
+                .add_to(ctx);
             }
         }
 
@@ -538,8 +554,9 @@ impl Component for ReplicaCellArray {
         let layers = ctx.layers();
         let outline = layers.get(Selector::Name("outline"))?;
 
-        let corner_ul = ctx.instantiate::<CornerTop>(&NoParams)?;
-        let left = ctx.instantiate::<LeftRight>(&NoParams)?;
+        let bitcell = self.params.bitcell;
+        let corner_ul = ctx.instantiate::<CornerTop>(&bitcell)?;
+        let left = ctx.instantiate::<LeftRight>(&bitcell)?;
         let left_bbox = left.bbox().into_rect();
         let left = RectBbox::new(
             left,
@@ -548,13 +565,13 @@ impl Component for ReplicaCellArray {
 
         let corner_ll = ctx.instantiate::<CornerBot>(&NoParams)?;
 
-        let top = ctx.instantiate::<Top>(&NoParams)?;
-        let center = ctx.instantiate::<Center>(&NoParams)?;
+        let top = ctx.instantiate::<Top>(&bitcell)?;
+        let center = ctx.instantiate::<Center>(&bitcell)?;
         let bot = ctx.instantiate::<Bot>(&NoParams)?;
 
         let corner_ur = corner_ul.clone().with_orientation(Named::ReflectHoriz);
         let right = ctx
-            .instantiate::<LeftRight>(&NoParams)?
+            .instantiate::<LeftRight>(&bitcell)?
             .with_orientation(Named::ReflectHoriz);
         let right_bbox = right.bbox().into_rect();
         let right = RectBbox::new(
@@ -659,7 +676,11 @@ mod tests {
         let ctx = setup_ctx();
         let work_dir = test_work_dir("test_replica_cell_array");
         ctx.write_layout::<ReplicaCellArray>(
-            &ReplicaCellArrayParams { rows: 24, cols: 2 },
+            &ReplicaCellArrayParams {
+                rows: 24,
+                cols: 2,
+                bitcell: BitcellType::Sp,
+            },
             out_gds(work_dir, "layout"),
         )
         .expect("failed to write layout");
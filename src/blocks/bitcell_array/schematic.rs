@@ -3,8 +3,8 @@ use substrate::index::IndexOwned;
 use substrate::schematic::circuit::Direction;
 use substrate::schematic::context::SchematicCtx;
 
-use super::SpCellArray;
-use crate::blocks::macros::{SpCell, SpColend, SpHorizWlstrapP, SpHstrap};
+use super::{BitcellType, SpCellArray};
+use crate::blocks::macros::{SpCell, SpCellOpt1a, SpColend, SpHorizWlstrapP, SpHstrap};
 
 impl SpCellArray {
     pub(crate) fn schematic(
@@ -21,7 +21,10 @@ impl SpCellArray {
 
         let make_cell =
             |ctx: &mut SchematicCtx, wl, bl, br, name| -> substrate::error::Result<()> {
-                let mut cell = ctx.instantiate::<SpCell>(&NoParams)?;
+                let mut cell = match self.params.bitcell {
+                    BitcellType::Sp => ctx.instantiate::<SpCell>(&NoParams)?,
+                    BitcellType::SpOpt1a => ctx.instantiate::<SpCellOpt1a>(&NoParams)?,
+                };
                 cell.connect_all([
                     ("BL", bl),
                     ("BR", br),
@@ -8,8 +8,9 @@ use crate::blocks::macros::{SenseAmp, SenseAmpCent};
 use crate::blocks::precharge::layout::{PrechargeCent, PrechargeEnd, PrechargeEndParams};
 use crate::blocks::precharge::Precharge;
 use crate::blocks::sram::layout::draw_via;
+use crate::blocks::sram::{OutputMode, WriteMode};
 use crate::blocks::tgatemux::{TGateMuxCent, TGateMuxEnd, TGateMuxGroup};
-use crate::blocks::wrdriver::layout::WriteDriverCent;
+use crate::blocks::wrdriver::layout::{WriteDriverCent, POWER_HEIGHT};
 use crate::blocks::wrdriver::WriteDriver;
 use arcstr::ArcStr;
 use grid::Grid;
@@ -23,7 +24,7 @@ use substrate::data::SubstrateCtx;
 use substrate::error::Result;
 use substrate::index::IndexOwned;
 use substrate::into_vec;
-use substrate::layout::cell::{CellPort, Port, PortConflictStrategy, PortId};
+use substrate::layout::cell::{CellPort, Instance, Port, PortConflictStrategy, PortId};
 use substrate::layout::context::LayoutCtx;
 use substrate::layout::elements::via::{Via, ViaExpansion, ViaParams};
 use substrate::layout::layers::selector::Selector;
@@ -566,11 +567,40 @@ impl Column {
         }
         grid.push_row(row);
 
+        // Write-through muxes
+        let mut wt_mux = if self.params.write_mode == WriteMode::WriteThrough {
+            let wt_mux = ctx.instantiate::<WriteThroughMux>(&NoParams)?;
+            let bbox = Rect::from_spans(
+                Span::with_start_and_length(
+                    wt_mux.layer_bbox(outline).into_rect().left() + pc_design.tap_width / 2,
+                    pc.brect().width(),
+                ),
+                wt_mux.layer_bbox(outline).into_rect().vspan(),
+            );
+
+            let mut row = Vec::new();
+            for _ in 0..offset {
+                row.push(None.into());
+            }
+            row.push(OptionTile::new(Tile::from(RectBbox::new(
+                wt_mux.clone(),
+                bbox,
+            ))));
+            for _ in offset + 1..mux_ratio {
+                row.push(None.into());
+            }
+            grid.push_row(row);
+            Some(wt_mux)
+        } else {
+            None
+        };
+
         let mut latch = ctx.instantiate::<DiffLatch>(&self.params.latch)?;
         let bbox = Rect::from_spans(
             Span::with_start_and_length(latch.brect().left(), pc.brect().width()),
             latch.brect().vspan(),
         );
+        let latch_row = grid.rows();
 
         let mut row = Vec::new();
         for _ in 0..offset {
@@ -585,35 +615,49 @@ impl Column {
         }
         grid.push_row(row);
 
-        // Data dff
-        let bbox = Rect::from_spans(
-            Span::with_start_and_length(
-                dff.layer_bbox(outline).into_rect().left() + pc_design.tap_width / 2,
-                pc.brect().width(),
-            ),
-            dff.layer_bbox(outline).into_rect().vspan(),
-        );
+        // Data dff, and the output dff of a registered column.
+        //
+        // The output dff is flipped so that its input faces the latch above it
+        // and its output faces the bottom of the column.
+        let mut out_dff = (self.params.output == OutputMode::Registered)
+            .then(|| dff.with_orientation(Named::ReflectVert));
+        let dff_row = grid.rows();
+        for dff in std::iter::once(&dff).chain(out_dff.as_ref()) {
+            let bbox = Rect::from_spans(
+                Span::with_start_and_length(
+                    dff.layer_bbox(outline).into_rect().left() + pc_design.tap_width / 2,
+                    pc.brect().width(),
+                ),
+                dff.layer_bbox(outline).into_rect().vspan(),
+            );
 
-        let mut row = Vec::new();
-        for _ in 0..offset {
-            row.push(None.into());
-        }
-        row.push(OptionTile::new(Tile::from(RectBbox::new(
-            dff.clone(),
-            bbox,
-        ))));
-        for _ in offset + 1..mux_ratio {
-            row.push(None.into());
+            let mut row = Vec::new();
+            for _ in 0..offset {
+                row.push(None.into());
+            }
+            row.push(OptionTile::new(Tile::from(RectBbox::new(
+                dff.clone(),
+                bbox,
+            ))));
+            for _ in offset + 1..mux_ratio {
+                row.push(None.into());
+            }
+            grid.push_row(row);
         }
-        grid.push_row(row);
 
         let mut tiler = GridTiler::new(grid);
         pc.translate(tiler.translation(0, 0));
         mux.translate(tiler.translation(1, 0));
         sa.translate(tiler.translation(2, offset));
         wrdrv.translate(tiler.translation(3, offset));
-        latch.translate(tiler.translation(4, offset));
-        dff.translate(tiler.translation(5, offset));
+        if let Some(wt_mux) = &mut wt_mux {
+            wt_mux.translate(tiler.translation(4, offset));
+        }
+        latch.translate(tiler.translation(latch_row, offset));
+        dff.translate(tiler.translation(dff_row, offset));
+        if let Some(out_dff) = &mut out_dff {
+            out_dff.translate(tiler.translation(dff_row + 1, offset));
+        }
         tiler.expose_ports(
             |port: CellPort, (i, j)| match port.name().as_str() {
                 "br_in" => Some(port.named("br").with_index(j)),
@@ -679,7 +723,12 @@ impl Column {
             .build()
             .unwrap();
         let rect1 = Rect::from_spans(center_track, jog.r2().vspan().add_point(jog_y));
-        let rect2 = Rect::from_spans(dout_track, Span::new(ctx.brect().bottom(), jog_y));
+        // A registered column routes the latch output to the output dff instead.
+        let dout_bottom = match &out_dff {
+            Some(out_dff) => out_dff.port("d")?.largest_rect(m0)?.bottom(),
+            None => ctx.brect().bottom(),
+        };
+        let rect2 = Rect::from_spans(dout_track, Span::new(dout_bottom, jog_y));
         let rect3 = Rect::from_spans(
             dout_track.union(center_track),
             Span::from_center_span_gridded(jog_y, 280, ctx.pdk().layout_grid()),
@@ -694,7 +743,24 @@ impl Column {
         ctx.draw_rect(m1, rect2);
         ctx.draw_rect(m1, rect3);
         ctx.draw(via)?;
-        ctx.add_port(CellPort::with_shape("dout", m1, rect2))?;
+        if let Some(out_dff) = &out_dff {
+            let dff_in = out_dff.port("d")?.largest_rect(m0)?;
+            draw_via(m0, dff_in, m1, rect2, ctx)?;
+
+            // Route the output dff to the bottom between its power rails.
+            let dff_out = out_dff.port("q")?.largest_rect(m0)?;
+            let q_track = Span::from_center_span_gridded(
+                latch.port("dout2")?.largest_rect(m0)?.center().x,
+                280,
+                ctx.pdk().layout_grid(),
+            );
+            let rect = Rect::from_spans(q_track, dff_out.vspan().add_point(ctx.brect().bottom()));
+            ctx.draw_rect(m1, rect);
+            draw_via(m0, dff_out, m1, rect, ctx)?;
+            ctx.add_port(CellPort::with_shape("dout", m1, rect))?;
+        } else {
+            ctx.add_port(CellPort::with_shape("dout", m1, rect2))?;
+        }
 
         // Route dff input to bottom.
         let dff_in = dff.port("d")?.largest_rect(m0)?;
@@ -711,7 +777,10 @@ impl Column {
         ctx.add_port(CellPort::with_shape("din", m1, rect1))?;
 
         // Route din and din_b to dff.
+        //
+        // In a write-through column, these tracks also pass the write-through muxes.
         let dout1 = latch.port("dout1")?.largest_rect(m0)?;
+        let mut data_tracks = Vec::with_capacity(2);
         for (in_port, out_port, center) in [
             (
                 "data",
@@ -734,7 +803,12 @@ impl Column {
             ctx.draw_ref(&via)?;
 
             let m1_track = Span::from_center_span_gridded(center, 280, ctx.pdk().layout_grid());
-            let m1_rect = Rect::from_spans(m1_track, dout1.vspan().union(out_port_rect.vspan()));
+            let mut m1_span = dout1.vspan().union(out_port_rect.vspan());
+            if let Some(wt_mux) = &wt_mux {
+                m1_span = m1_span.union(wt_mux.brect().vspan());
+            }
+            let m1_rect = Rect::from_spans(m1_track, m1_span);
+            data_tracks.push(m1_rect);
             let jog = SJog::builder()
                 .src(via.layer_bbox(m1).into_rect())
                 .dst(m1_rect)
@@ -755,6 +829,7 @@ impl Column {
         }
 
         // Route we and we_b to bottom
+        let mut we_track = None;
         for (side, port, in_port) in [(Sign::Neg, "en", "we"), (Sign::Pos, "en_b", "we_b")] {
             let port_rect = wrdrv.port(port)?.largest_rect(m2)?;
             let dff_m1_brect = dff.layer_bbox(m1).into_rect();
@@ -777,6 +852,37 @@ impl Column {
             ctx.add_port(CellPort::builder().id(in_port).add(m1, m1_rect).build())?;
             ctx.draw_rect(m2, m2_rect);
             ctx.draw(via)?;
+            if in_port == "we" {
+                we_track = Some(m1_rect);
+            }
+        }
+
+        // Route the write-through muxes between the sense amp outputs,
+        // which pass through the write driver, and the latch inputs.
+        if let Some(wt_mux) = &wt_mux {
+            let we_track = we_track.unwrap();
+            for (i, (sa_port, latch_port)) in
+                [("outp", "din1"), ("outn", "din2")].into_iter().enumerate()
+            {
+                let sa_out = sa.port(sa_port)?.largest_rect(m1)?;
+                let a0 = wt_mux.port(PortId::new("a0", i))?.largest_rect(m0)?;
+                let a0_track =
+                    Rect::from_spans(sa_out.hspan(), a0.vspan().add_point(wrdrv.brect().bottom()));
+                ctx.draw_rect(m1, a0_track);
+                connect_to_track(ctx, a0, a0_track)?;
+
+                let latch_in = latch.port(latch_port)?.largest_rect(m1)?;
+                let x = wt_mux.port(PortId::new("x", i))?.largest_rect(m0)?;
+                let x_track =
+                    Rect::from_spans(latch_in.hspan(), x.vspan().add_point(latch_in.top()));
+                ctx.draw_rect(m1, x_track);
+                connect_to_track(ctx, x, x_track)?;
+
+                let a1 = wt_mux.port(PortId::new("a1", i))?.largest_rect(m0)?;
+                connect_to_track(ctx, a1, data_tracks[i])?;
+                let sel = wt_mux.port(PortId::new("s", i))?.largest_rect(m0)?;
+                connect_to_track(ctx, sel, we_track)?;
+            }
         }
 
         // Expand nwells
@@ -790,6 +896,24 @@ impl Column {
     }
 }
 
+/// Connects a pin on metal 0 to a vertical metal 1 track
+/// with a metal 2 jog at the height of the pin.
+fn connect_to_track(ctx: &mut LayoutCtx, pin: Rect, track: Rect) -> Result<()> {
+    let layers = ctx.layers();
+    let m0 = layers.get(Selector::Metal(0))?;
+    let m1 = layers.get(Selector::Metal(1))?;
+    let m2 = layers.get(Selector::Metal(2))?;
+
+    let via = draw_via(m0, pin, m1, pin, ctx)?;
+    let pin_m1 = via.layer_bbox(m1).into_rect();
+    let via = draw_via(m1, pin_m1, m2, pin_m1, ctx)?;
+    let pin_m2 = via.layer_bbox(m2).into_rect();
+    let jog = Rect::from_spans(pin_m2.hspan().union(track.hspan()), pin_m2.vspan());
+    ctx.draw_rect(m2, jog);
+    draw_via(m1, track, m2, jog, ctx)?;
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ColCentParams {
     pub col: ColParams,
@@ -846,16 +970,23 @@ impl Component for ColumnCent {
         )]);
         grid.push_row(into_vec![sa.clone()]);
         grid.push_row(into_vec![wrdrv.clone()]);
+        if self.params.col.write_mode == WriteMode::WriteThrough {
+            grid.push_row(into_vec![ctx.instantiate::<WriteThroughMuxCent>(&NoParams)?]);
+        }
+        let latch_row = grid.rows();
         grid.push_row(into_vec![latch.clone()]);
         grid.push_row(into_vec![dff.clone()]);
+        if self.params.col.output == OutputMode::Registered {
+            grid.push_row(into_vec![dff.with_orientation(Named::ReflectVert)]);
+        }
 
         let mut tiler = GridTiler::new(grid);
         pc.translate(tiler.translation(0, 0));
         mux.translate(tiler.translation(1, 0));
         sa.translate(tiler.translation(2, 0));
         wrdrv.translate(tiler.translation(3, 0));
-        latch.translate(tiler.translation(4, 0));
-        dff.translate(tiler.translation(5, 0));
+        latch.translate(tiler.translation(latch_row, 0));
+        dff.translate(tiler.translation(latch_row + 1, 0));
         tiler.expose_ports(
             |port: CellPort, (i, _)| match port.name().as_str() {
                 "sel" | "sel_b" | "vdd" | "vss" => Some(port),
@@ -885,6 +1016,66 @@ impl Component for ColumnCent {
     }
 }
 
+/// Draws taps on either side of a standard cell rotated to fit in a column,
+/// exposing its power rails as `vdd` and `vss`.
+///
+/// Returns the horizontal span of the column, including half a tap cell on either side.
+fn draw_stdcell_taps(ctx: &mut LayoutCtx, cell: &Instance) -> Result<Span> {
+    let layers = ctx.layers();
+    let nwell = layers.get(Selector::Name("nwell"))?;
+    let nsdm = layers.get(Selector::Name("nsdm"))?;
+    let psdm = layers.get(Selector::Name("psdm"))?;
+    let outline = layers.get(Selector::Name("outline"))?;
+    let tap = layers.get(Selector::Name("tap"))?;
+    let m0 = layers.get(Selector::Metal(0))?;
+    let m1 = layers.get(Selector::Metal(1))?;
+
+    let pc = ctx.inner().run_script::<ColumnDesignScript>(&NoParams)?;
+
+    let bbox = cell.layer_bbox(outline).into_rect();
+
+    let hspan = Span::from_center_span_gridded(
+        bbox.center().x,
+        4 * pc.width + pc.tap_width,
+        ctx.pdk().layout_grid(),
+    );
+    let nwell_bbox = cell.layer_bbox(nwell).into_rect();
+    ctx.draw_rect(
+        nwell,
+        Rect::from_spans(
+            Span::new(hspan.start(), nwell_bbox.left()),
+            nwell_bbox.vspan(),
+        ),
+    );
+
+    for (side, vdd) in [(Sign::Neg, true), (Sign::Pos, false)] {
+        let r = Rect::from_spans(
+            Span::new(bbox.hspan().point(side), hspan.point(side)).shrink_all(200),
+            bbox.vspan(),
+        )
+        .shrink(200);
+        let viap = ViaParams::builder().layers(tap, m0).geometry(r, r).build();
+        let via = ctx.instantiate::<Via>(&viap)?;
+
+        ctx.draw_ref(&via)?;
+        let sdm_rect = via.layer_bbox(tap).into_rect().expand(130);
+        ctx.draw_rect(if vdd { nsdm } else { psdm }, sdm_rect);
+        let m0_bbox = via.layer_bbox(m0).into_rect();
+        ctx.draw_rect(
+            m0,
+            m0_bbox.with_hspan(m0_bbox.hspan().add_point(bbox.hspan().point(side))),
+        );
+
+        let port = if vdd { "vpwr" } else { "vgnd" };
+        let m1_rect = cell.port(port)?.largest_rect(m1)?;
+
+        let port = if vdd { "vdd" } else { "vss" };
+        ctx.merge_port(CellPort::with_shape(port, m1, m1_rect));
+    }
+
+    Ok(hspan)
+}
+
 pub struct TappedDff;
 
 impl Component for TappedDff {
@@ -910,57 +1101,11 @@ impl Component for TappedDff {
             .instantiate::<StdCell>(&dff.id())?
             .with_orientation(Named::R90);
         let layers = ctx.layers();
-        let nwell = layers.get(Selector::Name("nwell"))?;
-        let nsdm = layers.get(Selector::Name("nsdm"))?;
-        let psdm = layers.get(Selector::Name("psdm"))?;
-        let outline = layers.get(Selector::Name("outline"))?;
-        let tap = layers.get(Selector::Name("tap"))?;
         let m0 = layers.get(Selector::Metal(0))?;
         let m1 = layers.get(Selector::Metal(1))?;
         let m2 = layers.get(Selector::Metal(2))?;
 
-        let pc = ctx.inner().run_script::<ColumnDesignScript>(&NoParams)?;
-
-        let bbox = dff.layer_bbox(outline).into_rect();
-
-        let hspan = Span::from_center_span_gridded(
-            bbox.center().x,
-            4 * pc.width + pc.tap_width,
-            ctx.pdk().layout_grid(),
-        );
-        let nwell_bbox = dff.layer_bbox(nwell).into_rect();
-        ctx.draw_rect(
-            nwell,
-            Rect::from_spans(
-                Span::new(hspan.start(), nwell_bbox.left()),
-                nwell_bbox.vspan(),
-            ),
-        );
-
-        for (side, vdd) in [(Sign::Neg, true), (Sign::Pos, false)] {
-            let r = Rect::from_spans(
-                Span::new(bbox.hspan().point(side), hspan.point(side)).shrink_all(200),
-                bbox.vspan(),
-            )
-            .shrink(200);
-            let viap = ViaParams::builder().layers(tap, m0).geometry(r, r).build();
-            let via = ctx.instantiate::<Via>(&viap)?;
-
-            ctx.draw_ref(&via)?;
-            let sdm_rect = via.layer_bbox(tap).into_rect().expand(130);
-            ctx.draw_rect(if vdd { nsdm } else { psdm }, sdm_rect);
-            let m0_bbox = via.layer_bbox(m0).into_rect();
-            ctx.draw_rect(
-                m0,
-                m0_bbox.with_hspan(m0_bbox.hspan().add_point(bbox.hspan().point(side))),
-            );
-
-            let port = if vdd { "vpwr" } else { "vgnd" };
-            let m1_rect = dff.port(port)?.largest_rect(m1)?;
-
-            let port = if vdd { "vdd" } else { "vss" };
-            ctx.merge_port(CellPort::with_shape(port, m1, m1_rect));
-        }
+        let hspan = draw_stdcell_taps(ctx, &dff)?;
 
         // Route clock/reset to metal 2 tracks.
         let clk_rect = dff.port("clk")?.largest_rect(m0)?;
@@ -1182,6 +1327,169 @@ impl Component for DffColCent {
     }
 }
 
+pub struct TappedMux;
+
+impl Component for TappedMux {
+    type Params = NoParams;
+    fn new(
+        _params: &Self::Params,
+        _ctx: &substrate::data::SubstrateCtx,
+    ) -> substrate::error::Result<Self> {
+        Ok(Self)
+    }
+    fn name(&self) -> arcstr::ArcStr {
+        arcstr::literal!("tapped_mux")
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+    ) -> substrate::error::Result<()> {
+        let stdcells = ctx.inner().std_cell_db();
+        let lib = stdcells.try_lib_named("sky130_fd_sc_hs")?;
+        let mux2 = lib.try_cell_named("sky130_fd_sc_hs__mux2_2")?;
+        let mux = ctx
+            .instantiate::<StdCell>(&mux2.id())?
+            .with_orientation(Named::R90);
+        let layers = ctx.layers();
+        let outline = layers.get(Selector::Name("outline"))?;
+
+        let hspan = draw_stdcell_taps(ctx, &mux)?;
+        let bbox = mux.layer_bbox(outline).into_rect();
+
+        for port in ["a0", "a1", "s", "x"] {
+            ctx.merge_port(mux.port(port)?.into_cell_port());
+        }
+        ctx.draw_rect(outline, bbox.with_hspan(hspan));
+        ctx.draw(mux)?;
+        Ok(())
+    }
+}
+
+/// The muxes that drive the output latch of a write-through column.
+///
+/// Mux 0 drives `din1` of the latch and mux 1 drives `din2`.
+/// The rotated muxes are stacked with their outputs at the bottom.
+pub struct WriteThroughMux;
+
+impl Component for WriteThroughMux {
+    type Params = NoParams;
+    fn new(
+        _params: &Self::Params,
+        _ctx: &substrate::data::SubstrateCtx,
+    ) -> substrate::error::Result<Self> {
+        Ok(Self)
+    }
+    fn name(&self) -> arcstr::ArcStr {
+        arcstr::literal!("write_through_mux")
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+    ) -> substrate::error::Result<()> {
+        let mux = ctx.instantiate::<TappedMux>(&NoParams)?;
+        let layers = ctx.layers();
+        let outline = layers.get(Selector::Name("outline"))?;
+        let m1 = layers.get(Selector::Metal(1))?;
+        let m2 = layers.get(Selector::Metal(2))?;
+
+        let mux_bbox = mux.layer_bbox(outline).into_rect();
+        let hspan = mux_bbox.hspan();
+        let mut tiler = ArrayTiler::builder()
+            .push_num(RectBbox::new(mux, mux_bbox), 2)
+            .mode(AlignMode::Beneath)
+            .alt_mode(AlignMode::Left)
+            .build();
+        tiler.expose_ports(
+            |port: CellPort, i| match port.name().as_str() {
+                "vdd" | "vss" => Some(port),
+                _ => Some(port.with_index(i)),
+            },
+            PortConflictStrategy::Merge,
+        )?;
+        ctx.add_ports(tiler.ports().cloned())?;
+        let group = tiler.draw_ref()?;
+        let bbox = group.bbox().into_rect();
+        ctx.draw(group)?;
+
+        // The rails of the stacked muxes line up, so they share a `vdd` stripe
+        // above the muxes and a `vss` stripe beneath them.
+        let mut vspan = bbox.vspan();
+        for (port, stripe_span) in [
+            (
+                "vdd",
+                Span::with_start_and_length(bbox.top() + 200, POWER_HEIGHT),
+            ),
+            (
+                "vss",
+                Span::with_stop_and_length(bbox.bottom() - 200, POWER_HEIGHT),
+            ),
+        ] {
+            let power_stripe = Rect::from_spans(hspan, stripe_span);
+            for rail in tiler
+                .port_map()
+                .port(port)?
+                .shapes(m1)
+                .filter_map(|shape| shape.as_rect())
+            {
+                let rail = rail.with_vspan(rail.vspan().union(stripe_span));
+                ctx.draw_rect(m1, rail);
+                draw_via(m1, rail, m2, power_stripe, ctx)?;
+            }
+            ctx.draw_rect(m2, power_stripe);
+            ctx.merge_port(CellPort::with_shape(port, m2, power_stripe));
+            vspan = vspan.union(stripe_span);
+        }
+        ctx.draw_rect(
+            outline,
+            Rect::from_spans(hspan, vspan).expand_dir(Dir::Vert, 200),
+        );
+
+        Ok(())
+    }
+}
+
+pub struct WriteThroughMuxCent;
+
+impl Component for WriteThroughMuxCent {
+    type Params = NoParams;
+    fn new(
+        _params: &Self::Params,
+        _ctx: &substrate::data::SubstrateCtx,
+    ) -> substrate::error::Result<Self> {
+        Ok(Self)
+    }
+    fn name(&self) -> arcstr::ArcStr {
+        arcstr::literal!("write_through_mux_cent")
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+    ) -> substrate::error::Result<()> {
+        let layers = ctx.layers();
+        let outline = layers.get(Selector::Name("outline"))?;
+        let m2 = layers.get(Selector::Metal(2))?;
+
+        let mux = ctx.instantiate::<WriteThroughMux>(&NoParams)?;
+
+        let pc = ctx.inner().run_script::<ColumnDesignScript>(&NoParams)?;
+
+        let bbox = mux.layer_bbox(outline).into_rect();
+
+        let hspan = Span::new(0, pc.tap_width);
+
+        for port in ["vdd", "vss"] {
+            let r = Rect::from_spans(hspan, mux.port(port)?.largest_rect(m2)?.vspan());
+            ctx.draw_rect(m2, r);
+            ctx.merge_port(CellPort::with_shape(port, m2, r));
+        }
+        ctx.draw_rect(outline, bbox.with_hspan(hspan));
+        Ok(())
+    }
+}
+
 pub struct TappedColumn {
     pub params: ColParams,
 }
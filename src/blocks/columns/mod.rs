@@ -13,6 +13,7 @@ use super::sram::schematic::buffer_chain_num_stages;
 use super::tgatemux::TGateMuxParams;
 use super::wrdriver::WriteDriverParams;
 use crate::blocks::latch::DiffLatchParams;
+use crate::blocks::sram::{OutputMode, WriteMode};
 use serde::{Deserialize, Serialize};
 use subgeom::Span;
 use substrate::layout::layers::selector::Selector;
@@ -32,6 +33,14 @@ pub struct ColParams {
     pub cols: usize,
     pub include_wmask: bool,
     pub wmask_granularity: usize,
+    /// In write-through mode, each column drives its output latch
+    /// from the write data during writes.
+    #[serde(default)]
+    pub write_mode: WriteMode,
+    /// With a registered output, `dout` is driven by a flip-flop per data bit
+    /// that registers the output latches on the next clock edge.
+    #[serde(default)]
+    pub output: OutputMode,
 }
 
 impl ColParams {
@@ -190,6 +199,8 @@ impl Script for ColumnsPhysicalDesignScript {
                 use_multi_finger_invs: false,
                 dont_connect_outputs: false,
                 child_sizes: vec![1, 1],
+                suppression: None,
+                clamp: None,
             },
         })
     }
@@ -243,6 +254,8 @@ pub const COL_WMASK_PARAMS: ColParams = ColParams {
     cols: 16,
     include_wmask: true,
     wmask_granularity: 2,
+    write_mode: WriteMode::NoChange,
+    output: OutputMode::Latched,
 };
 
 pub const COL_PARAMS: ColParams = ColParams {
@@ -253,6 +266,8 @@ pub const COL_PARAMS: ColParams = ColParams {
     cols: 128,
     include_wmask: false,
     wmask_granularity: 8,
+    write_mode: WriteMode::NoChange,
+    output: OutputMode::Latched,
 };
 
 pub const COL_CAPACITANCES: ColCapacitances = ColCapacitances {
@@ -465,6 +480,102 @@ mod tests {
             .expect("failed to write layout");
     }
 
+    #[test]
+    fn test_column_16() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_column_16");
+        let params = ColParams {
+            mux: TGateMuxParams {
+                mux_ratio: 16,
+                ..MUX_PARAMS
+            },
+            cols: 256,
+            ..COL_PARAMS
+        };
+        ctx.write_layout::<Column>(&params, out_gds(&work_dir, "layout"))
+            .expect("failed to write layout");
+        ctx.write_schematic_to_file::<Column>(&params, out_spice(work_dir, "schematic"))
+            .expect("failed to write schematic");
+    }
+
+    #[test]
+    fn test_column_32() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_column_32");
+        let params = ColParams {
+            mux: TGateMuxParams {
+                mux_ratio: 32,
+                ..MUX_PARAMS
+            },
+            cols: 256,
+            ..COL_PARAMS
+        };
+        ctx.write_layout::<Column>(&params, out_gds(&work_dir, "layout"))
+            .expect("failed to write layout");
+        ctx.write_schematic_to_file::<Column>(&params, out_spice(work_dir, "schematic"))
+            .expect("failed to write schematic");
+    }
+
+    #[test]
+    fn test_col_peripherals_wide_mux() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_col_peripherals_wide_mux");
+        for mux_ratio in [16, 32] {
+            let params = ColParams {
+                mux: TGateMuxParams {
+                    mux_ratio,
+                    ..MUX_PARAMS
+                },
+                cols: 4 * mux_ratio,
+                ..COL_WMASK_PARAMS
+            };
+            let name = format!("col_peripherals_m{mux_ratio}");
+            ctx.write_layout::<ColPeripherals>(&params, out_gds(&work_dir, &name))
+                .expect("failed to write layout");
+            ctx.write_schematic_to_file::<ColPeripherals>(&params, out_spice(&work_dir, &name))
+                .expect("failed to write schematic");
+        }
+    }
+
+    #[test]
+    fn test_col_peripherals_write_through_registered() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_col_peripherals_write_through_registered");
+        for (name, params) in [
+            (
+                "col_peripherals_wt",
+                ColParams {
+                    write_mode: WriteMode::WriteThrough,
+                    ..COL_WMASK_PARAMS
+                },
+            ),
+            (
+                "col_peripherals_reg",
+                ColParams {
+                    output: OutputMode::Registered,
+                    ..COL_WMASK_PARAMS
+                },
+            ),
+        ] {
+            ctx.write_layout::<ColPeripherals>(&params, out_gds(&work_dir, name))
+                .expect("failed to write layout");
+            ctx.write_schematic_to_file::<ColPeripherals>(&params, out_spice(&work_dir, name))
+                .expect("failed to write schematic");
+
+            #[cfg(feature = "commercial")]
+            {
+                let lvs_work_dir = work_dir.join(format!("{name}_lvs"));
+                let output = ctx
+                    .write_lvs::<ColPeripherals>(&params, lvs_work_dir)
+                    .expect("failed to run LVS");
+                assert!(matches!(
+                    output.summary,
+                    substrate::verification::lvs::LvsSummary::Pass
+                ));
+            }
+        }
+    }
+
     #[test]
     fn test_tapped_column_4() {
         let ctx = setup_ctx();
@@ -10,6 +10,7 @@ use crate::blocks::decoder::DecoderStage;
 use crate::blocks::latch::DiffLatch;
 use crate::blocks::macros::SenseAmp;
 use crate::blocks::precharge::Precharge;
+use crate::blocks::sram::{OutputMode, WriteMode};
 use crate::blocks::tgatemux::TGateMux;
 use crate::blocks::wrdriver::WriteDriver;
 
@@ -222,13 +223,48 @@ impl Column {
         sa.set_name("sense_amp");
         ctx.add_instance(sa);
 
+        // The latch is set by a low input on `din2` and reset by a low input on `din1`.
+        // In write-through mode, the write data drives the latch while the write driver
+        // is enabled, and the sense amp outputs idle high otherwise.
+        let (latch_inp, latch_inn) = match self.params.write_mode {
+            WriteMode::NoChange => (sa_outp, sa_outn),
+            WriteMode::WriteThrough => {
+                let mux2 = lib.try_cell_named("sky130_fd_sc_hs__mux2_2")?;
+                let [latch_inp, latch_inn] = ctx.signals(["latch_inp", "latch_inn"]);
+                for (sa_out, data, latch_in, suffix) in
+                    [(sa_outp, q, latch_inp, "p"), (sa_outn, q_b, latch_inn, "n")]
+                {
+                    ctx.instantiate::<StdCell>(&mux2.id())?
+                        .with_connections([
+                            ("A0", sa_out),
+                            ("A1", data),
+                            ("S", we),
+                            ("X", latch_in),
+                            ("VPWR", vdd),
+                            ("VPB", vdd),
+                            ("VGND", vss),
+                            ("VNB", vss),
+                        ])
+                        .named(format!("write_through_mux_{suffix}"))
+                        .add_to(ctx);
+                }
+                (latch_inp, latch_inn)
+            }
+        };
+
+        // With a registered output, the latch output is registered on the next clock edge.
+        let latch_out = match self.params.output {
+            OutputMode::Latched => dout,
+            OutputMode::Registered => ctx.signal("dout_latched"),
+        };
+
         let mut latch = ctx.instantiate::<DiffLatch>(&self.params.latch)?;
         latch.connect_all([
             ("vdd", &vdd),
             ("vss", &vss),
-            ("din1", &sa_outp),
-            ("din2", &sa_outn),
-            ("dout1", &dout),
+            ("din1", &latch_inp),
+            ("din2", &latch_inn),
+            ("dout1", &latch_out),
             ("dout2", &diff_latch_outn),
         ]);
         latch.set_name("latch");
@@ -249,6 +285,24 @@ impl Column {
         dff.set_name("dff");
         ctx.add_instance(dff);
 
+        if self.params.output == OutputMode::Registered {
+            let dout_b = ctx.signal("dout_b");
+            ctx.instantiate::<StdCell>(&dfrtp.id())?
+                .with_connections([
+                    ("VPWR", vdd),
+                    ("VGND", vss),
+                    ("VNB", vss),
+                    ("VPB", vdd),
+                    ("CLK", clk),
+                    ("RESET_B", rstb),
+                    ("D", latch_out),
+                    ("Q", dout),
+                    ("Q_N", dout_b),
+                ])
+                .named("dout_dff")
+                .add_to(ctx);
+        }
+
         Ok(())
     }
 }
@@ -10,7 +10,7 @@ use substrate::layout::context::LayoutCtx;
 use substrate::layout::elements::via::{Via, ViaParams};
 use substrate::layout::group::Group;
 use substrate::layout::layers::selector::Selector;
-use substrate::layout::layers::LayerBoundBox;
+use substrate::layout::layers::{LayerBoundBox, LayerKey};
 use substrate::layout::placement::align::{AlignMode, AlignRect};
 use substrate::layout::placement::array::{ArrayTiler, ArrayTilerBuilder};
 use substrate::layout::placement::tile::LayerBbox;
@@ -21,9 +21,15 @@ use substrate::layout::routing::tracks::TrackLocator;
 use substrate::layout::Draw;
 use substrate::pdk::stdcell::StdCell;
 
+use crate::blocks::decoder::INV_PARAMS;
+use crate::blocks::delay_line::{TristateInvDelayLine, TristateInvDelayLineParams};
 use crate::blocks::macros::{SvtInv2, SvtInv4};
+use crate::blocks::sram::PortStyle;
 
-use super::{ControlLogicReplicaV2, EdgeDetector, InvChain, SrLatch, SvtInvChain};
+use super::{
+    ControlLogicReplicaV2, EdgeDetector, InvChain, SrLatch, SvtInvChain, TrimDelayLine,
+    TRIM_NAND_INPUTS,
+};
 use subgeom::transform::Translate;
 use subgeom::{Corner, Dir, Point, Rect, Side, Span};
 
@@ -41,7 +47,6 @@ impl ControlLogicReplicaV2 {
         let inv = ctx.instantiate::<StdCell>(&inv.id())?;
         let tap = lib.try_cell_named("sky130_fd_sc_hs__tap_2")?;
         let tap = ctx.instantiate::<StdCell>(&tap.id())?;
-        let tap = LayerBbox::new(tap, outline);
         let and2 = lib.try_cell_named("sky130_fd_sc_hs__and2_2")?;
         let and2 = ctx.instantiate::<StdCell>(&and2.id())?;
         let and2_med = lib.try_cell_named("sky130_fd_sc_hs__and2_4")?;
@@ -61,47 +66,19 @@ impl ControlLogicReplicaV2 {
         let biginv = ctx.instantiate::<StdCell>(&biginv.id())?;
         let edge_detector = ctx.instantiate::<EdgeDetector>(&NoParams)?;
         let sr_latch = ctx.instantiate::<SrLatch>(&NoParams)?;
+        let ce_gate = match self.params.port_style {
+            PortStyle::CeWe => None,
+            PortStyle::RenWen => {
+                let or2 = lib.try_cell_named("sky130_fd_sc_hs__or2_2")?;
+                Some(ctx.instantiate::<StdCell>(&or2.id())?)
+            }
+            PortStyle::CsbWeb => Some(inv.clone()),
+        };
 
         let mut rows = ArrayTiler::builder();
         rows.mode(AlignMode::Left).alt_mode(AlignMode::Beneath);
 
-        let create_row = |insts: &[(&str, &Instance)]| -> substrate::error::Result<Group> {
-            let mut row = new_row();
-            row.push(tap.clone());
-            for (_, inst) in insts {
-                row.push(LayerBbox::new((*inst).clone(), outline));
-                row.push(tap.clone());
-            }
-            let mut row = row.build();
-
-            let names: Vec<String> = insts.iter().map(|(name, _)| name.to_string()).collect();
-            row.expose_ports(
-                |port: CellPort, i| {
-                    let name = if i % 2 == 1 {
-                        &names[i / 2]
-                    } else {
-                        return None;
-                    };
-                    if let "vpwr" | "vgnd" | "vdd" | "vss" = port.name().as_str() {
-                        return None;
-                    }
-                    let port_name = format!("{}_{}", name, port.name());
-                    Some(port.named(port_name))
-                },
-                PortConflictStrategy::Error,
-            )?;
-            row.expose_ports(
-                |port: CellPort, _| match port.name().as_str() {
-                    "vpwr" => Some(port.named("vdd")),
-                    "vgnd" => Some(port.named("vss")),
-                    "vdd" | "vss" => Some(port),
-                    _ => None,
-                },
-                PortConflictStrategy::Merge,
-            )?;
-
-            row.generate()
-        };
+        let create_row = |insts: &[(&str, &Instance)]| std_cell_row(&tap, outline, insts);
 
         let mut inv_rbl = inv.clone();
         inv_rbl.reflect_horiz_anchored();
@@ -124,7 +101,7 @@ impl ControlLogicReplicaV2 {
             ("clkpd_inv", &inv),
             (
                 "clkpd_delay",
-                &ctx.instantiate::<InvChain>(&self.params.wlen_pulse_invs)?,
+                &ctx.instantiate::<InvChain>(&self.params.wlen_pulse_chain_invs())?,
             ),
             ("mux_wlen_rst", &mux2),
             (
@@ -163,17 +140,17 @@ impl ControlLogicReplicaV2 {
         row.set_orientation(Named::ReflectVert);
         rows.push(LayerBbox::new(row, outline));
 
-        rows.push(LayerBbox::new(
-            create_row(&[
-                (
-                    "decoder_replica",
-                    &ctx.instantiate::<SvtInvChain>(&self.params.decoder_delay_invs)?,
-                ),
-                ("pc_ctl", &sr_latch),
-                ("pc_b_buf", &buf),
-            ])?,
-            outline,
-        ));
+        let decoder_replica =
+            ctx.instantiate::<SvtInvChain>(&self.params.decoder_replica_chain_invs())?;
+        let mut row = vec![
+            ("decoder_replica", &decoder_replica),
+            ("pc_ctl", &sr_latch),
+            ("pc_b_buf", &buf),
+        ];
+        if let Some(ce_gate) = &ce_gate {
+            row.push(("ce_gate", ce_gate));
+        }
+        rows.push(LayerBbox::new(create_row(&row)?, outline));
 
         let mut rows = rows.build();
         rows.expose_ports(
@@ -198,15 +175,36 @@ impl ControlLogicReplicaV2 {
         )?;
         let group = rows.generate()?;
 
-        self.route(ctx, &group)?;
+        // Trim delay lines are placed beneath the rows, wordline trim first.
+        let mut trims = Vec::new();
+        if let Some(trim) = self.params.trim {
+            let mut prev = group.bbox();
+            for _ in 0..2 {
+                let mut inst = ctx.instantiate::<TrimDelayLine>(&trim)?;
+                inst.align_beneath(prev, 3_000);
+                inst.align_left(group.bbox());
+                prev = inst.bbox();
+                trims.push(inst);
+            }
+        }
+
+        self.route(ctx, &group, &trims)?;
 
         ctx.add_ports(
             group
                 .ports()
                 .filter(|port| matches!(port.name().as_str(), "vdd" | "vss")),
         )?;
+        for trim in &trims {
+            for port in ["vdd", "vss"] {
+                ctx.merge_port(trim.port(port)?.into_cell_port());
+            }
+        }
 
         ctx.draw(group)?;
+        for trim in trims {
+            ctx.draw(trim)?;
+        }
         Ok(())
     }
 
@@ -214,11 +212,21 @@ impl ControlLogicReplicaV2 {
         &self,
         ctx: &mut substrate::layout::context::LayoutCtx,
         group: &Group,
+        trims: &[Instance],
     ) -> substrate::error::Result<()> {
         let layers = ctx.layers();
         let m0 = layers.get(Selector::Metal(0))?;
         let m1 = layers.get(Selector::Metal(1))?;
         let m2 = layers.get(Selector::Metal(2))?;
+        let style = self.params.port_style;
+        let en_net = style.enable_pin();
+        let trim_bits = self.params.trim.map(|trim| trim.bits).unwrap_or_default();
+        // Router net of the write enable pin, named after the internal signal it drives.
+        let (we_pin_net, inv_we_net) = if style.active_low() {
+            ("we_b", "we")
+        } else {
+            ("we", "we_b")
+        };
 
         let via01 = ctx.instantiate::<Via>(
             &ViaParams::builder()
@@ -232,8 +240,12 @@ impl ControlLogicReplicaV2 {
                 .build(),
         )?;
 
+        let body = trims
+            .iter()
+            .fold(group.bbox(), |bbox, trim| bbox.union(trim.bbox()))
+            .into_rect();
         let mut router = GreedyRouter::with_config(GreedyRouterConfig {
-            area: group.brect().expand(8 * 680),
+            area: body.expand(8 * 680),
             layers: vec![
                 LayerConfig {
                     line: 320,
@@ -264,6 +276,12 @@ impl ControlLogicReplicaV2 {
                 let rect = shape.brect().expand(40);
                 router.block(layer, rect);
             }
+            for trim in trims {
+                for shape in trim.shapes_on(layer) {
+                    let rect = shape.brect().expand(40);
+                    router.block(layer, rect);
+                }
+            }
         }
 
         // Pins
@@ -284,8 +302,8 @@ impl ControlLogicReplicaV2 {
         let vtrack_start = vtracks.track_with_loc(TrackLocator::EndsBefore, group.brect().left());
 
         // left pins
-        let vtrack = vtracks
-            .index(vtracks.track_with_loc(TrackLocator::EndsBefore, group.brect().left() - 3_200));
+        let vtrack =
+            vtracks.index(vtracks.track_with_loc(TrackLocator::EndsBefore, body.left() - 3_200));
         for i in 0..num_left_pins {
             let htrack = htracks.index(htrack_start - 2 * (i as i64) - top_offset);
             left_pins.push(Rect::from_spans(vtrack, htrack));
@@ -301,8 +319,8 @@ impl ControlLogicReplicaV2 {
         );
 
         // bot pins
-        let htrack = htracks
-            .index(htracks.track_with_loc(TrackLocator::EndsBefore, group.brect().bottom()) - 8);
+        let htrack =
+            htracks.index(htracks.track_with_loc(TrackLocator::EndsBefore, body.bottom()) - 8);
         for i in 0..num_bot_pins {
             let vtrack = vtracks.index(vtrack_start + 2 * (i as i64) + left_offset);
             bot_pins.push(Rect::from_spans(vtrack, htrack));
@@ -311,16 +329,12 @@ impl ControlLogicReplicaV2 {
 
         router.block(
             m1,
-            Rect::from_spans(
-                group.brect().hspan(),
-                htrack.expand(true, 140).expand(false, 2000),
-            ),
+            Rect::from_spans(body.hspan(), htrack.expand(true, 140).expand(false, 2000)),
         );
 
         // right pins
-        let vtrack = vtracks.index(
-            vtracks.track_with_loc(TrackLocator::StartsAfter, group.brect().right() + 3_200),
-        );
+        let vtrack =
+            vtracks.index(vtracks.track_with_loc(TrackLocator::StartsAfter, body.right() + 3_200));
         for i in 0..num_right_pins {
             let htrack = htracks.index(htrack_start - 2 * (i as i64) - top_offset);
             right_pins.push(Rect::from_spans(vtrack, htrack));
@@ -329,10 +343,7 @@ impl ControlLogicReplicaV2 {
 
         router.block(
             m2,
-            Rect::from_spans(
-                vtrack.expand(true, 2000).expand(false, 140),
-                group.brect().vspan(),
-            ),
+            Rect::from_spans(vtrack.expand(true, 2000).expand(false, 140), body.vspan()),
         );
 
         // top pins
@@ -354,9 +365,9 @@ impl ControlLogicReplicaV2 {
         let clk_pin = left_pins[0];
         router.occupy(m1, clk_pin, "clk")?;
         let ce_pin = left_pins[1];
-        router.occupy(m1, ce_pin, "ce")?;
+        router.occupy(m1, ce_pin, en_net)?;
         let we_pin = left_pins[2];
-        router.occupy(m1, we_pin, "we")?;
+        router.occupy(m1, we_pin, we_pin_net)?;
         let resetb_pin = left_pins[3];
         router.occupy(m1, resetb_pin, "rstb")?;
 
@@ -419,6 +430,35 @@ impl ControlLogicReplicaV2 {
         ctx.draw_rect(m1, ce_in);
         router.occupy(m1, ce_in, "ce")?;
 
+        // enable pins -> ce_gate -> clk_gate.b
+        let mut enable_routes = Vec::new();
+        if style == PortStyle::CeWe {
+            enable_routes.push((ce_pin, ce_in, "ce"));
+        } else {
+            let ce_gate_out = if style == PortStyle::RenWen {
+                "ce_gate_x"
+            } else {
+                "ce_gate_y"
+            };
+            let mut ce_gate_pins = vec![("ce_gate_a", ce_pin, en_net), (ce_gate_out, ce_in, "ce")];
+            if style == PortStyle::RenWen {
+                ce_gate_pins.push(("ce_gate_b", we_pin, we_pin_net));
+            }
+            for (port, other, net) in ce_gate_pins {
+                let pin = group.port_map().port(port)?.largest_rect(m0)?;
+                let mut via = via01.clone();
+                via.align_centers_gridded(pin.bbox(), grid);
+                let pin = router.expand_to_grid(
+                    via.layer_bbox(m1).into_rect(),
+                    ExpandToGridStrategy::Corner(Corner::UpperRight),
+                );
+                ctx.draw(via)?;
+                ctx.draw_rect(m1, pin);
+                router.occupy(m1, pin, net)?;
+                enable_routes.push((other, pin, net));
+            }
+        }
+
         // rbl -> inv_rbl.a
         let pin = group.port_map().port("inv_rbl_a")?.largest_rect(m0)?;
         let mut via = via01.clone();
@@ -556,6 +596,21 @@ impl ControlLogicReplicaV2 {
         ctx.draw_rect(m1, clkp_b_in);
         router.occupy(m1, clkp_b_in, "clkp_b")?;
 
+        // Trim delay line pins are on the trim delay line's routing grid.
+        let trim_pin = |ctx: &mut LayoutCtx,
+                        router: &mut GreedyRouter,
+                        trim: &Instance,
+                        port: &str,
+                        net: &str|
+         -> substrate::error::Result<Rect> {
+            let pin = trim.port(port)?.largest_rect(m1)?;
+            let pin = router.expand_to_grid(pin, ExpandToGridStrategy::Minimum);
+            ctx.draw_rect(m1, pin);
+            router.occupy(m1, pin, net)?;
+            Ok(pin)
+        };
+        let mut trim_routes = Vec::new();
+
         // decoder_replica.dout
         let decrepend_out = group
             .port_map()
@@ -569,7 +624,16 @@ impl ControlLogicReplicaV2 {
         );
         ctx.draw(via)?;
         ctx.draw_rect(m1, decrepend_out);
-        router.occupy(m1, decrepend_out, "decrepend")?;
+        // With trim inputs, decoder_replica.dout -> trim_saen.din and trim_saen.dout drives decrepend.
+        let decrepend_out = if let Some(trim_saen) = trims.get(1) {
+            router.occupy(m1, decrepend_out, "decrepend0")?;
+            let decrepend0_in = trim_pin(ctx, &mut router, trim_saen, "din", "decrepend0")?;
+            trim_routes.push((decrepend_out, decrepend0_in, "decrepend0"));
+            trim_pin(ctx, &mut router, trim_saen, "dout", "decrepend")?
+        } else {
+            router.occupy(m1, decrepend_out, "decrepend")?;
+            decrepend_out
+        };
 
         // clkp_b -> pc_ctl.rb
         let pin = group.port_map().port("pc_ctl_rb")?.largest_rect(m0)?;
@@ -757,14 +821,36 @@ impl ControlLogicReplicaV2 {
         clkpdd_out_via.align_centers_gridded(src, grid);
         let mut clkpdd_in_via = via01.clone();
         clkpdd_in_via.align_centers_gridded(dst, grid);
-        let rect = clkpdd_out_via
-            .layer_bbox(m1)
-            .union(clkpdd_in_via.layer_bbox(m1))
-            .into_rect();
-        ctx.draw(clkpdd_out_via)?;
-        ctx.draw(clkpdd_in_via)?;
-        ctx.draw_rect(m1, rect);
-        router.occupy(m1, rect, "clkpdd")?;
+        if let Some(trim_wl) = trims.first() {
+            // clkpd_delay.dout -> trim_wl.din, trim_wl.dout -> mux_wlen_rst.a1
+            let clkpdd0_out = router.expand_to_grid(
+                clkpdd_out_via.layer_bbox(m1).into_rect(),
+                ExpandToGridStrategy::Corner(Corner::UpperLeft),
+            );
+            let clkpdd_in = router.expand_to_grid(
+                clkpdd_in_via.layer_bbox(m1).into_rect(),
+                ExpandToGridStrategy::Corner(Corner::UpperRight),
+            );
+            ctx.draw(clkpdd_out_via)?;
+            ctx.draw(clkpdd_in_via)?;
+            ctx.draw_rect(m1, clkpdd0_out);
+            ctx.draw_rect(m1, clkpdd_in);
+            router.occupy(m1, clkpdd0_out, "clkpdd0")?;
+            router.occupy(m1, clkpdd_in, "clkpdd")?;
+            let clkpdd0_in = trim_pin(ctx, &mut router, trim_wl, "din", "clkpdd0")?;
+            let clkpdd_out = trim_pin(ctx, &mut router, trim_wl, "dout", "clkpdd")?;
+            trim_routes.push((clkpdd0_out, clkpdd0_in, "clkpdd0"));
+            trim_routes.push((clkpdd_out, clkpdd_in, "clkpdd"));
+        } else {
+            let rect = clkpdd_out_via
+                .layer_bbox(m1)
+                .union(clkpdd_in_via.layer_bbox(m1))
+                .into_rect();
+            ctx.draw(clkpdd_out_via)?;
+            ctx.draw(clkpdd_in_via)?;
+            ctx.draw_rect(m1, rect);
+            router.occupy(m1, rect, "clkpdd")?;
+        }
 
         // rwl_buf.x
         let pin = group.port_map().port("rwl_buf_x")?.largest_rect(m1)?;
@@ -851,19 +937,27 @@ impl ControlLogicReplicaV2 {
         );
         ctx.draw(via)?;
         ctx.draw_rect(m1, we_in_inv);
-        router.occupy(m1, we_in_inv, "we")?;
+        router.occupy(m1, we_in_inv, we_pin_net)?;
 
         // inv_we.y
         let port = group.port_map().port("inv_we_y")?.largest_rect(m0)?;
         let mut via = via01.clone();
         via.align_centers_gridded(port.bbox(), grid);
-        let we_b_out = router.expand_to_grid(
+        let inv_we_out = router.expand_to_grid(
             via.layer_bbox(m1).into_rect(),
             ExpandToGridStrategy::Corner(Corner::UpperRight),
         );
         ctx.draw(via)?;
-        ctx.draw_rect(m1, we_b_out);
-        router.occupy(m1, we_b_out, "we_b")?;
+        ctx.draw_rect(m1, inv_we_out);
+        router.occupy(m1, inv_we_out, inv_we_net)?;
+
+        // An active low write enable drives the `we_b` logic directly,
+        // and its inverse drives the `we` logic.
+        let (we_src, we_b_src) = if style.active_low() {
+            (inv_we_out, we_pin)
+        } else {
+            (we_pin, inv_we_out)
+        };
 
         // we -> mux_wlen_rst.s
         let we_in = group.port_map().port("mux_wlen_rst_s")?.largest_rect(m0)?;
@@ -1043,7 +1137,9 @@ impl ControlLogicReplicaV2 {
             Ok(())
         };
         router.route_with_net(ctx, m1, clk_pin, m1, clk_in, "clk")?;
-        router.route_with_net(ctx, m1, ce_pin, m1, ce_in, "ce")?;
+        for (src, dst, net) in enable_routes {
+            router.route_with_net(ctx, m1, src, m1, dst, net)?;
+        }
         router.route_with_net(ctx, m1, pc_b0_out, m1, pc_b_in_buf, "pc_b0")?;
         router.route_with_net(ctx, m1, pc_b_out, m1, pc_b_pin, "pc_b")?;
         router.route_with_net(ctx, m1, resetb_pin, m1, resetb_in, "rstb")?;
@@ -1055,7 +1151,7 @@ impl ControlLogicReplicaV2 {
             router.route_with_net(ctx, m1, reset_out, m1, reset_in, "reset")?;
         }
         for we_b_in in we_b_ins {
-            router.route_with_net(ctx, m1, we_b_out, m1, we_b_in, "we_b")?;
+            router.route_with_net(ctx, m1, we_b_src, m1, we_b_in, "we_b")?;
         }
         route_pins(
             ctx,
@@ -1069,9 +1165,9 @@ impl ControlLogicReplicaV2 {
                 ("saen_set_b", &saen_set_bs),
             ],
         )?;
-        router.route_with_net(ctx, m1, we_pin, m1, we_in, "we")?;
-        router.route_with_net(ctx, m1, we_pin, m1, we_in_1, "we")?;
-        router.route_with_net(ctx, m1, we_pin, m1, we_in_inv, "we")?;
+        router.route_with_net(ctx, m1, we_src, m1, we_in, "we")?;
+        router.route_with_net(ctx, m1, we_src, m1, we_in_1, "we")?;
+        router.route_with_net(ctx, m1, we_pin, m1, we_in_inv, we_pin_net)?;
         router.route_with_net(ctx, m1, rbl_b_out, m1, rbl_b_in, "rbl_b")?;
         router.route_with_net(ctx, m1, rbl_b_out, m1, rbl_b_in2, "rbl_b")?;
         router.route_with_net(ctx, m1, rwl_out, m2, rwl_pin, "rwl")?;
@@ -1099,12 +1195,15 @@ impl ControlLogicReplicaV2 {
             wlen_rst_decoderd_in,
             "wlen_rst_decoderd",
         )?;
+        for (src, dst, net) in trim_routes {
+            router.route_with_net(ctx, m1, src, m1, dst, net)?;
+        }
 
         ctx.draw(router)?;
 
         ctx.add_port(CellPort::with_shape("clk", m1, clk_pin))?;
-        ctx.add_port(CellPort::with_shape("ce", m1, ce_pin))?;
-        ctx.add_port(CellPort::with_shape("we", m1, we_pin))?;
+        ctx.add_port(CellPort::with_shape(en_net, m1, ce_pin))?;
+        ctx.add_port(CellPort::with_shape(style.write_pin(), m1, we_pin))?;
         ctx.add_port(CellPort::with_shape("rstb", m1, resetb_pin))?;
 
         ctx.add_port(CellPort::with_shape("pc_b", m1, pc_b_pin))?;
@@ -1114,10 +1213,383 @@ impl ControlLogicReplicaV2 {
         ctx.add_port(CellPort::with_shape("wlen", m1, wlen_pin))?;
         ctx.add_port(CellPort::with_shape("rwl", m2, rwl_pin))?;
 
+        for (name, trim) in ["trim_wl", "trim_saen"].into_iter().zip(trims) {
+            for i in 0..trim_bits {
+                let pin = trim.port(PortId::new("code", i))?.largest_rect(m1)?;
+                ctx.add_port(CellPort::with_shape(PortId::new(name, i), m1, pin))?;
+            }
+        }
+
         Ok(())
     }
 }
 
+/// The number of taps decoded in each row of a [`TrimDelayLine`].
+const TRIM_TAPS_PER_ROW: usize = 4;
+
+impl TrimDelayLine {
+    pub(crate) fn layout(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+    ) -> substrate::error::Result<()> {
+        let bits = self.params.bits;
+        let taps = self.params.taps();
+        let layers = ctx.layers();
+        let outline = layers.get(Selector::Name("outline"))?;
+        let m0 = layers.get(Selector::Metal(0))?;
+        let m1 = layers.get(Selector::Metal(1))?;
+        let m2 = layers.get(Selector::Metal(2))?;
+        let grid = ctx.pdk().layout_grid();
+
+        let stdcells = ctx.inner().std_cell_db();
+        let lib = stdcells.try_lib_named("sky130_fd_sc_hs")?;
+        let inv = lib.try_cell_named("sky130_fd_sc_hs__inv_2")?;
+        let inv = ctx.instantiate::<StdCell>(&inv.id())?;
+        let nand = lib.try_cell_named(&format!("sky130_fd_sc_hs__nand{bits}_1"))?;
+        let nand = ctx.instantiate::<StdCell>(&nand.id())?;
+        let tap = lib.try_cell_named("sky130_fd_sc_hs__tap_2")?;
+        let tap = ctx.instantiate::<StdCell>(&tap.id())?;
+
+        // The code inverters are followed by rows of NAND gates and enable inverters.
+        let mut rows = ArrayTiler::builder();
+        rows.mode(AlignMode::Left).alt_mode(AlignMode::Beneath);
+        let names = (0..bits).map(|i| format!("inv_{i}")).collect::<Vec<_>>();
+        let cells = names
+            .iter()
+            .map(|name| (name.as_str(), &inv))
+            .collect::<Vec<_>>();
+        rows.push(LayerBbox::new(
+            std_cell_row(&tap, outline, &cells)?,
+            outline,
+        ));
+        for (i, start) in (0..taps).step_by(TRIM_TAPS_PER_ROW).enumerate() {
+            let names = (start..taps.min(start + TRIM_TAPS_PER_ROW))
+                .flat_map(|t| [format!("nand_{t}"), format!("ctl_inv_{t}")])
+                .collect::<Vec<_>>();
+            let cells = names
+                .iter()
+                .enumerate()
+                .map(|(j, name)| (name.as_str(), if j % 2 == 0 { &nand } else { &inv }))
+                .collect::<Vec<_>>();
+            let mut row = std_cell_row(&tap, outline, &cells)?;
+            if i % 2 == 0 {
+                row.set_orientation(Named::ReflectVert);
+            }
+            rows.push(LayerBbox::new(row, outline));
+        }
+        let mut rows = rows.build();
+        rows.expose_ports(
+            |port: CellPort, _| {
+                if let "vdd" | "vss" = port.name().as_str() {
+                    None
+                } else {
+                    Some(port)
+                }
+            },
+            PortConflictStrategy::Error,
+        )?;
+        rows.expose_ports(
+            |port: CellPort, _| {
+                if let "vdd" | "vss" = port.name().as_str() {
+                    Some(port)
+                } else {
+                    None
+                }
+            },
+            PortConflictStrategy::Merge,
+        )?;
+        let group = rows.generate()?;
+
+        // The delay line ports extend past its top, leaving a routing channel
+        // between the ends of the ports and the standard cells.
+        let mut delay_line = ctx.instantiate::<TristateInvDelayLine>(
+            &TristateInvDelayLineParams::new(taps, INV_PARAMS, INV_PARAMS),
+        )?;
+        delay_line.align_left(group.bbox());
+        let port_top = delay_line.port("clk_out")?.largest_rect(m1)?.top();
+        delay_line.translate(Point::new(0, group.brect().bottom() - 4_000 - port_top));
+        let port_top = delay_line.port("clk_out")?.largest_rect(m1)?.top();
+
+        let body = group.bbox().union(delay_line.bbox()).into_rect();
+        let mut router = GreedyRouter::with_config(GreedyRouterConfig {
+            area: body.expand(8 * 680),
+            layers: vec![
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Horiz,
+                    layer: m1,
+                },
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Vert,
+                    layer: m2,
+                },
+            ],
+        });
+
+        for layer in [m1, m2] {
+            for shape in group.shapes_on(layer) {
+                router.block(layer, shape.brect().expand(40));
+            }
+            for shape in delay_line.shapes_on(layer) {
+                router.block(layer, shape.brect().expand(40));
+            }
+        }
+
+        // Draws a delay line port from its track up to the routing channel.
+        let delay_line_pin = |ctx: &mut LayoutCtx,
+                              router: &mut GreedyRouter,
+                              port: Rect,
+                              net: &str|
+         -> substrate::error::Result<Rect> {
+            let rect = port.with_vspan(Span::new(port.center().y, port_top));
+            ctx.draw_rect(m1, rect);
+            router.block(m1, rect.expand(40));
+            let pin = router.expand_to_grid(
+                Rect::from_spans(rect.hspan(), Span::with_stop_and_length(port_top, 320)),
+                ExpandToGridStrategy::Minimum,
+            );
+            ctx.draw_rect(m1, pin);
+            router.occupy(m1, pin, net)?;
+            Ok(pin)
+        };
+
+        let via01 = ctx.instantiate::<Via>(
+            &ViaParams::builder()
+                .layers(m0, m1)
+                .geometry(
+                    Rect::from_point(Point::zero()),
+                    Rect::from_point(Point::zero()),
+                )
+                .bot_extension(Dir::Vert)
+                .top_extension(Dir::Vert)
+                .build(),
+        )?;
+        let snap_pin = |ctx: &mut LayoutCtx,
+                        router: &mut GreedyRouter,
+                        pin: &str,
+                        strategy: ExpandToGridStrategy,
+                        net: &str|
+         -> substrate::error::Result<Rect> {
+            let port = group.port_map().port(pin)?.largest_rect(m0)?;
+            let mut via = via01.clone();
+            via.align_centers_gridded(port.bbox(), grid);
+            let rect = router.expand_to_grid(via.layer_bbox(m1).into_rect(), strategy);
+            ctx.draw(via)?;
+            ctx.draw_rect(m1, rect);
+            router.occupy(m1, rect, net)?;
+            Ok(rect)
+        };
+
+        // Pins
+        let htracks = router.track_info(m1).tracks().clone();
+        let htrack_start = htracks.track_with_loc(TrackLocator::EndsBefore, body.top());
+        let vtracks = router.track_info(m2).tracks().clone();
+        let vtrack =
+            vtracks.index(vtracks.track_with_loc(TrackLocator::EndsBefore, body.left() - 3_200));
+        // Pins are spaced three tracks apart, since the routing grid of a parent cell may differ.
+        let mut pins = (0..bits + 2)
+            .map(|i| Rect::from_spans(vtrack, htracks.index(htrack_start - 3 * (i as i64) - 2)))
+            .collect::<Vec<_>>();
+        for &pin in &pins {
+            ctx.draw_rect(m1, pin);
+        }
+        let code_pins = pins.split_off(2);
+        let (din_pin, dout_pin) = (pins[0], pins[1]);
+        router.block(
+            m2,
+            Rect::from_spans(vtrack.expand(false, 2000).expand(true, 140), body.vspan()),
+        );
+        router.occupy(m1, din_pin, "din")?;
+        router.occupy(m1, dout_pin, "dout")?;
+
+        let mut routes = Vec::new();
+
+        // din -> delay_line.clk_in, delay_line.clk_out -> dout
+        let clk_in = delay_line.port("clk_in")?.largest_rect(m1)?;
+        routes.push((
+            din_pin,
+            delay_line_pin(ctx, &mut router, clk_in, "din")?,
+            "din".to_string(),
+        ));
+        let clk_out = delay_line.port("clk_out")?.largest_rect(m1)?;
+        routes.push((
+            delay_line_pin(ctx, &mut router, clk_out, "dout")?,
+            dout_pin,
+            "dout".to_string(),
+        ));
+
+        // code -> inv.a
+        let mut code_b_pins = Vec::new();
+        for (i, &pin) in code_pins.iter().enumerate() {
+            let net = format!("code_{i}");
+            router.occupy(m1, pin, &net)?;
+            let code_in = snap_pin(
+                ctx,
+                &mut router,
+                &format!("inv_{i}_a"),
+                ExpandToGridStrategy::Corner(Corner::UpperLeft),
+                &net,
+            )?;
+            routes.push((pin, code_in, net));
+            code_b_pins.push(snap_pin(
+                ctx,
+                &mut router,
+                &format!("inv_{i}_y"),
+                ExpandToGridStrategy::Corner(Corner::UpperRight),
+                &format!("code_b_{i}"),
+            )?);
+        }
+
+        for t in 0..taps {
+            // code, code_b -> nand.{a, b, c, d}
+            for (i, input) in TRIM_NAND_INPUTS.iter().take(bits).enumerate() {
+                let (src, net) = if self.params.selects_code(t, i) {
+                    (code_pins[i], format!("code_{i}"))
+                } else {
+                    (code_b_pins[i], format!("code_b_{i}"))
+                };
+                let dst = snap_pin(
+                    ctx,
+                    &mut router,
+                    &format!("nand_{t}_{input}"),
+                    ExpandToGridStrategy::Corner(if i % 2 == 0 {
+                        Corner::UpperRight
+                    } else {
+                        Corner::LowerLeft
+                    }),
+                    &net,
+                )?;
+                routes.push((src, dst, net));
+            }
+
+            // nand.y -> ctl_inv.a
+            let src = group
+                .port_map()
+                .port(format!("nand_{t}_y"))?
+                .largest_rect(m0)?;
+            let dst = group
+                .port_map()
+                .port(format!("ctl_inv_{t}_a"))?
+                .largest_rect(m0)?;
+            ctx.draw_rect(
+                m0,
+                Rect::from_spans(
+                    Span::new(src.right(), dst.left()),
+                    Span::from_center_span_gridded(dst.vspan().center(), 180, 10),
+                ),
+            );
+
+            // nand.y -> delay_line.ctl_b, ctl_inv.y -> delay_line.ctl
+            for (pin, port, net) in [
+                (format!("nand_{t}_y"), "ctl_b", format!("ctl_b_{t}")),
+                (format!("ctl_inv_{t}_y"), "ctl", format!("ctl_{t}")),
+            ] {
+                let src = snap_pin(
+                    ctx,
+                    &mut router,
+                    &pin,
+                    ExpandToGridStrategy::Corner(Corner::LowerRight),
+                    &net,
+                )?;
+                let port = delay_line.port(PortId::new(port, t))?.largest_rect(m1)?;
+                let dst = delay_line_pin(ctx, &mut router, port, &net)?;
+                routes.push((src, dst, net));
+            }
+        }
+
+        // Connect the delay line supplies to the standard cell rails.
+        for net in ["vdd", "vss"] {
+            let rail = delay_line
+                .port(net)?
+                .shapes(m1)
+                .filter_map(|shape| shape.as_rect())
+                .min_by_key(|rect| rect.left())
+                .unwrap();
+            let src = delay_line_pin(ctx, &mut router, rail, net)?;
+            let rail = group.port_map().port(net)?.first_rect(m1, Side::Bot)?;
+            let mut dst = Rect::from_spans(Span::until(200), rail.vspan());
+            dst.align_left(rail);
+            let dst = router.expand_to_grid(dst, ExpandToGridStrategy::Minimum);
+            ctx.draw_rect(m1, dst);
+            router.occupy(m1, dst, net)?;
+            routes.push((src, dst, net.to_string()));
+        }
+
+        for (src, dst, net) in routes {
+            router.route_with_net(ctx, m1, src, m1, dst, &net)?;
+        }
+        ctx.draw(router)?;
+
+        ctx.add_port(CellPort::with_shape("din", m1, din_pin))?;
+        ctx.add_port(CellPort::with_shape("dout", m1, dout_pin))?;
+        for (i, pin) in code_pins.into_iter().enumerate() {
+            ctx.add_port(CellPort::with_shape(PortId::new("code", i), m1, pin))?;
+        }
+        ctx.add_ports(
+            group
+                .ports()
+                .filter(|port| matches!(port.name().as_str(), "vdd" | "vss")),
+        )?;
+        for net in ["vdd", "vss"] {
+            ctx.merge_port(delay_line.port(net)?.into_cell_port());
+        }
+
+        ctx.draw(group)?;
+        ctx.draw(delay_line)?;
+        Ok(())
+    }
+}
+
+/// Tiles `insts` into a row separated by taps.
+///
+/// Ports are prefixed with the name of their instance,
+/// except for the supply rails, which are merged into `vdd` and `vss`.
+pub(crate) fn std_cell_row(
+    tap: &Instance,
+    outline: LayerKey,
+    insts: &[(&str, &Instance)],
+) -> substrate::error::Result<Group> {
+    let tap = LayerBbox::new(tap.clone(), outline);
+    let mut row = new_row();
+    row.push(tap.clone());
+    for (_, inst) in insts {
+        row.push(LayerBbox::new((*inst).clone(), outline));
+        row.push(tap.clone());
+    }
+    let mut row = row.build();
+
+    let names: Vec<String> = insts.iter().map(|(name, _)| name.to_string()).collect();
+    row.expose_ports(
+        |port: CellPort, i| {
+            let name = if i % 2 == 1 {
+                &names[i / 2]
+            } else {
+                return None;
+            };
+            if let "vpwr" | "vgnd" | "vdd" | "vss" = port.name().as_str() {
+                return None;
+            }
+            let port_name = format!("{}_{}", name, port.name());
+            Some(port.named(port_name))
+        },
+        PortConflictStrategy::Error,
+    )?;
+    row.expose_ports(
+        |port: CellPort, _| match port.name().as_str() {
+            "vpwr" => Some(port.named("vdd")),
+            "vgnd" => Some(port.named("vss")),
+            "vdd" | "vss" => Some(port),
+            _ => None,
+        },
+        PortConflictStrategy::Merge,
+    )?;
+
+    row.generate()
+}
+
 fn new_row<'a>() -> ArrayTilerBuilder<'a> {
     let mut row = ArrayTiler::builder();
     row.mode(AlignMode::ToTheRight).alt_mode(AlignMode::Top);
@@ -2,6 +2,8 @@ use arcstr::ArcStr;
 use serde::{Deserialize, Serialize};
 use substrate::component::{Component, NoParams};
 
+use crate::blocks::sram::PortStyle;
+
 pub mod layout;
 pub mod schematic;
 pub mod testbench;
@@ -18,6 +20,80 @@ pub struct ControlLogicParams {
     pub pc_set_delay_invs: usize,
     pub wrdrven_set_delay_invs: usize,
     pub wrdrven_rst_delay_invs: usize,
+    /// The enable and write enable inputs.
+    ///
+    /// Styles other than [`PortStyle::CeWe`] derive the chip enable
+    /// from their inputs with an additional gate.
+    #[serde(default)]
+    pub port_style: PortStyle,
+    /// Adds `trim_saen` and `trim_wl` inputs that adjust the sense amplifier enable
+    /// and write wordline pulse delays after fabrication.
+    #[serde(default)]
+    pub trim: Option<TimingTrimParams>,
+}
+
+/// Delay lines inserted into the sense amplifier enable and wordline pulse delay chains,
+/// each with a tap selected by a trim input.
+///
+/// Trim codes are two's complement offsets from the middle tap, so a code of zero
+/// gives approximately the delays of an untrimmed control logic.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct TimingTrimParams {
+    /// The width of each trim input.
+    pub bits: usize,
+}
+
+impl TimingTrimParams {
+    /// The number of taps of each delay line.
+    #[inline]
+    pub fn taps(&self) -> usize {
+        1 << self.bits
+    }
+
+    /// The number of inversions through a delay line set to its middle tap.
+    ///
+    /// Each tap adds an inverter and a tristate inverter to the two at the first tap.
+    #[inline]
+    pub fn nominal_invs(&self) -> usize {
+        self.taps() + 2
+    }
+
+    /// Whether tap `tap` is enabled by bit `bit` of the trim code being high.
+    ///
+    /// Inverting the sign bit maps a code of zero to the middle tap.
+    pub(crate) fn selects_code(&self, tap: usize, bit: usize) -> bool {
+        ((tap >> bit) & 1 == 1) != (bit == self.bits - 1)
+    }
+}
+
+impl ControlLogicParams {
+    /// The number of inverters in the wordline pulse delay chain.
+    ///
+    /// With trim inputs, part of the chain is replaced by a [`TrimDelayLine`]
+    /// whose middle tap has the same number of inversions.
+    pub fn wlen_pulse_chain_invs(&self) -> usize {
+        match self.trim {
+            Some(trim) => self
+                .wlen_pulse_invs
+                .saturating_sub(trim.nominal_invs())
+                .max(1),
+            None => self.wlen_pulse_invs,
+        }
+    }
+
+    /// The number of inverters in the decoder replica delay chain.
+    ///
+    /// With trim inputs, part of the chain is replaced by a [`TrimDelayLine`]
+    /// whose middle tap has the same number of inversions.
+    pub fn decoder_replica_chain_invs(&self) -> usize {
+        match self.trim {
+            Some(trim) => self
+                .decoder_delay_invs
+                .saturating_sub(trim.nominal_invs())
+                .max(2),
+            None => self.decoder_delay_invs,
+        }
+    }
 }
 
 impl Component for ControlLogicReplicaV2 {
@@ -51,6 +127,12 @@ impl Component for ControlLogicReplicaV2 {
             0,
             "write drive enable rst delay chain must have an even number of inverters"
         );
+        if let Some(trim) = params.trim {
+            assert!(
+                (2..=4).contains(&trim.bits),
+                "trim inputs must have between 2 and 4 bits"
+            );
+        }
         Ok(Self { params: *params })
     }
     fn name(&self) -> arcstr::ArcStr {
@@ -70,6 +152,46 @@ impl Component for ControlLogicReplicaV2 {
     }
 }
 
+/// The inputs of the trim decoder NAND gates, one per trim code bit.
+pub(crate) const TRIM_NAND_INPUTS: [&str; 4] = ["a", "b", "c", "d"];
+
+/// A [`TristateInvDelayLine`](crate::blocks::delay_line::TristateInvDelayLine)
+/// with the tap selected by a two's complement trim code.
+///
+/// The code is decoded into the delay line enables by standard cells.
+pub struct TrimDelayLine {
+    params: TimingTrimParams,
+}
+
+impl Component for TrimDelayLine {
+    type Params = TimingTrimParams;
+    fn new(
+        params: &Self::Params,
+        _ctx: &substrate::data::SubstrateCtx,
+    ) -> substrate::error::Result<Self> {
+        assert!(
+            (2..=4).contains(&params.bits),
+            "trim inputs must have between 2 and 4 bits"
+        );
+        Ok(Self { params: *params })
+    }
+    fn name(&self) -> arcstr::ArcStr {
+        arcstr::format!("trim_delay_line_{}", self.params.bits)
+    }
+    fn schematic(
+        &self,
+        ctx: &mut substrate::schematic::context::SchematicCtx,
+    ) -> substrate::error::Result<()> {
+        self.schematic(ctx)
+    }
+    fn layout(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+    ) -> substrate::error::Result<()> {
+        self.layout(ctx)
+    }
+}
+
 pub struct SrLatch;
 
 impl Component for SrLatch {
@@ -196,7 +318,10 @@ pub mod test {
     use crate::setup_ctx;
     use crate::tests::test_work_dir;
 
-    use super::{ControlLogicParams, ControlLogicReplicaV2, EdgeDetector, SrLatch};
+    use super::{
+        ControlLogicParams, ControlLogicReplicaV2, EdgeDetector, PortStyle, SrLatch,
+        TimingTrimParams, TrimDelayLine,
+    };
 
     const CONTROL_LOGIC_PARAMS: ControlLogicParams = ControlLogicParams {
         decoder_delay_invs: 12,
@@ -204,6 +329,8 @@ pub mod test {
         pc_set_delay_invs: 14,
         wrdrven_set_delay_invs: 4,
         wrdrven_rst_delay_invs: 0,
+        port_style: PortStyle::CeWe,
+        trim: None,
     };
 
     #[test]
@@ -244,6 +371,70 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn test_control_logic_replica_v2_port_styles() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_control_logic_replica_v2_port_styles");
+
+        for (name, port_style) in [("renwen", PortStyle::RenWen), ("csbweb", PortStyle::CsbWeb)] {
+            let params = ControlLogicParams {
+                port_style,
+                ..CONTROL_LOGIC_PARAMS
+            };
+            ctx.write_schematic_to_file::<ControlLogicReplicaV2>(
+                &params,
+                out_spice(&work_dir, &format!("netlist_{name}")),
+            )
+            .expect("failed to write schematic");
+
+            ctx.write_layout::<ControlLogicReplicaV2>(
+                &params,
+                out_gds(&work_dir, &format!("layout_{name}")),
+            )
+            .expect("failed to write layout");
+        }
+    }
+
+    #[test]
+    fn test_control_logic_replica_v2_trim() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_control_logic_replica_v2_trim");
+
+        let params = ControlLogicParams {
+            trim: Some(TimingTrimParams { bits: 2 }),
+            ..CONTROL_LOGIC_PARAMS
+        };
+        ctx.write_schematic_to_file::<ControlLogicReplicaV2>(
+            &params,
+            out_spice(&work_dir, "netlist"),
+        )
+        .expect("failed to write schematic");
+
+        ctx.write_layout::<ControlLogicReplicaV2>(&params, out_gds(&work_dir, "layout"))
+            .expect("failed to write layout");
+    }
+
+    #[test]
+    fn test_trim_delay_line() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_trim_delay_line");
+
+        for bits in 2..=4 {
+            let params = TimingTrimParams { bits };
+            ctx.write_schematic_to_file::<TrimDelayLine>(
+                &params,
+                out_spice(&work_dir, &format!("netlist_{bits}")),
+            )
+            .expect("failed to write schematic");
+
+            ctx.write_layout::<TrimDelayLine>(
+                &params,
+                out_gds(&work_dir, &format!("layout_{bits}")),
+            )
+            .expect("failed to write layout");
+        }
+    }
+
     #[cfg(feature = "commercial")]
     #[test]
     fn test_control_logic_replica_v2_tb() {
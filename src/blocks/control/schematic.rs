@@ -4,20 +4,36 @@ use substrate::pdk::stdcell::StdCell;
 use substrate::schematic::circuit::Direction;
 use substrate::schematic::context::SchematicCtx;
 
+use crate::blocks::decoder::INV_PARAMS;
+use crate::blocks::delay_line::{TristateInvDelayLine, TristateInvDelayLineParams};
 use crate::blocks::macros::{SvtInv2, SvtInv4};
+use crate::blocks::sram::PortStyle;
 
-use super::{ControlLogicReplicaV2, EdgeDetector, InvChain, SrLatch, SvtInvChain};
+use super::{
+    ControlLogicReplicaV2, EdgeDetector, InvChain, SrLatch, SvtInvChain, TrimDelayLine,
+    TRIM_NAND_INPUTS,
+};
 
 impl ControlLogicReplicaV2 {
     pub(crate) fn schematic(&self, ctx: &mut SchematicCtx) -> substrate::error::Result<()> {
         // PORTS
-        let [clk, ce, we, rstb, rbl] =
-            ctx.ports(["clk", "ce", "we", "rstb", "rbl"], Direction::Input);
+        let style = self.params.port_style;
+        let [clk, en, wr, rstb, rbl] = ctx.ports(
+            ["clk", style.enable_pin(), style.write_pin(), "rstb", "rbl"],
+            Direction::Input,
+        );
         let [saen, pc_b, rwl, wlen, wrdrven] = ctx.ports(
             ["saen", "pc_b", "rwl", "wlen", "wrdrven"],
             Direction::Output,
         );
         let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+        let trim = self.params.trim.map(|trim| {
+            (
+                trim,
+                ctx.bus_port("trim_saen", trim.bits, Direction::Input),
+                ctx.bus_port("trim_wl", trim.bits, Direction::Input),
+            )
+        });
 
         // SIGNALS
         let [clkd, clk_buf, clkp0, clkp, clkp_b, clkpd, clkpd_b, clkpdd, clkp_grst_b] = ctx
@@ -47,8 +63,19 @@ impl ControlLogicReplicaV2 {
             "wrdrven_grst_b",
             "wrdrven_b",
         ]);
-        let [reset, we_b, pc, pc_set_b, pc_b0, rbl_b] =
-            ctx.signals(["reset", "we_b", "pc", "pc_set_b", "pc_b0", "rbl_b"]);
+        let [reset, pc, pc_set_b, pc_b0, rbl_b] =
+            ctx.signals(["reset", "pc", "pc_set_b", "pc_b0", "rbl_b"]);
+        let ce = match style {
+            PortStyle::CeWe => en,
+            PortStyle::RenWen | PortStyle::CsbWeb => ctx.signal("ce"),
+        };
+        // An active low write enable drives the logic that expects `we_b` directly,
+        // and is inverted to drive the logic that expects `we`.
+        let (we, we_b) = if style.active_low() {
+            (ctx.signal("we"), wr)
+        } else {
+            (wr, ctx.signal("we_b"))
+        };
 
         // STANDARD CELLS
         let stdcells = ctx.inner().std_cell_db();
@@ -61,6 +88,7 @@ impl ControlLogicReplicaV2 {
         let mux2 = lib.try_cell_named("sky130_fd_sc_hs__mux2_4")?;
         let buf = lib.try_cell_named("sky130_fd_sc_hs__buf_16")?;
         let biginv = lib.try_cell_named("sky130_fd_sc_hs__inv_16")?;
+        let or2 = lib.try_cell_named("sky130_fd_sc_hs__or2_2")?;
 
         ctx.instantiate::<StdCell>(&biginv.id())?
             .with_connections([
@@ -74,6 +102,40 @@ impl ControlLogicReplicaV2 {
             .named("reset_inv")
             .add_to(ctx);
 
+        // ENABLE LOGIC
+        //
+        // Any access enables the clock. A read and write in the same cycle is a write.
+        match style {
+            PortStyle::CeWe => {}
+            PortStyle::RenWen => {
+                ctx.instantiate::<StdCell>(&or2.id())?
+                    .with_connections([
+                        ("A", en),
+                        ("B", wr),
+                        ("X", ce),
+                        ("VPWR", vdd),
+                        ("VPB", vdd),
+                        ("VGND", vss),
+                        ("VNB", vss),
+                    ])
+                    .named("ce_gate")
+                    .add_to(ctx);
+            }
+            PortStyle::CsbWeb => {
+                ctx.instantiate::<StdCell>(&inv.id())?
+                    .with_connections([
+                        ("A", en),
+                        ("Y", ce),
+                        ("VPWR", vdd),
+                        ("VPB", vdd),
+                        ("VGND", vss),
+                        ("VNB", vss),
+                    ])
+                    .named("ce_gate")
+                    .add_to(ctx);
+            }
+        }
+
         // CLK LOGIC
         ctx.instantiate::<InvChain>(&12)?
             .with_connections([("din", clk), ("dout", clkd), ("vdd", vdd), ("vss", vss)])
@@ -137,15 +199,32 @@ impl ControlLogicReplicaV2 {
             ])
             .named("clkpd_inv")
             .add_to(ctx);
-        ctx.instantiate::<InvChain>(&self.params.wlen_pulse_invs)?
+        let clkpdd0 = if trim.is_some() {
+            ctx.signal("clkpdd0")
+        } else {
+            clkpdd
+        };
+        ctx.instantiate::<InvChain>(&self.params.wlen_pulse_chain_invs())?
             .with_connections([
                 ("din", clkpd_b),
-                ("dout", clkpdd),
+                ("dout", clkpdd0),
                 ("vdd", vdd),
                 ("vss", vss),
             ])
             .named("clkpd_delay")
             .add_to(ctx);
+        if let Some((trim, _, trim_wl)) = trim {
+            ctx.instantiate::<TrimDelayLine>(&trim)?
+                .with_connections([
+                    ("din", clkpdd0),
+                    ("code", trim_wl),
+                    ("dout", clkpdd),
+                    ("vdd", vdd),
+                    ("vss", vss),
+                ])
+                .named("trim_wl")
+                .add_to(ctx);
+        }
 
         // REPLICA LOGIC
         //
@@ -164,15 +243,32 @@ impl ControlLogicReplicaV2 {
             ])
             .named("mux_wlen_rst")
             .add_to(ctx);
-        ctx.instantiate::<SvtInvChain>(&self.params.decoder_delay_invs)?
+        let decrepend0 = if trim.is_some() {
+            ctx.signal("decrepend0")
+        } else {
+            decrepend
+        };
+        ctx.instantiate::<SvtInvChain>(&self.params.decoder_replica_chain_invs())?
             .with_connections([
                 ("din", decrepstart),
-                ("dout", decrepend),
+                ("dout", decrepend0),
                 ("vdd", vdd),
                 ("vss", vss),
             ])
             .named("decoder_replica")
             .add_to(ctx);
+        if let Some((trim, trim_saen, _)) = trim {
+            ctx.instantiate::<TrimDelayLine>(&trim)?
+                .with_connections([
+                    ("din", decrepend0),
+                    ("code", trim_saen),
+                    ("dout", decrepend),
+                    ("vdd", vdd),
+                    ("vss", vss),
+                ])
+                .named("trim_saen")
+                .add_to(ctx);
+        }
         ctx.instantiate::<InvChain>(&self.params.pc_set_delay_invs)?
             .with_connections([
                 ("din", decrepend),
@@ -184,8 +280,8 @@ impl ControlLogicReplicaV2 {
             .add_to(ctx);
         ctx.instantiate::<StdCell>(&inv.id())?
             .with_connections([
-                ("A", we),
-                ("Y", we_b),
+                ("A", wr),
+                ("Y", if style.active_low() { we } else { we_b }),
                 ("VPWR", vdd),
                 ("VPB", vdd),
                 ("VGND", vss),
@@ -382,6 +478,90 @@ impl ControlLogicReplicaV2 {
     }
 }
 
+impl TrimDelayLine {
+    pub(crate) fn schematic(&self, ctx: &mut SchematicCtx) -> substrate::error::Result<()> {
+        let bits = self.params.bits;
+        let taps = self.params.taps();
+        let din = ctx.port("din", Direction::Input);
+        let code = ctx.bus_port("code", bits, Direction::Input);
+        let dout = ctx.port("dout", Direction::Output);
+        let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+
+        let code_b = ctx.bus("code_b", bits);
+        let [ctl, ctl_b] = ctx.buses(["ctl", "ctl_b"], taps);
+
+        let stdcells = ctx.inner().std_cell_db();
+        let lib = stdcells.try_lib_named("sky130_fd_sc_hs")?;
+        let inv = lib.try_cell_named("sky130_fd_sc_hs__inv_2")?;
+        let nand = lib.try_cell_named(&format!("sky130_fd_sc_hs__nand{bits}_1"))?;
+
+        for i in 0..bits {
+            ctx.instantiate::<StdCell>(&inv.id())?
+                .with_connections([
+                    ("A", code.index(i)),
+                    ("Y", code_b.index(i)),
+                    ("VPWR", vdd),
+                    ("VPB", vdd),
+                    ("VGND", vss),
+                    ("VNB", vss),
+                ])
+                .named(format!("inv_{i}"))
+                .add_to(ctx);
+        }
+
+        for t in 0..taps {
+            let mut decoder = ctx
+                .instantiate::<StdCell>(&nand.id())?
+                .with_connections([
+                    ("Y", ctl_b.index(t)),
+                    ("VPWR", vdd),
+                    ("VPB", vdd),
+                    ("VGND", vss),
+                    ("VNB", vss),
+                ])
+                .named(format!("nand_{t}"));
+            for (i, input) in TRIM_NAND_INPUTS.iter().take(bits).enumerate() {
+                decoder.connect(
+                    input.to_uppercase(),
+                    if self.params.selects_code(t, i) {
+                        code.index(i)
+                    } else {
+                        code_b.index(i)
+                    },
+                );
+            }
+            ctx.add_instance(decoder);
+            ctx.instantiate::<StdCell>(&inv.id())?
+                .with_connections([
+                    ("A", ctl_b.index(t)),
+                    ("Y", ctl.index(t)),
+                    ("VPWR", vdd),
+                    ("VPB", vdd),
+                    ("VGND", vss),
+                    ("VNB", vss),
+                ])
+                .named(format!("ctl_inv_{t}"))
+                .add_to(ctx);
+        }
+
+        ctx.instantiate::<TristateInvDelayLine>(&TristateInvDelayLineParams::new(
+            taps, INV_PARAMS, INV_PARAMS,
+        ))?
+        .with_connections([
+            ("clk_in", din),
+            ("clk_out", dout),
+            ("ctl", ctl),
+            ("ctl_b", ctl_b),
+            ("vdd", vdd),
+            ("vss", vss),
+        ])
+        .named("delay_line")
+        .add_to(ctx);
+
+        Ok(())
+    }
+}
+
 impl SrLatch {
     pub(crate) fn schematic(&self, ctx: &mut SchematicCtx) -> substrate::error::Result<()> {
         let [sb, rb] = ctx.ports(["sb", "rb"], Direction::Input);
@@ -19,6 +19,7 @@ use substrate::verification::simulation::testbench::Testbench;
 use substrate::verification::simulation::waveform::{TimeWaveform, Waveform};
 
 use super::{ControlLogicParams, ControlLogicReplicaV2, InvChain};
+use crate::blocks::sram::PortStyle;
 
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 #[builder(derive(Debug))]
@@ -201,6 +202,8 @@ impl Component for ControlLogicTestbench {
             pc_set_delay_invs: 8,
             wrdrven_set_delay_invs: 2,
             wrdrven_rst_delay_invs: 0,
+            port_style: PortStyle::CeWe,
+            trim: None,
         })?
         .with_connections([
             ("vdd", vdd),
@@ -9,9 +9,11 @@ use substrate::index::IndexOwned;
 
 use subgeom::bbox::BoundBox;
 use subgeom::orientation::Named;
+use subgeom::transform::Translate;
 use subgeom::{Corner, Dir, Point, Rect, Side, Sign, Span};
 use substrate::layout::cell::{CellPort, Element, Flatten, Port, PortConflictStrategy, PortId};
 use substrate::layout::context::LayoutCtx;
+use substrate::layout::elements::mos::LayoutMos;
 use substrate::layout::elements::via::{Via, ViaExpansion, ViaParams};
 use substrate::layout::group::elements::ElementGroup;
 use substrate::layout::DrawRef;
@@ -22,7 +24,7 @@ use substrate::layout::layers::{LayerBoundBox, LayerKey};
 use crate::blocks::decoder::{
     base_indices, Decoder, DecoderParams, DecoderPhysicalDesign, DecoderPhysicalDesignScript,
     DecoderStage, DecoderStageParams, DecoderStagePhysicalDesign, DecoderStagePhysicalDesignScript,
-    RoutingStyle,
+    RoutingStyle, SuppressionParams,
 };
 use crate::blocks::gate::{Gate, GateParams};
 use crate::blocks::sram::layout::draw_via;
@@ -31,6 +33,7 @@ use substrate::layout::placement::array::ArrayTiler;
 use substrate::layout::placement::place_bbox::PlaceBbox;
 use substrate::layout::routing::manual::jog::OffsetJog;
 use substrate::layout::routing::tracks::UniformTracks;
+use substrate::pdk::mos::{GateContactStrategy, LayoutMosParams, MosParams};
 
 struct Metadata {
     final_stage_width: i64,
@@ -70,6 +73,8 @@ impl Decoder {
             use_multi_finger_invs: self.params.use_multi_finger_invs,
             dont_connect_outputs: true,
             child_sizes,
+            suppression: self.params.suppression,
+            clamp: self.params.clamp,
         };
         let stage_dsn = ctx
             .inner()
@@ -86,6 +91,12 @@ impl Decoder {
         }
         ctx.add_port(inst.port("vdd")?).unwrap();
         ctx.add_port(inst.port("vss")?).unwrap();
+        if self.params.suppression.is_some() {
+            ctx.add_port(inst.port("suppress")?).unwrap();
+        }
+        if self.params.clamp.is_some() {
+            ctx.add_port(inst.port("clamp")?).unwrap();
+        }
 
         let mut x = 0;
         let mut next_addr = (0, 0);
@@ -100,6 +111,8 @@ impl Decoder {
                 ),
                 tree: super::DecoderTree { root: node.clone() },
                 use_multi_finger_invs: false,
+                suppression: None,
+                clamp: None,
             })?;
             child.place(Corner::UpperRight, Point::new(x, -340));
             x -= (child.brect().width() as usize)
@@ -561,6 +574,101 @@ impl DecoderStage {
             }
         }
 
+        // Place suppression devices and clamps above the final stage, each with their gates on
+        // a stripe named after the port that drives them and their sources on a `vss` stripe.
+        // The outputs are extended past all stripes so that they can still be routed from the
+        // top edge of the stage.
+        let pulldowns = [
+            (self.params.suppression, "suppress"),
+            (self.params.clamp, "clamp"),
+        ]
+        .into_iter()
+        .filter_map(|(params, gate_port)| params.map(|params| (params, gate_port)))
+        .collect::<Vec<_>>();
+        if !pulldowns.is_empty() {
+            let folding_factor = folding_factors[num_stages - 1];
+            let y_layer = if gate_params[num_stages - 1]
+                .gate_type()
+                .is_multi_finger_inv()
+            {
+                m1
+            } else {
+                dsn.li
+            };
+            let hspan = tiler.brect().hspan();
+            let mut ys = Vec::new();
+            for n in 0..self.params.num {
+                let y = tiler
+                    .port_map()
+                    .port(PortId::new(
+                        "y",
+                        n * folding_factor * num_stages + num_stages - 1,
+                    ))?
+                    .largest_rect(y_layer)?;
+                let y = if y_layer == m1 {
+                    y
+                } else {
+                    let via = ctx.instantiate::<DecoderVia>(&DecoderViaParams {
+                        rect: y.with_vspan(Span::with_stop_and_length(y.top(), 240)),
+                        via_metals: vec![dsn.li, m1],
+                    })?;
+                    ctx.draw_ref(&via)?;
+                    via.layer_bbox(m1).into_rect()
+                };
+                ys.push(y);
+            }
+
+            let mut stripe_bot = tiler.brect().top() + 600;
+            for (params, gate_port) in pulldowns {
+                let mut mos = ctx.instantiate::<SuppressionMos>(&params)?;
+                let lower =
+                    Rect::from_spans(hspan, Span::with_start_and_length(stripe_bot, dsn.line));
+                let mos_bot = lower.top() + 600;
+                let upper = Rect::from_spans(
+                    hspan,
+                    Span::with_start_and_length(mos_bot + mos.brect().height() + 600, dsn.line),
+                );
+                stripe_bot = upper.top() + 600;
+
+                for y in ys.iter() {
+                    let mos_y = mos.port("y")?.largest_rect(m1)?;
+                    mos.translate(Point::new(
+                        y.center().x - mos_y.center().x,
+                        mos_bot - mos.brect().bottom(),
+                    ));
+                    for (port, stripe) in [("vss", lower), ("gate", upper)] {
+                        let rect = mos.port(port)?.largest_rect(m1)?;
+                        let rect = rect.with_vspan(rect.vspan().union(stripe.vspan()));
+                        ctx.draw_rect(m1, rect);
+                        let via = ctx.instantiate::<DecoderVia>(&DecoderViaParams {
+                            rect: Rect::from_spans(rect.hspan(), stripe.vspan()),
+                            via_metals: vec![m1, m2],
+                        })?;
+                        ctx.draw(via)?;
+                    }
+                    ctx.draw_ref(&mos)?;
+                }
+
+                ctx.draw_rect(m2, lower);
+                ctx.merge_port(CellPort::with_shape("vss", m2, lower));
+                ctx.draw_rect(m2, upper);
+                ctx.add_port(CellPort::with_shape(gate_port, m2, upper))
+                    .unwrap();
+            }
+
+            let y_top = stripe_bot + 200;
+            for (n, y) in ys.into_iter().enumerate() {
+                let y = y.with_vspan(Span::new(y.bottom(), y_top));
+                ctx.draw_rect(m1, y);
+                ctx.merge_port(
+                    CellPort::builder()
+                        .id(PortId::new("y", n))
+                        .add(m1, y)
+                        .build(),
+                );
+            }
+        }
+
         let folding_factor = folding_factors[0];
         let tracks = UniformTracks::builder()
             .line(dsn.line)
@@ -1136,6 +1244,113 @@ impl Component for DecoderTap {
     }
 }
 
+/// The pull-down of a [`SuppressionParams`], with its fingers stacked vertically.
+///
+/// Exposes vertical m1 strips over the drains (`y`), sources (`vss`) and gates (`gate`).
+/// The `y` strip lies at the end of the drains away from the gate contacts,
+/// with the `vss` strip beyond it.
+pub struct SuppressionMos {
+    params: SuppressionParams,
+}
+
+impl Component for SuppressionMos {
+    type Params = SuppressionParams;
+    fn new(
+        params: &Self::Params,
+        _ctx: &substrate::data::SubstrateCtx,
+    ) -> substrate::error::Result<Self> {
+        Ok(Self { params: *params })
+    }
+
+    fn name(&self) -> arcstr::ArcStr {
+        arcstr::literal!("suppression_mos")
+    }
+
+    fn layout(&self, ctx: &mut LayoutCtx) -> Result<()> {
+        let layers = ctx.layers();
+        let m1 = layers.get(Selector::Metal(1))?;
+        let nmos = ctx.mos_db().default_nmos().unwrap().id();
+
+        let mos = ctx.instantiate::<LayoutMos>(&LayoutMosParams {
+            skip_sd_metal: vec![vec![]],
+            deep_nwell: true,
+            contact_strategy: GateContactStrategy::SingleSide,
+            devices: vec![MosParams {
+                w: self.params.width,
+                l: self.params.length,
+                m: 1,
+                nf: self.params.nf,
+                id: nmos,
+            }],
+        })?;
+        ctx.draw_ref(&mos)?;
+
+        let li = mos.port("sd_0_0")?.any_layer();
+        let gate = mos.port("gate")?.bbox(li).into_rect();
+        let drain = mos.port("sd_0_1")?.largest_rect(li)?;
+        let (y_hspan, vss_hspan) = if gate.center().x < drain.center().x {
+            let y_hspan = Span::with_stop_and_length(drain.right(), 240);
+            (
+                y_hspan,
+                Span::with_start_and_length(y_hspan.stop() + 200, 240),
+            )
+        } else {
+            let y_hspan = Span::with_start_and_length(drain.left(), 240);
+            (
+                y_hspan,
+                Span::with_stop_and_length(y_hspan.start() - 200, 240),
+            )
+        };
+
+        let mut y_vspan = drain.vspan();
+        let mut vss_vspan = drain.vspan();
+        for i in 0..=self.params.nf {
+            let sd = mos.port(format!("sd_0_{i}"))?.largest_rect(li)?;
+            let hspan = if i % 2 == 0 {
+                // Extend sources past the drain strip to reach the `vss` strip.
+                ctx.draw_rect(li, sd.with_hspan(sd.hspan().union(vss_hspan)));
+                vss_vspan = vss_vspan.union(sd.vspan());
+                vss_hspan
+            } else {
+                y_vspan = y_vspan.union(sd.vspan());
+                y_hspan
+            };
+            let via = ctx.instantiate::<DecoderVia>(&DecoderViaParams {
+                rect: Rect::from_spans(hspan, sd.vspan()),
+                via_metals: vec![li, m1],
+            })?;
+            ctx.draw(via)?;
+        }
+
+        let mut gate_rect: Option<Rect> = None;
+        for rect in mos
+            .port("gate")?
+            .shapes(li)
+            .filter_map(|shape| shape.as_rect())
+        {
+            let via = ctx.instantiate::<DecoderVia>(&DecoderViaParams {
+                rect,
+                via_metals: vec![li, m1],
+            })?;
+            let m1_rect = via.layer_bbox(m1).into_rect();
+            gate_rect = Some(gate_rect.map_or(m1_rect, |r| r.union(m1_rect.bbox()).into_rect()));
+            ctx.draw(via)?;
+        }
+        let gate_rect = gate_rect.unwrap();
+
+        for (name, rect) in [
+            ("y", Rect::from_spans(y_hspan, y_vspan)),
+            ("vss", Rect::from_spans(vss_hspan, vss_vspan)),
+            ("gate", gate_rect),
+        ] {
+            ctx.draw_rect(m1, rect);
+            ctx.add_port(CellPort::with_shape(name, m1, rect)).unwrap();
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct DecoderViaParams {
     pub rect: Rect,
@@ -31,6 +31,10 @@ pub struct DecoderParams {
     pub max_width: Option<i64>,
     pub tree: DecoderTree,
     pub use_multi_finger_invs: bool,
+    /// Pull-downs on the outputs of the final stage. See [`DecoderStageParams::suppression`].
+    pub suppression: Option<SuppressionParams>,
+    /// Clamps on the outputs of the final stage. See [`DecoderStageParams::clamp`].
+    pub clamp: Option<SuppressionParams>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -44,6 +48,27 @@ pub struct DecoderStageParams {
     pub use_multi_finger_invs: bool,
     pub dont_connect_outputs: bool,
     pub child_sizes: Vec<usize>,
+    /// Adds a `suppress` port and an NMOS from each output `y` to `vss` gated by it,
+    /// lowering the high level of the outputs while `suppress` is high.
+    pub suppression: Option<SuppressionParams>,
+    /// Adds a `clamp` port and an NMOS from each output `y` to `vss` gated by it,
+    /// holding the outputs low while `clamp` is high.
+    pub clamp: Option<SuppressionParams>,
+}
+
+/// The widest finger of a suppression device that fits within the pitch of a decoder output, in nm.
+pub const SUPPRESSION_FINGER_WIDTH: i64 = 560;
+
+/// An NMOS that fights the pull-up of a driver to lower its output high level.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct SuppressionParams {
+    /// The width of each finger.
+    pub width: i64,
+    pub length: i64,
+    /// The number of fingers.
+    ///
+    /// Narrow fingers keep the device within the pitch of a decoder output.
+    pub nf: u64,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -85,6 +110,28 @@ impl DecoderTree {
     }
 }
 
+impl DecoderTree {
+    /// Removes all outputs of the final decoder stage beyond the first `num`.
+    ///
+    /// Predecoder outputs that no longer drive any gate are left unconnected.
+    pub fn truncate(mut self, num: usize) -> Self {
+        self.root.truncate(num);
+        self
+    }
+}
+
+impl TreeNode {
+    fn truncate(&mut self, num: usize) {
+        assert!(num <= self.num);
+        self.num = num;
+        if let GateParams::Inv(_) | GateParams::FoldedInv(_) = self.gate {
+            if let [child] = &mut self.children[..] {
+                child.truncate(num);
+            }
+        }
+    }
+}
+
 fn size_decoder(tree: &PlanTreeNode, cwl: f64) -> TreeNode {
     path_map_tree(tree, &size_path, &cwl)
 }
@@ -190,6 +237,16 @@ pub(crate) fn primitive_gate_model(gate: PrimitiveGateType) -> GateModel {
     }
 }
 
+/// The model of an inverter with the given sizing, scaled from [`INV_MODEL`].
+pub(crate) fn inv_model(params: PrimitiveGateParams) -> GateModel {
+    let scale = params.nwidth as f64 / INV_PARAMS.nwidth as f64;
+    GateModel {
+        res: INV_MODEL.res / scale,
+        cin: INV_MODEL.cin * scale,
+        cout: INV_MODEL.cout * scale,
+    }
+}
+
 pub(crate) fn scale(gate: PrimitiveGateParams, scale: f64) -> PrimitiveGateParams {
     let nwidth = snap_to_grid((gate.nwidth as f64 * scale).round() as i64, 50);
     let pwidth = snap_to_grid((gate.pwidth as f64 * scale).round() as i64, 50);
@@ -466,6 +523,13 @@ impl PlanTreeNode {
 }
 
 impl DecoderStageParams {
+    /// The capacitance of each input of the first gate of the stage.
+    pub fn input_cap(&self) -> f64 {
+        let (gt, params) = self.gate.primitive_gates()[0];
+        let scale = params.nwidth as f64 / (primitive_gate_params(gt).nwidth as f64);
+        primitive_gate_model(gt).cin * scale
+    }
+
     pub fn time_constant(&self, cl: f64) -> f64 {
         let mut delay = 0.0;
         let mut gates = self.gate.primitive_gates();
@@ -783,12 +847,39 @@ mod tests {
             max_width: None,
             tree,
             use_multi_finger_invs: true,
+            suppression: None,
+            clamp: None,
         };
 
         ctx.write_schematic_to_file::<Decoder>(&params, out_spice(work_dir, "netlist"))
             .expect("failed to write schematic");
     }
 
+    #[test]
+    fn test_decode_truncated() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_decoder_truncated");
+
+        let tree = DecoderTree::new(6, 150e-15).truncate(48);
+        assert_eq!(tree.root.num, 48);
+        let params = DecoderParams {
+            pd: DecoderPhysicalDesignParams {
+                style: DecoderStyle::RowMatched,
+                dir: Dir::Horiz,
+            },
+            max_width: None,
+            tree,
+            use_multi_finger_invs: true,
+            suppression: None,
+            clamp: None,
+        };
+
+        ctx.write_schematic_to_file::<Decoder>(&params, out_spice(&work_dir, "netlist"))
+            .expect("failed to write schematic");
+        ctx.write_layout::<Decoder>(&params, out_gds(&work_dir, "layout"))
+            .expect("failed to write layout");
+    }
+
     #[test]
     fn test_decoder_stage() {
         let ctx = setup_ctx();
@@ -822,6 +913,8 @@ mod tests {
             use_multi_finger_invs: true,
             dont_connect_outputs: false,
             child_sizes: vec![2, 2],
+            suppression: None,
+            clamp: None,
         };
 
         ctx.write_schematic_to_file::<DecoderStage>(&params, out_spice(&work_dir, "netlist"))
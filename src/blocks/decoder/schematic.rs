@@ -1,8 +1,12 @@
 use itertools::Itertools;
 use subgeom::bbox::BoundBox;
 use substrate::index::IndexOwned;
+use substrate::pdk::mos::query::Query;
+use substrate::pdk::mos::spec::MosKind;
+use substrate::pdk::mos::MosParams;
 use substrate::schematic::circuit::Direction;
 use substrate::schematic::context::SchematicCtx;
+use substrate::schematic::elements::mos::SchematicMos;
 
 use substrate::schematic::signal::Slice;
 
@@ -43,13 +47,16 @@ impl Decoder {
             use_multi_finger_invs: self.params.use_multi_finger_invs,
             dont_connect_outputs: true,
             child_sizes,
+            suppression: self.params.suppression,
+            clamp: self.params.clamp,
         };
         let mut inst = ctx
             .instantiate::<DecoderStage>(&params)?
             .with_connections([("vdd", vdd), ("vss", vss)]);
         let layout_inst = ctx.inner().instantiate_layout::<DecoderStage>(&params)?;
         ctx.bubble_filter_map(&mut inst, |port| {
-            port.name().starts_with("y").then_some(port.name().into())
+            (port.name().starts_with("y") || matches!(port.name().as_str(), "suppress" | "clamp"))
+                .then_some(port.name().into())
         });
         if node.children.is_empty() {
             ctx.bubble_filter_map(&mut inst, |port| {
@@ -72,6 +79,8 @@ impl Decoder {
                     ),
                     tree: super::DecoderTree { root: node.clone() },
                     use_multi_finger_invs: false,
+                    suppression: None,
+                    clamp: None,
                 })?
                 .with_connections([("vdd", vdd), ("vss", vss)]);
 
@@ -159,6 +168,15 @@ impl DecoderStage {
                 inn: ctx.bus_port("in", self.params.num, Direction::Input),
             },
         };
+        let pulldowns = [
+            (self.params.suppression, "suppress"),
+            (self.params.clamp, "clamp"),
+        ]
+        .into_iter()
+        .filter_map(|(params, port)| {
+            params.map(|params| (params, ctx.port(port, Direction::Input), port))
+        })
+        .collect::<Vec<_>>();
         let x: Vec<_> = (0..num_stages - 1)
             .map(|i| ctx.bus(format!("x_{i}"), self.params.num))
             .collect();
@@ -215,6 +233,34 @@ impl DecoderStage {
                 }
             }
         }
+
+        if !pulldowns.is_empty() {
+            let nmos_id = ctx
+                .mos_db()
+                .query(Query::builder().kind(MosKind::Nmos).build().unwrap())?
+                .id();
+            for (params, gate, name) in pulldowns {
+                for i in 0..self.params.num {
+                    for j in 0..params.nf {
+                        let mut mn = ctx.instantiate::<SchematicMos>(&MosParams {
+                            w: params.width,
+                            l: params.length,
+                            m: 1,
+                            nf: 1,
+                            id: nmos_id,
+                        })?;
+                        mn.connect_all([
+                            ("d", &y.index(i)),
+                            ("g", &gate),
+                            ("s", &vss),
+                            ("b", &vss),
+                        ]);
+                        mn.set_name(format!("{name}_{i}_{j}"));
+                        ctx.add_instance(mn);
+                    }
+                }
+            }
+        }
         Ok(())
     }
 }
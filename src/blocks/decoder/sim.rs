@@ -65,6 +65,8 @@ impl Component for DecoderCriticalPathTb {
             max_width: None,
             tree,
             use_multi_finger_invs: true,
+            suppression: None,
+            clamp: None,
         };
         ctx.instantiate::<Decoder>(&decoder_params)?
             .with_connections([
@@ -0,0 +1,173 @@
+//! Estimated Liberty models of delay lines.
+//!
+//! Timing is estimated from the RC models of the delay line's gates,
+//! with the delay line set to its longest delay.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::decoder::inv_model;
+use crate::liberty::{
+    Bus, Cell, Direction, DriverModel, InternalPower, Library, Pin, TableTemplate, Timing,
+    TimingSense, TimingType, DEFAULT_LOADS, DEFAULT_SLEWS,
+};
+use crate::Result;
+
+use super::tb::DelayLineKind;
+use super::PassGateKind;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DelayLineLibParams {
+    /// Name of the library and of the delay line cell.
+    pub name: String,
+    pub inner: DelayLineKind,
+    pub vdd: f64,
+    /// Area of the delay line in square microns.
+    pub area: f64,
+}
+
+/// Estimated electrical parameters of a delay line.
+struct DelayLineEstimate {
+    /// The path from `clk_in` to `clk_out`.
+    driver: DriverModel,
+    clk_in_cap: f64,
+    /// Capacitance of each bit of `ctl` and `ctl_b`.
+    ctl_cap: f64,
+    /// Internal capacitance switched by each transition of `clk_in`.
+    switched_cap: f64,
+}
+
+impl DelayLineKind {
+    fn estimate(&self) -> DelayLineEstimate {
+        match *self {
+            DelayLineKind::Naive(params) => {
+                let stages = params.stages as f64;
+                let inv1 = inv_model(params.inv1);
+                let inv2 = inv_model(params.inv2);
+                // Tristate buffers have stacked output devices, doubling their resistance.
+                let (pass_res, pass_cap) = match params.pass {
+                    PassGateKind::TransmissionGate(gate) => {
+                        let model = inv_model(gate);
+                        (model.res, model.cin)
+                    }
+                    PassGateKind::TristateBuf(buf) => {
+                        (2. * inv_model(buf.inv2).res, inv_model(buf.inv1).cin)
+                    }
+                };
+                let stage = inv1.res * inv2.cin + inv2.res * (inv1.cin + pass_cap);
+                let cout = stages * pass_cap;
+                DelayLineEstimate {
+                    driver: DriverModel {
+                        intrinsic: stages * stage,
+                        res: inv2.res + pass_res,
+                        cout,
+                    },
+                    clk_in_cap: inv1.cin,
+                    ctl_cap: pass_cap / 2.,
+                    switched_cap: stages * (inv1.cin + inv2.cin + pass_cap) + cout,
+                }
+            }
+            DelayLineKind::TristateInv(params) => {
+                let stages = params.stages as f64;
+                let inv = inv_model(params.inv);
+                let tristate = inv_model(params.tristate_inv);
+                let tristate_res = 2. * tristate.res;
+                // Each node of the return path is loaded by the input of the next tristate
+                // inverter and the outputs of the two tristate inverters driving it.
+                let return_cap = 3. * tristate.cin;
+                let forward = stages * inv.res * (inv.cin + tristate.cin);
+                let back = (stages - 1.) * tristate_res * return_cap;
+                DelayLineEstimate {
+                    driver: DriverModel {
+                        intrinsic: forward + back,
+                        res: tristate_res,
+                        cout: 2. * tristate.cin,
+                    },
+                    clk_in_cap: inv.cin,
+                    ctl_cap: tristate.cin,
+                    switched_cap: stages * (inv.cin + tristate.cin) + (stages - 1.) * return_cap,
+                }
+            }
+        }
+    }
+}
+
+/// An estimated Liberty library for a delay line.
+///
+/// The `clk_in` to `clk_out` arc is modeled at the longest delay setting.
+pub fn delay_line_library(params: &DelayLineLibParams) -> Library {
+    let estimate = params.inner.estimate();
+    let delay_template = TableTemplate::delay(&DEFAULT_SLEWS, &DEFAULT_LOADS);
+    let power_template = TableTemplate::power(&DEFAULT_SLEWS);
+    let max_transition = DEFAULT_SLEWS.iter().copied().reduce(f64::max);
+
+    let mut clk_in = Pin::new("clk_in", Direction::Input);
+    clk_in.capacitance = Some(estimate.clk_in_cap);
+    clk_in.max_transition = max_transition;
+
+    let mut clk_out = Pin::new("clk_out", Direction::Output);
+    clk_out.timing.push(Timing {
+        related_pin: "clk_in".to_string(),
+        timing_type: TimingType::Combinational,
+        timing_sense: Some(TimingSense::PositiveUnate),
+        tables: estimate.driver.delay_tables(&delay_template),
+    });
+    clk_out.internal_power.push(InternalPower::switching(
+        Some("clk_in".to_string()),
+        &power_template,
+        estimate.switched_cap,
+        params.vdd,
+    ));
+
+    let ctl = |name: &str| {
+        let mut pin = Pin::new(name, Direction::Input);
+        pin.capacitance = Some(estimate.ctl_cap);
+        pin.max_transition = max_transition;
+        Bus {
+            width: params.inner.stages(),
+            pin,
+            bit_capacitance: Vec::new(),
+        }
+    };
+
+    Library {
+        name: params.name.clone(),
+        voltage: params.vdd,
+        temperature: 25.,
+        templates: vec![delay_template],
+        power_templates: vec![power_template],
+        cells: vec![Cell {
+            name: params.name.clone(),
+            area: params.area,
+            leakage_power: None,
+            pins: vec![clk_in, clk_out],
+            buses: vec![ctl("ctl"), ctl("ctl_b")],
+        }],
+    }
+}
+
+pub fn save_delay_line_lib(path: impl AsRef<Path>, params: &DelayLineLibParams) -> Result<()> {
+    delay_line_library(params).save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::delay_line::tests::TRISTATE_INV_DELAY_LINE_PARAMS;
+
+    use super::*;
+
+    #[test]
+    fn test_delay_line_library() {
+        let lib = delay_line_library(&DelayLineLibParams {
+            name: "tristate_inv_delay_line_128".to_string(),
+            inner: DelayLineKind::TristateInv(TRISTATE_INV_DELAY_LINE_PARAMS),
+            vdd: 1.8,
+            area: 0.,
+        })
+        .to_string();
+        assert!(lib.contains("      pin (ctl_b[127]) {\n"));
+        assert!(lib.contains("        timing_type : combinational;\n"));
+        assert!(lib.contains("      internal_power () {\n"));
+    }
+}
@@ -31,6 +31,7 @@ use super::decoder::layout::{DecoderGateParams, DecoderTap};
 use super::decoder::DecoderPhysicalDesign;
 use super::gate::{Inv, PrimitiveGateParams};
 
+pub mod liberty;
 pub mod tb;
 pub mod transmission;
 pub mod tristate;
@@ -76,6 +77,20 @@ pub enum DelayLineTracks {
     EnRight = 7,
 }
 
+impl TristateInvDelayLineParams {
+    pub const fn new(
+        stages: usize,
+        inv: PrimitiveGateParams,
+        tristate_inv: PrimitiveGateParams,
+    ) -> Self {
+        Self {
+            stages,
+            inv,
+            tristate_inv,
+        }
+    }
+}
+
 impl Component for NaiveDelayLine {
     type Params = NaiveDelayLineParams;
 
@@ -718,14 +733,17 @@ impl Script for DelayLineTapDesignScript {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+
+    use subgeom::bbox::BoundBox;
 
     use crate::blocks::gate::PrimitiveGateParams;
-    use crate::paths::{out_gds, out_spice};
+    use crate::paths::{out_gds, out_lib, out_spice};
     use crate::setup_ctx;
     use crate::tests::test_work_dir;
 
-    use super::tb::{DelayLineTb, DelayLineTbParams};
+    use super::liberty::{save_delay_line_lib, DelayLineLibParams};
+    use super::tb::{DelayLineKind, DelayLineTb, DelayLineTbParams};
     use super::tristate::TristateBufParams;
     use super::{
         NaiveDelayLine, NaiveDelayLineParams, TristateInvDelayLine, TristateInvDelayLineParams,
@@ -762,11 +780,12 @@ mod tests {
         pass: super::PassGateKind::TristateBuf(TRISTATE_SIZING),
     };
 
-    const TRISTATE_INV_DELAY_LINE_PARAMS: TristateInvDelayLineParams = TristateInvDelayLineParams {
-        stages: 128,
-        inv: INV_SIZING,
-        tristate_inv: INV_SIZING,
-    };
+    pub(crate) const TRISTATE_INV_DELAY_LINE_PARAMS: TristateInvDelayLineParams =
+        TristateInvDelayLineParams {
+            stages: 128,
+            inv: INV_SIZING,
+            tristate_inv: INV_SIZING,
+        };
 
     const NAIVE_DELAY_LINE_TGATE_TB_PARAMS: DelayLineTbParams = DelayLineTbParams {
         inner: super::tb::DelayLineKind::Naive(NAIVE_DELAY_LINE_TGATE_PARAMS),
@@ -835,6 +854,18 @@ mod tests {
             .expect("failed to run simulation");
     }
 
+    #[test]
+    fn test_tristate_inv_delay_line_abstract() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_tristate_inv_delay_line_abstract");
+        crate::abs::write_component_abstract::<TristateInvDelayLine>(
+            &ctx,
+            &TRISTATE_INV_DELAY_LINE_PARAMS,
+            crate::paths::out_lef(&work_dir, "abstract"),
+        )
+        .expect("failed to write abstract");
+    }
+
     #[test]
     fn test_tristate_inv_delay_line() {
         let ctx = setup_ctx();
@@ -848,29 +879,29 @@ mod tests {
         ctx.write_layout::<TristateInvDelayLine>(&TRISTATE_INV_DELAY_LINE_PARAMS, &gds_path)
             .expect("failed to write schematic");
 
+        let cell = ctx
+            .instantiate_layout::<TristateInvDelayLine>(&TRISTATE_INV_DELAY_LINE_PARAMS)
+            .unwrap();
+        let name = cell.cell().name();
+        let brect = cell.brect();
+        save_delay_line_lib(
+            out_lib(&work_dir, name),
+            &DelayLineLibParams {
+                name: name.to_string(),
+                inner: DelayLineKind::TristateInv(TRISTATE_INV_DELAY_LINE_PARAMS),
+                vdd: 1.8,
+                area: brect.width() as f64 * brect.height() as f64 / 1e6,
+            },
+        )
+        .expect("failed to write lib");
+
         #[cfg(feature = "commercial")]
         {
-            use crate::liberate::save_delay_line_lib;
             use crate::paths::out_verilog;
             use crate::verilog::save_delay_line_verilog;
             use substrate::schematic::netlist::NetlistPurpose;
             use substrate::verification::pex::PexInput;
 
-            let cell = ctx
-                .instantiate_layout::<TristateInvDelayLine>(&TRISTATE_INV_DELAY_LINE_PARAMS)
-                .unwrap();
-            let name = cell.cell().name();
-
-            let lib_path = crate::paths::out_lib(&work_dir, name);
-            save_delay_line_lib(
-                lib_path,
-                &crate::verilog::DelayLineParams {
-                    module_name: name.to_string(),
-                    control_width: TRISTATE_INV_DELAY_LINE_PARAMS.stages,
-                },
-            )
-            .expect("failed to write lib file from template");
-
             let verilog_path = out_verilog(&work_dir, name);
             save_delay_line_verilog(
                 &verilog_path,
@@ -17,7 +17,7 @@ pub enum DelayLineKind {
 }
 
 impl DelayLineKind {
-    fn stages(self) -> usize {
+    pub fn stages(self) -> usize {
         match self {
             DelayLineKind::Naive(params) => params.stages,
             DelayLineKind::TristateInv(params) => params.stages,
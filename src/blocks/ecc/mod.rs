@@ -0,0 +1,456 @@
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+use subgeom::bbox::BoundBox;
+use subgeom::{Rect, Span};
+use substrate::component::{error, Component};
+use substrate::data::SubstrateCtx;
+use substrate::error::Result;
+use substrate::layout::cell::{Instance, Port, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::elements::via::{Via, ViaParams};
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::placement::align::AlignRect;
+use substrate::schematic::circuit::Direction;
+use substrate::schematic::context::SchematicCtx;
+use substrate::schematic::signal::Signal;
+
+use crate::blocks::logic::channel::{strap_supplies, Channel, STRAP_SPACE};
+use crate::blocks::logic::{LogicBlock, LogicBlockParams, LogicBuilder};
+use crate::blocks::sram::{MuxRatio, Sram, SramParams};
+
+/// The error correction scheme applied to each word.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EccMode {
+    /// No error correction.
+    #[default]
+    None,
+    /// A Hamming code extended with an overall parity bit,
+    /// which corrects single-bit errors and detects double-bit errors.
+    Secded,
+}
+
+/// The number of Hamming check bits required to correct
+/// a single error in a word of `data_width` bits.
+fn hamming_bits(data_width: usize) -> usize {
+    let mut r = 0;
+    while (1 << r) < data_width + r + 1 {
+        r += 1;
+    }
+    r
+}
+
+/// The number of check bits stored alongside each word of `data_width` bits
+/// by a SECDED code.
+pub fn secded_check_bits(data_width: usize) -> usize {
+    hamming_bits(data_width) + 1
+}
+
+/// The 1-indexed Hamming codeword position of each data bit.
+///
+/// Check bits occupy the power-of-two positions.
+fn data_positions(data_width: usize) -> Vec<usize> {
+    (1..)
+        .filter(|pos: &usize| !pos.is_power_of_two())
+        .take(data_width)
+        .collect()
+}
+
+/// The logic that computes SECDED check bits from a data word.
+///
+/// `check[i]` for `i` less than the number of Hamming bits is the parity of the data bits
+/// whose codeword position has bit `i` set.
+/// The last check bit is the parity of all data and Hamming bits.
+pub fn secded_encoder(data_width: usize) -> LogicBlockParams {
+    let k = data_width;
+    let r = hamming_bits(k);
+    let positions = data_positions(k);
+
+    let mut logic = LogicBuilder::new(arcstr::format!("secded_encoder_{k}"));
+    let din = logic.input("din", k);
+    let check = logic.output("check", r + 1);
+
+    let mut bits = (0..r)
+        .map(|i| {
+            let covered = positions
+                .iter()
+                .zip(&din)
+                .filter(|(&pos, _)| pos & (1 << i) != 0)
+                .map(|(_, &bit)| bit)
+                .collect::<Vec<_>>();
+            logic.xor_all(&covered)
+        })
+        .collect::<Vec<_>>();
+    let all = din.iter().chain(&bits).copied().collect::<Vec<_>>();
+    bits.push(logic.xor_all(&all));
+
+    for (bit, y) in bits.into_iter().zip(check) {
+        logic.buf(bit, y);
+    }
+    logic.build()
+}
+
+/// The logic that corrects single-bit errors and flags double-bit errors in a word
+/// read alongside its SECDED check bits.
+pub fn secded_decoder(data_width: usize) -> LogicBlockParams {
+    let k = data_width;
+    let r = hamming_bits(k);
+    let positions = data_positions(k);
+
+    let mut logic = LogicBuilder::new(arcstr::format!("secded_decoder_{k}"));
+    let data = logic.input("data", k);
+    let check = logic.input("check", r + 1);
+    let dout = logic.output("dout", k);
+    let err_corrected = logic.output("err_corrected", 1)[0];
+    let err_uncorrectable = logic.output("err_uncorrectable", 1)[0];
+
+    // The syndrome is the codeword position of a single flipped bit,
+    // or zero if no Hamming-covered bit flipped.
+    let syndrome = (0..r)
+        .map(|i| {
+            let mut covered = positions
+                .iter()
+                .zip(&data)
+                .filter(|(&pos, _)| pos & (1 << i) != 0)
+                .map(|(_, &bit)| bit)
+                .collect::<Vec<_>>();
+            covered.push(check[i]);
+            logic.xor_all(&covered)
+        })
+        .collect::<Vec<_>>();
+    let syndrome_b = syndrome.iter().map(|&s| logic.inv(s)).collect::<Vec<_>>();
+
+    // An odd number of flipped bits causes an overall parity error.
+    let all = data.iter().chain(&check).copied().collect::<Vec<_>>();
+    let parity = logic.xor_all(&all);
+    let parity_b = logic.inv(parity);
+    let nonzero = logic.or_all(&syndrome);
+    let uncorrectable = logic.and2(nonzero, parity_b);
+    logic.buf(parity, err_corrected);
+    logic.buf(uncorrectable, err_uncorrectable);
+
+    for ((&pos, &bit), &y) in positions.iter().zip(&data).zip(&dout) {
+        let mut terms = (0..r)
+            .map(|i| {
+                if pos & (1 << i) != 0 {
+                    syndrome[i]
+                } else {
+                    syndrome_b[i]
+                }
+            })
+            .collect::<Vec<_>>();
+        terms.push(parity);
+        let flip = logic.and_all(&terms);
+        let x = logic.xor2(bit, flip);
+        logic.buf(x, y);
+    }
+    logic.build()
+}
+
+/// An [`Sram`] that stores SECDED check bits alongside each word.
+///
+/// Check bits are computed by the logic of [`secded_encoder`] on write
+/// and verified by the logic of [`secded_decoder`] on read,
+/// both of which sit to the right of the SRAM.
+/// Since check bits cover the whole word, there is no write mask.
+pub struct SramEcc {
+    params: SramEccParams,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct SramEccParams {
+    mux_ratio: MuxRatio,
+    num_words: usize,
+    data_width: usize,
+}
+
+impl SramEccParams {
+    pub const fn new(mux_ratio: MuxRatio, num_words: usize, data_width: usize) -> Self {
+        Self {
+            mux_ratio,
+            num_words,
+            data_width,
+        }
+    }
+
+    #[inline]
+    pub fn num_words(&self) -> usize {
+        self.num_words
+    }
+
+    #[inline]
+    pub fn data_width(&self) -> usize {
+        self.data_width
+    }
+
+    #[inline]
+    pub fn check_bits(&self) -> usize {
+        secded_check_bits(self.data_width)
+    }
+
+    #[inline]
+    pub fn addr_width(&self) -> usize {
+        self.sram().addr_width()
+    }
+
+    /// The parameters of the underlying SRAM, which stores data and check bits.
+    pub fn sram(&self) -> SramParams {
+        let width = self.data_width + self.check_bits();
+        SramParams::new(width, self.mux_ratio, self.num_words, width)
+    }
+
+    /// The name of the SRAM with ECC cell with these parameters.
+    pub fn name(&self) -> ArcStr {
+        arcstr::format!(
+            "sram22_{}x{}m{}_secded",
+            self.num_words,
+            self.data_width,
+            self.mux_ratio as u8,
+        )
+    }
+}
+
+impl Component for SramEcc {
+    type Params = SramEccParams;
+
+    fn new(params: &Self::Params, _ctx: &SubstrateCtx) -> Result<Self> {
+        if params.data_width == 0 {
+            return Err(error::Error::InvalidParams.into());
+        }
+        Ok(Self { params: *params })
+    }
+
+    fn name(&self) -> ArcStr {
+        self.params.name()
+    }
+
+    fn schematic(&self, ctx: &mut SchematicCtx) -> Result<()> {
+        let dw = self.params.data_width();
+        let cw = self.params.check_bits();
+
+        let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+        let [clk, we, ce, rstb] = ctx.ports(["clk", "we", "ce", "rstb"], Direction::Input);
+        let addr = ctx.bus_port("addr", self.params.addr_width(), Direction::Input);
+        let din = ctx.bus_port("din", dw, Direction::Input);
+        let dout = ctx.bus_port("dout", dw, Direction::Output);
+        let [err_corrected, err_uncorrectable] =
+            ctx.ports(["err_corrected", "err_uncorrectable"], Direction::Output);
+
+        let din_check = ctx.bus("din_check", cw);
+        let dout_raw = ctx.bus("dout_raw", dw);
+        let dout_check = ctx.bus("dout_check", cw);
+
+        ctx.instantiate::<LogicBlock>(&secded_encoder(dw))?
+            .with_connections([
+                ("vdd", vdd),
+                ("vss", vss),
+                ("din", din),
+                ("check", din_check),
+            ])
+            .named("encoder")
+            .add_to(ctx);
+
+        ctx.instantiate::<Sram>(&self.params.sram())?
+            .with_connections([
+                ("vdd", vdd),
+                ("vss", vss),
+                ("clk", clk),
+                ("we", we),
+                ("ce", ce),
+                ("rstb", rstb),
+                ("addr", addr),
+                ("wmask", vdd),
+            ])
+            .with_connections([
+                ("din", Signal::new(vec![din, din_check])),
+                ("dout", Signal::new(vec![dout_raw, dout_check])),
+            ])
+            .named("sram")
+            .add_to(ctx);
+
+        ctx.instantiate::<LogicBlock>(&secded_decoder(dw))?
+            .with_connections([
+                ("vdd", vdd),
+                ("vss", vss),
+                ("data", dout_raw),
+                ("check", dout_check),
+                ("dout", dout),
+                ("err_corrected", err_corrected),
+                ("err_uncorrectable", err_uncorrectable),
+            ])
+            .named("decoder")
+            .add_to(ctx);
+
+        Ok(())
+    }
+
+    fn layout(&self, ctx: &mut LayoutCtx) -> Result<()> {
+        let dw = self.params.data_width();
+        let cw = self.params.check_bits();
+        let m1 = ctx.layers().get(Selector::Metal(1))?;
+        let m2 = ctx.layers().get(Selector::Metal(2))?;
+
+        // The encoder and decoder sit to the right of the SRAM,
+        // with their pins level with those of the SRAM.
+        let sram_params = self.params.sram();
+        let sram = ctx.instantiate::<Sram>(&sram_params)?;
+        let mut encoder = ctx.instantiate::<LogicBlock>(&secded_encoder(dw))?;
+        encoder.align_to_the_right_of(sram.bbox(), STRAP_SPACE);
+        encoder.align_bottom(sram.bbox());
+        let mut decoder = ctx.instantiate::<LogicBlock>(&secded_decoder(dw))?;
+        decoder.align_to_the_right_of(encoder.bbox(), STRAP_SPACE);
+        decoder.align_bottom(sram.bbox());
+
+        // The `vdd` rails of the encoder also reach the outer supply ring.
+        for net in ["vdd", "vss"] {
+            ctx.add_port(sram.port(net)?.into_cell_port())?;
+        }
+        let ring_vdd = sram
+            .port("vdd")?
+            .shapes(m1)
+            .filter_map(|shape| shape.as_rect())
+            .max_by_key(|rect| rect.right())
+            .unwrap();
+        strap_supplies(ctx, &encoder, Some(ring_vdd.left()))?;
+        strap_supplies(ctx, &decoder, None)?;
+
+        // The write mask is tied high where its pins cross the outer supply ring.
+        for i in 0..sram_params.wmask_width() {
+            let pin = sram.port(PortId::new("wmask", i))?.largest_rect(m1)?;
+            let ring = sram
+                .port("vdd")?
+                .shapes(m2)
+                .filter_map(|shape| shape.as_rect())
+                .filter(|rect| {
+                    rect.left() <= pin.left()
+                        && pin.right() <= rect.right()
+                        && pin.bottom() <= rect.bottom()
+                        && rect.top() <= pin.top()
+                })
+                .min_by_key(|rect| rect.bottom())
+                .unwrap();
+            let via = ctx.instantiate::<Via>(
+                &ViaParams::builder()
+                    .layers(m1, m2)
+                    .geometry(pin, ring)
+                    .build(),
+            )?;
+            ctx.draw(via)?;
+        }
+
+        // Everything else is connected in a channel beneath the SRAM and the ECC logic,
+        // which has a track for every net that may cross it.
+        let mut channel = Channel::new(
+            ctx,
+            Span::new(sram.brect().left(), decoder.brect().right()),
+            sram.brect().bottom(),
+            2 * (dw + cw) + 16,
+        )?;
+        let pin = |inst: &Instance, port: &str, i: usize| -> Result<Rect> {
+            inst.port(PortId::new(port, i))?.largest_rect(m1)
+        };
+
+        for j in 0..dw {
+            let net = format!("din_{j}");
+            let src = channel.pin(ctx, pin(&sram, "din", j)?, &net)?;
+            let dst = channel.pin(ctx, pin(&encoder, "din", j)?, &net)?;
+            let port = channel.macro_pin(ctx, PortId::new("din", j), src, &net)?;
+            channel.connect(port, src, &net);
+            channel.connect(port, dst, &net);
+        }
+        for i in 0..cw {
+            let net = format!("din_check_{i}");
+            let src = channel.pin(ctx, pin(&encoder, "check", i)?, &net)?;
+            let dst = channel.pin(ctx, pin(&sram, "din", dw + i)?, &net)?;
+            channel.connect(src, dst, &net);
+        }
+        for j in 0..dw + cw {
+            let (dst, net) = if j < dw {
+                (pin(&decoder, "data", j)?, format!("dout_raw_{j}"))
+            } else {
+                (
+                    pin(&decoder, "check", j - dw)?,
+                    format!("dout_check_{}", j - dw),
+                )
+            };
+            let src = channel.pin(ctx, pin(&sram, "dout", j)?, &net)?;
+            let dst = channel.pin(ctx, dst, &net)?;
+            channel.connect(src, dst, &net);
+        }
+        for (port, width) in [("dout", dw), ("err_corrected", 1), ("err_uncorrectable", 1)] {
+            for i in 0..width {
+                let net = format!("{port}_{i}");
+                let src = channel.pin(ctx, pin(&decoder, port, i)?, &net)?;
+                let dst = channel.macro_pin(ctx, PortId::new(port, i), src, &net)?;
+                channel.connect(src, dst, &net);
+            }
+        }
+        for (port, width) in [
+            ("addr", sram_params.addr_width()),
+            ("we", 1),
+            ("ce", 1),
+            ("clk", 1),
+            ("rstb", 1),
+        ] {
+            for i in 0..width {
+                let net = format!("{port}_{i}");
+                let dst = channel.pin(ctx, pin(&sram, port, i)?, &net)?;
+                let src = channel.macro_pin(ctx, PortId::new(port, i), dst, &net)?;
+                channel.connect(src, dst, &net);
+            }
+        }
+        channel.draw(ctx)?;
+
+        ctx.draw(sram)?;
+        ctx.draw(encoder)?;
+        ctx.draw(decoder)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::paths::{out_gds, out_lef, out_spice, out_verilog};
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+    use crate::verilog::save_ecc_verilog;
+
+    use super::*;
+
+    const SRAM22_64X32M4_SECDED: SramEccParams = SramEccParams::new(MuxRatio::M4, 64, 32);
+
+    #[test]
+    fn test_secded_check_bits() {
+        assert_eq!(secded_check_bits(8), 5);
+        assert_eq!(secded_check_bits(32), 7);
+        assert_eq!(secded_check_bits(64), 8);
+        assert_eq!(data_positions(4), vec![3, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_sram_ecc() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sram_ecc");
+        ctx.write_schematic_to_file::<SramEcc>(
+            &SRAM22_64X32M4_SECDED,
+            out_spice(&work_dir, "schematic"),
+        )
+        .expect("failed to write schematic");
+        save_ecc_verilog(
+            out_verilog(&work_dir, &*SRAM22_64X32M4_SECDED.name()),
+            &SRAM22_64X32M4_SECDED,
+        )
+        .expect("failed to write behavioral model");
+        ctx.write_layout::<SramEcc>(
+            &SRAM22_64X32M4_SECDED,
+            out_gds(&work_dir, &*SRAM22_64X32M4_SECDED.name()),
+        )
+        .expect("failed to write layout");
+        crate::abs::write_component_abstract::<SramEcc>(
+            &ctx,
+            &SRAM22_64X32M4_SECDED,
+            out_lef(&work_dir, &*SRAM22_64X32M4_SECDED.name()),
+        )
+        .expect("failed to write abstract");
+    }
+}
@@ -1,12 +1,17 @@
 use serde::{Deserialize, Serialize};
 use subgeom::bbox::BoundBox;
 use subgeom::ring::Ring;
-use subgeom::Rect;
+use subgeom::{Dir, Rect, Side, Span};
 use substrate::component::Component;
 use substrate::layout::cell::CellPort;
-use substrate::layout::elements::via::{Via, ViaParams};
+use substrate::layout::elements::via::{Via, ViaExpansion, ViaParams};
 use substrate::layout::layers::selector::Selector;
 use substrate::layout::layers::{LayerBoundBox, LayerKey};
+use substrate::layout::placement::align::AlignRect;
+
+use self::switches::{PowerSwitchParams, PowerSwitches, SWITCH_PINS};
+
+pub mod switches;
 
 pub struct GuardRing {
     params: GuardRingParams,
@@ -36,6 +41,11 @@ pub struct GuardRingParams {
     pub v_metal: LayerKey,
     pub h_width: i64,
     pub v_width: i64,
+    /// Header switches placed to the right of the rings.
+    ///
+    /// If present, the `vdd` ring carries `vdd_periph`, and the ports of
+    /// [`PowerSwitches`] other than `vdd_periph` and `vss` are exposed.
+    pub switches: Option<PowerSwitchParams>,
 }
 
 pub struct SupplyRings {
@@ -90,6 +100,7 @@ where
             v_metal,
             h_width,
             v_width,
+            switches: None,
         };
         let ring = ctx.instantiate::<GuardRing>(&params)?;
         ctx.add_ports(ring.ports()).unwrap();
@@ -213,6 +224,72 @@ impl Component for GuardRing {
         }
         ctx.draw_rect(dnw, dnw_boundary);
 
+        if let Some(switches) = self.params.switches {
+            self.draw_switches(ctx, vdd_ring, vss_ring, switches)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GuardRing {
+    /// Places the header switches to the right of the rings,
+    /// connecting `vdd_periph` to the `vdd` ring and `vss` to the `vss` ring.
+    fn draw_switches(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+        vdd_ring: Ring,
+        vss_ring: Ring,
+        params: PowerSwitchParams,
+    ) -> substrate::error::Result<()> {
+        let (h_metal, v_metal) = (self.params.h_metal, self.params.v_metal);
+        let mut switches = ctx.instantiate::<PowerSwitches>(&params)?;
+        switches.align_to_the_right_of(vdd_ring.outer().bbox(), 6_000);
+        switches.align_bottom(vdd_ring.outer().bbox());
+        let left = switches.brect().left();
+
+        // `vdd_periph` runs left to the right side of the `vdd` ring.
+        let vdd_side = vdd_ring.dir_rects(Dir::Vert)[1];
+        let rail = switches.port("vdd_periph")?.largest_rect(h_metal)?;
+        let rail = rail.with_hspan(rail.hspan().union(vdd_side.hspan()));
+        ctx.draw_rect(h_metal, rail);
+        let viap = ViaParams::builder()
+            .layers(v_metal, h_metal)
+            .geometry(vdd_side, rail)
+            .expand(ViaExpansion::LongerDirection)
+            .build();
+        let via = ctx.instantiate::<Via>(&viap)?;
+        ctx.draw(via)?;
+
+        // `vss` drops down to the gap between the bottom sides of the rings,
+        // then runs left beneath the `vdd` ring to the right side of the `vss` ring.
+        let vss_side = vss_ring.dir_rects(Dir::Vert)[1];
+        let rail = switches.port("vss")?.first_rect(v_metal, Side::Left)?;
+        let lane = Span::new(left - 1_400, left - 600);
+        let gap = Span::new(
+            vdd_ring.dir_rects(Dir::Horiz)[0].top(),
+            vss_ring.dir_rects(Dir::Horiz)[0].bottom(),
+        );
+        let h_span = Span::from_center_span_gridded(gap.center(), 800, ctx.pdk().layout_grid());
+        let jog = Rect::from_spans(vss_side.hspan().union(lane), h_span);
+        let lane = Rect::from_spans(lane, h_span.union(rail.vspan()));
+        ctx.draw_rect(v_metal, rail.with_hspan(rail.hspan().union(lane.hspan())));
+        ctx.draw_rect(v_metal, lane);
+        ctx.draw_rect(h_metal, jog);
+        for target in [vss_side, lane] {
+            let viap = ViaParams::builder()
+                .layers(v_metal, h_metal)
+                .geometry(target, jog)
+                .build();
+            let via = ctx.instantiate::<Via>(&viap)?;
+            ctx.draw(via)?;
+        }
+
+        for port in ["vdd", "vdd_array"].into_iter().chain(SWITCH_PINS) {
+            ctx.add_port(switches.port(port)?.into_cell_port())?;
+        }
+        ctx.draw(switches)?;
+
         Ok(())
     }
 }
@@ -244,6 +321,7 @@ mod tests {
             v_metal: m1,
             h_width: 1_360,
             v_width: 1_360,
+            switches: None,
         };
         ctx.write_layout::<GuardRing>(&params, out_gds(work_dir, "layout"))
             .expect("failed to write layout");
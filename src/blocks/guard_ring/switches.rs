@@ -0,0 +1,633 @@
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+use subgeom::bbox::BoundBox;
+use subgeom::{Corner, Dir, Point, Rect, Side, Span};
+use substrate::component::Component;
+use substrate::data::SubstrateCtx;
+use substrate::layout::cell::{CellPort, Instance};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::elements::mos::LayoutMos;
+use substrate::layout::elements::via::{Via, ViaParams};
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::layers::LayerBoundBox;
+use substrate::layout::placement::align::AlignRect;
+use substrate::layout::routing::auto::grid::ExpandToGridStrategy;
+use substrate::layout::routing::auto::{GreedyRouter, GreedyRouterConfig, LayerConfig};
+use substrate::layout::routing::tracks::TrackLocator;
+use substrate::pdk::mos::query::Query;
+use substrate::pdk::mos::spec::MosKind;
+use substrate::pdk::mos::{GateContactStrategy, LayoutMosParams, MosParams};
+use substrate::pdk::stdcell::StdCell;
+use substrate::schematic::circuit::Direction;
+use substrate::schematic::context::SchematicCtx;
+use substrate::schematic::elements::mos::SchematicMos;
+
+use crate::blocks::control::layout::std_cell_row;
+
+/// Width of each finger of the header switches in nm.
+pub const SWITCH_FINGER_WIDTH: i64 = 2_000;
+/// Gate length of the header switches in nm.
+pub const SWITCH_LENGTH: i64 = 150;
+
+/// The signal pins of [`PowerSwitches`], from left to right along its bottom edge.
+pub const SWITCH_PINS: [&str; 5] = ["clk", "sleep", "rstb_gated", "rstb", "retain"];
+
+/// Width of the m1 strips over the sources, drains and gates of a header switch.
+const STRIP_WIDTH: i64 = 400;
+/// Width of the supply rails above the header switches.
+const RAIL_WIDTH: i64 = 800;
+/// Height of the routing channel between the standard cells and the header switches.
+const GATE_CHANNEL: i64 = 5_000;
+
+/// Header switches between the supply rings and the blocks they enclose,
+/// and the logic that holds those blocks in reset while they wake up.
+///
+/// `vdd_periph` is connected to `vdd` while `sleep` is low.
+/// `vdd_array` is also connected to `vdd` while `sleep` is low, and is otherwise
+/// held a threshold voltage below `vdd` by a diode-connected PMOS while `retain` is high.
+/// `rstb_gated` is held low until the first rising edge of `clk` after `sleep` falls,
+/// giving `vdd_periph` a cycle to settle, and follows `rstb` afterwards.
+/// Everything is supplied by `vdd`, so it stays powered while asleep.
+pub struct PowerSwitches {
+    params: PowerSwitchParams,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PowerSwitchParams {
+    /// Number of fingers of the header switch supplying `vdd_periph`.
+    pub periph_fingers: usize,
+    /// Number of fingers of the header switch supplying `vdd_array`.
+    pub array_fingers: usize,
+    /// Number of fingers of each of the retention enable and diode devices.
+    pub retention_fingers: usize,
+}
+
+impl Component for PowerSwitches {
+    type Params = PowerSwitchParams;
+
+    fn new(params: &Self::Params, _ctx: &SubstrateCtx) -> substrate::error::Result<Self> {
+        if params.periph_fingers == 0 || params.array_fingers == 0 || params.retention_fingers == 0
+        {
+            return Err(substrate::component::error::Error::InvalidParams.into());
+        }
+        Ok(Self { params: *params })
+    }
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!(
+            "power_switches_{}_{}_{}",
+            self.params.periph_fingers,
+            self.params.array_fingers,
+            self.params.retention_fingers
+        )
+    }
+
+    fn schematic(&self, ctx: &mut SchematicCtx) -> substrate::error::Result<()> {
+        let [vdd, vss, vdd_periph, vdd_array] =
+            ctx.ports(["vdd", "vss", "vdd_periph", "vdd_array"], Direction::InOut);
+        let [clk, rstb, sleep, retain] =
+            ctx.ports(["clk", "rstb", "sleep", "retain"], Direction::Input);
+        let rstb_gated = ctx.port("rstb_gated", Direction::Output);
+        let [retain_b, vdd_retain, sleep_b, awake, awake_b] =
+            ctx.signals(["retain_b", "vdd_retain", "sleep_b", "awake", "awake_b"]);
+
+        let stdcells = ctx.inner().std_cell_db();
+        let lib = stdcells.try_lib_named("sky130_fd_sc_hs")?;
+        let inv = lib.try_cell_named("sky130_fd_sc_hs__inv_2")?;
+        let and2 = lib.try_cell_named("sky130_fd_sc_hs__and2_2")?;
+        let dff = lib.try_cell_named("sky130_fd_sc_hs__dfrbp_2")?;
+        for (name, cell, connections) in [
+            ("retain_inv", inv, vec![("A", retain), ("Y", retain_b)]),
+            ("sleep_inv", inv, vec![("A", sleep), ("Y", sleep_b)]),
+            (
+                "wake_dff",
+                dff,
+                vec![
+                    ("CLK", clk),
+                    ("RESET_B", sleep_b),
+                    ("D", vdd),
+                    ("Q", awake),
+                    ("Q_N", awake_b),
+                ],
+            ),
+            (
+                "rstb_gate",
+                and2,
+                vec![("A", rstb), ("B", awake), ("X", rstb_gated)],
+            ),
+        ] {
+            ctx.instantiate::<StdCell>(&cell.id())?
+                .with_connections(connections)
+                .with_connections([("VPWR", vdd), ("VPB", vdd), ("VGND", vss), ("VNB", vss)])
+                .named(name)
+                .add_to(ctx);
+        }
+
+        let pmos_id = ctx
+            .mos_db()
+            .query(Query::builder().kind(MosKind::Pmos).build().unwrap())?
+            .id();
+        let pmos = |m: usize| MosParams {
+            w: SWITCH_FINGER_WIDTH,
+            l: SWITCH_LENGTH,
+            m: m as u64,
+            nf: 1,
+            id: pmos_id,
+        };
+
+        for (name, d, g, s, m) in [
+            (
+                "MPperiph",
+                vdd_periph,
+                sleep,
+                vdd,
+                self.params.periph_fingers,
+            ),
+            ("MParray", vdd_array, sleep, vdd, self.params.array_fingers),
+            (
+                "MPretain",
+                vdd_retain,
+                retain_b,
+                vdd,
+                self.params.retention_fingers,
+            ),
+            (
+                "MPdiode",
+                vdd_array,
+                vdd_array,
+                vdd_retain,
+                self.params.retention_fingers,
+            ),
+        ] {
+            let mut mp = ctx.instantiate::<SchematicMos>(&pmos(m))?;
+            mp.connect_all([("d", &d), ("g", &g), ("s", &s), ("b", &vdd)]);
+            mp.set_name(name);
+            ctx.add_instance(mp);
+        }
+
+        Ok(())
+    }
+
+    fn layout(&self, ctx: &mut LayoutCtx) -> substrate::error::Result<()> {
+        let layers = ctx.layers();
+        let outline = layers.get(Selector::Name("outline"))?;
+        let nwell = layers.get(Selector::Name("nwell"))?;
+        let m0 = layers.get(Selector::Metal(0))?;
+        let m1 = layers.get(Selector::Metal(1))?;
+        let m2 = layers.get(Selector::Metal(2))?;
+        let grid = ctx.pdk().layout_grid();
+
+        let stdcells = ctx.inner().std_cell_db();
+        let lib = stdcells.try_lib_named("sky130_fd_sc_hs")?;
+        let inv = lib.try_cell_named("sky130_fd_sc_hs__inv_2")?;
+        let inv = ctx.instantiate::<StdCell>(&inv.id())?;
+        let and2 = lib.try_cell_named("sky130_fd_sc_hs__and2_2")?;
+        let and2 = ctx.instantiate::<StdCell>(&and2.id())?;
+        let dff = lib.try_cell_named("sky130_fd_sc_hs__dfrbp_2")?;
+        let dff = ctx.instantiate::<StdCell>(&dff.id())?;
+        let tap = lib.try_cell_named("sky130_fd_sc_hs__tap_2")?;
+        let tap = ctx.instantiate::<StdCell>(&tap.id())?;
+
+        let row = std_cell_row(
+            &tap,
+            outline,
+            &[
+                ("retain_inv", &inv),
+                ("sleep_inv", &inv),
+                ("wake_dff", &dff),
+                ("rstb_gate", &and2),
+            ],
+        )?;
+
+        // The header switches sit above the standard cells, sharing the nwell of their
+        // pull-ups so that the taps of the row also bias the switches.
+        let mut headers: Vec<Instance> = Vec::new();
+        for fingers in [
+            self.params.periph_fingers,
+            self.params.array_fingers,
+            self.params.retention_fingers,
+            self.params.retention_fingers,
+        ] {
+            let mut mos = ctx.instantiate::<HeaderMos>(&fingers)?;
+            mos.align_above(row.bbox(), GATE_CHANNEL);
+            match headers.last() {
+                Some(prev) => mos.align_to_the_right_of(prev.bbox(), 1_000),
+                None => mos.align_left(row.bbox()),
+            }
+            headers.push(mos);
+        }
+        let headers_bbox = headers
+            .iter()
+            .map(|mos| mos.brect())
+            .reduce(|acc, rect| acc.union(rect.bbox()).into_rect())
+            .unwrap();
+        ctx.draw_rect(
+            nwell,
+            Rect::from_spans(
+                row.brect()
+                    .hspan()
+                    .union(headers_bbox.hspan().expand_all(400)),
+                Span::new(row.brect().top() - 1_400, headers_bbox.top() + 400),
+            ),
+        );
+
+        let body = row.brect().union(headers_bbox.bbox()).into_rect();
+        let area = Rect::from_spans(
+            body.hspan().expand_all(8 * 680),
+            Span::new(body.bottom() - 8 * 680, headers_bbox.bottom()),
+        );
+        let mut router = GreedyRouter::with_config(GreedyRouterConfig {
+            area,
+            layers: vec![
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Horiz,
+                    layer: m1,
+                },
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Vert,
+                    layer: m2,
+                },
+            ],
+        });
+        for layer in [m1, m2] {
+            for shape in row.shapes_on(layer) {
+                router.block(layer, shape.brect().expand(40));
+            }
+        }
+
+        // Supply rails above the header switches, from bottom to top:
+        // `vdd`, `vdd_retain`, `vdd_array` and `vdd_periph`.
+        // The `vdd` rail reaches a strap to the right of the router that connects it
+        // to the standard cell row.
+        let vdd_strap = Span::with_start_and_length(area.right() + 400, RAIL_WIDTH);
+        let rail_hspan = Span::new(area.left(), vdd_strap.stop());
+        let rail_vspans = (0..4)
+            .map(|i| {
+                Span::with_start_and_length(
+                    headers_bbox.top() + 1_000 + i * (RAIL_WIDTH + 600),
+                    RAIL_WIDTH,
+                )
+            })
+            .collect::<Vec<_>>();
+        let [periph, array, retain, diode] = &headers[..] else {
+            unreachable!()
+        };
+        let mut vdd_retain_hspan: Option<Span> = None;
+        for (inst, port, i) in [
+            (periph, "s", 0),
+            (periph, "d", 3),
+            (array, "s", 0),
+            (array, "d", 2),
+            (retain, "s", 0),
+            (retain, "d", 1),
+            (diode, "s", 1),
+            (diode, "d", 2),
+            (diode, "g", 2),
+        ] {
+            let strip = inst.port(port)?.largest_rect(m1)?;
+            let rail = rail_vspans[i];
+            let strip = strip.with_vspan(strip.vspan().union(rail));
+            ctx.draw_rect(m1, strip);
+            let via_rect = Rect::from_spans(strip.hspan(), rail);
+            let via = ctx.instantiate::<Via>(
+                &ViaParams::builder()
+                    .layers(m1, m2)
+                    .geometry(via_rect, via_rect)
+                    .build(),
+            )?;
+            ctx.draw(via)?;
+            if i == 1 {
+                vdd_retain_hspan =
+                    Some(vdd_retain_hspan.map_or(strip.hspan(), |span| span.union(strip.hspan())));
+            }
+        }
+        ctx.draw_rect(
+            m2,
+            Rect::from_spans(vdd_retain_hspan.unwrap(), rail_vspans[1]),
+        );
+        for (name, rail) in [
+            ("vdd", rail_vspans[0]),
+            ("vdd_array", rail_vspans[2]),
+            ("vdd_periph", rail_vspans[3]),
+        ] {
+            let rect = Rect::from_spans(rail_hspan, rail);
+            ctx.draw_rect(m2, rect);
+            ctx.add_port(CellPort::with_shape(name, m2, rect))?;
+        }
+        ctx.add_ports(
+            row.ports()
+                .filter(|port| matches!(port.name().as_str(), "vdd" | "vss")),
+        )?;
+
+        let vdd_rail = row.port_map().port("vdd")?.first_rect(m1, Side::Right)?;
+        let vdd_rail = vdd_rail.with_hspan(vdd_rail.hspan().union(vdd_strap));
+        let vdd_strap = Rect::from_spans(vdd_strap, vdd_rail.vspan().union(rail_vspans[0]));
+        router.block(m1, vdd_rail.expand(40));
+        for rect in [vdd_rail, vdd_strap] {
+            ctx.draw_rect(m1, rect);
+            ctx.merge_port(CellPort::with_shape("vdd", m1, rect));
+        }
+        let via_rect = Rect::from_spans(vdd_strap.hspan(), rail_vspans[0]);
+        let via = ctx.instantiate::<Via>(
+            &ViaParams::builder()
+                .layers(m1, m2)
+                .geometry(via_rect, via_rect)
+                .build(),
+        )?;
+        ctx.draw(via)?;
+
+        // The `vss` rail is extended to the left edge of the router,
+        // where it can be connected to a supply ring.
+        let vss_rail = row.port_map().port("vss")?.first_rect(m1, Side::Left)?;
+        let vss_rail = vss_rail.with_hspan(
+            vss_rail
+                .hspan()
+                .union(Span::new(area.left(), vss_rail.left())),
+        );
+        ctx.draw_rect(m1, vss_rail);
+        router.block(m1, vss_rail.expand(40));
+        ctx.merge_port(CellPort::with_shape("vss", m1, vss_rail));
+
+        // Pins are spaced three tracks apart along the bottom of the router,
+        // since the routing grid of a parent cell may differ.
+        let htracks = router.track_info(m1).tracks().clone();
+        let vtracks = router.track_info(m2).tracks().clone();
+        let htrack =
+            htracks.index(htracks.track_with_loc(TrackLocator::StartsAfter, area.bottom()));
+        let vtrack_start = vtracks.track_with_loc(TrackLocator::StartsAfter, body.left());
+        let mut pins = Vec::new();
+        for (i, name) in SWITCH_PINS.into_iter().enumerate() {
+            let pin = Rect::from_spans(vtracks.index(vtrack_start + 3 * i as i64), htrack);
+            ctx.draw_rect(m1, pin);
+            router.occupy(m1, pin, name)?;
+            ctx.add_port(CellPort::with_shape(name, m1, pin))?;
+            pins.push(pin);
+        }
+        let [clk, sleep, rstb_gated, rstb, retain_pin] = pins[..] else {
+            unreachable!()
+        };
+
+        let via01 = ctx.instantiate::<Via>(
+            &ViaParams::builder()
+                .layers(m0, m1)
+                .geometry(
+                    Rect::from_point(Point::zero()),
+                    Rect::from_point(Point::zero()),
+                )
+                .bot_extension(Dir::Vert)
+                .top_extension(Dir::Vert)
+                .build(),
+        )?;
+        let snap_pin = |ctx: &mut LayoutCtx,
+                        router: &mut GreedyRouter,
+                        pin: &str,
+                        corner: Corner,
+                        net: &str|
+         -> substrate::error::Result<Rect> {
+            let port = row.port_map().port(pin)?.largest_rect(m0)?;
+            let mut via = via01.clone();
+            via.align_centers_gridded(port.bbox(), grid);
+            let rect = router.expand_to_grid(
+                via.layer_bbox(m1).into_rect(),
+                ExpandToGridStrategy::Corner(corner),
+            );
+            ctx.draw(via)?;
+            ctx.draw_rect(m1, rect);
+            router.occupy(m1, rect, net)?;
+            Ok(rect)
+        };
+
+        // Draws the gate strip of a header switch down into the routing channel.
+        let gate_pin = |ctx: &mut LayoutCtx,
+                        router: &mut GreedyRouter,
+                        inst: &Instance,
+                        net: &str|
+         -> substrate::error::Result<Rect> {
+            let gate = inst.port("g")?.largest_rect(m1)?;
+            let pin = router.expand_to_grid(
+                Rect::from_spans(
+                    gate.hspan(),
+                    Span::with_stop_and_length(headers_bbox.bottom() - 1_000, 320),
+                ),
+                ExpandToGridStrategy::Minimum,
+            );
+            let rect = gate.with_vspan(gate.vspan().union(pin.vspan()));
+            ctx.draw_rect(m1, rect);
+            router.block(m1, rect.expand(40));
+            ctx.draw_rect(m1, pin);
+            router.occupy(m1, pin, net)?;
+            Ok(pin)
+        };
+
+        let vdd_pin = snap_pin(ctx, &mut router, "wake_dff_d", Corner::LowerLeft, "vdd")?;
+        let vdd_rail = row.port_map().port("vdd")?.first_rect(m1, Side::Top)?;
+        let vdd_dst = router.expand_to_grid(
+            Rect::from_spans(
+                Span::from_center_span_gridded(vdd_pin.center().x, 200, grid),
+                vdd_rail.vspan(),
+            ),
+            ExpandToGridStrategy::Minimum,
+        );
+        ctx.draw_rect(m1, vdd_dst);
+        router.occupy(m1, vdd_dst, "vdd")?;
+
+        let routes = vec![
+            (
+                retain_pin,
+                snap_pin(
+                    ctx,
+                    &mut router,
+                    "retain_inv_a",
+                    Corner::UpperLeft,
+                    "retain",
+                )?,
+                "retain",
+            ),
+            (
+                snap_pin(
+                    ctx,
+                    &mut router,
+                    "retain_inv_y",
+                    Corner::LowerRight,
+                    "retain_b",
+                )?,
+                gate_pin(ctx, &mut router, retain, "retain_b")?,
+                "retain_b",
+            ),
+            (
+                sleep,
+                snap_pin(ctx, &mut router, "sleep_inv_a", Corner::UpperLeft, "sleep")?,
+                "sleep",
+            ),
+            (sleep, gate_pin(ctx, &mut router, periph, "sleep")?, "sleep"),
+            (sleep, gate_pin(ctx, &mut router, array, "sleep")?, "sleep"),
+            (
+                snap_pin(
+                    ctx,
+                    &mut router,
+                    "sleep_inv_y",
+                    Corner::LowerRight,
+                    "sleep_b",
+                )?,
+                snap_pin(
+                    ctx,
+                    &mut router,
+                    "wake_dff_reset_b",
+                    Corner::UpperRight,
+                    "sleep_b",
+                )?,
+                "sleep_b",
+            ),
+            (
+                clk,
+                snap_pin(ctx, &mut router, "wake_dff_clk", Corner::UpperLeft, "clk")?,
+                "clk",
+            ),
+            (vdd_pin, vdd_dst, "vdd"),
+            (
+                snap_pin(ctx, &mut router, "wake_dff_q", Corner::LowerRight, "awake")?,
+                snap_pin(ctx, &mut router, "rstb_gate_b", Corner::UpperRight, "awake")?,
+                "awake",
+            ),
+            (
+                rstb,
+                snap_pin(ctx, &mut router, "rstb_gate_a", Corner::UpperLeft, "rstb")?,
+                "rstb",
+            ),
+            (
+                snap_pin(
+                    ctx,
+                    &mut router,
+                    "rstb_gate_x",
+                    Corner::LowerRight,
+                    "rstb_gated",
+                )?,
+                rstb_gated,
+                "rstb_gated",
+            ),
+        ];
+        for (src, dst, net) in routes {
+            router.route_with_net(ctx, m1, src, m1, dst, net)?;
+        }
+        ctx.draw(router)?;
+
+        ctx.draw(row)?;
+        for mos in headers {
+            ctx.draw(mos)?;
+        }
+        Ok(())
+    }
+}
+
+/// One header switch of [`PowerSwitches`], with its fingers stacked vertically.
+///
+/// Exposes vertical m1 strips over the drains (`d`), sources (`s`) and gates (`g`).
+/// The `d` strip lies at the end of the drains away from the gate contacts,
+/// with the `s` strip beyond it.
+struct HeaderMos {
+    fingers: usize,
+}
+
+impl Component for HeaderMos {
+    type Params = usize;
+
+    fn new(params: &Self::Params, _ctx: &SubstrateCtx) -> substrate::error::Result<Self> {
+        Ok(Self { fingers: *params })
+    }
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("header_mos_{}", self.fingers)
+    }
+
+    fn layout(&self, ctx: &mut LayoutCtx) -> substrate::error::Result<()> {
+        let m1 = ctx.layers().get(Selector::Metal(1))?;
+        let pmos = ctx.mos_db().default_pmos().unwrap().id();
+
+        let mos = ctx.instantiate::<LayoutMos>(&LayoutMosParams {
+            skip_sd_metal: vec![vec![]],
+            deep_nwell: false,
+            contact_strategy: GateContactStrategy::SingleSide,
+            devices: vec![MosParams {
+                w: SWITCH_FINGER_WIDTH,
+                l: SWITCH_LENGTH,
+                m: 1,
+                nf: self.fingers as u64,
+                id: pmos,
+            }],
+        })?;
+        ctx.draw_ref(&mos)?;
+
+        let li = mos.port("sd_0_0")?.any_layer();
+        let gate = mos.port("gate")?.bbox(li).into_rect();
+        let drain = mos.port("sd_0_1")?.largest_rect(li)?;
+        let (d_hspan, s_hspan) = if gate.center().x < drain.center().x {
+            let d_hspan = Span::with_stop_and_length(drain.right(), STRIP_WIDTH);
+            (
+                d_hspan,
+                Span::with_start_and_length(d_hspan.stop() + 200, STRIP_WIDTH),
+            )
+        } else {
+            let d_hspan = Span::with_start_and_length(drain.left(), STRIP_WIDTH);
+            (
+                d_hspan,
+                Span::with_stop_and_length(d_hspan.start() - 200, STRIP_WIDTH),
+            )
+        };
+
+        let mut d_vspan = drain.vspan();
+        let mut s_vspan = drain.vspan();
+        for i in 0..=self.fingers {
+            let sd = mos.port(format!("sd_0_{i}"))?.largest_rect(li)?;
+            let hspan = if i % 2 == 0 {
+                // Extend sources past the drain strip to reach the source strip.
+                ctx.draw_rect(li, sd.with_hspan(sd.hspan().union(s_hspan)));
+                s_vspan = s_vspan.union(sd.vspan());
+                s_hspan
+            } else {
+                d_vspan = d_vspan.union(sd.vspan());
+                d_hspan
+            };
+            let rect = Rect::from_spans(hspan, sd.vspan());
+            let via = ctx.instantiate::<Via>(
+                &ViaParams::builder()
+                    .layers(li, m1)
+                    .geometry(rect, rect)
+                    .build(),
+            )?;
+            ctx.draw(via)?;
+        }
+
+        let mut gate_rect: Option<Rect> = None;
+        for rect in mos
+            .port("gate")?
+            .shapes(li)
+            .filter_map(|shape| shape.as_rect())
+        {
+            let via = ctx.instantiate::<Via>(
+                &ViaParams::builder()
+                    .layers(li, m1)
+                    .geometry(rect, rect)
+                    .build(),
+            )?;
+            let m1_rect = via.layer_bbox(m1).into_rect();
+            gate_rect = Some(gate_rect.map_or(m1_rect, |r| r.union(m1_rect.bbox()).into_rect()));
+            ctx.draw(via)?;
+        }
+        let gate_rect = gate_rect.unwrap();
+
+        for (name, rect) in [
+            ("d", Rect::from_spans(d_hspan, d_vspan)),
+            ("s", Rect::from_spans(s_hspan, s_vspan)),
+            ("g", gate_rect),
+        ] {
+            ctx.draw_rect(m1, rect);
+            ctx.add_port(CellPort::with_shape(name, m1, rect)).unwrap();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+//! Connections between a [`LogicBlock`](super::LogicBlock) and the cells beside it.
+
+use std::collections::HashSet;
+
+use subgeom::bbox::BoundBox;
+use subgeom::{Dir, Rect, Span};
+use substrate::error::Result;
+use substrate::layout::cell::{CellPort, Instance, Port, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::elements::via::{Via, ViaParams};
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::layers::LayerKey;
+use substrate::layout::routing::auto::grid::ExpandToGridStrategy;
+use substrate::layout::routing::auto::{GreedyRouter, GreedyRouterConfig, LayerConfig};
+use substrate::layout::routing::tracks::TrackLocator;
+
+/// Space left of a logic block for the straps that connect its supply rails, in nm.
+pub const STRAP_SPACE: i64 = 4_000;
+
+/// Pitch of the routing tracks of a [`Channel`].
+pub const TRACK_PITCH: i64 = 460;
+
+/// A routing channel beneath a row of cells with m1 pins on their bottom edges.
+///
+/// Pins are extended into the channel and connected to each other
+/// or to macro pins along the bottom of the channel.
+pub struct Channel {
+    m1: LayerKey,
+    top: i64,
+    area: Rect,
+    router: GreedyRouter,
+    used: HashSet<i64>,
+    routes: Vec<(Rect, Rect, String)>,
+}
+
+impl Channel {
+    /// Creates a channel spanning `hspan` beneath `top`, with `tracks` horizontal tracks.
+    pub fn new(ctx: &LayoutCtx, hspan: Span, top: i64, tracks: usize) -> Result<Self> {
+        let m1 = ctx.layers().get(Selector::Metal(1))?;
+        let m2 = ctx.layers().get(Selector::Metal(2))?;
+        let area = Rect::from_spans(hspan, Span::new(top - TRACK_PITCH * tracks as i64, top));
+        let router = GreedyRouter::with_config(GreedyRouterConfig {
+            area,
+            layers: vec![
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Horiz,
+                    layer: m1,
+                },
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Vert,
+                    layer: m2,
+                },
+            ],
+        });
+        Ok(Self {
+            m1,
+            top,
+            area,
+            router,
+            used: HashSet::new(),
+            routes: Vec::new(),
+        })
+    }
+
+    /// Extends the m1 `pin`, which ends at the top of the channel, into the channel.
+    ///
+    /// Returns the end of the extension on the routing grid.
+    pub fn pin(&mut self, ctx: &mut LayoutCtx, pin: Rect, net: &str) -> Result<Rect> {
+        let stop = self.top - 2 * TRACK_PITCH;
+        let rect = pin.with_vspan(pin.vspan().add_point(stop));
+        ctx.draw_rect(self.m1, rect);
+        self.router.block(self.m1, rect.expand(40));
+        let dst = self.router.expand_to_grid(
+            Rect::from_spans(rect.hspan(), Span::with_stop_and_length(stop, 320)),
+            ExpandToGridStrategy::Minimum,
+        );
+        ctx.draw_rect(self.m1, dst);
+        self.router.occupy(self.m1, dst, net)?;
+        Ok(dst)
+    }
+
+    /// Adds a macro pin on the bottom edge of the channel, as close as possible to
+    /// beneath `above`.
+    ///
+    /// Macro pins are at least three tracks apart. Returns the pin's point on the routing grid.
+    pub fn macro_pin(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        port: PortId,
+        above: Rect,
+        net: &str,
+    ) -> Result<Rect> {
+        let m2 = ctx.layers().get(Selector::Metal(2))?;
+        let htracks = self.router.track_info(self.m1).tracks();
+        let htrack =
+            htracks.index(htracks.track_with_loc(TrackLocator::StartsAfter, self.area.bottom()));
+        let vtracks = self.router.track_info(m2).tracks();
+        let mut track = vtracks.track_with_loc(TrackLocator::Nearest, above.center().x);
+        while (track - 2..=track + 2).any(|t| self.used.contains(&t)) {
+            track += 1;
+        }
+        self.used.insert(track);
+        let pin = Rect::from_spans(vtracks.index(track), htrack);
+
+        ctx.draw_rect(self.m1, pin);
+        self.router.occupy(self.m1, pin, net)?;
+        let rect = pin.with_vspan(pin.vspan().add_point(self.area.bottom()));
+        ctx.draw_rect(self.m1, rect);
+        ctx.add_port(CellPort::with_shape(port, self.m1, rect))?;
+        Ok(pin)
+    }
+
+    /// Connects `src` and `dst`, both returned by [`Channel::pin`] or [`Channel::macro_pin`].
+    pub fn connect(&mut self, src: Rect, dst: Rect, net: &str) {
+        self.routes.push((src, dst, net.to_string()));
+    }
+
+    /// Routes all connections and draws the routes.
+    pub fn draw(mut self, ctx: &mut LayoutCtx) -> Result<()> {
+        for (src, dst, net) in std::mem::take(&mut self.routes) {
+            self.router
+                .route_with_net(ctx, self.m1, src, self.m1, dst, &net)?;
+        }
+        ctx.draw(self.router)?;
+        Ok(())
+    }
+}
+
+/// Connects the supply rails of a logic block to m2 straps in the [`STRAP_SPACE`]
+/// to its left, adding the straps to the `vdd` and `vss` ports.
+///
+/// If `vdd_stop` is given, the `vdd` rails are also extended left to that coordinate.
+pub fn strap_supplies(ctx: &mut LayoutCtx, block: &Instance, vdd_stop: Option<i64>) -> Result<()> {
+    let m1 = ctx.layers().get(Selector::Metal(1))?;
+    let m2 = ctx.layers().get(Selector::Metal(2))?;
+    let left = block.brect().left();
+    let vdd_strap = Span::with_start_and_length(left - STRAP_SPACE + 600, 800);
+    let vss_strap = Span::with_start_and_length(vdd_strap.stop() + 600, 800);
+    for (net, strap) in [("vdd", vdd_strap), ("vss", vss_strap)] {
+        let strap = Rect::from_spans(strap, block.brect().vspan());
+        ctx.draw_rect(m2, strap);
+        ctx.merge_port(CellPort::with_shape(net, m2, strap));
+        for rail in block
+            .port(net)?
+            .shapes(m1)
+            .filter_map(|shape| shape.as_rect())
+            .filter(|rect| rect.left() == left)
+        {
+            let stop = match vdd_stop {
+                Some(stop) if net == "vdd" => stop,
+                _ => strap.left(),
+            };
+            let rail = rail.with_hspan(Span::new(stop, left));
+            ctx.draw_rect(m1, rail);
+            let via = ctx.instantiate::<Via>(
+                &ViaParams::builder()
+                    .layers(m1, m2)
+                    .geometry(rail, strap)
+                    .build(),
+            )?;
+            ctx.draw(via)?;
+        }
+    }
+    Ok(())
+}
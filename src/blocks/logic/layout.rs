@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use subgeom::bbox::BoundBox;
+use subgeom::orientation::Named;
+use subgeom::{Corner, Dir, Point, Rect, Span};
+use substrate::layout::cell::{CellPort, Port, PortConflictStrategy, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::elements::via::{Via, ViaParams};
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::layers::LayerBoundBox;
+use substrate::layout::placement::align::{AlignMode, AlignRect};
+use substrate::layout::placement::array::ArrayTiler;
+use substrate::layout::placement::tile::LayerBbox;
+use substrate::layout::routing::auto::grid::ExpandToGridStrategy;
+use substrate::layout::routing::auto::{GreedyRouter, GreedyRouterConfig, LayerConfig};
+use substrate::layout::routing::tracks::TrackLocator;
+use substrate::pdk::stdcell::StdCell;
+use substrate::schematic::circuit::Direction;
+
+use crate::blocks::control::layout::std_cell_row;
+
+use super::{LogicBlock, Net, VDD, VSS};
+
+/// Pitch of the routing tracks of a [`LogicBlock`] on each layer.
+const TRACK_PITCH: i64 = 460;
+
+/// Corners of the routing grid to which input pins are snapped, in the order of a cell's inputs.
+///
+/// Outputs are snapped to the lower right corner, so that no two pins of a cell
+/// expand onto the same grid point.
+const INPUT_CORNERS: [Corner; 3] = [Corner::UpperLeft, Corner::LowerLeft, Corner::UpperRight];
+
+/// The name of `net` in the router.
+fn net_name(net: Net) -> String {
+    match net {
+        VDD => "vdd".to_string(),
+        VSS => "vss".to_string(),
+        _ => format!("n_{net}"),
+    }
+}
+
+impl LogicBlock {
+    pub(crate) fn layout(&self, ctx: &mut LayoutCtx) -> substrate::error::Result<()> {
+        let gates = self.params.gates();
+        let layers = ctx.layers();
+        let outline = layers.get(Selector::Name("outline"))?;
+        let m0 = layers.get(Selector::Metal(0))?;
+        let m1 = layers.get(Selector::Metal(1))?;
+        let m2 = layers.get(Selector::Metal(2))?;
+        let grid = ctx.pdk().layout_grid();
+
+        let stdcells = ctx.inner().std_cell_db();
+        let lib = stdcells.try_lib_named("sky130_fd_sc_hs")?;
+        let tap = lib.try_cell_named("sky130_fd_sc_hs__tap_2")?;
+        let tap = ctx.instantiate::<StdCell>(&tap.id())?;
+        let mut cells = HashMap::new();
+        for gate in gates {
+            if !cells.contains_key(&gate.cell) {
+                let cell = lib.try_cell_named(gate.cell.cell_name())?;
+                cells.insert(gate.cell, ctx.instantiate::<StdCell>(&cell.id())?);
+            }
+        }
+
+        // Rows hold about as many cells as there are rows, keeping the block close to square.
+        // Alternate rows are flipped to share supply rails.
+        let per_row = (gates.len() as f64).sqrt().ceil() as usize;
+        let names = (0..gates.len())
+            .map(|i| format!("gate_{i}"))
+            .collect::<Vec<_>>();
+        let mut rows = ArrayTiler::builder();
+        rows.mode(AlignMode::Left).alt_mode(AlignMode::Beneath);
+        for (i, start) in (0..gates.len()).step_by(per_row).enumerate() {
+            let insts = (start..gates.len().min(start + per_row))
+                .map(|j| (names[j].as_str(), &cells[&gates[j].cell]))
+                .collect::<Vec<_>>();
+            let mut row = std_cell_row(&tap, outline, &insts)?;
+            if i % 2 == 1 {
+                row.set_orientation(Named::ReflectVert);
+            }
+            rows.push(LayerBbox::new(row, outline));
+        }
+        let mut rows = rows.build();
+        rows.expose_ports(
+            |port: CellPort, _| {
+                if let "vdd" | "vss" = port.name().as_str() {
+                    None
+                } else {
+                    Some(port)
+                }
+            },
+            PortConflictStrategy::Error,
+        )?;
+        rows.expose_ports(
+            |port: CellPort, _| {
+                if let "vdd" | "vss" = port.name().as_str() {
+                    Some(port)
+                } else {
+                    None
+                }
+            },
+            PortConflictStrategy::Merge,
+        )?;
+        let group = rows.generate()?;
+
+        // The router extends past the cells far enough to fit a pin for every port bit.
+        let body = group.brect();
+        let pin_count = self
+            .params
+            .ports()
+            .map(|(_, nets, _)| nets.len())
+            .sum::<usize>();
+        let area = Rect::from_spans(
+            body.hspan()
+                .union(Span::with_start_and_length(
+                    body.left(),
+                    3 * TRACK_PITCH * pin_count as i64,
+                ))
+                .expand_all(8 * 680),
+            body.vspan().expand_all(8 * 680),
+        );
+        let mut router = GreedyRouter::with_config(GreedyRouterConfig {
+            area,
+            layers: vec![
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Horiz,
+                    layer: m1,
+                },
+                LayerConfig {
+                    line: 320,
+                    space: 140,
+                    dir: Dir::Vert,
+                    layer: m2,
+                },
+            ],
+        });
+        for layer in [m1, m2] {
+            for shape in group.shapes_on(layer) {
+                router.block(layer, shape.brect().expand(40));
+            }
+        }
+
+        // Supply rails are extended to the left edge of the router,
+        // where they can be connected to the supplies of a parent cell.
+        ctx.add_ports(
+            group
+                .ports()
+                .filter(|port| matches!(port.name().as_str(), "vdd" | "vss")),
+        )?;
+        for net in ["vdd", "vss"] {
+            let mut rails: HashMap<Span, i64> = HashMap::new();
+            for rect in group
+                .port_map()
+                .port(net)?
+                .shapes(m1)
+                .filter_map(|shape| shape.as_rect())
+            {
+                let left = rails.entry(rect.vspan()).or_insert(rect.left());
+                *left = (*left).min(rect.left());
+            }
+            for (vspan, left) in rails {
+                let rail = Rect::from_spans(Span::new(area.left(), left), vspan);
+                ctx.draw_rect(m1, rail);
+                router.block(m1, rail.expand(40));
+                ctx.merge_port(CellPort::with_shape(net, m1, rail));
+            }
+        }
+
+        // Pins are spaced three tracks apart along the bottom of the router,
+        // since the routing grid of a parent cell may differ.
+        let htracks = router.track_info(m1).tracks().clone();
+        let vtracks = router.track_info(m2).tracks().clone();
+        let htrack =
+            htracks.index(htracks.track_with_loc(TrackLocator::StartsAfter, area.bottom()));
+        let vtrack_start = vtracks.track_with_loc(TrackLocator::StartsAfter, body.left());
+        let mut sources: HashMap<Net, Rect> = HashMap::new();
+        let mut sinks: Vec<(Net, Rect)> = Vec::new();
+        let bits = self.params.ports().flat_map(|(name, nets, direction)| {
+            nets.iter()
+                .enumerate()
+                .map(move |(i, &net)| (PortId::new(name.clone(), i), net, direction))
+        });
+        for (i, (port_id, net, direction)) in bits.enumerate() {
+            let pin = Rect::from_spans(vtracks.index(vtrack_start + 3 * i as i64), htrack);
+            ctx.draw_rect(m1, pin);
+            router.occupy(m1, pin, &net_name(net))?;
+            ctx.add_port(CellPort::with_shape(port_id, m1, pin))?;
+            match direction {
+                Direction::Input => {
+                    sources.insert(net, pin);
+                }
+                _ => sinks.push((net, pin)),
+            }
+        }
+
+        let via01 = ctx.instantiate::<Via>(
+            &ViaParams::builder()
+                .layers(m0, m1)
+                .geometry(
+                    Rect::from_point(Point::zero()),
+                    Rect::from_point(Point::zero()),
+                )
+                .bot_extension(Dir::Vert)
+                .top_extension(Dir::Vert)
+                .build(),
+        )?;
+        let snap_pin = |ctx: &mut LayoutCtx,
+                        router: &mut GreedyRouter,
+                        pin: &str,
+                        corner: Corner,
+                        net: &str|
+         -> substrate::error::Result<Rect> {
+            let port = group.port_map().port(pin)?.largest_rect(m0)?;
+            let mut via = via01.clone();
+            via.align_centers_gridded(port.bbox(), grid);
+            let rect = router.expand_to_grid(
+                via.layer_bbox(m1).into_rect(),
+                ExpandToGridStrategy::Corner(corner),
+            );
+            ctx.draw(via)?;
+            ctx.draw_rect(m1, rect);
+            router.occupy(m1, rect, net)?;
+            Ok(rect)
+        };
+
+        let mut ties = Vec::new();
+        for (i, gate) in gates.iter().enumerate() {
+            for ((pin, &net), corner) in gate
+                .cell
+                .inputs()
+                .iter()
+                .zip(&gate.inputs)
+                .zip(INPUT_CORNERS)
+            {
+                let rect = snap_pin(
+                    ctx,
+                    &mut router,
+                    &format!("gate_{i}_{}", pin.to_lowercase()),
+                    corner,
+                    &net_name(net),
+                )?;
+                if net == VDD || net == VSS {
+                    ties.push((net, rect));
+                } else {
+                    sinks.push((net, rect));
+                }
+            }
+            let rect = snap_pin(
+                ctx,
+                &mut router,
+                &format!("gate_{i}_{}", gate.cell.output().to_lowercase()),
+                Corner::LowerRight,
+                &net_name(gate.output),
+            )?;
+            sources.insert(gate.output, rect);
+        }
+
+        // Constant inputs are tied to the nearest rail of their supply.
+        for (net, pin) in ties {
+            let name = net_name(net);
+            let rail = group
+                .port_map()
+                .port(name.as_str())?
+                .shapes(m1)
+                .filter_map(|shape| shape.as_rect())
+                .min_by_key(|rail| (rail.center().y - pin.center().y).abs())
+                .unwrap();
+            let dst = router.expand_to_grid(
+                Rect::from_spans(
+                    Span::from_center_span_gridded(pin.center().x, 200, grid),
+                    rail.vspan(),
+                ),
+                ExpandToGridStrategy::Minimum,
+            );
+            ctx.draw_rect(m1, dst);
+            router.occupy(m1, dst, &name)?;
+            router.route_with_net(ctx, m1, pin, m1, dst, &name)?;
+        }
+
+        for (net, dst) in sinks {
+            router.route_with_net(ctx, m1, sources[&net], m1, dst, &net_name(net))?;
+        }
+        ctx.draw(router)?;
+        ctx.draw(group)?;
+
+        Ok(())
+    }
+}
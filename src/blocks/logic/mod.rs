@@ -0,0 +1,478 @@
+//! Blocks of random logic built from standard cells.
+
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+use substrate::component::{error, Component};
+use substrate::data::SubstrateCtx;
+use substrate::index::IndexOwned;
+use substrate::pdk::stdcell::StdCell;
+use substrate::schematic::circuit::Direction;
+use substrate::schematic::context::SchematicCtx;
+use substrate::schematic::signal::Slice;
+
+pub mod channel;
+pub mod layout;
+
+/// A net of a [`LogicBlock`].
+pub type Net = usize;
+
+/// The ground net of every [`LogicBlock`].
+pub const VSS: Net = 0;
+/// The supply net of every [`LogicBlock`].
+pub const VDD: Net = 1;
+
+/// The standard cells from which a [`LogicBlock`] is built.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum LogicCell {
+    Inv,
+    Buf,
+    Nand2,
+    Nor2,
+    And2,
+    Or2,
+    Xor2,
+    /// Selects `A1` when `S` is high and `A0` otherwise.
+    Mux2,
+    /// A flip-flop with an active low asynchronous reset.
+    Dff,
+}
+
+impl LogicCell {
+    /// The name of the cell in the standard cell library.
+    pub fn cell_name(&self) -> &'static str {
+        match self {
+            Self::Inv => "sky130_fd_sc_hs__inv_2",
+            Self::Buf => "sky130_fd_sc_hs__buf_2",
+            Self::Nand2 => "sky130_fd_sc_hs__nand2_2",
+            Self::Nor2 => "sky130_fd_sc_hs__nor2_2",
+            Self::And2 => "sky130_fd_sc_hs__and2_2",
+            Self::Or2 => "sky130_fd_sc_hs__or2_2",
+            Self::Xor2 => "sky130_fd_sc_hs__xor2_2",
+            Self::Mux2 => "sky130_fd_sc_hs__mux2_2",
+            Self::Dff => "sky130_fd_sc_hs__dfrbp_2",
+        }
+    }
+
+    /// The input pins of the cell, in the order of [`LogicGate::inputs`].
+    pub fn inputs(&self) -> &'static [&'static str] {
+        match self {
+            Self::Inv | Self::Buf => &["A"],
+            Self::Nand2 | Self::Nor2 | Self::And2 | Self::Or2 | Self::Xor2 => &["A", "B"],
+            Self::Mux2 => &["S", "A0", "A1"],
+            Self::Dff => &["CLK", "RESET_B", "D"],
+        }
+    }
+
+    /// The output pin of the cell.
+    pub fn output(&self) -> &'static str {
+        match self {
+            Self::Inv | Self::Nand2 | Self::Nor2 => "Y",
+            Self::Dff => "Q",
+            _ => "X",
+        }
+    }
+}
+
+/// A standard cell of a [`LogicBlock`] and the nets connected to its pins.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct LogicGate {
+    pub cell: LogicCell,
+    pub inputs: Vec<Net>,
+    pub output: Net,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct LogicBlockParams {
+    name: ArcStr,
+    nets: usize,
+    inputs: Vec<(ArcStr, Vec<Net>)>,
+    outputs: Vec<(ArcStr, Vec<Net>)>,
+    gates: Vec<LogicGate>,
+}
+
+impl LogicBlockParams {
+    #[inline]
+    pub fn name(&self) -> &ArcStr {
+        &self.name
+    }
+
+    #[inline]
+    pub fn gates(&self) -> &[LogicGate] {
+        &self.gates
+    }
+
+    /// The input and output ports, inputs first, with the nets of each bit.
+    pub fn ports(&self) -> impl Iterator<Item = (&ArcStr, &[Net], Direction)> {
+        self.inputs
+            .iter()
+            .map(|(name, nets)| (name, &nets[..], Direction::Input))
+            .chain(
+                self.outputs
+                    .iter()
+                    .map(|(name, nets)| (name, &nets[..], Direction::Output)),
+            )
+    }
+
+    /// The largest number of gates on a path from the `from` ports to the `to` ports.
+    ///
+    /// Paths end at flip-flops. Returns 0 if no `to` port is reachable.
+    pub fn depth(&self, from: &[&str], to: &[&str]) -> usize {
+        let ports = |names: &[&str]| {
+            self.ports()
+                .filter(|(name, _, _)| names.contains(&name.as_str()))
+                .flat_map(|(_, nets, _)| nets.iter().copied())
+                .collect::<Vec<_>>()
+        };
+        let mut levels = vec![None; self.nets];
+        for net in ports(from) {
+            levels[net] = Some(0);
+        }
+        // Gates need not be in topological order, so propagate until nothing changes.
+        // A path visits each gate at most once unless it loops, which would never settle.
+        for _ in 0..=self.gates.len() {
+            let mut changed = false;
+            for gate in self.gates.iter().filter(|gate| gate.cell != LogicCell::Dff) {
+                let level = gate
+                    .inputs
+                    .iter()
+                    .filter_map(|&net| levels[net])
+                    .max()
+                    .map(|level: usize| level + 1);
+                if level > levels[gate.output] {
+                    levels[gate.output] = level;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        ports(to)
+            .into_iter()
+            .filter_map(|net| levels[net])
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Builds the netlist of a [`LogicBlock`].
+///
+/// Each gate drives a new net, except for those driving output ports.
+pub struct LogicBuilder {
+    params: LogicBlockParams,
+}
+
+impl LogicBuilder {
+    pub fn new(name: impl Into<ArcStr>) -> Self {
+        Self {
+            params: LogicBlockParams {
+                name: name.into(),
+                nets: 2,
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                gates: Vec::new(),
+            },
+        }
+    }
+
+    pub fn build(self) -> LogicBlockParams {
+        self.params
+    }
+
+    /// Adds a net to be driven later by [`LogicBuilder::gate_to`],
+    /// such as the output of a flip-flop that feeds back to its own input.
+    pub fn net(&mut self) -> Net {
+        self.params.nets += 1;
+        self.params.nets - 1
+    }
+
+    fn nets(&mut self, width: usize) -> Vec<Net> {
+        (0..width).map(|_| self.net()).collect()
+    }
+
+    /// Adds an input port of the given width.
+    pub fn input(&mut self, name: impl Into<ArcStr>, width: usize) -> Vec<Net> {
+        let nets = self.nets(width);
+        self.params.inputs.push((name.into(), nets.clone()));
+        nets
+    }
+
+    /// Adds an output port of the given width, whose bits must each be driven by a gate.
+    pub fn output(&mut self, name: impl Into<ArcStr>, width: usize) -> Vec<Net> {
+        let nets = self.nets(width);
+        self.params.outputs.push((name.into(), nets.clone()));
+        nets
+    }
+
+    pub fn gate(&mut self, cell: LogicCell, inputs: &[Net]) -> Net {
+        let y = self.net();
+        self.gate_to(cell, inputs, y);
+        y
+    }
+
+    pub fn gate_to(&mut self, cell: LogicCell, inputs: &[Net], y: Net) {
+        assert_eq!(inputs.len(), cell.inputs().len());
+        self.params.gates.push(LogicGate {
+            cell,
+            inputs: inputs.to_vec(),
+            output: y,
+        });
+    }
+
+    /// Drives `y` with the value of `a`.
+    pub fn buf(&mut self, a: Net, y: Net) {
+        self.gate_to(LogicCell::Buf, &[a], y);
+    }
+
+    pub fn inv(&mut self, a: Net) -> Net {
+        self.gate(LogicCell::Inv, &[a])
+    }
+
+    pub fn nand2(&mut self, a: Net, b: Net) -> Net {
+        self.gate(LogicCell::Nand2, &[a, b])
+    }
+
+    pub fn nor2(&mut self, a: Net, b: Net) -> Net {
+        self.gate(LogicCell::Nor2, &[a, b])
+    }
+
+    pub fn and2(&mut self, a: Net, b: Net) -> Net {
+        self.gate(LogicCell::And2, &[a, b])
+    }
+
+    pub fn or2(&mut self, a: Net, b: Net) -> Net {
+        self.gate(LogicCell::Or2, &[a, b])
+    }
+
+    pub fn xor2(&mut self, a: Net, b: Net) -> Net {
+        self.gate(LogicCell::Xor2, &[a, b])
+    }
+
+    /// Selects `a1` when `s` is high and `a0` otherwise.
+    pub fn mux2(&mut self, s: Net, a0: Net, a1: Net) -> Net {
+        self.gate(LogicCell::Mux2, &[s, a0, a1])
+    }
+
+    /// A flip-flop clocked by `clk` and reset low while `rstb` is low.
+    pub fn dff(&mut self, clk: Net, rstb: Net, d: Net) -> Net {
+        self.gate(LogicCell::Dff, &[clk, rstb, d])
+    }
+
+    /// ANDs together all inputs. Returns [`VDD`] if there are no inputs.
+    pub fn and_all(&mut self, inputs: &[Net]) -> Net {
+        self.reduce(inputs, VDD, Self::and2)
+    }
+
+    /// ORs together all inputs. Returns [`VSS`] if there are no inputs.
+    pub fn or_all(&mut self, inputs: &[Net]) -> Net {
+        self.reduce(inputs, VSS, Self::or2)
+    }
+
+    /// XORs together all inputs. Returns [`VSS`] if there are no inputs.
+    pub fn xor_all(&mut self, inputs: &[Net]) -> Net {
+        self.reduce(inputs, VSS, Self::xor2)
+    }
+
+    /// Combines `inputs` with a balanced tree of `op`.
+    pub fn reduce(
+        &mut self,
+        inputs: &[Net],
+        empty: Net,
+        op: fn(&mut Self, Net, Net) -> Net,
+    ) -> Net {
+        if inputs.is_empty() {
+            return empty;
+        }
+        let mut level = inputs.to_vec();
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match *pair {
+                    [a, b] => op(self, a, b),
+                    [a] => a,
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        level[0]
+    }
+}
+
+/// A block of standard cells placed in rows and connected by a router.
+///
+/// Pins lie along the bottom of the block, inputs first.
+pub struct LogicBlock {
+    params: LogicBlockParams,
+}
+
+impl Component for LogicBlock {
+    type Params = LogicBlockParams;
+
+    fn new(params: &Self::Params, _ctx: &SubstrateCtx) -> substrate::error::Result<Self> {
+        let driven = params
+            .gates
+            .iter()
+            .map(|gate| gate.output)
+            .chain(
+                params
+                    .inputs
+                    .iter()
+                    .flat_map(|(_, nets)| nets.iter().copied()),
+            )
+            .collect::<Vec<_>>();
+        let mut sorted = driven.clone();
+        sorted.sort();
+        sorted.dedup();
+        // Every net has at most one driver, and every output and gate input is driven.
+        let undriven = |net: &Net| *net != VDD && *net != VSS && sorted.binary_search(net).is_err();
+        if params.gates.is_empty()
+            || sorted.len() != driven.len()
+            || sorted.contains(&VDD)
+            || sorted.contains(&VSS)
+            || params
+                .outputs
+                .iter()
+                .flat_map(|(_, nets)| nets)
+                .chain(params.gates.iter().flat_map(|gate| &gate.inputs))
+                .any(undriven)
+        {
+            return Err(error::Error::InvalidParams.into());
+        }
+        Ok(Self {
+            params: params.clone(),
+        })
+    }
+
+    fn name(&self) -> ArcStr {
+        self.params.name.clone()
+    }
+
+    fn schematic(&self, ctx: &mut SchematicCtx) -> substrate::error::Result<()> {
+        let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+        let mut nets: Vec<Option<Slice>> = vec![None; self.params.nets];
+        nets[VSS] = Some(vss);
+        nets[VDD] = Some(vdd);
+        for (name, bits, direction) in self.params.ports() {
+            let port = ctx.bus_port(name.clone(), bits.len(), direction);
+            for (i, &net) in bits.iter().enumerate() {
+                nets[net] = Some(port.index(i));
+            }
+        }
+        for (i, net) in nets.iter_mut().enumerate() {
+            if net.is_none() {
+                *net = Some(ctx.signal(arcstr::format!("n_{i}")));
+            }
+        }
+        let net = |net: Net| nets[net].unwrap();
+
+        let stdcells = ctx.inner().std_cell_db();
+        let lib = stdcells.try_lib_named("sky130_fd_sc_hs")?;
+        for (i, gate) in self.params.gates.iter().enumerate() {
+            let cell = lib.try_cell_named(gate.cell.cell_name())?;
+            let mut inst = ctx
+                .instantiate::<StdCell>(&cell.id())?
+                .with_connections([
+                    (gate.cell.output(), net(gate.output)),
+                    ("VPWR", vdd),
+                    ("VPB", vdd),
+                    ("VGND", vss),
+                    ("VNB", vss),
+                ])
+                .named(format!("gate_{i}"));
+            for (&pin, &input) in gate.cell.inputs().iter().zip(&gate.inputs) {
+                inst.connect(pin, net(input));
+            }
+            if gate.cell == LogicCell::Dff {
+                inst.connect("Q_N", ctx.signal(format!("gate_{i}_q_n")));
+            }
+            ctx.add_instance(inst);
+        }
+
+        Ok(())
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut substrate::layout::context::LayoutCtx,
+    ) -> substrate::error::Result<()> {
+        self.layout(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::paths::{out_gds, out_spice};
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+
+    use super::*;
+
+    fn full_adder() -> LogicBlockParams {
+        let mut logic = LogicBuilder::new("full_adder");
+        let [a, b, cin] = ["a", "b", "cin"].map(|name| logic.input(name, 1)[0]);
+        let [s, cout] = ["s", "cout"].map(|name| logic.output(name, 1)[0]);
+        let ab = logic.xor2(a, b);
+        let sum = logic.xor2(ab, cin);
+        logic.buf(sum, s);
+        let x = logic.nand2(a, b);
+        let y = logic.nand2(ab, cin);
+        let carry = logic.nand2(x, y);
+        logic.buf(carry, cout);
+        logic.build()
+    }
+
+    #[test]
+    fn test_logic_block_params() {
+        let ctx = setup_ctx();
+        assert!(ctx
+            .instantiate_schematic::<LogicBlock>(&full_adder())
+            .is_ok());
+
+        // An undriven output.
+        let mut logic = LogicBuilder::new("undriven");
+        let a = logic.input("a", 1)[0];
+        logic.output("y", 1);
+        logic.inv(a);
+        assert!(ctx
+            .instantiate_schematic::<LogicBlock>(&logic.build())
+            .is_err());
+    }
+
+    #[test]
+    fn test_logic_depth() {
+        let adder = full_adder();
+        assert_eq!(adder.depth(&["a", "b", "cin"], &["s", "cout"]), 4);
+        assert_eq!(adder.depth(&["cin"], &["s"]), 2);
+        assert_eq!(adder.depth(&["s"], &["cout"]), 0);
+    }
+
+    #[test]
+    fn test_logic_block() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_logic_block");
+        ctx.write_schematic_to_file::<LogicBlock>(&full_adder(), out_spice(&work_dir, "schematic"))
+            .expect("failed to write schematic");
+        ctx.write_layout::<LogicBlock>(&full_adder(), out_gds(&work_dir, "layout"))
+            .expect("failed to write layout");
+
+        #[cfg(feature = "commercial")]
+        {
+            let drc_work_dir = work_dir.join("drc");
+            let output = ctx
+                .write_drc::<LogicBlock>(&full_adder(), drc_work_dir)
+                .expect("failed to run DRC");
+            assert!(matches!(
+                output.summary,
+                substrate::verification::drc::DrcSummary::Pass
+            ));
+            let lvs_work_dir = work_dir.join("lvs");
+            let output = ctx
+                .write_lvs::<LogicBlock>(&full_adder(), lvs_work_dir)
+                .expect("failed to run LVS");
+            assert!(matches!(
+                output.summary,
+                substrate::verification::lvs::LvsSummary::Pass
+            ));
+        }
+    }
+}
@@ -292,7 +292,7 @@ pub struct SpHorizWlstrapP;
 #[hard_macro(
     name = "sram_sp_cell_opt1a",
     pdk = "sky130-open",
-    path_fn = "layout_path",
+    path_fn = "path",
     gds_cell_name = "sky130_fd_bd_sram__sram_sp_cell_opt1a",
     spice_subckt_name = "sky130_fd_bd_sram__sram_sp_cell_opt1a"
 )]
@@ -301,8 +301,9 @@ pub struct SpCellOpt1a;
 #[hard_macro(
     name = "sram_sp_cell_opt1a_replica",
     pdk = "sky130-open",
-    path_fn = "layout_path",
-    gds_cell_name = "sky130_fd_bd_sram__openram_sp_cell_opt1a_replica"
+    path_fn = "path",
+    gds_cell_name = "sky130_fd_bd_sram__openram_sp_cell_opt1a_replica",
+    spice_subckt_name = "sram_sp_cell_opt1a_replica"
 )]
 pub struct SpCellOpt1aReplica;
 
@@ -378,6 +379,15 @@ pub struct SpWlstrapa;
 )]
 pub struct SpWlstrapaP;
 
+#[hard_macro(
+    name = "openram_dp_cell",
+    pdk = "sky130-open",
+    path_fn = "path",
+    gds_cell_name = "sky130_fd_bd_sram__openram_dp_cell",
+    spice_subckt_name = "openram_dp_cell"
+)]
+pub struct DpCell;
+
 #[cfg(test)]
 mod tests {
 
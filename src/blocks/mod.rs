@@ -1,12 +1,15 @@
+pub mod bist;
 pub mod bitcell_array;
 pub mod coarse_tdc;
 pub mod columns;
 pub mod control;
 pub mod decoder;
 pub mod delay_line;
+pub mod ecc;
 pub mod gate;
 pub mod guard_ring;
 pub mod latch;
+pub mod logic;
 pub mod macros;
 pub mod precharge;
 pub mod senseamp;
@@ -0,0 +1,292 @@
+//! Banks that divide the words of an [`Sram`](super::Sram) among several bitcell arrays.
+//!
+//! The banks share the input registers, control logic and row decoder of the SRAM core,
+//! which drives a bank select decoder that enables the wordline drivers of one bank.
+//! Each bank has its own column circuitry, whose outputs are selected by [`bank_logic`].
+//! The two halves of an array with divided wordlines are organized as two such banks.
+
+use std::collections::HashMap;
+
+use subgeom::bbox::BoundBox;
+use subgeom::{Rect, Span};
+use substrate::error::Result;
+use substrate::index::IndexOwned;
+use substrate::layout::cell::{Port, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::group::Group;
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::placement::align::AlignRect;
+use substrate::schematic::circuit::Direction;
+use substrate::schematic::context::SchematicCtx;
+use substrate::schematic::signal::Signal;
+
+use crate::blocks::logic::channel::{strap_supplies, Channel, STRAP_SPACE};
+use crate::blocks::logic::{LogicBlock, LogicBlockParams, LogicBuilder, LogicCell};
+
+use super::{OutputMode, PortStyle, SramInner, SramParams, WriteMode};
+
+/// Ports of the SRAM core that are connected to the bank logic rather than to pins.
+const CORE_PORTS: [&str; 3] = ["wmask", "din", "dout"];
+
+/// The bank select register and the output mux of a banked SRAM.
+///
+/// All banks sense and latch their bitlines on every read, but only the bank selected by
+/// the most significant bits of `addr` has a wordline enabled. The register holds the bank
+/// of the most recent access that updates `dout`, and the mux passes that bank's data to `dout`.
+pub fn bank_logic(params: &SramParams) -> LogicBlockParams {
+    let banks = params.banks();
+    let bits = params.bank_select_bits();
+    let dw = params.data_width();
+    let port_style = params.port_style();
+
+    let mut logic = LogicBuilder::new(arcstr::format!("{}_bank_mux", params.name()));
+    let [clk, rstb, enable, write] = [
+        "clk",
+        "rstb",
+        port_style.enable_pin(),
+        port_style.write_pin(),
+    ]
+    .map(|name| logic.input(name, 1)[0]);
+    let bank_addr = logic.input("bank_addr", bits);
+    let bank_dout = logic.input("bank_dout", banks * dw);
+    let dout = logic.output("dout", dw);
+
+    // An access updates `dout` if it is a read, or a write that is written through.
+    let (enable, write) = if port_style.active_low() {
+        (logic.inv(enable), logic.inv(write))
+    } else {
+        (enable, write)
+    };
+    let update = match params.write_mode() {
+        WriteMode::NoChange => {
+            let write_b = logic.inv(write);
+            logic.and2(enable, write_b)
+        }
+        WriteMode::WriteThrough if port_style == PortStyle::RenWen => logic.or2(enable, write),
+        WriteMode::WriteThrough => enable,
+    };
+
+    let mut sel = (0..bits)
+        .map(|i| {
+            let q = logic.net();
+            let d = logic.mux2(update, q, bank_addr[i]);
+            logic.gate_to(LogicCell::Dff, &[clk, rstb, d], q);
+            q
+        })
+        .collect::<Vec<_>>();
+    // Registered outputs appear a cycle later, and so do their banks.
+    if params.output() == OutputMode::Registered {
+        sel = sel.into_iter().map(|q| logic.dff(clk, rstb, q)).collect();
+    }
+
+    for j in 0..dw {
+        let mut level = (0..banks)
+            .map(|b| bank_dout[b * dw + j])
+            .collect::<Vec<_>>();
+        for &s in sel.iter() {
+            level = level
+                .chunks(2)
+                .map(|pair| logic.mux2(s, pair[0], pair[1]))
+                .collect();
+        }
+        logic.buf(level[0], dout[j]);
+    }
+
+    logic.build()
+}
+
+/// Draws the schematic of a banked SRAM.
+///
+/// `din` and `wmask` are shared by all banks of the SRAM core,
+/// while the data of each bank reaches `dout` through the [`bank_logic`].
+pub(crate) fn draw_bank_schematic(ctx: &mut SchematicCtx, params: &SramParams) -> Result<()> {
+    let banks = params.banks();
+    let port_style = params.port_style();
+    let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+    let [clk, rstb, enable, write] = ctx.ports(
+        [
+            "clk",
+            "rstb",
+            port_style.enable_pin(),
+            port_style.write_pin(),
+        ],
+        Direction::Input,
+    );
+    let addr = ctx.bus_port("addr", params.addr_width(), Direction::Input);
+    let wmask = ctx.bus_port("wmask", params.wmask_width(), Direction::Input);
+    let din = ctx.bus_port("din", params.data_width(), Direction::Input);
+    let dout = ctx.bus_port("dout", params.data_width(), Direction::Output);
+    let bank_dout = ctx.bus("bank_dout", banks * params.data_width());
+
+    let mut inner = ctx
+        .instantiate::<SramInner>(params)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("clk", clk),
+            ("rstb", rstb),
+            (port_style.enable_pin(), enable),
+            (port_style.write_pin(), write),
+            ("addr", addr),
+            ("dout", bank_dout),
+        ])
+        .with_connection("wmask", Signal::new(vec![wmask; banks]))
+        .with_connection("din", Signal::new(vec![din; banks]))
+        .named("sram");
+    ctx.bubble_filter_map(&mut inner, |port| {
+        let name = port.name().as_ref();
+        (!CORE_PORTS.contains(&name)
+            && ![
+                "vdd",
+                "vss",
+                "clk",
+                "rstb",
+                "addr",
+                port_style.enable_pin(),
+                port_style.write_pin(),
+            ]
+            .contains(&name))
+        .then_some(port.name().into())
+    });
+    ctx.add_instance(inner);
+
+    ctx.instantiate::<LogicBlock>(&bank_logic(params))?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("clk", clk),
+            ("rstb", rstb),
+            (port_style.enable_pin(), enable),
+            (port_style.write_pin(), write),
+            (
+                "bank_addr",
+                addr.index(params.addr_width() - params.bank_select_bits()..),
+            ),
+            ("bank_dout", bank_dout),
+            ("dout", dout),
+        ])
+        .named("bank_mux")
+        .add_to(ctx);
+
+    Ok(())
+}
+
+/// Places the [`bank_logic`] to the right of `group` and connects it through a channel
+/// beneath both, drawing `group` and adding the macro pins.
+///
+/// `group` holds the SRAM core and its guard ring, with the core's pins
+/// extended to the bottom of the ring. The `din` and `wmask` pins of the macro
+/// fan out to those of every bank.
+pub(crate) fn draw_bank_logic(
+    ctx: &mut LayoutCtx,
+    group: Group,
+    params: &SramParams,
+) -> Result<()> {
+    let m1 = ctx.layers().get(Selector::Metal(1))?;
+    let banks = params.banks();
+    let bank_addr = params.addr_width() - params.bank_select_bits();
+    let dw = params.data_width();
+    let trim_bits = params.timing_trim().unwrap_or_default();
+    let port_style = params.port_style();
+
+    let mut stubs = HashMap::new();
+    for port in group.ports() {
+        if let "vdd" | "vss" = port.name().as_str() {
+            ctx.merge_port(port);
+        } else {
+            stubs.insert(port.id().clone(), port.largest_rect(m1)?);
+        }
+    }
+    let ring_vdd = group
+        .port_map()
+        .port("vdd")?
+        .shapes(m1)
+        .filter_map(|shape| shape.as_rect())
+        .max_by_key(|rect| rect.right())
+        .unwrap();
+    let brect = group.brect();
+    ctx.draw(group)?;
+
+    let mut logic = ctx.instantiate::<LogicBlock>(&bank_logic(params))?;
+    logic.align_to_the_right_of(brect, STRAP_SPACE);
+    logic.align_bottom(brect);
+    strap_supplies(ctx, &logic, Some(ring_vdd.left()))?;
+
+    let nets = 2 * (params.addr_width() + params.bank_select_bits() + 8 + 2 * trim_bits)
+        + 2 * (banks + 1) * (2 * dw + params.wmask_width());
+    let mut channel = Channel::new(
+        ctx,
+        Span::new(brect.left(), logic.brect().right()),
+        brect.bottom(),
+        nets + 16,
+    )?;
+    let logic_pin = |port: &str, i: usize| -> Result<Rect> {
+        logic.port(PortId::new(port, i))?.largest_rect(m1)
+    };
+
+    // The clock, reset, enables and bank bits go to both the SRAM core and the bank logic.
+    let shared = [
+        "clk",
+        "rstb",
+        port_style.enable_pin(),
+        port_style.write_pin(),
+    ]
+    .into_iter()
+    .map(|port| (port, 0, PortId::new(port, 0)))
+    .chain(
+        (bank_addr..params.addr_width())
+            .map(|i| ("addr", i, PortId::new("bank_addr", i - bank_addr))),
+    );
+    for (port, i, logic_port) in shared {
+        let net = format!("{port}_{i}");
+        let sram = channel.pin(ctx, stubs[&PortId::new(port, i)], &net)?;
+        let bank_mux = channel.pin(ctx, logic.port(logic_port)?.largest_rect(m1)?, &net)?;
+        let pin = channel.macro_pin(ctx, PortId::new(port, i), sram, &net)?;
+        channel.connect(pin, sram, &net);
+        channel.connect(pin, bank_mux, &net);
+    }
+    for (port, width) in [
+        ("addr", bank_addr),
+        ("trim_saen", trim_bits),
+        ("trim_wl", trim_bits),
+    ] {
+        for i in 0..width {
+            let net = format!("{port}_{i}");
+            let sram = channel.pin(ctx, stubs[&PortId::new(port, i)], &net)?;
+            let pin = channel.macro_pin(ctx, PortId::new(port, i), sram, &net)?;
+            channel.connect(pin, sram, &net);
+        }
+    }
+
+    // Data inputs and write mask bits are written to every bank.
+    for (port, width) in [("din", dw), ("wmask", params.wmask_width())] {
+        for i in 0..width {
+            let net = format!("{port}_{i}");
+            let dsts = (0..banks)
+                .map(|b| channel.pin(ctx, stubs[&PortId::new(port, b * width + i)], &net))
+                .collect::<Result<Vec<_>>>()?;
+            let pin = channel.macro_pin(ctx, PortId::new(port, i), dsts[0], &net)?;
+            for dst in dsts {
+                channel.connect(pin, dst, &net);
+            }
+        }
+    }
+
+    // Data outputs of every bank pass through the output mux.
+    for i in 0..banks * dw {
+        let net = format!("bank_dout_{i}");
+        let src = channel.pin(ctx, stubs[&PortId::new("dout", i)], &net)?;
+        let dst = channel.pin(ctx, logic_pin("bank_dout", i)?, &net)?;
+        channel.connect(src, dst, &net);
+    }
+    for i in 0..dw {
+        let net = format!("dout_{i}");
+        let src = channel.pin(ctx, logic_pin("dout", i)?, &net)?;
+        let pin = channel.macro_pin(ctx, PortId::new("dout", i), src, &net)?;
+        channel.connect(src, pin, &net);
+    }
+    channel.draw(ctx)?;
+    ctx.draw(logic)?;
+
+    Ok(())
+}
@@ -0,0 +1,523 @@
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+use subgeom::bbox::BoundBox;
+use subgeom::orientation::Named;
+use subgeom::transform::Translate;
+use subgeom::{Corner, Dir, Point, Rect, Span};
+use substrate::component::{error, Component};
+use substrate::data::SubstrateCtx;
+use substrate::error::ErrorSource;
+use substrate::layout::cell::{CellPort, Element, Instance, PortConflictStrategy, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::elements::via::{Via, ViaExpansion, ViaParams};
+use substrate::layout::group::Group;
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::layers::{LayerKey, LayerSpec};
+use substrate::layout::routing::auto::straps::PlacedStraps;
+use substrate::layout::straps::SingleSupplyNet;
+use substrate::schematic::circuit::Direction;
+use substrate::schematic::context::SchematicCtx;
+use substrate::schematic::signal::{Signal, Slice};
+
+use crate::blocks::bitcell_array::dual_port::{DpCellArray, DpCellArrayParams};
+use crate::blocks::guard_ring::{GuardRing, GuardRingParams, SupplyRings};
+
+use super::schematic::{port_periphery, ColumnSignals, PeripherySignals};
+use super::{
+    connect_straps_to_rings, CoreStrap, OutputMode, PortStyle, SramInner, SramParams,
+    SramPhysicalDesignScript, WriteMode,
+};
+
+/// A 1R1W SRAM built from dual-port bitcells.
+///
+/// The write port (`wclk`, `wce`, `waddr`, `wmask`, `din`) and
+/// the read port (`rclk`, `rce`, `raddr`, `dout`) each have their own
+/// input registers, decoders, control logic and column circuitry.
+/// Port 0 of the bitcell array is used for writes and port 1 for reads.
+///
+/// The periphery of the write port is laid out beneath and to the left of the array,
+/// and that of the read port is rotated by 180 degrees to lie above and to its right.
+/// The read port therefore sees the rows and columns of the array in reverse,
+/// which it undoes by complementing its registered address and reversing its data bits.
+pub struct Sram1r1w {
+    params: Sram1r1wParams,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct Sram1r1wParams {
+    /// Parameters shared by the read and write ports.
+    pub port: SramParams,
+}
+
+impl Sram1r1wParams {
+    pub const fn new(port: SramParams) -> Self {
+        Self { port }
+    }
+
+    #[inline]
+    pub fn num_words(&self) -> usize {
+        self.port.num_words()
+    }
+
+    #[inline]
+    pub fn data_width(&self) -> usize {
+        self.port.data_width()
+    }
+
+    #[inline]
+    pub fn wmask_width(&self) -> usize {
+        self.port.wmask_width()
+    }
+
+    #[inline]
+    pub fn addr_width(&self) -> usize {
+        self.port.addr_width()
+    }
+
+    /// The name of the 1R1W SRAM cell with these parameters.
+    pub fn name(&self) -> ArcStr {
+        arcstr::format!("{}_1r1w", self.port.name())
+    }
+}
+
+/// The periphery of one port of a [`Sram1r1w`], drawn around its bitcell array.
+///
+/// Only has a layout, in which the bitcell array is left for [`Sram1r1w`] to draw.
+pub struct Sram1r1wPort {
+    params: Sram1r1wPortParams,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct Sram1r1wPortParams {
+    /// Parameters shared by the read and write ports.
+    pub sram: SramParams,
+    /// The port of the bitcell array, 0 for the write port or 1 for the read port.
+    pub port: usize,
+}
+
+impl Component for Sram1r1wPort {
+    type Params = Sram1r1wPortParams;
+
+    fn new(params: &Self::Params, _ctx: &SubstrateCtx) -> substrate::error::Result<Self> {
+        if params.port > 1 {
+            return Err(error::Error::InvalidParams.into());
+        }
+        Ok(Self { params: *params })
+    }
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("sram22_1r1w_port{}", self.params.port)
+    }
+
+    fn schematic(&self, _ctx: &mut SchematicCtx) -> substrate::error::Result<()> {
+        Err(ErrorSource::Component(error::Error::ViewUnsupported(
+            substrate::component::View::Schematic,
+        ))
+        .into())
+    }
+
+    fn layout(&self, ctx: &mut LayoutCtx) -> substrate::error::Result<()> {
+        SramInner {
+            params: self.params.sram,
+        }
+        .layout_dual_port(self.params.port, ctx)
+    }
+}
+
+impl Component for Sram1r1w {
+    type Params = Sram1r1wParams;
+
+    fn new(params: &Self::Params, _ctx: &SubstrateCtx) -> substrate::error::Result<Self> {
+        // Each port has its own control logic, and the trim pins are not yet shared between them.
+        // The other options change the pins or the organization of a single-port SRAM,
+        // which the two ports and the behavioral model do not share.
+        let port = &params.port;
+        if port.timing_trim().is_some()
+            || port.port_style() != PortStyle::CeWe
+            || port.write_mode() != WriteMode::NoChange
+            || port.output() != OutputMode::Latched
+            || port.power_gating()
+            || port.redundancy().is_some()
+            || port.split_array()
+            || port.banks() > 1
+            || port.pin_placement().is_some()
+            || port.power_straps().is_some()
+        {
+            return Err(error::Error::InvalidParams.into());
+        }
+        Ok(Self { params: *params })
+    }
+
+    fn name(&self) -> ArcStr {
+        self.params.name()
+    }
+
+    fn schematic(&self, ctx: &mut SchematicCtx) -> substrate::error::Result<()> {
+        let params = &self.params.port;
+        let dsn = ctx.inner().run_script::<SramPhysicalDesignScript>(params)?;
+
+        let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+        let [wclk, rclk, wce, rce, rstb] =
+            ctx.ports(["wclk", "rclk", "wce", "rce", "rstb"], Direction::Input);
+        let waddr = ctx.bus_port("waddr", params.addr_width(), Direction::Input);
+        let raddr = ctx.bus_port("raddr", params.addr_width(), Direction::Input);
+        let wmask = ctx.bus_port("wmask", params.wmask_width(), Direction::Input);
+        let din = ctx.bus_port("din", params.data_width(), Direction::Input);
+        let dout = ctx.bus_port("dout", params.data_width(), Direction::Output);
+
+        let [bl0, br0, bl1, br1] = ctx.buses(["bl0", "br0", "bl1", "br1"], params.cols());
+        let [wl0, wl1] = ctx.buses(["wl0", "wl1"], params.rows());
+        let w_dout_noconn = ctx.bus("w_dout_noconn", params.data_width());
+
+        // Write port. The sense amplifiers of this port are never enabled.
+        port_periphery(
+            ctx,
+            params,
+            &dsn,
+            "w_",
+            PeripherySignals {
+                vdd,
+                vss,
+                clk: wclk,
+                we: vdd,
+                ce: wce,
+                rstb,
+                addr: waddr,
+                wl: wl0,
+                columns: vec![ColumnSignals {
+                    bl: bl0,
+                    br: br0,
+                    wmask: wmask.into(),
+                    din: din.into(),
+                    dout: w_dout_noconn.into(),
+                }],
+                trim: None,
+                sleep: None,
+                bank_en: None,
+                complement_addr: false,
+            },
+        )?;
+
+        // Read port. The write drivers of this port are never enabled.
+        //
+        // The port sees the rows and columns of the array in reverse, so its address is
+        // complemented and its data bits are reversed.
+        port_periphery(
+            ctx,
+            params,
+            &dsn,
+            "r_",
+            PeripherySignals {
+                vdd,
+                vss,
+                clk: rclk,
+                we: vss,
+                ce: rce,
+                rstb,
+                addr: raddr,
+                wl: wl1,
+                columns: vec![ColumnSignals {
+                    bl: bl1,
+                    br: br1,
+                    wmask: Signal::new(vec![vss; params.wmask_width()]),
+                    din: Signal::new(vec![vss; params.data_width()]),
+                    dout: Signal::new(
+                        (0..params.data_width())
+                            .rev()
+                            .map(|i| dout.index(i))
+                            .collect(),
+                    ),
+                }],
+                trim: None,
+                sleep: None,
+                bank_en: None,
+                complement_addr: true,
+            },
+        )?;
+
+        let reversed =
+            |bus: Slice| Signal::new((0..bus.width()).rev().map(|i| bus.index(i)).collect());
+
+        ctx.instantiate::<DpCellArray>(&DpCellArrayParams {
+            rows: dsn.bitcells.rows,
+            cols: dsn.bitcells.cols,
+            mux_ratio: dsn.bitcells.mux_ratio,
+        })?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("bl0", bl0),
+            ("br0", br0),
+            ("wl0", wl0),
+        ])
+        .with_connections([
+            ("bl1", reversed(bl1)),
+            ("br1", reversed(br1)),
+            ("wl1", reversed(wl1)),
+        ])
+        .named("bitcell_array")
+        .add_to(ctx);
+
+        Ok(())
+    }
+
+    fn layout(&self, ctx: &mut LayoutCtx) -> substrate::error::Result<()> {
+        let params = &self.params.port;
+        let dsn = ctx.inner().run_script::<SramPhysicalDesignScript>(params)?;
+        let layers = ctx.layers();
+        let outline = layers.get(Selector::Name("outline"))?;
+        let m1 = layers.get(Selector::Metal(1))?;
+        let m2 = layers.get(Selector::Metal(2))?;
+        let m3 = layers.get(Selector::Metal(3))?;
+
+        let mut group = Group::new();
+        let array_params = DpCellArrayParams {
+            rows: dsn.bitcells.rows,
+            cols: dsn.bitcells.cols,
+            mux_ratio: dsn.bitcells.mux_ratio,
+        };
+        let array = ctx.instantiate::<DpCellArray>(&array_params)?;
+        let abrect = array.brect();
+
+        // Align the array drawn around by each periphery with the real one.
+        let mut ports = Vec::with_capacity(2);
+        for port in 0..2 {
+            let mut inst = ctx.instantiate::<Sram1r1wPort>(&Sram1r1wPortParams {
+                sram: *params,
+                port,
+            })?;
+            if port == 1 {
+                inst.set_orientation(Named::R180);
+            }
+            let ghost = inst.port("array")?.largest_rect(outline)?;
+            inst.translate(Point::new(
+                abrect.left() - ghost.left(),
+                abrect.bottom() - ghost.bottom(),
+            ));
+            ports.push(inst);
+        }
+
+        let core = ports
+            .iter()
+            .fold(array.bbox(), |bbox, inst| bbox.union(inst.bbox()))
+            .into_rect();
+        let ring = ctx.instantiate::<GuardRing>(&GuardRingParams {
+            enclosure: core.expand(RSTB_CHANNEL),
+            h_metal: m2,
+            v_metal: m1,
+            h_width: 1_360,
+            v_width: 1_360,
+            switches: None,
+        })?;
+        let rings = ring.cell().get_metadata::<SupplyRings>();
+
+        // The power straps of the read port are placed in the coordinates of its periphery,
+        // which is rotated by 180 degrees about the array.
+        let ghost = array.clone().with_orientation(Named::R180).brect();
+        let (tx, ty) = (abrect.left() + ghost.right(), abrect.bottom() + ghost.top());
+        let mut straps = Vec::new();
+        for (port, inst) in ports.iter().enumerate() {
+            let placed = inst.cell().get_metadata::<PlacedStraps>();
+            for layer in [m1, m2] {
+                straps.extend(placed.on_layer(layer).map(|strap| {
+                    let rect = strap.rect;
+                    if port == 0 {
+                        CoreStrap {
+                            layer,
+                            rect,
+                            net: strap.net,
+                            lower_boundary: strap.lower_boundary,
+                            upper_boundary: strap.upper_boundary,
+                        }
+                    } else {
+                        CoreStrap {
+                            layer,
+                            rect: Rect::new(
+                                Point::new(tx - rect.right(), ty - rect.top()),
+                                Point::new(tx - rect.left(), ty - rect.bottom()),
+                            ),
+                            net: strap.net,
+                            lower_boundary: strap.upper_boundary,
+                            upper_boundary: strap.lower_boundary,
+                        }
+                    }
+                }));
+            }
+        }
+        connect_straps_to_rings(ctx, &mut group, straps, rings, false)?;
+
+        for port in ["vdd", "vss"] {
+            group.add_port_with_strategy(
+                ring.port(format!("ring_{port}"))?
+                    .into_cell_port()
+                    .named(port),
+                PortConflictStrategy::Merge,
+            )?;
+            for inst in ports.iter() {
+                group.add_port_with_strategy(inst.port(port)?, PortConflictStrategy::Merge)?;
+            }
+        }
+
+        // Route the pins of the write port to the bottom edge of the guard ring,
+        // and those of the read port to its top edge.
+        let ring_bbox = ring.bbox().into_rect();
+        let data_width = params.data_width();
+        for (port, edge, pins) in [
+            (
+                0,
+                ring_bbox.bottom(),
+                vec![
+                    ("addr", "waddr", params.addr_width()),
+                    ("din", "din", data_width),
+                    ("wmask", "wmask", params.wmask_width()),
+                    ("ce", "wce", 1),
+                    ("clk", "wclk", 1),
+                    ("rstb", "rstb", 1),
+                ],
+            ),
+            (
+                1,
+                ring_bbox.top(),
+                vec![
+                    ("addr", "raddr", params.addr_width()),
+                    ("dout", "dout", data_width),
+                    ("ce", "rce", 1),
+                    ("clk", "rclk", 1),
+                ],
+            ),
+        ] {
+            for (name, pin, width) in pins {
+                for i in 0..width {
+                    let rect = ports[port].port(PortId::new(name, i))?.largest_rect(m1)?;
+                    let rect = rect.with_vspan(rect.vspan().add_point(edge));
+                    // The read port sees the data bits in reverse.
+                    let idx = if name == "dout" { width - 1 - i } else { i };
+                    group.add(Element::new(LayerSpec::drawing(m1), rect));
+                    group.add_port(
+                        CellPort::builder()
+                            .id(PortId::new(pin, idx))
+                            .add(m1, rect)
+                            .build(),
+                    )?;
+                }
+            }
+        }
+
+        // Tie the write enable of the write port high, and the write enable, data and
+        // write mask of the read port low, to the nearest horizontal rail of the supply ring.
+        for (port, name, width, net) in [
+            (0, "we", 1, SingleSupplyNet::Vdd),
+            (1, "we", 1, SingleSupplyNet::Vss),
+            (1, "din", data_width, SingleSupplyNet::Vss),
+            (1, "wmask", params.wmask_width(), SingleSupplyNet::Vss),
+        ] {
+            let ring = match net {
+                SingleSupplyNet::Vdd => rings.vdd,
+                SingleSupplyNet::Vss => rings.vss,
+            };
+            let rail = ring.dir_rects(Dir::Horiz)[port];
+            for i in 0..width {
+                let rect = ports[port].port(PortId::new(name, i))?.largest_rect(m1)?;
+                let rect = rect.with_vspan(rect.vspan().union(rail.vspan()));
+                group.add(Element::new(LayerSpec::drawing(m1), rect));
+                group.add_instance(via(m1, rect, m2, rail, ctx)?);
+            }
+        }
+
+        // Both ports share `rstb`, whose pin is beneath the SRAM. The reset of the read port
+        // is brought to it on m3 around the left of the SRAM, between the SRAM and its guard ring.
+        let mut pads = Vec::with_capacity(2);
+        for (port, vspan) in [
+            (0, Span::with_stop_and_length(core.bottom() - 1_000, 800)),
+            (1, Span::with_start_and_length(core.top() + 1_000, 800)),
+        ] {
+            let rect = ports[port].port("rstb")?.largest_rect(m1)?;
+            let pad = rect.with_vspan(vspan);
+            let rect = rect.with_vspan(rect.vspan().union(vspan));
+            group.add(Element::new(LayerSpec::drawing(m1), rect));
+            group.add(Element::new(LayerSpec::drawing(m2), pad));
+            group.add(Element::new(LayerSpec::drawing(m3), pad));
+            group.add_instance(via(m1, rect, m2, pad, ctx)?);
+            group.add_instance(via(m2, pad, m3, pad, ctx)?);
+            pads.push(pad);
+        }
+        let (w_pad, r_pad) = (pads[0], pads[1]);
+        let spine = Span::with_stop_and_length(core.left() - RSTB_CHANNEL / 2, 800);
+        for rect in [
+            Rect::from_spans(spine.union(w_pad.hspan()), w_pad.vspan()),
+            Rect::from_spans(spine, w_pad.vspan().union(r_pad.vspan())),
+            Rect::from_spans(spine.union(r_pad.hspan()), r_pad.vspan()),
+        ] {
+            group.add(Element::new(LayerSpec::drawing(m3), rect));
+        }
+
+        group.add_instance(array);
+        for inst in ports {
+            group.add_instance(inst);
+        }
+        group.add_instance(ring);
+
+        group.place(Corner::LowerLeft, Point::zero());
+        ctx.add_ports(group.ports())?;
+        ctx.draw(group)?;
+
+        Ok(())
+    }
+}
+
+/// The space between a 1R1W SRAM and its guard ring, in which the reset of the read port
+/// runs to the `rstb` pin.
+const RSTB_CHANNEL: i64 = 3_000;
+
+/// Instantiates a via between two rects, with the bottom layer first.
+fn via(
+    layer1: LayerKey,
+    rect1: Rect,
+    layer2: LayerKey,
+    rect2: Rect,
+    ctx: &mut LayoutCtx,
+) -> substrate::error::Result<Instance> {
+    ctx.instantiate::<Via>(
+        &ViaParams::builder()
+            .layers(layer1, layer2)
+            .geometry(rect1, rect2)
+            .expand(ViaExpansion::LongerDirection)
+            .build(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::sram::MuxRatio;
+    use crate::paths::*;
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+    use crate::verilog::save_1r1w_verilog;
+
+    use super::*;
+
+    const SRAM22_64X32M4W8_1R1W: Sram1r1wParams =
+        Sram1r1wParams::new(SramParams::new(8, MuxRatio::M4, 64, 32));
+
+    #[test]
+    fn test_sram_1r1w() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sram_1r1w");
+        let name = SRAM22_64X32M4W8_1R1W.name();
+        ctx.write_schematic_to_file::<Sram1r1w>(
+            &SRAM22_64X32M4W8_1R1W,
+            out_spice(&work_dir, "schematic"),
+        )
+        .expect("failed to write schematic");
+        save_1r1w_verilog(out_verilog(&work_dir, &*name), &SRAM22_64X32M4W8_1R1W)
+            .expect("failed to write behavioral model");
+        ctx.write_layout::<Sram1r1w>(&SRAM22_64X32M4W8_1R1W, out_gds(&work_dir, &*name))
+            .expect("failed to write layout");
+        crate::abs::write_component_abstract::<Sram1r1w>(
+            &ctx,
+            &SRAM22_64X32M4W8_1R1W,
+            out_lef(&work_dir, &*name),
+        )
+        .expect("failed to write abstract");
+    }
+}
@@ -0,0 +1,514 @@
+//! Analytical timing estimates of SRAMs.
+//!
+//! Estimates are built from the RC time constants computed by [`SramPhysicalDesignScript`]
+//! and the delay chain lengths of the control logic, so they take no simulation.
+//! Time constants are used directly as delays, which is pessimistic.
+//! The flip-flops are modeled from the Liberty file of their standard cell library.
+//!
+//! [`SramPhysicalDesignScript`]: super::SramPhysicalDesignScript
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::blocks::decoder::{inv_model, INV_MODEL};
+use crate::blocks::ecc::{secded_decoder, secded_encoder, SramEccParams};
+use crate::blocks::gate::PrimitiveGateParams;
+use crate::blocks::latch::DiffLatchParams;
+use crate::blocks::logic::{LogicBlockParams, LogicCell};
+use crate::liberty::{
+    DffModel, Direction, DriverModel, Pin, TableKind, TableTemplate, Timing, TimingSense,
+    TimingType,
+};
+use crate::Result;
+
+use super::redundancy::repair_logic;
+use super::testbench::characterize::{
+    sram_library, CharacterizeParams, Constraint, DelayTables, SramTiming,
+};
+use super::testbench::TbInput;
+use super::{OutputMode, WriteMode};
+
+/// The standard cell library of the SRAM flip-flops.
+const DFF_LIB: &str = "sky130_fd_sc_hs";
+/// The standard cell used for every flip-flop of the SRAM.
+const DFF_CELL: &str = "sky130_fd_sc_hs__dfrbp_2";
+/// Sizing of the cross-coupled inverters of `sramgen_sp_sense_amp`.
+const SENSE_AMP_INV: PrimitiveGateParams = PrimitiveGateParams {
+    nwidth: 3_360,
+    pwidth: 4_000,
+    length: 150,
+};
+/// Number of series devices discharging each sense amplifier output:
+/// the tail, input and cross-coupled NMOS.
+const SENSE_AMP_STACK: f64 = 3.;
+/// Sheet resistance of metal 2 in ohms per square, from the sky130 process documentation.
+const M2_SHEET_RES: f64 = 0.125;
+/// Width of the metal 2 clock stripe of the column flip-flops in nm,
+/// set by the via from the flip-flop clock pin.
+const CLK_STRIPE_WIDTH: f64 = 260.;
+
+/// Delay of a 1x inverter driving a 1x inverter,
+/// the unit of the control logic delay chains.
+fn inv_delay() -> f64 {
+    INV_MODEL.res * (INV_MODEL.cin + INV_MODEL.cout)
+}
+
+/// Time for the sense amplifiers and output latches to resolve once enabled.
+///
+/// A sense amplifier output discharges through its stack of NMOS, loaded by the other
+/// half of the amplifier and the latch input. The latch input inverter then drives the
+/// set or reset pull-downs, and the latch flips, driving the output inverter.
+/// The NMOS-only pull-downs are modeled as inverters of the same NMOS width,
+/// which overestimates their capacitance.
+fn sense_delay(latch: &DiffLatchParams) -> f64 {
+    let sa = inv_model(SENSE_AMP_INV);
+    let inv_in = inv_model(latch.inv_in);
+    let invq = inv_model(latch.invq);
+    let inv_out = inv_model(latch.inv_out);
+    let pulldown = inv_model(PrimitiveGateParams {
+        nwidth: latch.nwidth,
+        pwidth: 0,
+        length: latch.lch,
+    });
+    SENSE_AMP_STACK * sa.res * (sa.cin + inv_in.cin)
+        + inv_in.res * pulldown.cin
+        + invq.res * (invq.cin + inv_out.cin)
+}
+
+/// Estimates the timing of an SRAM from its physical design,
+/// modeling its flip-flops with `dff`.
+///
+/// Only the SRAM, physical design, slews and loads of `params` are used.
+pub fn estimate(params: &CharacterizeParams, dff: &DffModel) -> SramTiming {
+    let sram = &params.sram;
+    let dsn = &params.dsn;
+    let tau = &dsn.time_constants;
+    let control = &dsn.control;
+    let col = &dsn.col_params;
+    let inv = inv_delay();
+    let sense = sense_delay(&col.latch);
+
+    // The wordline rises once both the address and the delayed wordline enable
+    // have propagated through the address gates and row decoder.
+    let wl_rise = f64::max(
+        dff.driver.intrinsic,
+        control.decoder_delay_invs as f64 * inv,
+    ) + tau.wlen
+        + tau.addr_gate
+        + tau.row_decoder;
+    let read_done = wl_rise + control.wlen_pulse_invs as f64 * inv + tau.sense_en + sense;
+    let write_done =
+        wl_rise + control.wrdrven_set_delay_invs as f64 * inv + tau.write_driver_en + tau.col_nand;
+    let cycle_done =
+        f64::max(read_done, write_done) + control.pc_set_delay_invs as f64 * inv + tau.pc_b;
+
+    // In write-through mode, the output latches are driven
+    // from the write data once the write drivers are enabled.
+    let dout_done = match sram.write_mode() {
+        WriteMode::NoChange => read_done,
+        WriteMode::WriteThrough => f64::max(read_done, write_done + sense),
+    };
+
+    // A registered output drives `dout` from the output flip-flops on the next clock edge,
+    // which must come after the output latches have resolved.
+    let (driver, min_period) = match sram.output() {
+        OutputMode::Latched => (
+            DriverModel {
+                intrinsic: dout_done,
+                res: inv_model(col.latch.inv_out).res,
+                cout: 0.,
+            },
+            cycle_done,
+        ),
+        OutputMode::Registered => (dff.driver, f64::max(cycle_done, dout_done + dff.setup)),
+    };
+    let delay = params
+        .slews
+        .iter()
+        .map(|&slew| {
+            params
+                .loads
+                .iter()
+                .map(|&load| driver.delay(slew, load))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let transition = params
+        .slews
+        .iter()
+        .map(|_| {
+            params
+                .loads
+                .iter()
+                .map(|&load| driver.transition(load))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // The flip-flops of `din`, `wmask` and a registered `dout` are clocked through
+    // a stripe spanning the column circuitry (of each half of a split array),
+    // which delays their clock edge and lengthens their hold time.
+    // A late clock also shortens their setup time, which is not credited.
+    let output_dffs = match sram.output() {
+        OutputMode::Latched => 0,
+        OutputMode::Registered => sram.data_width(),
+    };
+    let halves = sram.data_width() / col.word_length();
+    let col_dffs = (sram.data_width() + sram.wmask_width() + output_dffs) / halves;
+    let stripe_res =
+        M2_SHEET_RES * (col.cols * sram.bitcell().width() as usize) as f64 / CLK_STRIPE_WIDTH;
+    let col_clk_delay = stripe_res * col_dffs as f64 * dff.clk_cap / 2.;
+    let constraints = params
+        .constrained_inputs()
+        .into_iter()
+        .map(|input| {
+            let clk_delay = match input {
+                TbInput::Din | TbInput::Wmask => col_clk_delay,
+                TbInput::Ce | TbInput::We | TbInput::Addr => 0.,
+            };
+            let constraint = Constraint {
+                setup: params
+                    .slews
+                    .iter()
+                    .map(|slew| dff.setup + slew / 2.)
+                    .collect(),
+                hold: vec![dff.hold + clk_delay; params.slews.len()],
+            };
+            (input, constraint)
+        })
+        .collect();
+
+    // Every input is registered, as is `dout` if the output is registered.
+    // The clock and reset also drive the control logic.
+    let num_dffs = dsn.num_dffs + sram.data_width() + sram.wmask_width() + output_dffs;
+    let style = sram.port_style();
+    let mut pin_caps = HashMap::from_iter(
+        [
+            (style.enable_pin(), 1),
+            (style.write_pin(), 1),
+            ("addr", sram.addr_width()),
+            ("din", sram.data_width()),
+            ("wmask", sram.wmask_width()),
+        ]
+        .map(|(name, width)| (name.to_string(), vec![dff.d_cap; width])),
+    );
+    for (name, cap) in [("clk", dff.clk_cap), ("rstb", dff.reset_b_cap)] {
+        pin_caps.insert(
+            name.to_string(),
+            vec![num_dffs as f64 * cap + INV_MODEL.cin],
+        );
+    }
+
+    SramTiming {
+        clk_to_dout: DelayTables {
+            cell_rise: delay.clone(),
+            cell_fall: delay,
+            rise_transition: transition.clone(),
+            fall_transition: transition,
+        },
+        constraints,
+        pin_caps,
+        min_period: Some(min_period),
+        power: None,
+    }
+}
+
+/// Delay of each gate of a [`LogicBlock`](crate::blocks::logic::LogicBlock),
+/// modeled as a 1x inverter driving four others.
+fn logic_gate_delay() -> f64 {
+    INV_MODEL.res * (4. * INV_MODEL.cin + INV_MODEL.cout)
+}
+
+/// Delay of the longest path from the `from` ports to the `to` ports of `logic`.
+fn logic_delay(logic: &LogicBlockParams, from: &[&str], to: &[&str]) -> f64 {
+    logic.depth(from, to) as f64 * logic_gate_delay()
+}
+
+/// Delays `dout` by `delay`, such as that of logic between the SRAM and its pins.
+fn delay_dout(timing: &mut SramTiming, delay: f64) {
+    let tables = &mut timing.clk_to_dout;
+    for value in tables
+        .cell_rise
+        .iter_mut()
+        .chain(tables.cell_fall.iter_mut())
+        .flatten()
+    {
+        *value += delay;
+    }
+}
+
+/// Lengthens the setup time of `input` by `delay`, such as that of logic
+/// between its pin and the SRAM.
+///
+/// The hold time is not shortened, which is pessimistic.
+fn delay_input(timing: &mut SramTiming, input: TbInput, delay: f64) {
+    if let Some(constraint) = timing.constraints.get_mut(&input) {
+        for setup in constraint.setup.iter_mut() {
+            *setup += delay;
+        }
+    }
+}
+
+/// Estimates the timing of an SRAM and writes the resulting Liberty library to `path`.
+///
+/// The flip-flops are modeled from the Liberty file of their standard cell library.
+/// If the SRAM has spare rows or columns, the repair logic delays the inputs and `dout`,
+/// and the repair register pins are included.
+pub fn generate_estimate_lib(params: &CharacterizeParams, path: impl AsRef<Path>) -> Result<()> {
+    let dff = DffModel::load(DFF_LIB, DFF_CELL)?;
+    let mut timing = estimate(params, &dff);
+    if params.sram.redundancy().is_none() {
+        return sram_library(params, &timing).save(path);
+    }
+
+    let logic = repair_logic(&params.sram);
+    let core_ports = logic
+        .ports()
+        .map(|(name, _, _)| name.as_str())
+        .filter(|name| name.starts_with("core_") && *name != "core_dout")
+        .collect::<Vec<_>>();
+    for input in params.constrained_inputs() {
+        let pin = input.pin_name(params.sram.port_style());
+        delay_input(&mut timing, input, logic_delay(&logic, &[pin], &core_ports));
+    }
+    delay_dout(&mut timing, logic_delay(&logic, &["core_dout"], &["dout"]));
+
+    // The repair register is a chain of flip-flops clocked by `clk`,
+    // each loaded through a mux selected by `repair_se`.
+    let n = params.sram.repair_width();
+    let mut library = sram_library(params, &timing);
+    let delay_template = TableTemplate::delay(&params.slews, &params.loads);
+    let constraint_template = TableTemplate::constraint(&params.slews);
+    let max_transition = params.slews.iter().copied().reduce(f64::max);
+    let cell = &mut library.cells[0];
+    for pin in cell.pins.iter_mut() {
+        match pin.name.as_str() {
+            "clk" => *pin.capacitance.get_or_insert(0.) += n as f64 * dff.clk_cap,
+            "rstb" => *pin.capacitance.get_or_insert(0.) += n as f64 * dff.reset_b_cap,
+            _ => {}
+        }
+    }
+    let setup = params
+        .slews
+        .iter()
+        .map(|slew| dff.setup + slew / 2. + logic_gate_delay())
+        .collect::<Vec<_>>();
+    let hold = vec![dff.hold; params.slews.len()];
+    for (name, cap) in [("repair_se", n as f64), ("repair_si", 1.)] {
+        let mut pin = Pin::new(name, Direction::Input);
+        pin.capacitance = Some(cap * INV_MODEL.cin);
+        pin.max_transition = max_transition;
+        for (timing_type, values) in [
+            (TimingType::SetupRising, &setup),
+            (TimingType::HoldRising, &hold),
+        ] {
+            let table = constraint_template.table(vec![values.clone()]);
+            pin.timing.push(Timing {
+                related_pin: "clk".to_string(),
+                timing_type,
+                timing_sense: None,
+                tables: vec![
+                    (TableKind::RiseConstraint, table.clone()),
+                    (TableKind::FallConstraint, table),
+                ],
+            });
+        }
+        cell.pins.push(pin);
+    }
+    let mut repair_so = Pin::new("repair_so", Direction::Output);
+    let table = |value: &dyn Fn(f64, f64) -> f64| {
+        delay_template.table(
+            params
+                .slews
+                .iter()
+                .map(|&slew| params.loads.iter().map(|&load| value(slew, load)).collect())
+                .collect(),
+        )
+    };
+    let delay = table(&|slew, load| dff.driver.delay(slew, load) + logic_gate_delay());
+    let transition = table(&|_, load| dff.driver.transition(load));
+    repair_so.timing.push(Timing {
+        related_pin: "clk".to_string(),
+        timing_type: TimingType::RisingEdge,
+        timing_sense: Some(TimingSense::NonUnate),
+        tables: vec![
+            (TableKind::CellRise, delay.clone()),
+            (TableKind::CellFall, delay),
+            (TableKind::RiseTransition, transition.clone()),
+            (TableKind::FallTransition, transition),
+        ],
+    });
+    cell.pins.push(repair_so);
+    library.save(path)
+}
+
+/// The name of the estimated Liberty library of an SRAM with ECC,
+/// given the parameters of its underlying SRAM.
+pub fn ecc_lib_name(params: &CharacterizeParams, ecc: &SramEccParams) -> String {
+    params
+        .lib_name()
+        .replacen(params.sram.name().as_str(), &ecc.name(), 1)
+}
+
+/// Estimates the timing of an SRAM with ECC and writes the resulting Liberty library to `path`.
+///
+/// `params` describe the SRAM that stores the data and check bits, given by
+/// [`SramEccParams::sram`]. The encoder lengthens the setup time of `din`,
+/// and the decoder delays `dout` and the error flags.
+pub fn generate_ecc_estimate_lib(
+    params: &CharacterizeParams,
+    ecc: &SramEccParams,
+    path: impl AsRef<Path>,
+) -> Result<()> {
+    let dff = DffModel::load(DFF_LIB, DFF_CELL)?;
+    let mut timing = estimate(params, &dff);
+    let dw = ecc.data_width();
+    let encoder = secded_encoder(dw);
+    let decoder = secded_decoder(dw);
+    delay_input(
+        &mut timing,
+        TbInput::Din,
+        logic_delay(&encoder, &["din"], &["check"]),
+    );
+    delay_dout(
+        &mut timing,
+        logic_delay(
+            &decoder,
+            &["data", "check"],
+            &["dout", "err_corrected", "err_uncorrectable"],
+        ),
+    );
+
+    // Each data input also drives the encoder gates that cover it.
+    let din = encoder
+        .ports()
+        .find(|(name, _, _)| name.as_str() == "din")
+        .map(|(_, nets, _)| nets.to_vec())
+        .unwrap_or_default();
+    let fanout = |net| {
+        encoder
+            .gates()
+            .iter()
+            .filter(|gate| gate.cell != LogicCell::Dff)
+            .flat_map(|gate| gate.inputs.iter())
+            .filter(|&&input| input == net)
+            .count()
+    };
+
+    let mut library = sram_library(params, &timing);
+    library.name = ecc_lib_name(params, ecc);
+    let cell = &mut library.cells[0];
+    cell.name = ecc.name().to_string();
+    // Check bits cover the whole word, so there is no write mask.
+    cell.buses.retain(|bus| bus.pin.name != "wmask");
+    for bus in cell.buses.iter_mut() {
+        match bus.pin.name.as_str() {
+            "din" => {
+                bus.width = dw;
+                bus.bit_capacitance.truncate(dw);
+                for (cap, &net) in bus.bit_capacitance.iter_mut().zip(&din) {
+                    *cap += fanout(net) as f64 * INV_MODEL.cin;
+                }
+            }
+            "dout" => bus.width = dw,
+            _ => {}
+        }
+    }
+    let dout = cell
+        .buses
+        .iter()
+        .find(|bus| bus.pin.name == "dout")
+        .map(|bus| bus.pin.clone())
+        .expect("library has no dout bus");
+    for name in ["err_corrected", "err_uncorrectable"] {
+        cell.pins.push(Pin {
+            name: name.to_string(),
+            ..dout.clone()
+        });
+    }
+    library.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::sram::tests::{SRAM22_64X32M4W8, SRAM22_64X32M4W8_REG};
+    use crate::blocks::sram::{MuxRatio, SramParams, SramPhysicalDesignScript};
+    use crate::paths::out_lib;
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+
+    use super::*;
+
+    #[test]
+    fn test_estimate_sram22_64x32m4w8() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_estimate_sram22_64x32m4w8");
+        let dsn = ctx
+            .run_script::<SramPhysicalDesignScript>(&SRAM22_64X32M4W8)
+            .expect("failed to run design script");
+        let params = CharacterizeParams::builder()
+            .work_dir(work_dir.clone())
+            .sram(SRAM22_64X32M4W8)
+            .dsn(dsn)
+            .corner("tt")
+            .vdd(1.8)
+            .build()
+            .unwrap();
+
+        let dff = DffModel::load(DFF_LIB, DFF_CELL).expect("failed to read flip-flop model");
+        let timing = estimate(&params, &dff);
+        let min_period = timing.min_period.unwrap();
+        assert!(timing.clk_to_dout.cell_rise[0][0] > 0.);
+        assert!(timing.clk_to_dout.cell_rise[0][0] < min_period);
+        assert!(timing.clk_to_dout.cell_rise[2][2] > timing.clk_to_dout.cell_rise[0][0]);
+
+        // The column flip-flops see a later clock edge than the address flip-flops.
+        let hold = |input: TbInput| timing.constraints[&input].hold[0];
+        assert!(hold(TbInput::Din) > hold(TbInput::Addr));
+        assert_eq!(hold(TbInput::Ce), hold(TbInput::Addr));
+
+        // A registered output trades a cycle of latency for a shorter clock to output delay.
+        let registered = estimate(
+            &CharacterizeParams {
+                sram: SRAM22_64X32M4W8_REG,
+                ..params.clone()
+            },
+            &dff,
+        );
+        assert!(registered.clk_to_dout.cell_rise[0][0] < timing.clk_to_dout.cell_rise[0][0]);
+        assert!(registered.min_period.unwrap() >= min_period);
+
+        generate_estimate_lib(&params, out_lib(&work_dir, &params.lib_name()))
+            .expect("failed to write lib");
+    }
+
+    #[test]
+    fn test_estimate_variants() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_estimate_variants");
+        let characterize_params = |sram: SramParams| {
+            let dsn = ctx
+                .run_script::<SramPhysicalDesignScript>(&sram)
+                .expect("failed to run design script");
+            CharacterizeParams::builder()
+                .work_dir(work_dir.clone())
+                .sram(sram)
+                .dsn(dsn)
+                .corner("tt")
+                .vdd(1.8)
+                .build()
+                .unwrap()
+        };
+
+        let redundant = characterize_params(SRAM22_64X32M4W8.with_redundancy(4, 2));
+        generate_estimate_lib(&redundant, out_lib(&work_dir, &redundant.lib_name()))
+            .expect("failed to write lib");
+
+        let ecc = SramEccParams::new(MuxRatio::M4, 64, 32);
+        let params = characterize_params(ecc.sram());
+        let lib_name = ecc_lib_name(&params, &ecc);
+        assert!(lib_name.starts_with(ecc.name().as_str()));
+        generate_ecc_estimate_lib(&params, &ecc, out_lib(&work_dir, &lib_name))
+            .expect("failed to write lib");
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 use subgeom::bbox::BoundBox;
@@ -17,6 +17,7 @@ use substrate::layout::layers::{LayerBoundBox, LayerKey};
 use substrate::layout::placement::align::{AlignMode, AlignRect};
 use substrate::layout::placement::array::ArrayTiler;
 use substrate::layout::placement::place_bbox::PlaceBbox;
+use substrate::layout::routing::auto::grid::ExpandToGridStrategy;
 use substrate::layout::routing::auto::straps::{RoutedStraps, Target};
 use substrate::layout::routing::auto::{GreedyRouter, GreedyRouterConfig, LayerConfig};
 use substrate::layout::routing::manual::jog::OffsetJog;
@@ -29,11 +30,13 @@ use substrate::schematic::circuit::Direction;
 use substrate::schematic::elements::mos::SchematicMos;
 use substrate::script::Script;
 
+use crate::blocks::bitcell_array::dual_port::{DpCellArray, DpCellArrayParams};
 use crate::blocks::bitcell_array::replica::ReplicaCellArray;
 use crate::blocks::bitcell_array::SpCellArray;
 use crate::blocks::columns::layout::DffArray;
 use crate::blocks::columns::{self, ColPeripherals, ColumnDesignScript};
 use crate::blocks::control::ControlLogicReplicaV2;
+use crate::blocks::decoder::layout::SuppressionMos;
 use crate::blocks::decoder::{Decoder, DecoderStage};
 use crate::blocks::precharge::layout::ReplicaPrecharge;
 
@@ -42,6 +45,8 @@ use super::{SramInner, SramPhysicalDesignScript};
 /// Returns the layer used for routing in the provided direction.
 ///
 /// The SRAM top level only uses m1 and m2 for vertical and horizontal routing, respectively.
+/// The only exceptions are the global wordlines of a split or banked array and the address
+/// gate outputs crossing the left half of an array with a central row decoder, which run on m3.
 fn get_layer(dir: Dir, ctx: &LayoutCtx) -> Result<LayerKey> {
     ctx.layers().get(Selector::Metal(match dir {
         Dir::Horiz => 2,
@@ -174,6 +179,139 @@ fn draw_route(
     Ok(())
 }
 
+/// Routes a wordline driver output on m1 to a wordline on m2 to its right.
+fn route_wordline(
+    src: Rect,
+    dst: Rect,
+    router: &mut GreedyRouter,
+    ctx: &mut LayoutCtx,
+) -> Result<()> {
+    let m1 = get_layer(Dir::Vert, ctx)?;
+    let m2 = get_layer(Dir::Horiz, ctx)?;
+    // The via straddles the edge of `src` facing `dst`.
+    let edge = if dst.right() < src.left() {
+        src.left()
+    } else {
+        src.right()
+    };
+    let src = src.with_hspan(Span::new(edge - 600, edge + 600));
+    let via = draw_via(m1, src, m2, src, ctx)?;
+    router.block(m1, via.bbox().into_rect());
+    let m2_rect_a = via.layer_bbox(m2).into_rect();
+    let m2_rect_a = m2_rect_a.with_vspan(m2_rect_a.vspan().union(dst.vspan()));
+    let m2_rect_b = dst.with_hspan(m2_rect_a.hspan().union(dst.hspan()));
+    draw_rect(m2, m2_rect_a, router, ctx);
+    draw_rect(m2, m2_rect_b, router, ctx);
+    Ok(())
+}
+
+/// The pitch of the jog tracks of a fan, in nm.
+const FAN_PITCH: i64 = 680;
+/// The width of the jog tracks of a fan, in nm.
+const FAN_WIDTH: i64 = 320;
+/// The minimum space kept between two wires of a fan on the same layer, in nm.
+const FAN_SPACE: i64 = 200;
+/// The space between a dual-port bitcell array and the nearest jog track of a fan, in nm.
+///
+/// Leaves room for the supply bar of the array on that side.
+const FAN_CLEARANCE: i64 = 2_000;
+
+/// Assigns each wire of a fan to a jog track, given the spans of its source and
+/// destination runs across the fan. Tracks are numbered from the destination side.
+///
+/// Wires that jog the least are assigned first. A wire whose source run lines up with
+/// the destination run of another wire jogs farther from the destination than that wire,
+/// so that their runs do not overlap. Returns an error if no assignment satisfies this.
+fn fan_tracks(wires: &[(Span, Span)]) -> Result<Vec<usize>> {
+    let near = |a: Span, b: Span| a.expand_all(FAN_SPACE).intersects(&b);
+    let mut order = (0..wires.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&w| (wires[w].0.center() - wires[w].1.center()).abs());
+
+    let mut tracks: Vec<Option<usize>> = vec![None; wires.len()];
+    let mut jogs: Vec<Vec<Span>> = Vec::new();
+    for w in order {
+        let (src, dst) = wires[w];
+        let jog = src.union(dst);
+        let mut lower = 0;
+        let mut upper = usize::MAX;
+        for (k, track) in tracks.iter().enumerate() {
+            if let Some(track) = *track {
+                if near(src, wires[k].1) {
+                    lower = lower.max(track + 1);
+                }
+                if near(dst, wires[k].0) {
+                    upper = upper.min(track);
+                }
+            }
+        }
+        let track = (lower..upper)
+            .find(|&t| {
+                jogs.get(t)
+                    .map_or(true, |spans| spans.iter().all(|&span| !near(span, jog)))
+            })
+            .ok_or(substrate::component::error::Error::InvalidParams)?;
+        if track >= jogs.len() {
+            jogs.resize(track + 1, Vec::new());
+        }
+        jogs[track].push(jog);
+        tracks[w] = Some(track);
+    }
+    Ok(tracks.into_iter().map(Option::unwrap).collect())
+}
+
+/// The distance from a dual-port bitcell array to the far side of the jog tracks
+/// assigned by [`fan_tracks`].
+fn fan_depth(tracks: &[usize]) -> i64 {
+    FAN_CLEARANCE + FAN_PITCH * tracks.iter().max().map_or(0, |&t| t as i64 + 1)
+}
+
+/// Draws a fan of wires whose jog tracks were assigned by [`fan_tracks`].
+///
+/// Each wire runs along `dir` from `src` to its jog track, jogs across on the layer
+/// for the other direction, and runs on to `dst`. Sources lie before destinations along `dir`,
+/// and track 0 ends at `first_track`.
+fn draw_fan(
+    wires: &[(Rect, Rect)],
+    tracks: &[usize],
+    dir: Dir,
+    first_track: i64,
+    router: &mut GreedyRouter,
+    ctx: &mut LayoutCtx,
+) -> Result<()> {
+    let run_layer = get_layer(dir, ctx)?;
+    let jog_layer = get_layer(!dir, ctx)?;
+    for (&(src, dst), &track) in wires.iter().zip(tracks) {
+        let track = Span::with_stop_and_length(first_track - FAN_PITCH * track as i64, FAN_WIDTH);
+        let jog = Rect::span_builder()
+            .with(dir, track)
+            .with(!dir, src.span(!dir).union(dst.span(!dir)))
+            .build();
+        let src = src.with_span(src.span(dir).union(track), dir);
+        let dst = dst.with_span(dst.span(dir).union(track), dir);
+        draw_rect(run_layer, src, router, ctx);
+        draw_rect(run_layer, dst, router, ctx);
+        draw_rect(jog_layer, jog, router, ctx);
+        draw_routing_via(run_layer, src, jog_layer, jog, ctx)?;
+        draw_routing_via(run_layer, dst, jog_layer, jog, ctx)?;
+    }
+    Ok(())
+}
+
+/// Instantiates the via from a wordline driver output on m1 to m2 that straddles
+/// the right edge of the output, without drawing it.
+fn wordline_via(src: Rect, ctx: &mut LayoutCtx) -> Result<Instance> {
+    let m1 = get_layer(Dir::Vert, ctx)?;
+    let m2 = get_layer(Dir::Horiz, ctx)?;
+    let src = src.with_hspan(Span::new(src.right() - 600, src.right() + 600));
+    ctx.instantiate::<Via>(
+        &ViaParams::builder()
+            .layers(m1, m2)
+            .geometry(src, src)
+            .expand(ViaExpansion::LongerDirection)
+            .build(),
+    )
+}
+
 pub struct ColumnMos {
     params: ColumnMosParams,
 }
@@ -772,8 +910,55 @@ impl Component for ReplicaMetalRouting {
     }
 }
 
+/// The instances added to the layout of an SRAM by splitting its bitcell array
+/// into halves or banks, ordered from left to right.
+struct Sections {
+    /// The wordline drivers of every half or bank.
+    drivers: Vec<Instance>,
+    /// The bitcells of every half or bank but the leftmost.
+    bitcells: Vec<Instance>,
+    /// The column circuitry of every half or bank but the leftmost.
+    cols: Vec<Instance>,
+    /// Whether the row decoder sits between two halves, with the wordline drivers
+    /// of the left half mirrored to face it.
+    central: bool,
+}
+
+impl Sections {
+    /// The side of wordline driver `i` facing away from its bitcells, where its inputs are.
+    fn input_side(&self, i: usize) -> Side {
+        if self.central && i == 0 {
+            Side::Right
+        } else {
+            Side::Left
+        }
+    }
+
+    /// The side of wordline driver `i` facing its bitcells, where its outputs are.
+    fn output_side(&self, i: usize) -> Side {
+        match self.input_side(i) {
+            Side::Left => Side::Right,
+            _ => Side::Left,
+        }
+    }
+}
+
 impl SramInner {
     pub(crate) fn layout(&self, ctx: &mut LayoutCtx) -> Result<()> {
+        self.draw_layout(None, ctx)
+    }
+
+    /// Draws the periphery of port `port` of a 1R1W SRAM around an array of dual-port
+    /// bitcells, which is left for the parent cell to draw.
+    ///
+    /// The array is exposed as the `array` port on the outline layer. The periphery of
+    /// port 1 faces that of port 0 across the array, so its array is rotated by 180 degrees.
+    /// The rows and columns it sees are reversed, which its complemented address undoes.
+    pub(crate) fn layout_dual_port(&self, port: usize, ctx: &mut LayoutCtx) -> Result<()> {
+        self.draw_layout(Some(port), ctx)
+    }
+
+    fn draw_layout(&self, dual_port: Option<usize>, ctx: &mut LayoutCtx) -> Result<()> {
         let dsn = ctx
             .inner()
             .run_script::<SramPhysicalDesignScript>(&self.params)?;
@@ -781,9 +966,69 @@ impl SramInner {
         let m0 = layers.get(Selector::Metal(0))?;
         let m1 = layers.get(Selector::Metal(1))?;
         let m2 = layers.get(Selector::Metal(2))?;
-
-        let bitcells = ctx.instantiate::<SpCellArray>(&dsn.bitcells)?;
+        let m3 = layers.get(Selector::Metal(3))?;
+
+        let bitcells = match dual_port {
+            Some(port) => ctx
+                .instantiate::<DpCellArray>(&DpCellArrayParams {
+                    rows: dsn.bitcells.rows,
+                    cols: dsn.bitcells.cols,
+                    mux_ratio: dsn.bitcells.mux_ratio,
+                })?
+                .with_orientation(if port == 0 {
+                    Named::Default
+                } else {
+                    Named::R180
+                }),
+            None => ctx.instantiate::<SpCellArray>(&dsn.bitcells)?,
+        };
+        // The index of the array wordline or bitline reached by wordline or bitline `i` of `n`.
+        let array_index = |i: usize, n: usize| {
+            if dual_port == Some(1) {
+                n - 1 - i
+            } else {
+                i
+            }
+        };
+        let (wl_name, bl_name, br_name) = match dual_port {
+            Some(port) => (
+                arcstr::format!("wl{port}"),
+                arcstr::format!("bl{port}"),
+                arcstr::format!("br{port}"),
+            ),
+            None => (
+                arcstr::literal!("wl"),
+                arcstr::literal!("bl"),
+                arcstr::literal!("br"),
+            ),
+        };
         let mut cols = ctx.instantiate::<ColPeripherals>(&dsn.col_params)?;
+        let mut sections = dsn
+            .wl_driver
+            .as_ref()
+            .map(|params| -> Result<Sections> {
+                let driver = ctx
+                    .instantiate::<DecoderStage>(params)?
+                    .with_orientation(Named::R90Cw);
+                let sections = self.params.sections();
+                let central = self.params.central_decoder();
+                let mut drivers = vec![driver; sections];
+                if central {
+                    drivers[0].set_orientation(Named::FlipMinusYx);
+                }
+                Ok(Sections {
+                    drivers,
+                    bitcells: vec![bitcells.clone(); sections - 1],
+                    cols: vec![cols.clone(); sections - 1],
+                    central,
+                })
+            })
+            .transpose()?;
+        let mut bank_select = dsn
+            .bank_select
+            .as_ref()
+            .map(|params| ctx.instantiate::<DecoderStage>(params))
+            .transpose()?;
         ctx.set_metadata(*cols.cell().get_metadata::<columns::layout::Metadata>());
         let mut decoder = ctx
             .instantiate::<Decoder>(&dsn.row_decoder)?
@@ -816,25 +1061,125 @@ impl SramInner {
             .instantiate::<ReplicaMetalRouting>(&dsn.replica_routing)?
             .with_orientation(Named::ReflectVert);
 
-        // Align row decoders to left of bitcell array.
-        decoder.align_to_the_left_of(bitcells.bbox(), 7_000);
+        if let Some(sections) = sections.as_mut() {
+            // Align the wordline drivers of each half or bank to the left of its bitcells,
+            // with each half or bank to the right of the previous one and row decoders
+            // to the left of all of them.
+            //
+            // With a central row decoder, the mirrored drivers of the left half are instead
+            // aligned to its right, followed by the row decoder and the right half.
+            let grid = ctx.pdk().layout_grid();
+            let mut prev = if sections.central {
+                sections.drivers[0].align_to_the_right_of(bitcells.bbox(), 7_000);
+                decoder.align_to_the_right_of(sections.drivers[0].bbox(), 4_000);
+                decoder.bbox()
+            } else {
+                sections.drivers[0].align_to_the_left_of(bitcells.bbox(), 7_000);
+                bitcells.bbox()
+            };
+            sections.drivers[0].align_centers_vertically_gridded(bitcells.bbox(), grid);
+            let mut space = if sections.central { 4_000 } else { 13_000 };
+            for ((driver, section_bitcells), section_cols) in sections.drivers[1..]
+                .iter_mut()
+                .zip(sections.bitcells.iter_mut())
+                .zip(sections.cols.iter_mut())
+            {
+                driver.align_to_the_right_of(prev, space);
+                driver.align_centers_vertically_gridded(bitcells.bbox(), grid);
+                section_bitcells.align_to_the_right_of(driver.bbox(), 7_000);
+                section_bitcells.align_bottom(bitcells.bbox());
+                section_cols.align_beneath(section_bitcells.bbox(), 4_000);
+                section_cols.align_centers_horizontally_gridded(section_bitcells.bbox(), grid);
+                prev = section_bitcells.bbox();
+                space = 13_000;
+            }
+
+            if !sections.central {
+                decoder.align_to_the_left_of(sections.drivers[0].bbox(), 4_000);
+            }
+        } else {
+            // Align row decoders to left of bitcell array.
+            decoder.align_to_the_left_of(bitcells.bbox(), 7_000);
+        }
         decoder.align_centers_vertically_gridded(bitcells.bbox(), ctx.pdk().layout_grid());
 
-        // Align wlen buffer and address gate to the left of the row decoder.
+        // The row decoder is pitch-matched to the single-port bitcell, so the wordlines of
+        // a dual-port array fan out to its taller rows between the decoder and the array.
+        let wl_fan = dual_port
+            .map(|_| -> Result<Vec<usize>> {
+                let wires = (0..self.params.rows())
+                    .map(|i| -> Result<(Span, Span)> {
+                        let src = decoder.port(PortId::new("y", i))?.largest_rect(m1)?;
+                        let src = wordline_via(src, ctx)?.layer_bbox(m2).into_rect();
+                        let dst = bitcells
+                            .port(PortId::new(
+                                wl_name.clone(),
+                                array_index(i, self.params.rows()),
+                            ))?
+                            .first_rect(m2, Side::Left)?;
+                        Ok((src.vspan(), dst.vspan()))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                fan_tracks(&wires)
+            })
+            .transpose()?;
+        if let Some(tracks) = wl_fan.as_ref() {
+            decoder.align_to_the_left_of(bitcells.bbox(), fan_depth(tracks) + 2_000);
+        }
+
+        // Align wlen buffer and address gate to the left of the row decoder, or of the left half
+        // of an array with a central row decoder.
         //
-        // Need enough vertical tracks to route outputs to decoder.
-        addr_gate.align_to_the_left_of(
-            decoder.bbox(),
-            1_400 + 1_400 * self.params.row_bits() as i64,
-        );
+        // Need enough vertical tracks to route outputs to decoder. The outputs crossing the left
+        // half also need room for their m2 pads outside of the m2 blockage around the bitcells.
+        let (row_periphery, space) = if self.params.central_decoder() {
+            (bitcells.bbox(), 9_400)
+        } else {
+            (decoder.bbox(), 1_400)
+        };
+        addr_gate
+            .align_to_the_left_of(row_periphery, space + 1_400 * self.params.row_bits() as i64);
         wlen_buffer.align_right(addr_gate.bbox());
         wlen_buffer.translate(Point::new(-2_000, 0));
-        wlen_buffer.align_bottom(decoder.bbox());
+        wlen_buffer.align_bottom(row_periphery);
         addr_gate.align_above(wlen_buffer.bbox(), 2_000);
 
         // Align column peripherals under bitcell array.
-        cols.align_beneath(bitcells.bbox(), 4_000);
+        //
+        // The column peripherals are also pitch-matched to the single-port bitcell, so the
+        // bitlines of a dual-port array fan out to its wider columns in between.
         cols.align_centers_horizontally_gridded(bitcells.bbox(), ctx.pdk().layout_grid());
+        let bl_fan = dual_port
+            .map(|_| -> Result<Vec<usize>> {
+                let mut wires = Vec::new();
+                for i in 0..dsn.col_params.cols {
+                    for (port_name, array_name) in [("bl", &bl_name), ("br", &br_name)] {
+                        let src = cols.port(PortId::new(port_name, i))?.largest_rect(m1)?;
+                        let dst = bitcells
+                            .port(PortId::new(
+                                array_name.clone(),
+                                array_index(i, dsn.col_params.cols),
+                            ))?
+                            .first_rect(m1, Side::Bot)?;
+                        wires.push((src.hspan(), dst.hspan()));
+                    }
+                }
+                fan_tracks(&wires)
+            })
+            .transpose()?;
+        let cols_space = bl_fan
+            .as_ref()
+            .map_or(4_000, |tracks| fan_depth(tracks) + 2_000);
+        cols.align_beneath(bitcells.bbox(), cols_space);
+
+        // The control logic and replica column sit beneath the row periphery, clear of the
+        // bitlines fanning out beneath a dual-port array.
+        let upper_periphery = if bl_fan.is_some() {
+            let rect = row_periphery.into_rect();
+            rect.with_vspan(rect.vspan().add_point(cols.brect().top() + 4_000))
+        } else {
+            row_periphery.into_rect()
+        };
 
         // Align pc_b buffer with pc_b port of column peripherals.
         //
@@ -883,7 +1228,7 @@ impl SramInner {
             .union(write_driver_en_buffer.bbox())
             .into_rect();
         control.set_orientation(Named::R90);
-        control.align_beneath(decoder.bbox(), 6_000);
+        control.align_beneath(upper_periphery, 6_000);
         control.align_to_the_left_of(
             buffer_bbox,
             2_100 + 1_400 * self.params.col_select_bits() as i64,
@@ -892,7 +1237,7 @@ impl SramInner {
         // Align replica bitcell array to left of control logic, with replica precharge
         // aligned to top of control logic.
         rbl.align_to_the_left_of(control.bbox(), 4_480);
-        replica_pc.align_beneath(decoder.bbox(), 6_000);
+        replica_pc.align_beneath(upper_periphery, 6_000);
         replica_pc.align_centers_horizontally_gridded(rbl.bbox(), ctx.pdk().layout_grid());
         replica_nmos.align_beneath(
             replica_pc.port("rbl")?.largest_rect(m2).unwrap().bbox(),
@@ -909,18 +1254,54 @@ impl SramInner {
             4_000,
         );
 
-        // Align DFFs to the left of column peripherals and underneath all other objects.
+        // Align the replica wordline suppression device to the left of the replica bitcell array,
+        // clear of the m2 blockage around the array.
+        let mut rwl_suppression = dsn
+            .rwl_suppression
+            .map(|params| ctx.instantiate::<SuppressionMos>(&params))
+            .transpose()?;
+        if let Some(mos) = rwl_suppression.as_mut() {
+            mos.align_to_the_left_of(rbl.bbox(), 4_000);
+            mos.align_centers_vertically_gridded(
+                rbl.port(PortId::new("wl", dsn.rbl_wl_index))?
+                    .largest_rect(m2)
+                    .unwrap()
+                    .bbox(),
+                ctx.pdk().layout_grid(),
+            );
+        }
+
+        // Align DFFs to the left of column peripherals and underneath all other objects,
+        // leaving room for the address, control, trim and sleep routing in between.
+        let trim_bits = self.params.timing_trim().unwrap_or_default();
         dffs.align_right(pc_b_buffer.bbox());
         dffs.align_beneath(
             control
                 .bbox()
                 .union(rbl.bbox())
                 .union(write_driver_en_buffer.bbox()),
-            5_500 + 1_400 * self.params.addr_width() as i64,
+            5_500
+                + 1_400
+                    * (self.params.addr_width()
+                        + trim_bits
+                        + usize::from(self.params.power_gating())) as i64,
         );
 
+        // Align the bank select decoder to the left of the DFFs holding the bank bits,
+        // clear of the trim and sleep pins.
+        if let Some(bank_select) = bank_select.as_mut() {
+            bank_select.align_top(dffs.bbox());
+            bank_select
+                .align_to_the_left_of(dffs.bbox(), 4_000 + 1_400 * (2 * trim_bits + 1) as i64);
+        }
+
         // Draw instances.
-        ctx.draw_ref(&bitcells)?;
+        if dual_port.is_some() {
+            let outline = ctx.layers().get(Selector::Name("outline"))?;
+            ctx.add_port(CellPort::with_shape("array", outline, bitcells.brect()))?;
+        } else {
+            ctx.draw_ref(&bitcells)?;
+        }
         ctx.draw_ref(&cols)?;
         ctx.draw_ref(&decoder)?;
         ctx.draw_ref(&addr_gate)?;
@@ -935,10 +1316,28 @@ impl SramInner {
         ctx.draw_ref(&replica_pc)?;
         ctx.draw_ref(&replica_nmos)?;
         ctx.draw_ref(&replica_routing)?;
+        if let Some(mos) = rwl_suppression.as_ref() {
+            ctx.draw_ref(mos)?;
+        }
+        if let Some(sections) = sections.as_ref() {
+            for inst in sections
+                .drivers
+                .iter()
+                .chain(&sections.bitcells)
+                .chain(&sections.cols)
+            {
+                ctx.draw_ref(inst)?;
+            }
+        }
+        if let Some(bank_select) = bank_select.as_ref() {
+            ctx.draw_ref(bank_select)?;
+        }
 
         // Set up autorouter for automatic strap placement.
         let router_bbox = ctx
             .brect()
+            .union(bitcells.bbox())
+            .into_rect()
             .expand(8 * 680)
             .expand_side(Side::Right, 4 * 680)
             .expand_side(Side::Left, 1_400 * self.params.row_bits() as i64)
@@ -963,6 +1362,24 @@ impl SramInner {
         });
         let m1_tracks = router.track_info(m1).tracks().clone();
         let m2_tracks = router.track_info(m2).tracks().clone();
+        let wl_drivers = sections
+            .as_ref()
+            .map(|sections| sections.drivers.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        // Each decoder with the side its outputs are on.
+        let decoder_outputs = std::iter::once((&decoder, Side::Right))
+            .chain(sections.iter().flat_map(|sections| {
+                (0..sections.drivers.len())
+                    .map(move |i| (&sections.drivers[i], sections.output_side(i)))
+            }))
+            .collect::<Vec<_>>();
+        let halves = std::iter::once((&bitcells, &cols))
+            .chain(
+                sections
+                    .iter()
+                    .flat_map(|sections| sections.bitcells.iter().zip(sections.cols.iter())),
+            )
+            .collect::<Vec<_>>();
 
         // Block appropriate areas in router for each instance.
         for inst in [
@@ -978,7 +1395,12 @@ impl SramInner {
             &replica_pc,
             &replica_nmos,
             &replica_routing,
-        ] {
+        ]
+        .into_iter()
+        .chain(rwl_suppression.as_ref())
+        .chain(bank_select.as_ref())
+        .chain(wl_drivers.iter().copied())
+        {
             for layer in [m1, m2] {
                 for shape in inst.shapes_on(layer) {
                     let rect = shape.brect();
@@ -990,33 +1412,37 @@ impl SramInner {
         // Block full m2 layer bbox of decoders due to wrong direction routing.
         // Also block m1 to the right of buffers and column decoder to prevent power strap placement in
         // between vias.
-        for inst in [
-            &decoder,
+        for (inst, side) in [
             &col_dec,
             &pc_b_buffer,
             &sense_en_buffer,
             &write_driver_en_buffer,
-        ] {
+        ]
+        .into_iter()
+        .map(|inst| (inst, Side::Right))
+        .chain(decoder_outputs.iter().copied())
+        {
             for port in ["vdd", "vss"] {
                 router.block(m2, inst.port(port)?.bbox(m2).into_rect());
             }
-            router.block(m1, inst.brect().expand_side(Side::Right, 1_400));
+            router.block(m1, inst.brect().expand_side(side, 1_400));
         }
 
-        // Block extra for decoder to prevent extra power straps from being placed.
-        for port in ["vdd", "vss"] {
-            router.block(
-                m2,
-                decoder
-                    .port(port)?
-                    .bbox(m2)
-                    .into_rect()
-                    .expand_side(Side::Right, 6_000),
-            );
+        // Block extra for decoders to prevent extra power straps from being placed.
+        for &(inst, side) in decoder_outputs.iter() {
+            for port in ["vdd", "vss"] {
+                router.block(
+                    m2,
+                    inst.port(port)?
+                        .bbox(m2)
+                        .into_rect()
+                        .expand_side(side, 6_000),
+                );
+            }
         }
 
         // Block entirety of bounding box for bitcells, replica bitcells, and column peripherals.
-        for inst in [&bitcells, &cols] {
+        for inst in halves.iter().flat_map(|&(bitcells, cols)| [bitcells, cols]) {
             router.block(
                 m1,
                 inst.brect()
@@ -1044,18 +1470,59 @@ impl SramInner {
                 .expand_dir(Dir::Vert, 140),
         );
 
+        // The periphery of the other port of a dual-port array lies above and to its right.
+        if dual_port.is_some() {
+            let brect = bitcells.brect();
+            for layer in [m1, m2] {
+                router.block(
+                    layer,
+                    router_bbox.with_vspan(Span::new(brect.top() + 140, router_bbox.top())),
+                );
+                router.block(
+                    layer,
+                    router_bbox.with_hspan(Span::new(brect.right() + 140, router_bbox.right())),
+                );
+            }
+        }
+
         // Route precharges to bitcell array.
-        for i in 0..self.params.cols() {
-            for port_name in ["bl", "br"] {
-                let src = cols
-                    .port(PortId::new(port_name, i))?
-                    .largest_rect(m1)
-                    .unwrap();
-                let dst = bitcells
-                    .port(PortId::new(port_name, i))?
-                    .largest_rect(m1)
-                    .unwrap();
-                draw_rect(m1, src.union(dst.bbox()).into_rect(), &mut router, ctx);
+        if let Some(tracks) = bl_fan.as_ref() {
+            let mut wires = Vec::new();
+            for i in 0..dsn.col_params.cols {
+                for (port_name, array_name) in [("bl", &bl_name), ("br", &br_name)] {
+                    let src = cols.port(PortId::new(port_name, i))?.largest_rect(m1)?;
+                    let dst = bitcells
+                        .port(PortId::new(
+                            array_name.clone(),
+                            array_index(i, dsn.col_params.cols),
+                        ))?
+                        .first_rect(m1, Side::Bot)?;
+                    wires.push((src, dst));
+                }
+            }
+            draw_fan(
+                &wires,
+                tracks,
+                Dir::Vert,
+                bitcells.brect().bottom() - FAN_CLEARANCE,
+                &mut router,
+                ctx,
+            )?;
+        } else {
+            for &(bitcells, cols) in halves.iter() {
+                for i in 0..dsn.col_params.cols {
+                    for port_name in ["bl", "br"] {
+                        let src = cols
+                            .port(PortId::new(port_name, i))?
+                            .largest_rect(m1)
+                            .unwrap();
+                        let dst = bitcells
+                            .port(PortId::new(port_name, i))?
+                            .largest_rect(m1)
+                            .unwrap();
+                        draw_rect(m1, src.union(dst.bbox()).into_rect(), &mut router, ctx);
+                    }
+                }
             }
         }
 
@@ -1071,9 +1538,9 @@ impl SramInner {
             ctx.add_port(
                 CellPort::builder()
                     .id(if i == dsn.num_dffs - 1 {
-                        "we".into()
+                        self.params.port_style().write_pin().into()
                     } else if i == dsn.num_dffs - 2 {
-                        "ce".into()
+                        self.params.port_style().enable_pin().into()
                     } else {
                         PortId::new("addr", self.params.addr_width() - i - 1)
                     })
@@ -1092,6 +1559,38 @@ impl SramInner {
         // Maps predecoder port spans to the previous m2 track used to connect to the input bus.
         // Increments each time a track is used.
         let mut m2_prev_track = HashMap::new();
+        // With a central row decoder, the outputs cross the left half on m3 midway between
+        // two of the global wordlines, which run on m3 over the decoder at the height of
+        // each row. Each output is jogged to an m2 pad to the left of the half.
+        let wl_midpoints = sections
+            .as_ref()
+            .filter(|sections| sections.central)
+            .map(|sections| -> Result<Vec<i64>> {
+                let mut rows = (0..self.params.rows())
+                    .map(|i| -> Result<i64> {
+                        Ok(sections.drivers[0]
+                            .port(PortId::new("in", i))?
+                            .largest_rect(m0)
+                            .unwrap()
+                            .center()
+                            .y)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                rows.sort();
+                Ok(rows
+                    .windows(2)
+                    .map(|pair| (pair[0] + pair[1]) / 2)
+                    .collect())
+            })
+            .transpose()?;
+        let pad_hspan = Span::with_start_and_length(
+            m1_tracks
+                .index(addr_gate_m1_track_idx + 2 * self.params.row_bits() as i64)
+                .stop()
+                + 400,
+            800,
+        );
+        let mut prev_midpoint = HashMap::new();
         for i in 0..self.params.row_bits() {
             for j in 0..2 {
                 let idx = 2 * i + j;
@@ -1104,16 +1603,48 @@ impl SramInner {
                     .largest_rect(m1)
                     .unwrap();
 
-                // Choose the track to use to connect to the predecoder input bus.
-                // If bus has already been connected to, use next track.
-                // Otherwise, use bottom most track contained by the bus's vertical span.
-                let m2_track_final_idx = *m2_prev_track
-                    .entry(predecode_port.vspan())
-                    .and_modify(|v| *v += 1)
-                    .or_insert(
-                        m2_tracks
-                            .track_with_loc(TrackLocator::StartsAfter, predecode_port.bottom()),
-                    );
+                let (m2_track_final_idx, dst_layer, dst) =
+                    if let Some(midpoints) = wl_midpoints.as_ref() {
+                        // Use the next midpoint within the bus's vertical span,
+                        // starting from the bottom most one.
+                        let midpoint = *prev_midpoint
+                            .entry(predecode_port.vspan())
+                            .and_modify(|v| *v += 1)
+                            .or_insert_with(|| {
+                                midpoints.partition_point(|&y| y - 160 < predecode_port.bottom())
+                            });
+                        let vspan = Span::from_center_span_gridded(
+                            midpoints[midpoint],
+                            320,
+                            ctx.pdk().layout_grid(),
+                        );
+                        let pad = Rect::from_spans(pad_hspan, vspan);
+                        let m3_rect =
+                            Rect::from_spans(Span::new(pad.left(), predecode_port.right()), vspan);
+                        let landing = Rect::from_spans(predecode_port.hspan(), vspan);
+                        ctx.draw_rect(m3, m3_rect);
+                        draw_via(m2, pad, m3, m3_rect, ctx)?;
+                        draw_rect(m2, landing, &mut router, ctx);
+                        draw_via(m2, landing, m3, m3_rect, ctx)?;
+                        draw_via(m1, predecode_port, m2, landing, ctx)?;
+                        (
+                            m2_tracks.track_with_loc(TrackLocator::Nearest, vspan.center()),
+                            m2,
+                            pad,
+                        )
+                    } else {
+                        // Choose the track to use to connect to the predecoder input bus.
+                        // If bus has already been connected to, use next track.
+                        // Otherwise, use bottom most track contained by the bus's vertical span.
+                        let m2_track_final_idx = *m2_prev_track
+                            .entry(predecode_port.vspan())
+                            .and_modify(|v| *v += 1)
+                            .or_insert(m2_tracks.track_with_loc(
+                                TrackLocator::StartsAfter,
+                                predecode_port.bottom(),
+                            ));
+                        (m2_track_final_idx, m1, predecode_port)
+                    };
 
                 // Jog the address gate output port to the nearest m2 track.
                 let m2_track_idx = m2_tracks.track_with_loc(TrackLocator::Nearest, y.top());
@@ -1136,8 +1667,8 @@ impl SramInner {
                 draw_route(
                     m1,
                     via_m1,
-                    m1,
-                    predecode_port,
+                    dst_layer,
+                    dst,
                     Dir::Horiz,
                     vec![m2_track_idx, m1_track_idx, m2_track_final_idx],
                     &mut router,
@@ -1257,21 +1788,115 @@ impl SramInner {
             track_idx += num_tracks;
         }
 
-        // Route wordline driver to bitcell array
-        for i in 0..self.params.rows() {
-            let src = decoder.port(PortId::new("y", i))?.largest_rect(m1).unwrap();
-            let src = src.with_hspan(Span::with_stop_and_length(src.right() + 600, 1_200));
-            let dst = bitcells
-                .port(PortId::new("wl", i))?
-                .largest_rect(m2)
-                .unwrap();
-            let via = draw_via(m1, src, m2, src, ctx)?;
-            router.block(m1, via.bbox().into_rect());
-            let m2_rect_a = via.layer_bbox(m2).into_rect();
-            let m2_rect_a = m2_rect_a.with_vspan(m2_rect_a.vspan().union(dst.vspan()));
-            let m2_rect_b = dst.with_hspan(m2_rect_a.hspan().union(dst.hspan()));
-            draw_rect(m2, m2_rect_a, &mut router, ctx);
-            draw_rect(m2, m2_rect_b, &mut router, ctx);
+        // Route the row decoder to the wordline drivers of a split or banked array.
+        //
+        // The global wordlines reach the inputs of the drivers to the right of the row decoder
+        // on m2, and run over the decoder and bitcells on m3 to the inputs of the other drivers.
+        if let Some(sections) = sections.as_ref() {
+            for i in 0..self.params.rows() {
+                let mut pads = Vec::new();
+                let mut via_rects = Vec::new();
+                for (d, driver) in sections.drivers.iter().enumerate() {
+                    let port_rect = driver.port(PortId::new("in", i))?.largest_rect(m0).unwrap();
+                    let (m2_hspan, m0_hspan, via_hspan) = if sections.input_side(d) == Side::Left {
+                        let inner = port_rect.left() - 620;
+                        let outer = driver.brect().left() - 1_400;
+                        (
+                            Span::new(outer, inner),
+                            Span::with_stop_and_length(inner, 320),
+                            Span::with_start_and_length(outer, 800),
+                        )
+                    } else {
+                        let inner = port_rect.right() + 620;
+                        let outer = driver.brect().right() + 1_400;
+                        (
+                            Span::new(inner, outer),
+                            Span::with_start_and_length(inner, 320),
+                            Span::with_stop_and_length(outer, 800),
+                        )
+                    };
+                    let m2_rect = Rect::from_spans(
+                        m2_hspan,
+                        Span::from_center_span_gridded(
+                            port_rect.center().y,
+                            320,
+                            ctx.pdk().layout_grid(),
+                        ),
+                    );
+                    let m0_rect = port_rect.with_hspan(port_rect.hspan().union(m0_hspan));
+                    ctx.draw_rect(m0, m0_rect);
+                    draw_rect(m2, m2_rect, &mut router, ctx);
+                    let via = draw_via(m0, m0_rect, m1, m2_rect, ctx)?;
+                    router.block(m1, via.layer_bbox(m1).brect());
+                    let via = draw_via(m1, m0_rect, m2, m2_rect, ctx)?;
+                    router.block(m1, via.layer_bbox(m1).brect());
+                    pads.push(m2_rect);
+                    via_rects.push(m2_rect.with_hspan(via_hspan));
+                }
+
+                let src = decoder.port(PortId::new("y", i))?.largest_rect(m1).unwrap();
+                route_wordline(src, pads[usize::from(sections.central)], &mut router, ctx)?;
+
+                let m3_rect = Rect::from_spans(
+                    Span::new(via_rects[0].left(), via_rects.last().unwrap().right()),
+                    Span::from_center_span_gridded(
+                        pads[0].center().y,
+                        400,
+                        ctx.pdk().layout_grid(),
+                    ),
+                );
+                ctx.draw_rect(m3, m3_rect);
+                for via_rect in via_rects {
+                    draw_via(m2, via_rect, m3, m3_rect, ctx)?;
+                }
+            }
+        }
+
+        // Route wordline drivers to bitcell arrays.
+        let wl_routes = if let Some(sections) = sections.as_ref() {
+            sections
+                .drivers
+                .iter()
+                .zip(halves.iter().map(|&(bitcells, _)| bitcells))
+                .collect()
+        } else if wl_fan.is_none() {
+            vec![(&decoder, &bitcells)]
+        } else {
+            Vec::new()
+        };
+        if let Some(tracks) = wl_fan.as_ref() {
+            let mut wires = Vec::new();
+            for i in 0..self.params.rows() {
+                let src = decoder.port(PortId::new("y", i))?.largest_rect(m1)?;
+                let via = wordline_via(src, ctx)?;
+                ctx.draw_ref(&via)?;
+                router.block(m1, via.bbox().into_rect());
+                let dst = bitcells
+                    .port(PortId::new(
+                        wl_name.clone(),
+                        array_index(i, self.params.rows()),
+                    ))?
+                    .first_rect(m2, Side::Left)?;
+                wires.push((via.layer_bbox(m2).into_rect(), dst));
+            }
+            draw_fan(
+                &wires,
+                tracks,
+                Dir::Horiz,
+                bitcells.brect().left() - FAN_CLEARANCE,
+                &mut router,
+                ctx,
+            )?;
+        }
+        for (driver, bitcells) in wl_routes {
+            for i in 0..self.params.rows() {
+                let src = driver.port(PortId::new("y", i))?.largest_rect(m1).unwrap();
+                let dst = bitcells
+                    .port(PortId::new("wl", i))?
+                    .largest_rect(m2)
+                    .unwrap();
+                route_wordline(src, dst, &mut router, ctx)?;
+            }
         }
 
         // Route column decoders to mux.
@@ -1410,8 +2035,16 @@ impl SramInner {
 
         // Route ce and we to DFFs.
         for (port, m2_track_idx, dff_idx) in [
-            ("ce", m2_ce_track_idx, dsn.num_dffs - 2),
-            ("we", m2_we_track_idx, dsn.num_dffs - 1),
+            (
+                self.params.port_style().enable_pin(),
+                m2_ce_track_idx,
+                dsn.num_dffs - 2,
+            ),
+            (
+                self.params.port_style().write_pin(),
+                m2_we_track_idx,
+                dsn.num_dffs - 1,
+            ),
         ] {
             let control_port = control.port(port)?.largest_rect(m1).unwrap();
             let dff_port = dffs
@@ -1433,6 +2066,55 @@ impl SramInner {
             )?;
         }
 
+        // Connect the column circuitry of each half or bank to the control signals
+        // of the one to its left, bridging each stripe across the gap between them.
+        for pair in halves.windows(2) {
+            let (left_cols, right_cols) = (pair[0].1, pair[1].1);
+            let ports = ["pc_b", "sense_en", "clk", "rstb"]
+                .into_iter()
+                .map(|name| (PortId::from(name), m2))
+                .chain(
+                    (0..self.params.mux_ratio())
+                        .flat_map(|i| ["sel", "sel_b"].map(|name| (PortId::new(name, i), m2))),
+                )
+                .chain(std::iter::once((PortId::from("we"), m1)));
+            for (port_id, layer) in ports {
+                let left = left_cols
+                    .port(port_id.clone())?
+                    .shapes(layer)
+                    .filter_map(|shape| shape.as_rect())
+                    .collect::<Vec<_>>();
+                let right = right_cols
+                    .port(port_id)?
+                    .shapes(layer)
+                    .filter_map(|shape| shape.as_rect())
+                    .collect::<Vec<_>>();
+                let spans = left.iter().map(|rect| rect.vspan()).collect::<HashSet<_>>();
+                for span in spans {
+                    let start = left
+                        .iter()
+                        .filter(|rect| rect.vspan() == span)
+                        .map(|rect| rect.right())
+                        .max()
+                        .unwrap();
+                    let Some(stop) = right
+                        .iter()
+                        .filter(|rect| rect.vspan() == span)
+                        .map(|rect| rect.left())
+                        .min()
+                    else {
+                        continue;
+                    };
+                    draw_rect(
+                        layer,
+                        Rect::from_spans(Span::new(start, stop), span),
+                        &mut router,
+                        ctx,
+                    );
+                }
+            }
+        }
+
         // Route replica cell array to replica precharge
         for i in 0..2 {
             for port_name in ["bl", "br"] {
@@ -1630,12 +2312,16 @@ impl SramInner {
         }
 
         // Route column select bits.
+        //
+        // The address of port 1 of a dual-port array is complemented by swapping the true
+        // and complementary outputs of its DFFs.
+        let complement = usize::from(dual_port == Some(1));
         for i in 0..self.params.col_select_bits() {
             for j in 0..2 {
                 let idx = 2 * i + j;
                 let dff_idx = dsn.num_dffs - i - 3;
                 let port_rect = col_dec
-                    .port(format!("predecode_{i}_{j}"))?
+                    .port(format!("predecode_{i}_{}", j ^ complement))?
                     .largest_rect(m1)
                     .unwrap();
                 let rect = if j == 0 {
@@ -1665,7 +2351,10 @@ impl SramInner {
                 let m1_track_idx = m1_write_driver_en_track_idx + 2 + idx as i64;
                 let m1_track = m1_tracks.index(m1_track_idx);
                 let m2_track_b_idx = if m1_track.start() < dff_port.left() {
-                    dff_m2_track_idx + 2 * self.params.addr_width() as i64 - 1 - idx as i64
+                    dff_m2_track_idx
+                        + 2 * (self.params.row_bits() + self.params.col_select_bits()) as i64
+                        - 1
+                        - idx as i64
                 } else {
                     dff_m2_track_idx + 2 * self.params.row_bits() as i64 + idx as i64
                 };
@@ -1689,7 +2378,7 @@ impl SramInner {
                 let idx = 2 * i + j;
                 let dff_idx = dsn.num_dffs - i - 3 - self.params.col_select_bits();
                 let port_rect = addr_gate
-                    .port(PortId::new("in", idx))?
+                    .port(PortId::new("in", idx ^ complement))?
                     .largest_rect(m0)
                     .unwrap();
                 let rect = if j == 0 {
@@ -1777,10 +2466,16 @@ impl SramInner {
         straps.set_strap_layers([m1, m2]);
 
         // Helper function for connecting bitcell ports to power straps.
+        //
+        // If `gated` is set, the `vdd` collectors are instead left for `vdd_array`,
+        // which is supplied by its own header switch. The m2 collectors to the right
+        // of the array are returned so that they can be connected to the switch.
         let mut connect_bitcells_to_straps = |inst: &Instance,
                                               port_ids: Vec<(PortId, SingleSupplyNet)>,
-                                              brect_expand: i64|
-         -> Result<()> {
+                                              brect_expand: i64,
+                                              gated: bool|
+         -> Result<Vec<Rect>> {
+            let mut gated_collectors = Vec::new();
             let target_brect = inst
                 .brect()
                 .expand_dir(Dir::Horiz, brect_expand)
@@ -1824,14 +2519,21 @@ impl SramInner {
                             .with(!dir, span)
                             .build();
                         ctx.draw_rect(layer, curr);
-                        straps.add_target(layer, Target::new(net, curr));
+                        if gated && net == SingleSupplyNet::Vdd {
+                            router.block(layer, curr.expand(360));
+                            if layer == m2 && sign == Sign::Pos {
+                                gated_collectors.push(curr);
+                            }
+                        } else {
+                            straps.add_target(layer, Target::new(net, curr));
+                        }
                     }
                 }
             }
-            Ok(())
+            Ok(gated_collectors)
         };
 
-        // Connect bitcell array to power straps.
+        // Connect bitcell arrays to power straps.
         let mut port_ids: Vec<(PortId, SingleSupplyNet)> = ["vpwr", "vgnd", "vpb", "vnb"]
             .into_iter()
             .map(|x| {
@@ -1852,7 +2554,21 @@ impl SramInner {
             }
         }
 
-        connect_bitcells_to_straps(&bitcells, port_ids, 5_720)?;
+        let vdd_array = if dual_port.is_some() {
+            vec![Vec::new()]
+        } else {
+            halves
+                .iter()
+                .map(|&(bitcells, _)| {
+                    connect_bitcells_to_straps(
+                        bitcells,
+                        port_ids.clone(),
+                        5_720,
+                        self.params.power_gating(),
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
 
         // Connect replica bitcell array to power straps.
         let mut port_ids: Vec<(PortId, SingleSupplyNet)> = ["vpwr", "vgnd", "vpb", "vnb"]
@@ -1873,46 +2589,167 @@ impl SramInner {
                 port_ids.push((PortId::new("wl", i), SingleSupplyNet::Vss));
             }
         }
-        connect_bitcells_to_straps(&rbl, port_ids, 3_200)?;
+        connect_bitcells_to_straps(&rbl, port_ids, 3_200, false)?;
+
+        // Connect a dual-port array to power straps through bars on the sides facing this port,
+        // between the array and the fans. The `vdd` lines of the bottom row run down to an m2 bar
+        // that extends to the left of the array, and the `vss` lines of the leftmost column
+        // run left to an m1 bar that extends beneath it.
+        if dual_port.is_some() {
+            let brect = bitcells.brect();
+            let vdd_bar = Rect::from_spans(
+                Span::new(brect.left() - 4_000, brect.right()),
+                Span::with_stop_and_length(brect.bottom() - 600, 800),
+            );
+            let vss_bar = Rect::from_spans(
+                Span::with_stop_and_length(brect.left() - 600, 800),
+                Span::new(brect.bottom() - 3_500, brect.top()),
+            );
+            draw_rect(m2, vdd_bar, &mut router, ctx);
+            draw_rect(m1, vss_bar, &mut router, ctx);
 
-        // Connect column circuitry to power straps.
-        for layer in [m1, m2] {
-            for port_name in ["vdd", "vss"] {
-                for port in cols
-                    .port(port_name)?
-                    .shapes(layer)
-                    .filter_map(|shape| shape.as_rect())
-                    .filter(|rect| rect.height() < 5000)
-                {
-                    let new_span = cols.brect().hspan().expand_all(5_000);
-                    if layer == m2 {
-                        ctx.merge_port(CellPort::with_shape(
-                            port_name,
-                            m2,
-                            Rect::from_spans(new_span, port.vspan()),
-                        ));
+            let vdd = bitcells
+                .port("vdd")?
+                .shapes(m1)
+                .filter_map(|shape| shape.as_rect())
+                .collect::<Vec<_>>();
+            let bottom = vdd.iter().map(|rect| rect.bottom()).min().unwrap();
+            for rect in vdd.into_iter().filter(|rect| rect.bottom() == bottom) {
+                let rect = rect.with_vspan(rect.vspan().add_point(vdd_bar.bottom()));
+                draw_rect(m1, rect, &mut router, ctx);
+                draw_via(m1, rect, m2, vdd_bar, ctx)?;
+            }
+
+            let vss = bitcells
+                .port("vss")?
+                .shapes(m2)
+                .filter_map(|shape| shape.as_rect())
+                .collect::<Vec<_>>();
+            let left = vss.iter().map(|rect| rect.left()).min().unwrap();
+            for rect in vss.into_iter().filter(|rect| rect.left() == left) {
+                let rect = rect.with_hspan(rect.hspan().add_point(vss_bar.left()));
+                draw_rect(m2, rect, &mut router, ctx);
+                draw_via(m1, vss_bar, m2, rect, ctx)?;
+            }
+
+            straps.add_target(m2, Target::new(SingleSupplyNet::Vdd, vdd_bar));
+            straps.add_target(m1, Target::new(SingleSupplyNet::Vss, vss_bar));
+        }
+
+        // The gated collectors of the rightmost bitcell array are extended to the header switch.
+        for &rect in vdd_array.last().unwrap() {
+            ctx.merge_port(CellPort::with_shape("vdd_array", m2, rect));
+        }
+
+        if let Some(sections) = sections.as_ref() {
+            // Connect the `suppress` and `clamp` stripes of all wordline drivers
+            // to those of the leftmost wordline drivers with m3 lines above the drivers.
+            let wl_driver = dsn.wl_driver.as_ref().unwrap();
+            let mut bot = sections
+                .drivers
+                .iter()
+                .chain(halves.iter().map(|&(bitcells, _)| bitcells))
+                .map(|inst| inst.brect().top())
+                .max()
+                .unwrap()
+                + 1_000;
+            for (port, present) in [
+                ("suppress", wl_driver.suppression.is_some()),
+                ("clamp", wl_driver.clamp.is_some()),
+            ] {
+                if !present {
+                    continue;
+                }
+                let stripes = sections
+                    .drivers
+                    .iter()
+                    .map(|driver| -> Result<Rect> {
+                        Ok(driver.port(port)?.largest_rect(m2).unwrap())
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let line = Rect::from_spans(
+                    Span::new(stripes[0].left(), stripes.last().unwrap().right()),
+                    Span::with_start_and_length(bot, 400),
+                );
+                ctx.draw_rect(m3, line);
+                for stripe in stripes {
+                    let stripe = stripe.with_vspan(stripe.vspan().union(line.vspan()));
+                    draw_rect(m2, stripe, &mut router, ctx);
+                    draw_via(m2, stripe, m3, line, ctx)?;
+                }
+                bot = line.top() + 1_000;
+            }
+
+            // The gated collectors of all but the rightmost array are tied to a rail above
+            // the arrays with m1 spines to their right, since the array to their right blocks
+            // them from being extended to the header switch.
+            if self.params.power_gating() {
+                let rail_vspan = Span::with_start_and_length(bot, 800);
+                let rail = Rect::from_spans(
+                    Span::new(
+                        bitcells.brect().right() + 4_000,
+                        halves.last().unwrap().0.brect().right() + 5_720,
+                    ),
+                    rail_vspan,
+                );
+                draw_rect(m2, rail, &mut router, ctx);
+                for (collectors, &(array, _)) in vdd_array.iter().zip(halves.iter()).rev().skip(1) {
+                    let spine = Rect::from_spans(
+                        Span::with_start_and_length(array.brect().right() + 4_000, 800),
+                        collectors
+                            .iter()
+                            .map(|rect| rect.vspan())
+                            .fold(rail_vspan, |a, b| a.union(b)),
+                    );
+                    draw_rect(m1, spine, &mut router, ctx);
+                    for &rect in collectors.iter().chain([&rail]) {
+                        draw_via(m1, spine, m2, rect, ctx)?;
                     }
-                    for sign in [Sign::Neg, Sign::Pos] {
-                        let rect = port.with_hspan(Span::new(
-                            new_span.point(sign),
-                            port.hspan().point(sign) - sign.as_int() * 800,
-                        ));
-                        if layer == m1 {
-                            draw_via(m1, port, m2, rect, ctx)?;
-                            ctx.merge_port(CellPort::with_shape(port_name, m2, rect));
+                }
+                ctx.merge_port(CellPort::with_shape("vdd_array", m2, rail));
+            }
+        }
+
+        // Connect column circuitry to power straps.
+        for &(_, cols) in halves.iter() {
+            for layer in [m1, m2] {
+                for port_name in ["vdd", "vss"] {
+                    for port in cols
+                        .port(port_name)?
+                        .shapes(layer)
+                        .filter_map(|shape| shape.as_rect())
+                        .filter(|rect| rect.height() < 5000)
+                    {
+                        let new_span = cols.brect().hspan().expand_all(5_000);
+                        if layer == m2 {
+                            ctx.merge_port(CellPort::with_shape(
+                                port_name,
+                                m2,
+                                Rect::from_spans(new_span, port.vspan()),
+                            ));
+                        }
+                        for sign in [Sign::Neg, Sign::Pos] {
+                            let rect = port.with_hspan(Span::new(
+                                new_span.point(sign),
+                                port.hspan().point(sign) - sign.as_int() * 800,
+                            ));
+                            if layer == m1 {
+                                draw_via(m1, port, m2, rect, ctx)?;
+                                ctx.merge_port(CellPort::with_shape(port_name, m2, rect));
+                            }
+                            draw_rect(m2, rect, &mut router, ctx);
+                            straps.add_target(
+                                m2,
+                                Target::new(
+                                    match port_name {
+                                        "vdd" => SingleSupplyNet::Vdd,
+                                        "vss" => SingleSupplyNet::Vss,
+                                        _ => unreachable!(),
+                                    },
+                                    rect,
+                                ),
+                            );
                         }
-                        draw_rect(m2, rect, &mut router, ctx);
-                        straps.add_target(
-                            m2,
-                            Target::new(
-                                match port_name {
-                                    "vdd" => SingleSupplyNet::Vdd,
-                                    "vss" => SingleSupplyNet::Vss,
-                                    _ => unreachable!(),
-                                },
-                                rect,
-                            ),
-                        );
                     }
                 }
             }
@@ -1928,7 +2765,10 @@ impl SramInner {
             &pc_b_buffer,
             &sense_en_buffer,
             &write_driver_en_buffer,
-        ] {
+        ]
+        .into_iter()
+        .chain(wl_drivers.iter().copied())
+        {
             for port_name in ["vdd", "vss"] {
                 for port in inst.port(port_name)?.shapes(m1) {
                     if let Shape::Rect(rect) = port {
@@ -1956,7 +2796,10 @@ impl SramInner {
             &pc_b_buffer,
             &sense_en_buffer,
             &write_driver_en_buffer,
-        ] {
+        ]
+        .into_iter()
+        .chain(wl_drivers.iter().copied())
+        {
             for port_name in ["vdd", "vss"] {
                 for port in inst
                     .port(port_name)?
@@ -1987,12 +2830,28 @@ impl SramInner {
             }
         }
 
+        // Tie the enables of the wordline drivers of a split array high, extending them past
+        // the drivers like the m2 power straps above. Those of the banks, including the halves
+        // of a divided-wordline array, are routed to the bank select decoder below.
+        for driver in wl_drivers.iter().filter(|_| bank_select.is_none()) {
+            let wl_en = driver.port("wl_en")?.largest_rect(m1).unwrap();
+            let rect = wl_en.with_vspan(driver.brect().vspan().expand_all(2_000));
+            draw_rect(m1, rect, &mut router, ctx);
+            straps.add_target(m1, Target::new(SingleSupplyNet::Vdd, rect));
+        }
+
         // Connect m2 power straps to grid.
         for (inst, port_names, expand) in [
             (&wlen_buffer, vec!["vdd", "vss"], 2_000),
             (&replica_pc, vec!["vdd"], 5_520),
             (&replica_nmos, vec!["vdd", "vss"], 5_520),
-        ] {
+        ]
+        .into_iter()
+        .chain(
+            bank_select
+                .as_ref()
+                .map(|inst| (inst, vec!["vdd", "vss"], 2_000)),
+        ) {
             for port_name in port_names {
                 for port in inst.port(port_name)?.shapes(m2) {
                     if let Shape::Rect(rect) = port {
@@ -2015,51 +2874,302 @@ impl SramInner {
             }
         }
 
-        // Route column peripheral outputs to pins on bounding box of SRAM
-        let groups = self.params.data_width();
+        // Route column peripheral outputs to pins on bounding box of SRAM.
+        //
+        // Each half of a split array holds a contiguous half of the data and write mask bits,
+        // while each bank has its own data and write mask bits.
+        let groups = self.params.section_data_width();
         for (port, width) in [
             ("dout", groups),
             ("din", groups),
-            ("wmask", self.params.wmask_width()),
+            ("wmask", groups / self.params.wmask_granularity()),
         ] {
-            for i in 0..width {
-                let port_id = PortId::new(port, i);
-                let rect = cols.port(port_id.clone())?.largest_rect(m1).unwrap();
-                match port {
-                    "wmask" => {
-                        let m2_rect =
-                            rect.with_vspan(Span::with_stop_and_length(rect.bottom() + 320, 800));
-                        let pin_rect = m2_rect
-                            .with_vspan(Span::new(router_bbox.bottom(), m2_rect.bottom() + 320));
-                        draw_rect(m2, m2_rect, &mut router, ctx);
-                        draw_rect(m1, pin_rect, &mut router, ctx);
-                        draw_via(m1, rect, m2, m2_rect, ctx)?;
-                        draw_via(m1, pin_rect, m2, m2_rect, ctx)?;
-                        ctx.add_port(CellPort::builder().id(port_id).add(m1, pin_rect).build())?;
-                    }
-                    "dout" => {
-                        let pin_rect =
-                            rect.with_vspan(Span::with_start_and_length(router_bbox.bottom(), 320));
-                        let m2_rect =
-                            rect.with_vspan(Span::with_start_and_length(pin_rect.top() - 320, 800));
-                        let m1_rect =
-                            rect.with_vspan(Span::new(m2_rect.top() - 320, rect.bottom()));
-                        draw_rect(m1, m1_rect, &mut router, ctx);
-                        draw_rect(m2, m2_rect, &mut router, ctx);
-                        draw_rect(m1, pin_rect, &mut router, ctx);
-                        draw_via(m1, m1_rect, m2, m2_rect, ctx)?;
-                        draw_via(m1, pin_rect, m2, m2_rect, ctx)?;
-                        ctx.add_port(CellPort::builder().id(port_id).add(m1, pin_rect).build())?;
-                    }
-                    _ => {
-                        let rect = rect.with_vspan(rect.vspan().add_point(router_bbox.bottom()));
-                        draw_rect(m1, rect, &mut router, ctx);
-                        ctx.add_port(CellPort::builder().id(port_id).add(m1, rect).build())?;
+            for (j, &(_, cols)) in halves.iter().enumerate() {
+                for i in 0..width {
+                    let port_id = PortId::new(port, j * width + i);
+                    let rect = cols.port(PortId::new(port, i))?.largest_rect(m1).unwrap();
+                    match port {
+                        "wmask" => {
+                            let m2_rect = rect
+                                .with_vspan(Span::with_stop_and_length(rect.bottom() + 320, 800));
+                            let pin_rect = m2_rect.with_vspan(Span::new(
+                                router_bbox.bottom(),
+                                m2_rect.bottom() + 320,
+                            ));
+                            draw_rect(m2, m2_rect, &mut router, ctx);
+                            draw_rect(m1, pin_rect, &mut router, ctx);
+                            draw_via(m1, rect, m2, m2_rect, ctx)?;
+                            draw_via(m1, pin_rect, m2, m2_rect, ctx)?;
+                            ctx.add_port(
+                                CellPort::builder().id(port_id).add(m1, pin_rect).build(),
+                            )?;
+                        }
+                        "dout" => {
+                            let pin_rect = rect
+                                .with_vspan(Span::with_start_and_length(router_bbox.bottom(), 320));
+                            let m2_rect = rect
+                                .with_vspan(Span::with_start_and_length(pin_rect.top() - 320, 800));
+                            let m1_rect =
+                                rect.with_vspan(Span::new(m2_rect.top() - 320, rect.bottom()));
+                            draw_rect(m1, m1_rect, &mut router, ctx);
+                            draw_rect(m2, m2_rect, &mut router, ctx);
+                            draw_rect(m1, pin_rect, &mut router, ctx);
+                            draw_via(m1, m1_rect, m2, m2_rect, ctx)?;
+                            draw_via(m1, pin_rect, m2, m2_rect, ctx)?;
+                            ctx.add_port(
+                                CellPort::builder().id(port_id).add(m1, pin_rect).build(),
+                            )?;
+                        }
+                        _ => {
+                            let rect =
+                                rect.with_vspan(rect.vspan().add_point(router_bbox.bottom()));
+                            draw_rect(m1, rect, &mut router, ctx);
+                            ctx.add_port(CellPort::builder().id(port_id).add(m1, rect).build())?;
+                        }
                     }
                 }
             }
         }
 
+        // The final wordline drivers, whose `suppress` and `clamp` stripes are routed to `read`
+        // and `sleep`. In a split or banked array, the leftmost ones also carry the stripes of the
+        // others.
+        let (wl_stage, suppression, clamp) = match (sections.as_ref(), dsn.wl_driver.as_ref()) {
+            (Some(sections), Some(params)) => (
+                &sections.drivers[0],
+                params.suppression.is_some(),
+                params.clamp.is_some(),
+            ),
+            _ => (
+                &decoder,
+                dsn.row_decoder.suppression.is_some(),
+                dsn.row_decoder.clamp.is_some(),
+            ),
+        };
+
+        // Route the read enable to the wordline suppression devices.
+        if suppression || rwl_suppression.is_some() {
+            let (port, side, loc) = if self.params.port_style().active_low() {
+                ("q", Side::Right, TrackLocator::EndsBefore)
+            } else {
+                ("q_n", Side::Left, TrackLocator::StartsAfter)
+            };
+            let rect = dffs
+                .port(PortId::new(port, dsn.num_dffs - 1))?
+                .first_rect(m0, side)?;
+            let track_span = m1_tracks
+                .index(m1_tracks.track_with_loc(loc, rect.side(side) - 140 * side.sign().as_int()));
+            let m0_rect = rect.with_hspan(track_span);
+            let via = draw_via(m0, m0_rect, m1, m0_rect, ctx)?;
+            let read = router.expand_to_grid(
+                Rect::from_spans(track_span, via.layer_bbox(m1).into_rect().vspan()),
+                ExpandToGridStrategy::Minimum,
+            );
+            ctx.draw_rect(m1, read);
+            router.occupy(m1, read, "read")?;
+
+            if suppression {
+                // Bring the drivers' `suppress` stripe out beneath the drivers.
+                let suppress = wl_stage.port("suppress")?.largest_rect(m2).unwrap();
+                let dst = router.expand_to_grid(
+                    Rect::from_spans(
+                        suppress.hspan(),
+                        Span::with_stop_and_length(wl_stage.brect().bottom() - 800, 400),
+                    ),
+                    ExpandToGridStrategy::Minimum,
+                );
+                let m2_rect = Rect::from_spans(
+                    suppress.hspan().union(dst.hspan()),
+                    Span::new(dst.bottom(), suppress.bottom() + 320),
+                );
+                draw_rect(m2, m2_rect, &mut router, ctx);
+                ctx.draw_rect(m1, dst);
+                draw_via(m1, dst, m2, m2_rect, ctx)?;
+                router.occupy(m1, dst, "read")?;
+                router.route_with_net(ctx, m1, read, m1, dst, "read")?;
+            }
+
+            if let Some(mos) = rwl_suppression.as_ref() {
+                // Connect the drains to the replica wordline.
+                let wl = rbl
+                    .port(PortId::new("wl", dsn.rbl_wl_index))?
+                    .largest_rect(m2)
+                    .unwrap();
+                let y = mos.port("y")?.largest_rect(m1).unwrap();
+                let m2_rect = wl.with_hspan(wl.hspan().union(y.hspan()));
+                draw_rect(m2, m2_rect, &mut router, ctx);
+                draw_via(m1, y, m2, m2_rect, ctx)?;
+
+                // Bring the gates out above the device.
+                let gate = mos.port("gate")?.largest_rect(m1).unwrap();
+                let dst = router.expand_to_grid(
+                    Rect::from_spans(
+                        gate.hspan(),
+                        Span::with_start_and_length(mos.brect().top() + 800, 400),
+                    ),
+                    ExpandToGridStrategy::Minimum,
+                );
+                ctx.draw_rect(m1, gate.with_vspan(gate.vspan().union(dst.vspan())));
+                ctx.draw_rect(m1, dst);
+                router.occupy(m1, dst, "read")?;
+                router.route_with_net(ctx, m1, read, m1, dst, "read")?;
+
+                straps.add_target(
+                    m1,
+                    Target::new(
+                        SingleSupplyNet::Vss,
+                        mos.port("vss")?.largest_rect(m1).unwrap(),
+                    ),
+                );
+            }
+        }
+
+        // Route the trim inputs to pins to the left of the DFFs.
+        let m1_trim_track_idx =
+            m1_tracks.track_with_loc(TrackLocator::EndsBefore, dffs.brect().left() - 1_400);
+        for (i, (name, bit)) in ["trim_saen", "trim_wl"]
+            .into_iter()
+            .flat_map(|name| (0..trim_bits).map(move |bit| (name, bit)))
+            .enumerate()
+        {
+            let port_id = PortId::new(name, bit);
+            let net = format!("{name}_{bit}");
+            let control_port = router.expand_to_grid(
+                control.port(port_id.clone())?.largest_rect(m1).unwrap(),
+                ExpandToGridStrategy::Minimum,
+            );
+            ctx.draw_rect(m1, control_port);
+            router.occupy(m1, control_port, &net)?;
+
+            let m1_pin = Rect::from_spans(
+                m1_tracks.index(m1_trim_track_idx - 2 * i as i64),
+                Span::with_start_and_length(router_bbox.bottom(), 320),
+            );
+            ctx.draw_rect(m1, m1_pin);
+            router.occupy(m1, m1_pin, &net)?;
+            ctx.add_port(CellPort::with_shape(port_id, m1, m1_pin))?;
+            router.route_with_net(ctx, m1, control_port, m1, m1_pin, &net)?;
+        }
+
+        // Route the drivers' `clamp` stripe to a `sleep` pin to the left of the trim pins.
+        if clamp {
+            let clamp = wl_stage.port("clamp")?.largest_rect(m2).unwrap();
+            let dst = router.expand_to_grid(
+                Rect::from_spans(
+                    clamp.hspan(),
+                    Span::with_stop_and_length(wl_stage.brect().bottom() - 1_600, 400),
+                ),
+                ExpandToGridStrategy::Minimum,
+            );
+            let m2_rect = Rect::from_spans(
+                clamp.hspan().union(dst.hspan()),
+                Span::new(dst.bottom(), clamp.bottom() + 320),
+            );
+            draw_rect(m2, m2_rect, &mut router, ctx);
+            ctx.draw_rect(m1, dst);
+            draw_via(m1, dst, m2, m2_rect, ctx)?;
+            router.occupy(m1, dst, "sleep")?;
+
+            let m1_pin = Rect::from_spans(
+                m1_tracks.index(m1_trim_track_idx - 2 * (2 * trim_bits) as i64),
+                Span::with_start_and_length(router_bbox.bottom(), 320),
+            );
+            ctx.draw_rect(m1, m1_pin);
+            router.occupy(m1, m1_pin, "sleep")?;
+            ctx.add_port(CellPort::with_shape("sleep", m1, m1_pin))?;
+            router.route_with_net(ctx, m1, dst, m1, m1_pin, "sleep")?;
+        }
+
+        // Route the bank bits to the bank select decoder, and its outputs to the enables
+        // of the wordline drivers of each bank.
+        if let Some(bank_select) = bank_select.as_ref() {
+            let bank_bits = self.params.bank_select_bits();
+            let stripes = (0..bank_bits)
+                .flat_map(|i| (0..2).map(move |j| (i, j)))
+                .map(|(i, j)| -> Result<Rect> {
+                    Ok(bank_select
+                        .port(format!("predecode_{i}_{j}"))?
+                        .largest_rect(m2)
+                        .unwrap())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let stripe_bot = stripes.iter().map(|rect| rect.bottom()).min().unwrap();
+
+            // Each stripe is extended to an m1 stub to the right of the decoder. Lower stripes
+            // end closer to the decoder, so that they do not cross the stubs of higher stripes.
+            let m1_stub_track_idx = m1_tracks
+                .track_with_loc(TrackLocator::StartsAfter, bank_select.brect().right() + 700);
+            for (idx, &stripe) in stripes.iter().enumerate() {
+                let (i, j) = (idx / 2, idx % 2);
+                let net = format!("bank_addr_{i}_{j}");
+                let track_span =
+                    m1_tracks.index(m1_stub_track_idx + (stripes.len() - 1 - idx) as i64);
+                let m2_rect = stripe.with_hspan(stripe.hspan().union(track_span));
+                draw_rect(m2, m2_rect, &mut router, ctx);
+                let stub = router.expand_to_grid(
+                    Rect::from_spans(track_span, Span::new(stripe_bot - 1_200, stripe.top())),
+                    ExpandToGridStrategy::Minimum,
+                );
+                ctx.draw_rect(m1, stub);
+                draw_via(m1, stub, m2, m2_rect, ctx)?;
+                router.occupy(m1, stub, &net)?;
+
+                // Bank bit `i` is stored by DFF `bank_bits - i - 1`.
+                let (port, side, loc) = if j == 0 {
+                    ("q_n", Side::Left, TrackLocator::StartsAfter)
+                } else {
+                    ("q", Side::Right, TrackLocator::EndsBefore)
+                };
+                let rect = dffs
+                    .port(PortId::new(port, bank_bits - i - 1))?
+                    .first_rect(m0, side)?;
+                let track_span = m1_tracks.index(
+                    m1_tracks.track_with_loc(loc, rect.side(side) - 140 * side.sign().as_int()),
+                );
+                let m0_rect = rect.with_hspan(track_span);
+                let via = draw_via(m0, m0_rect, m1, m0_rect, ctx)?;
+                let dff_port = router.expand_to_grid(
+                    Rect::from_spans(track_span, via.layer_bbox(m1).into_rect().vspan()),
+                    ExpandToGridStrategy::Minimum,
+                );
+                ctx.draw_rect(m1, dff_port);
+                router.occupy(m1, dff_port, &net)?;
+                router.route_with_net(ctx, m1, dff_port, m1, stub, &net)?;
+            }
+
+            // Bring each output out above the decoder and each enable out beneath its drivers.
+            for (b, driver) in wl_drivers.iter().enumerate() {
+                let net = format!("bank_en_{b}");
+                let y = bank_select
+                    .port(PortId::new("y", b))?
+                    .largest_rect(m1)
+                    .unwrap();
+                let src = router.expand_to_grid(
+                    Rect::from_spans(
+                        y.hspan(),
+                        Span::with_start_and_length(bank_select.brect().top() + 800, 400),
+                    ),
+                    ExpandToGridStrategy::Minimum,
+                );
+                ctx.draw_rect(m1, y.with_vspan(Span::new(y.bottom(), src.top())));
+                ctx.draw_rect(m1, src.with_hspan(src.hspan().union(y.hspan())));
+                router.occupy(m1, src, &net)?;
+
+                let wl_en = driver.port("wl_en")?.largest_rect(m1).unwrap();
+                let dst = router.expand_to_grid(
+                    Rect::from_spans(
+                        wl_en.hspan(),
+                        Span::with_stop_and_length(driver.brect().bottom() - 800, 400),
+                    ),
+                    ExpandToGridStrategy::Minimum,
+                );
+                ctx.draw_rect(m1, wl_en.with_vspan(Span::new(dst.bottom(), wl_en.top())));
+                ctx.draw_rect(m1, dst.with_hspan(dst.hspan().union(wl_en.hspan())));
+                router.occupy(m1, dst, &net)?;
+                router.route_with_net(ctx, m1, src, m1, dst, &net)?;
+            }
+        }
+
         let straps = straps.fill(&router, ctx)?;
         ctx.set_metadata(straps);
 
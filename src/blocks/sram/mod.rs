@@ -1,24 +1,24 @@
 use self::schematic::fanout_buffer_stage;
 use crate::blocks::bitcell_array::replica::ReplicaCellArray;
 use crate::blocks::columns::ColumnsPhysicalDesignScript;
-use crate::blocks::control::{ControlLogicParams, ControlLogicReplicaV2};
+use crate::blocks::control::{ControlLogicParams, ControlLogicReplicaV2, TimingTrimParams};
 use crate::blocks::precharge::layout::ReplicaPrecharge;
 use crate::blocks::precharge::PrechargeParams;
 use arcstr::ArcStr;
 use layout::{ReplicaColumnMos, ReplicaColumnMosParams, ReplicaMetalRoutingParams};
 use serde::{Deserialize, Serialize};
-use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_repr::Serialize_repr;
 use std::path::{Path, PathBuf};
 use subgeom::bbox::BoundBox;
 use subgeom::{snap_to_grid, Corner, Dir, Point, Rect, Span};
 use substrate::component::{error, Component};
 use substrate::data::SubstrateCtx;
 use substrate::error::ErrorSource;
-use substrate::layout::cell::{CellPort, Element, Port, PortConflictStrategy, PortId};
+use substrate::layout::cell::{CellPort, Element, Instance, Port, PortConflictStrategy, PortId};
 use substrate::layout::elements::via::{Via, ViaExpansion, ViaParams};
 use substrate::layout::group::Group;
 use substrate::layout::layers::selector::Selector;
-use substrate::layout::layers::LayerSpec;
+use substrate::layout::layers::{LayerKey, LayerSpec};
 use substrate::layout::placement::place_bbox::PlaceBbox;
 use substrate::layout::routing::auto::straps::PlacedStraps;
 use substrate::layout::straps::SingleSupplyNet;
@@ -26,41 +26,242 @@ use substrate::schematic::circuit::Direction;
 use substrate::schematic::context::SchematicCtx;
 use substrate::script::Script;
 
+use self::pins::PinPlacement;
+use self::redundancy::Redundancy;
+use super::bist::march::MarchAlgorithm;
 use super::bitcell_array::replica::ReplicaCellArrayParams;
-use super::bitcell_array::SpCellArrayParams;
+use super::bitcell_array::{BitcellType, SpCellArrayParams};
 use super::columns::{self, ColParams, ColPeripherals, COL_CAPACITANCES, COL_PARAMS};
 use super::decoder::{
     Decoder, DecoderParams, DecoderPhysicalDesignParams, DecoderStageParams, DecoderStyle,
-    DecoderTree, RoutingStyle, INV_MODEL, INV_PARAMS, NAND2_MODEL, NAND2_PARAMS,
+    DecoderTree, RoutingStyle, SuppressionParams, INV_MODEL, INV_PARAMS, NAND2_MODEL, NAND2_PARAMS,
+    SUPPRESSION_FINGER_WIDTH,
 };
-use super::gate::{AndParams, GateParams};
+use super::ecc::EccMode;
+use super::gate::sizing::InverterGateTreeNode;
+use super::gate::{AndParams, GateParams, PrimitiveGateParams};
+use super::guard_ring::switches::{PowerSwitchParams, PowerSwitches};
 use super::guard_ring::{GuardRing, GuardRingParams, SupplyRings};
 use super::precharge::layout::ReplicaPrechargeParams;
 use crate::blocks::columns::layout::DffArray;
 use crate::blocks::decoder::{DecoderStage, NAND3_MODEL};
 use crate::blocks::tgatemux::{TGateMux, TGateMuxParams};
 
+pub mod banked;
+pub mod dual_port;
+pub mod estimate;
 pub mod layout;
+pub mod pins;
+pub mod redundancy;
 pub mod schematic;
 pub mod testbench;
 
 pub const WORDLINE_CAP_PER_CELL: f64 = 0.00000000000001472468276676486 / 12.;
 pub const BITLINE_CAP_PER_CELL: f64 = 0.00000000000008859364177937068 / 128.;
 
+/// The minimum number of rows in the bitcell array.
+///
+/// Arrays with fewer words per column are padded with rows
+/// that hold the addresses at or above the number of words.
+pub const MIN_ROWS: usize = 16;
+
 /// The threshold at which further decoder scaling does not help,
 /// since delay is dominated by routing resistance/capacitance.
 pub const WORDLINE_CAP_MAX: f64 = 500e-15;
 
-#[derive(Debug, Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
+/// The fraction of vdd by which wordline underdrive lowers the wordline high level.
+pub const WORDLINE_UNDERDRIVE: f64 = 0.1;
+/// Total NMOS width of the `sky130_fd_sc_hs__buf_16` driving the replica wordline, in nm.
+const RWL_BUF_NWIDTH: i64 = 11_840;
+/// The NMOS clamps that hold wordlines low while the periphery is powered down.
+const WL_CLAMP: SuppressionParams = SuppressionParams {
+    width: 420,
+    length: 150,
+    nf: 1,
+};
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SramConfig {
     pub num_words: usize,
     pub data_width: usize,
-    pub mux_ratio: MuxRatio,
+    /// Required unless `aspect_ratio`, `max_width` or `max_height` is given,
+    /// in which case the planner chooses the mux ratio if it is omitted.
+    #[serde(default)]
+    pub mux_ratio: Option<MuxRatio>,
     pub write_size: usize,
+    #[serde(default)]
+    pub ports: Ports,
+    #[serde(default)]
+    pub ecc: EccMode,
+    /// Wraps the SRAM with a built-in self-test controller that runs this march test.
+    #[serde(default)]
+    pub bist: Option<MarchAlgorithm>,
+    #[serde(default)]
+    pub spare_rows: usize,
+    #[serde(default)]
+    pub spare_cols: usize,
+    #[serde(default)]
+    pub power_straps: Option<PowerStrapParams>,
+    #[serde(default)]
+    pub pins: Option<PinPlacement>,
+    /// Splits the bitcell array into two halves, each with its own wordline drivers.
+    #[serde(default)]
+    pub split_array: bool,
+    /// Enables the wordline drivers of only the half of a split array holding the selected column.
+    #[serde(default)]
+    pub divided_wordlines: bool,
+    /// Divides the words among this many banks, which must be 2 or 4.
+    #[serde(default)]
+    pub banks: Option<usize>,
+    #[serde(default)]
+    pub bitcell: BitcellType,
+    #[serde(default)]
+    pub port_style: PortStyle,
+    #[serde(default)]
+    pub write_mode: WriteMode,
+    #[serde(default)]
+    pub output: OutputMode,
+    /// Adds `sleep` and `retain` pins that power down the periphery.
+    #[serde(default)]
+    pub power_gating: bool,
+    /// Lowers the wordline high level during reads.
+    #[serde(default)]
+    pub wordline_underdrive: bool,
+    /// Adds `trim_saen` and `trim_wl` pins of this many bits that adjust the control logic delays.
+    #[serde(default)]
+    pub timing_trim: Option<usize>,
+    /// Desired ratio of width to height of the macro.
+    #[serde(default)]
+    pub aspect_ratio: Option<f64>,
+    /// Maximum width of the macro in microns.
+    #[serde(default)]
+    pub max_width: Option<f64>,
+    /// Maximum height of the macro in microns.
+    #[serde(default)]
+    pub max_height: Option<f64>,
     #[cfg(feature = "commercial")]
     pub pex_level: Option<calibre::pex::PexLevel>,
 }
 
+/// Power straps drawn across the whole SRAM on a pair of upper metal layers,
+/// for connection to a chip-level power grid.
+///
+/// Horizontal straps are drawn on `metal` and vertical straps on the layer above.
+/// Straps alternate between vdd and vss, are broken around existing shapes on their
+/// layers, and connect to the internal supply rails they cross through via stacks.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerStrapParams {
+    /// Metal layer of the horizontal straps. Must be 3 or 4.
+    pub metal: usize,
+    /// Width of each strap in nm.
+    pub width: i64,
+    /// Distance between adjacent straps of the same net in nm.
+    pub pitch: i64,
+}
+
+impl Default for PowerStrapParams {
+    fn default() -> Self {
+        Self {
+            metal: 3,
+            width: 1_600,
+            pitch: 20_000,
+        }
+    }
+}
+
+/// The enable and write enable pins of an SRAM.
+///
+/// Every style has a single read/write port and only changes how
+/// an access and its direction are selected. The pins are registered
+/// on the rising edge of `clk` like every other input.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortStyle {
+    /// An active high chip enable `ce` and write enable `we`.
+    #[default]
+    CeWe,
+    /// Separate active high read enable `ren` and write enable `wen`.
+    ///
+    /// If both are high, the cycle is a write.
+    RenWen,
+    /// Active low chip select `csb` and write enable `web`, as in OpenRAM.
+    CsbWeb,
+}
+
+impl PortStyle {
+    /// The pin that enables an access.
+    pub fn enable_pin(&self) -> &'static str {
+        match self {
+            Self::CeWe => "ce",
+            Self::RenWen => "ren",
+            Self::CsbWeb => "csb",
+        }
+    }
+
+    /// The pin that selects a write.
+    pub fn write_pin(&self) -> &'static str {
+        match self {
+            Self::CeWe => "we",
+            Self::RenWen => "wen",
+            Self::CsbWeb => "web",
+        }
+    }
+
+    /// Whether the enable and write pins are active low.
+    #[inline]
+    pub fn active_low(&self) -> bool {
+        matches!(self, Self::CsbWeb)
+    }
+
+    /// The levels of the enable and write pins that select an access
+    /// with chip enable `ce` and write enable `we`.
+    pub fn pin_levels(&self, ce: bool, we: bool) -> (bool, bool) {
+        match self {
+            Self::CeWe => (ce, we),
+            Self::RenWen => (ce && !we, ce && we),
+            Self::CsbWeb => (!ce, !we),
+        }
+    }
+}
+
+/// The value of `dout` after a write.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteMode {
+    /// `dout` keeps the data of the last read.
+    #[default]
+    NoChange,
+    /// The written data is forwarded to `dout`.
+    ///
+    /// Bits masked off by `wmask` keep their previous value.
+    WriteThrough,
+}
+
+/// How `dout` is driven after a read.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// `dout` is driven by the output latches during the cycle after a read is registered.
+    #[default]
+    Latched,
+    /// The output latches are registered by a flip-flop stage on the next clock edge,
+    /// giving a read latency of two cycles.
+    Registered,
+}
+
+/// The read and write ports of an SRAM.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Ports {
+    /// A single port shared by reads and writes.
+    #[default]
+    #[serde(rename = "1rw")]
+    OneRw,
+    /// A read port and a write port with their own clocks, built from dual-port bitcells.
+    #[serde(rename = "1r1w")]
+    OneROneW,
+}
+
 pub fn parse_sram_config(path: impl AsRef<Path>) -> anyhow::Result<SramConfig> {
     let contents = std::fs::read_to_string(path)?;
     let data = toml::from_str(&contents)?;
@@ -83,11 +284,34 @@ pub struct SramAggregator {
     params: Vec<SramParams>,
 }
 
-#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+/// The number of bitcell columns per data bit.
+///
+/// There is no 2:1 ratio. The sense amp, write driver, latch and data flip-flop of
+/// each bit are four bitcell columns wide, so they cannot be tiled on a two-column pitch.
+#[derive(Serialize_repr, PartialEq, Eq, Debug, Clone, Copy, Hash)]
 #[repr(u8)]
 pub enum MuxRatio {
     M4 = 4,
     M8 = 8,
+    M16 = 16,
+    M32 = 32,
+}
+
+impl<'de> Deserialize<'de> for MuxRatio {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            4 => Ok(Self::M4),
+            8 => Ok(Self::M8),
+            16 => Ok(Self::M16),
+            32 => Ok(Self::M32),
+            2 => Err(serde::de::Error::custom(
+                "a mux ratio of 2 is not supported, since the column periphery of each bit is four bitcells wide",
+            )),
+            ratio => Err(serde::de::Error::custom(format!(
+                "invalid mux ratio {ratio}, expected one of 4, 8, 16 or 32"
+            ))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +326,19 @@ pub struct SramParams {
     mux_ratio: MuxRatio,
     num_words: usize,
     data_width: usize,
+    power_straps: Option<PowerStrapParams>,
+    pins: Option<PinPlacement>,
+    split_array: bool,
+    divided_wordlines: bool,
+    banks: usize,
+    bitcell: BitcellType,
+    port_style: PortStyle,
+    write_mode: WriteMode,
+    output: OutputMode,
+    power_gating: bool,
+    wordline_underdrive: bool,
+    timing_trim: Option<usize>,
+    redundancy: Option<Redundancy>,
 }
 
 impl SramParams {
@@ -116,9 +353,312 @@ impl SramParams {
             mux_ratio,
             num_words,
             data_width,
+            power_straps: None,
+            pins: None,
+            split_array: false,
+            divided_wordlines: false,
+            banks: 1,
+            bitcell: BitcellType::Sp,
+            port_style: PortStyle::CeWe,
+            write_mode: WriteMode::NoChange,
+            output: OutputMode::Latched,
+            power_gating: false,
+            wordline_underdrive: false,
+            timing_trim: None,
+            redundancy: None,
+        }
+    }
+
+    /// Builds the bitcell array, replica array and their end and strap cells from `bitcell`.
+    pub const fn with_bitcell(mut self, bitcell: BitcellType) -> Self {
+        self.bitcell = bitcell;
+        self
+    }
+
+    #[inline]
+    pub fn bitcell(&self) -> BitcellType {
+        self.bitcell
+    }
+
+    /// Exposes the enable and write enable pins of `port_style`
+    /// in place of `ce` and `we`.
+    pub const fn with_port_style(mut self, port_style: PortStyle) -> Self {
+        self.port_style = port_style;
+        self
+    }
+
+    #[inline]
+    pub fn port_style(&self) -> PortStyle {
+        self.port_style
+    }
+
+    /// Sets whether writes forward their data to `dout`.
+    pub const fn with_write_mode(mut self, write_mode: WriteMode) -> Self {
+        self.write_mode = write_mode;
+        self
+    }
+
+    #[inline]
+    pub fn write_mode(&self) -> WriteMode {
+        self.write_mode
+    }
+
+    /// Sets whether `dout` is registered by an output flip-flop stage.
+    pub const fn with_output(mut self, output: OutputMode) -> Self {
+        self.output = output;
+        self
+    }
+
+    #[inline]
+    pub fn output(&self) -> OutputMode {
+        self.output
+    }
+
+    /// Adds `sleep` and `retain` pins.
+    ///
+    /// While `sleep` is high, header switches disconnect the decoders, control logic
+    /// and column circuitry from `vdd`. The bitcell array is also disconnected unless
+    /// `retain` is high, in which case it is biased a threshold voltage below `vdd`
+    /// to hold its contents.
+    pub const fn with_power_gating(mut self) -> Self {
+        self.power_gating = true;
+        self
+    }
+
+    #[inline]
+    pub fn power_gating(&self) -> bool {
+        self.power_gating
+    }
+
+    /// Adds a wordline underdrive read assist to the wordline drivers.
+    ///
+    /// During reads, an NMOS on each wordline and on the replica wordline
+    /// holds its high level about [`WORDLINE_UNDERDRIVE`] of `vdd` below `vdd`,
+    /// which improves read stability at fast corners.
+    pub const fn with_wordline_underdrive(mut self) -> Self {
+        self.wordline_underdrive = true;
+        self
+    }
+
+    #[inline]
+    pub fn wordline_underdrive(&self) -> bool {
+        self.wordline_underdrive
+    }
+
+    /// Adds `trim_saen` and `trim_wl` pins of `bits` bits each.
+    ///
+    /// The pins select taps of delay lines in the control logic, adjusting when
+    /// the sense amplifiers are enabled and how long the wordlines stay high during writes.
+    /// Each is a two's complement offset from the generated delay, which a code of zero selects.
+    /// `bits` must be between 2 and 4.
+    pub const fn with_timing_trim(mut self, bits: usize) -> Self {
+        self.timing_trim = Some(bits);
+        self
+    }
+
+    /// The width of the trim pins, if any.
+    #[inline]
+    pub fn timing_trim(&self) -> Option<usize> {
+        self.timing_trim
+    }
+
+    /// Adds `spare_rows` spare rows and `spare_cols` spare data bits,
+    /// along with `repair_se`, `repair_si` and `repair_so` pins.
+    ///
+    /// Faulty rows and data bits are replaced by spares according to the contents
+    /// of a repair register, which is loaded through a shift register port:
+    /// while `repair_se` is high, each rising edge of `clk` shifts `repair_si`
+    /// into the register and shifts the last bit out on `repair_so`.
+    ///
+    /// The repair register is cleared while `rstb` is low.
+    /// The first bit shifted in ends up at the end of the register.
+    /// From the start of the register, there is one entry per spare row,
+    /// followed by one entry per spare column:
+    /// * Each spare row entry is a valid bit followed by the row address to replace,
+    ///   least significant bit first. The row address is `addr` without the column select bits.
+    /// * Each spare column entry is a valid bit followed by the index of the data bit to replace,
+    ///   least significant bit first.
+    ///
+    /// Each row and data bit should be repaired by at most one spare.
+    /// `spare_rows` must be a multiple of 4.
+    pub const fn with_redundancy(mut self, spare_rows: usize, spare_cols: usize) -> Self {
+        self.redundancy = Some(Redundancy {
+            spare_rows,
+            spare_cols,
+        });
+        self
+    }
+
+    #[inline]
+    pub fn redundancy(&self) -> Option<Redundancy> {
+        self.redundancy
+    }
+
+    /// The width of the row address in each spare row entry.
+    #[inline]
+    pub fn row_addr_width(&self) -> usize {
+        self.addr_width() - self.col_select_bits()
+    }
+
+    /// The width of the data bit index in each spare column entry.
+    #[inline]
+    pub fn col_index_width(&self) -> usize {
+        self.data_width.next_power_of_two().ilog2() as usize
+    }
+
+    /// The total number of bits in the repair register, if any.
+    pub fn repair_width(&self) -> usize {
+        self.redundancy.map_or(0, |redundancy| {
+            redundancy.spare_rows * (1 + self.row_addr_width())
+                + redundancy.spare_cols * (1 + self.col_index_width())
+        })
+    }
+
+    /// The parameters of the SRAM core, which includes the spare rows and columns.
+    ///
+    /// Spare rows follow the regular rows, including any padding.
+    /// The core has a write mask bit per data bit,
+    /// so that spare columns can take on the write mask of the bit they replace.
+    pub fn core(&self) -> SramParams {
+        match self.redundancy {
+            None => *self,
+            Some(redundancy) => SramParams {
+                wmask_granularity: 1,
+                num_words: (self.rows() + redundancy.spare_rows) * self.mux_ratio(),
+                data_width: self.data_width + redundancy.spare_cols,
+                redundancy: None,
+                ..*self
+            },
+        }
+    }
+
+    /// The number of rising clock edges from registering a read to its data
+    /// appearing on `dout`.
+    #[inline]
+    pub fn read_latency(&self) -> usize {
+        match self.output {
+            OutputMode::Latched => 1,
+            OutputMode::Registered => 2,
+        }
+    }
+
+    /// Splits the bitcell array into two halves on either side of the row decoder.
+    ///
+    /// The row decoder drives global wordlines into a wordline driver per half,
+    /// so each driver sees only half of the wordline load.
+    /// Each half holds half of the data bits and has its own column circuitry,
+    /// so the data width and write mask width must be even.
+    pub const fn with_split_array(mut self) -> Self {
+        self.split_array = true;
+        self
+    }
+
+    #[inline]
+    pub fn split_array(&self) -> bool {
+        self.split_array
+    }
+
+    /// Divides every wordline between two halves on either side of the row decoder,
+    /// enabling only the wordline drivers of the half holding the selected column.
+    ///
+    /// Each half holds half of the columns multiplexed onto every data bit, so the halves
+    /// are organized like two banks of `mux_ratio` columns per bit, one selected by the most
+    /// significant bit of `addr`. The column select of the whole array therefore has twice
+    /// the mux ratio of each half, which the name of the SRAM reports.
+    pub const fn with_divided_wordlines(mut self) -> Self {
+        self.divided_wordlines = true;
+        self.banks = 2;
+        self
+    }
+
+    #[inline]
+    pub fn divided_wordlines(&self) -> bool {
+        self.divided_wordlines
+    }
+
+    /// Whether the row decoder sits between two half-arrays.
+    #[inline]
+    pub(crate) fn central_decoder(&self) -> bool {
+        self.split_array || self.divided_wordlines
+    }
+
+    /// Divides the words among `banks` bitcell arrays placed side by side.
+    ///
+    /// Each bank has its own wordline drivers and column circuitry, so its bitlines
+    /// only span the rows of one bank. The banks share the input registers,
+    /// control logic and row decoder. The most significant bits of `addr` select
+    /// the wordline drivers of one bank, and `dout` is taken from the bank
+    /// of the most recent read.
+    ///
+    /// `banks` must be 2 or 4, and each bank must hold a power of two number of words
+    /// filling at least [`MIN_ROWS`] rows.
+    pub const fn with_banks(mut self, banks: usize) -> Self {
+        self.banks = banks;
+        self
+    }
+
+    #[inline]
+    pub fn banks(&self) -> usize {
+        self.banks
+    }
+
+    /// The number of address bits that select a bank.
+    #[inline]
+    pub fn bank_select_bits(&self) -> usize {
+        self.banks.ilog2() as usize
+    }
+
+    /// The number of bitcell arrays placed side by side,
+    /// each with its own wordline drivers and column circuitry.
+    #[inline]
+    pub(crate) fn sections(&self) -> usize {
+        if self.split_array {
+            2
+        } else {
+            self.banks
+        }
+    }
+
+    /// The number of bitcell columns in each array.
+    #[inline]
+    pub(crate) fn section_cols(&self) -> usize {
+        if self.split_array {
+            self.cols() / 2
+        } else {
+            self.cols()
         }
     }
 
+    /// The number of data bits read from or written to each array.
+    #[inline]
+    pub(crate) fn section_data_width(&self) -> usize {
+        self.section_cols() / self.mux_ratio()
+    }
+
+    /// Routes the signal pins to the macro boundary according to `pins`.
+    ///
+    /// By default, pins are drawn on m1 at the bottom of the SRAM.
+    pub const fn with_pin_placement(mut self, pins: PinPlacement) -> Self {
+        self.pins = Some(pins);
+        self
+    }
+
+    #[inline]
+    pub fn pin_placement(&self) -> Option<PinPlacement> {
+        self.pins
+    }
+
+    /// Draws power straps over the SRAM on upper metal layers.
+    pub const fn with_power_straps(mut self, power_straps: PowerStrapParams) -> Self {
+        self.power_straps = Some(power_straps);
+        self
+    }
+
+    #[inline]
+    pub fn power_straps(&self) -> Option<PowerStrapParams> {
+        self.power_straps
+    }
+
     #[inline]
     pub fn wmask_width(&self) -> usize {
         self.data_width / self.wmask_granularity
@@ -126,7 +666,7 @@ impl SramParams {
 
     #[inline]
     pub fn row_bits(&self) -> usize {
-        (self.num_words / self.mux_ratio as usize).ilog2() as usize
+        self.rows().next_power_of_two().ilog2() as usize
     }
 
     #[inline]
@@ -134,9 +674,11 @@ impl SramParams {
         (self.mux_ratio as usize).ilog2() as usize
     }
 
+    /// The number of rows in the bitcell array of each bank,
+    /// including any padding up to [`MIN_ROWS`].
     #[inline]
     pub fn rows(&self) -> usize {
-        self.num_words / self.mux_ratio as usize
+        (self.num_words / self.mux_ratio as usize / self.banks).max(MIN_ROWS)
     }
 
     #[inline]
@@ -166,22 +708,80 @@ impl SramParams {
 
     #[inline]
     pub fn addr_width(&self) -> usize {
-        self.num_words.ilog2() as usize
+        self.num_words.next_power_of_two().ilog2() as usize
     }
 
     /// The name of the SRAM cell with these parameters.
     pub fn name(&self) -> arcstr::ArcStr {
+        let suffix = if self.split_array {
+            "_split".to_string()
+        } else if self.divided_wordlines {
+            "_dwl".to_string()
+        } else if self.banks > 1 {
+            format!("_b{}", self.banks)
+        } else {
+            String::new()
+        };
+        let bitcell = match self.bitcell {
+            BitcellType::Sp => "",
+            BitcellType::SpOpt1a => "_opt1a",
+        };
+        let port_style = match self.port_style {
+            PortStyle::CeWe => "",
+            PortStyle::RenWen => "_renwen",
+            PortStyle::CsbWeb => "_csbweb",
+        };
+        let write_mode = match self.write_mode {
+            WriteMode::NoChange => "",
+            WriteMode::WriteThrough => "_wt",
+        };
+        let output = match self.output {
+            OutputMode::Latched => "",
+            OutputMode::Registered => "_reg",
+        };
+        let power_gating = if self.power_gating { "_pg" } else { "" };
+        let wordline_underdrive = if self.wordline_underdrive {
+            "_wlud"
+        } else {
+            ""
+        };
+        let timing_trim = self
+            .timing_trim
+            .map(|bits| format!("_trim{bits}"))
+            .unwrap_or_default();
+        let redundancy = self
+            .redundancy
+            .map(|r| format!("_r{}c{}", r.spare_rows, r.spare_cols))
+            .unwrap_or_default();
+        // The halves of a divided-wordline array each hold half of the columns of every bit.
+        let mux_ratio = if self.divided_wordlines {
+            2 * self.mux_ratio()
+        } else {
+            self.mux_ratio()
+        };
         arcstr::format!(
-            "sram22_{}x{}m{}w{}",
+            "sram22_{}x{}m{}w{}{}{}{}{}{}{}{}{}{}",
             self.num_words,
             self.data_width,
-            self.mux_ratio as u8,
-            self.wmask_granularity()
+            mux_ratio,
+            self.wmask_granularity(),
+            suffix,
+            bitcell,
+            port_style,
+            write_mode,
+            output,
+            power_gating,
+            wordline_underdrive,
+            timing_trim,
+            redundancy,
         )
     }
 
+    /// Parameters of the column circuitry beneath one bitcell array.
+    ///
+    /// Each half of a split array and each bank has its own column circuitry.
     pub(crate) fn col_params(&self) -> ColParams {
-        let bl_cap = (self.rows() + 4) as f64 * BITLINE_CAP_PER_CELL;
+        let bl_cap = (self.rows() + 4) as f64 * self.bitcell.bitline_cap_per_cell();
         let pc_scale = f64::max(bl_cap / COL_CAPACITANCES.pc_b / 8.0, 0.4);
         let mux_scale = f64::max(bl_cap / COL_CAPACITANCES.sel / 8.0, 0.5);
         let wrdrvscale = f64::max(bl_cap / COL_CAPACITANCES.we / 6.0, 0.4);
@@ -193,9 +793,24 @@ impl SramParams {
                 ..COL_PARAMS.mux.scale(mux_scale)
             },
             latch: COL_PARAMS.latch,
-            cols: self.cols(),
+            cols: self.section_cols(),
             wmask_granularity: self.wmask_granularity(),
             include_wmask: true,
+            write_mode: self.write_mode,
+            output: self.output,
+        }
+    }
+
+    /// Sizes the header switches of a power-gated SRAM.
+    ///
+    /// The periphery header carries the precharge and write driver current of every column.
+    /// The array header only supplies the bitcell pull-ups, and the retention devices
+    /// only the leakage of the array.
+    pub(crate) fn power_switch_params(&self) -> PowerSwitchParams {
+        PowerSwitchParams {
+            periph_fingers: self.cols() / 2,
+            array_fingers: usize::max(self.cols() / 16, 2),
+            retention_fingers: usize::max(self.rows() * self.cols() / 8192, 1),
         }
     }
 }
@@ -204,6 +819,7 @@ pub struct SramPhysicalDesignScript;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SramPhysicalDesign {
+    /// The bitcell array, or each half of a split array.
     pub(crate) bitcells: SpCellArrayParams,
     pub(crate) row_decoder: DecoderParams,
     pub(crate) addr_gate: DecoderStageParams,
@@ -224,6 +840,33 @@ pub struct SramPhysicalDesign {
     pub(crate) sense_en_routing_tracks: i64,
     pub(crate) write_driver_en_routing_tracks: i64,
     pub(crate) col_dec_routing_tracks: i64,
+    /// Wordline drivers of each half of a split array or of each bank.
+    pub(crate) wl_driver: Option<DecoderStageParams>,
+    /// Decoder of the bank select bits driving the `wl_en` inputs of the wordline drivers of each bank.
+    pub(crate) bank_select: Option<DecoderStageParams>,
+    /// Pull-down lowering the high level of the replica wordline during reads.
+    pub(crate) rwl_suppression: Option<SuppressionParams>,
+    pub(crate) time_constants: SramTimeConstants,
+}
+
+/// RC time constants of the SRAM periphery in seconds, each driving its expected load.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SramTimeConstants {
+    /// Row decoder driving a wordline,
+    /// including the wordline drivers of a split array.
+    pub row_decoder: f64,
+    /// Address gates driving the row decoder predecoders.
+    pub addr_gate: f64,
+    /// Wordline enable buffer driving the address gates.
+    pub wlen: f64,
+    /// Precharge enable buffer driving the precharge devices.
+    pub pc_b: f64,
+    /// Sense amplifier enable buffer driving the sense amplifiers.
+    pub sense_en: f64,
+    /// Write driver enable buffer driving the column write mask NAND gates.
+    pub write_driver_en: f64,
+    /// Column write mask NAND gate driving the write drivers.
+    pub col_nand: f64,
 }
 
 impl Script for SramPhysicalDesignScript {
@@ -234,8 +877,30 @@ impl Script for SramPhysicalDesignScript {
         params: &Self::Params,
         ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self::Output> {
-        let wl_cap = (params.cols() + 4) as f64 * WORDLINE_CAP_PER_CELL * 1.5; // safety factor.
+        // Wordlines of a split array only span half of the columns.
+        let wl_cols = params.section_cols();
+        let wl_cap = (wl_cols + 4) as f64 * params.bitcell.wordline_cap_per_cell() * 1.5; // safety factor.
         let clamped_wl_cap = f64::min(wl_cap, WORDLINE_CAP_MAX);
+        let sections = params.sections();
+        let wl_driver =
+            (sections > 1).then(|| wordline_driver_stage(params.rows(), clamped_wl_cap));
+        // The row decoder of a split or banked array drives the wordline drivers of every array.
+        let row_decoder_cap = wl_driver.as_ref().map_or(clamped_wl_cap, |driver| {
+            sections as f64 * driver.input_cap()
+        });
+        // Each output of the bank select decoder drives the `wl_en` input of every driver of a bank.
+        let bank_select = (params.banks > 1).then(|| {
+            let vert_buffer = DecoderPhysicalDesignParams {
+                style: DecoderStyle::Minimum,
+                dir: Dir::Vert,
+            };
+            let driver = wl_driver.as_ref().unwrap();
+            bank_select_stage(
+                vert_buffer,
+                params.bank_select_bits(),
+                params.rows() as f64 * driver.input_cap(),
+            )
+        });
         let mut col_params = params.col_params();
         let cols = ctx.instantiate_layout::<ColPeripherals>(&col_params)?;
         // +2 for dummy bitcells, then div_ceil by 6 and multiply by 2 for at least 0.9/3 = 0.3 V
@@ -246,6 +911,7 @@ impl Script for SramPhysicalDesignScript {
         let rbl = ReplicaCellArrayParams {
             rows: rbl_rows,
             cols: 2,
+            bitcell: params.bitcell,
         };
         let rbl_inst = ctx.instantiate_layout::<ReplicaCellArray>(&rbl)?;
         let addr_gate = DecoderStageParams {
@@ -264,23 +930,33 @@ impl Script for SramPhysicalDesignScript {
             use_multi_finger_invs: true,
             dont_connect_outputs: false,
             child_sizes: vec![],
+            suppression: None,
+            clamp: None,
         };
         let addr_gate_inst = ctx.instantiate_layout::<DecoderStage>(&addr_gate)?;
+        // Each half of a split array and each bank has its own column circuitry,
+        // driven by shared buffers.
+        let col_instances = sections as f64;
         let pc_b_cap = COL_CAPACITANCES.pc_b
             * (col_params.cols + 4) as f64
             * col_params.pc.pull_up_width as f64
-            / COL_PARAMS.pc.pull_up_width as f64;
+            / COL_PARAMS.pc.pull_up_width as f64
+            * col_instances;
         let wlen_cap = NAND2_MODEL.cin * (params.addr_width() * 2) as f64;
-        let wrdrven_cap = COL_CAPACITANCES.we * col_params.wmask_bits() as f64;
-        let saen_cap = COL_CAPACITANCES.saen * (col_params.cols / col_params.mux.mux_ratio) as f64;
+        let wrdrven_cap = COL_CAPACITANCES.we * col_params.wmask_bits() as f64 * col_instances;
+        let saen_cap = COL_CAPACITANCES.saen
+            * (col_params.cols / col_params.mux.mux_ratio) as f64
+            * col_instances;
         let col_sel_cap = COL_CAPACITANCES.sel
             * (col_params.cols / col_params.mux.mux_ratio) as f64
             * col_params.mux.pwidth as f64
-            / COL_PARAMS.mux.pwidth as f64;
+            / COL_PARAMS.mux.pwidth as f64
+            * col_instances;
         let col_sel_b_cap = COL_CAPACITANCES.sel_b
             * (col_params.cols / col_params.mux.mux_ratio) as f64
             * col_params.mux.pwidth as f64
-            / COL_PARAMS.mux.pwidth as f64;
+            / COL_PARAMS.mux.pwidth as f64
+            * col_instances;
 
         let horiz_buffer = DecoderPhysicalDesignParams {
             style: DecoderStyle::Minimum,
@@ -290,7 +966,6 @@ impl Script for SramPhysicalDesignScript {
             style: DecoderStyle::Minimum,
             dir: Dir::Vert,
         };
-
         let wlen_buffer = DecoderStageParams {
             max_width: Some(addr_gate_inst.brect().height() - 2_000),
             ..fanout_buffer_stage(vert_buffer, wlen_cap)
@@ -310,6 +985,8 @@ impl Script for SramPhysicalDesignScript {
             // TODO use tgate mux input cap
             tree: DecoderTree::new(params.col_select_bits(), col_sel_cap + col_sel_b_cap),
             use_multi_finger_invs: true,
+            suppression: None,
+            clamp: None,
         };
         let mut sense_en_buffer = DecoderStageParams {
             max_width: None,
@@ -323,8 +1000,9 @@ impl Script for SramPhysicalDesignScript {
         // Add inverters to pc_b buffer to equalize wrdrven and pc_b delay.
         let col_dsn = ctx.run_script::<ColumnsPhysicalDesignScript>(&col_params)?;
         let pcb_tau = pc_b_buffer.time_constant(pc_b_cap);
-        let wrdrven_tau = write_driver_en_buffer.time_constant(wrdrven_cap)
-            + col_dsn.nand.time_constant(col_dsn.cl_max);
+        let col_nand_tau = col_dsn.nand.time_constant(col_dsn.cl_max);
+        let wrdrven_buffer_tau = write_driver_en_buffer.time_constant(wrdrven_cap);
+        let wrdrven_tau = wrdrven_buffer_tau + col_nand_tau;
         let sae_tau = sense_en_buffer.time_constant(saen_cap);
         let pc_b_delay_invs = ((1.2 * (1.35 * f64::max(wrdrven_tau, sae_tau) - pcb_tau)
             / (INV_MODEL.res * (INV_MODEL.cin + INV_MODEL.cout)))
@@ -339,12 +1017,26 @@ impl Script for SramPhysicalDesignScript {
             .max(1.)
             .round() as usize
             * 2;
-        let row_decoder_tree = DecoderTree::new(params.row_bits(), clamped_wl_cap);
+        let row_decoder_tree =
+            DecoderTree::new(params.row_bits(), row_decoder_cap).truncate(params.rows());
+        let wl_time_constant = |cl: f64| match &wl_driver {
+            Some(driver) => {
+                row_decoder_tree.root.time_constant(row_decoder_cap) + driver.time_constant(cl)
+            }
+            None => row_decoder_tree.root.time_constant(cl),
+        };
+        let time_constants = SramTimeConstants {
+            row_decoder: wl_time_constant(wl_cap),
+            addr_gate: addr_gate.time_constant(NAND3_MODEL.cin * 4.),
+            wlen: wlen_buffer.time_constant(wlen_cap),
+            pc_b: pcb_tau,
+            sense_en: sae_tau,
+            write_driver_en: wrdrven_buffer_tau,
+            col_nand: col_nand_tau,
+        };
         let decoder_delay_invs = (f64::max(
             4.0,
-            (row_decoder_tree.root.time_constant(wl_cap)
-                + addr_gate.time_constant(NAND3_MODEL.cin * 4.)
-                + wlen_buffer.time_constant(wlen_cap)
+            (time_constants.row_decoder + time_constants.addr_gate + time_constants.wlen
                 - f64::min(sae_tau, wrdrven_tau))
                 / (INV_MODEL.res * (INV_MODEL.cin + INV_MODEL.cout)),
         ) / 2.0)
@@ -353,10 +1045,8 @@ impl Script for SramPhysicalDesignScript {
             + 2;
         let wlen_pulse_invs = (f64::max(
             2.0,
-            (0.25 * row_decoder_tree.root.time_constant(wl_cap)
-                + 6.0
-                    * (row_decoder_tree.root.time_constant(wl_cap)
-                        - row_decoder_tree.root.time_constant(clamped_wl_cap)))
+            (0.25 * wl_time_constant(wl_cap)
+                + 6.0 * (wl_time_constant(wl_cap) - wl_time_constant(clamped_wl_cap)))
                 / (INV_MODEL.res * (INV_MODEL.cin + INV_MODEL.cout)),
         ) / 2.0)
             .round() as usize
@@ -368,7 +1058,9 @@ impl Script for SramPhysicalDesignScript {
             pc_set_delay_invs: pc_b_delay_invs,
             wrdrven_set_delay_invs,
             wrdrven_rst_delay_invs: 0, // TODO: Implement delay to equalize sense amp and
-                                       // write driver rest delay
+            // write driver rest delay
+            port_style: params.port_style,
+            trim: None,
         };
         let row_decoder = DecoderParams {
             pd: DecoderPhysicalDesignParams {
@@ -378,8 +1070,22 @@ impl Script for SramPhysicalDesignScript {
             max_width: None,
             tree: row_decoder_tree,
             use_multi_finger_invs: true,
+            suppression: None,
+            clamp: None,
         };
 
+        // The suppression devices are added to the final wordline drivers only after
+        // the row decoder layout has been used for sizing.
+        let wl_suppression = |driver: PrimitiveGateParams| {
+            params
+                .wordline_underdrive
+                .then(|| wordline_suppression(driver.nwidth, driver.length))
+        };
+        let rwl_suppression = params
+            .wordline_underdrive
+            .then(|| wordline_suppression(RWL_BUF_NWIDTH, 150));
+        let wl_clamp = params.power_gating.then_some(WL_CLAMP);
+
         let control_inst = ctx.instantiate_layout::<ControlLogicReplicaV2>(&control)?;
 
         let col_dec_inst = ctx.instantiate_layout::<Decoder>(&col_decoder)?;
@@ -481,10 +1187,20 @@ impl Script for SramPhysicalDesignScript {
         Ok(Self::Output {
             bitcells: SpCellArrayParams {
                 rows: params.rows(),
-                cols: params.cols(),
+                cols: wl_cols,
                 mux_ratio: params.mux_ratio(),
+                bitcell: params.bitcell,
+            },
+            row_decoder: DecoderParams {
+                suppression: if wl_driver.is_none() {
+                    let (_, driver) = *row_decoder.tree.root.gate.primitive_gates().last().unwrap();
+                    wl_suppression(driver)
+                } else {
+                    None
+                },
+                clamp: if wl_driver.is_none() { wl_clamp } else { None },
+                ..row_decoder
             },
-            row_decoder,
             addr_gate,
             // TODO: change decoder tree to provide correct fanout for inverted output
             col_decoder,
@@ -512,15 +1228,105 @@ impl Script for SramPhysicalDesignScript {
                 ),
             },
             col_params,
-            control,
+            control: ControlLogicParams {
+                trim: params.timing_trim.map(|bits| TimingTrimParams { bits }),
+                ..control
+            },
             pc_b_routing_tracks,
             write_driver_en_routing_tracks,
             sense_en_routing_tracks,
             col_dec_routing_tracks,
+            wl_driver: wl_driver.map(|driver| DecoderStageParams {
+                suppression: wl_suppression(*driver.invs.last().unwrap()),
+                clamp: wl_clamp,
+                ..driver
+            }),
+            bank_select,
+            rwl_suppression,
+            time_constants,
         })
     }
 }
 
+/// A decoder of `bits` bank select bits with `2^bits` outputs, each sized to drive `cl`.
+///
+/// Input `predecode_{i}_0` is the complement of bit `i` and `predecode_{i}_1` is bit `i`.
+/// Output `i` is high when the bits are `i`.
+fn bank_select_stage(pd: DecoderPhysicalDesignParams, bits: usize, cl: f64) -> DecoderStageParams {
+    let stages = schematic::buffer_chain_num_stages(cl);
+    let invs = InverterGateTreeNode::buffer(stages)
+        .elaborate()
+        .size(cl)
+        .as_inv_chain();
+    let (gate, invs) = if bits == 1 {
+        (GateParams::FoldedInv(invs[0]), &invs[1..])
+    } else {
+        let nand_scale = invs[0].nwidth as f64 / INV_PARAMS.nwidth as f64;
+        (
+            GateParams::And2(AndParams {
+                nand: NAND2_PARAMS.scale(nand_scale),
+                inv: invs[1],
+            }),
+            &invs[2..],
+        )
+    };
+    DecoderStageParams {
+        pd,
+        routing_style: RoutingStyle::Decoder,
+        max_width: None,
+        gate,
+        invs: invs.to_vec(),
+        num: 1 << bits,
+        use_multi_finger_invs: true,
+        dont_connect_outputs: false,
+        child_sizes: vec![2; bits],
+        suppression: None,
+        clamp: None,
+    }
+}
+
+/// Wordline drivers of one half of a split array or of one bank, sized to drive `cl`.
+///
+/// Each driver ANDs a global wordline with `wl_en`. The drivers are pitch matched
+/// to the bitcell rows like the row decoder, and end in multi-finger inverters
+/// so that their outputs are on m1.
+fn wordline_driver_stage(rows: usize, cl: f64) -> DecoderStageParams {
+    let inv_scale = f64::max(cl / (4. * INV_MODEL.cin), 1.);
+    let nand_scale = f64::max(inv_scale * INV_MODEL.cin / (4. * NAND2_MODEL.cin), 1.);
+    DecoderStageParams {
+        pd: DecoderPhysicalDesignParams {
+            style: DecoderStyle::RowMatched,
+            dir: Dir::Horiz,
+        },
+        routing_style: RoutingStyle::Driver,
+        max_width: None,
+        invs: vec![INV_PARAMS.scale(inv_scale)],
+        gate: GateParams::Nand2(NAND2_PARAMS.scale(nand_scale)),
+        num: rows,
+        use_multi_finger_invs: true,
+        dont_connect_outputs: false,
+        child_sizes: vec![],
+        suppression: None,
+        clamp: None,
+    }
+}
+
+/// A pull-down that lowers the high level of a wordline by about [`WORDLINE_UNDERDRIVE`] of vdd,
+/// given the width and length of the pull-down of its driver.
+///
+/// Drivers have pull-ups as strong as their pull-downs,
+/// so the pull-up and the suppression device divide vdd in the ratio of their widths.
+/// The device is split into fingers of at most [`SUPPRESSION_FINGER_WIDTH`].
+fn wordline_suppression(nwidth: i64, length: i64) -> SuppressionParams {
+    let width = nwidth as f64 * WORDLINE_UNDERDRIVE / (1. - WORDLINE_UNDERDRIVE);
+    let nf = (width / SUPPRESSION_FINGER_WIDTH as f64).ceil().max(1.);
+    SuppressionParams {
+        width: i64::max(snap_to_grid((width / nf).round() as i64, 10), 420),
+        length,
+        nf: nf as u64,
+    }
+}
+
 impl Component for SramInner {
     type Params = SramParams;
     fn new(
@@ -552,6 +1358,37 @@ impl Component for Sram {
         params: &Self::Params,
         _ctx: &substrate::data::SubstrateCtx,
     ) -> substrate::error::Result<Self> {
+        if let Some(redundancy) = params.redundancy {
+            // The repair logic sits beside the guard ring, where the power switches,
+            // pin routing and power straps would otherwise go.
+            if redundancy.spare_rows % 4 != 0
+                || redundancy.spare_rows + redundancy.spare_cols == 0
+                || params.power_gating
+                || params.split_array
+                || params.pins.is_some()
+                || params.power_straps.is_some()
+            {
+                return Err(error::Error::InvalidParams.into());
+            }
+        }
+        if params.divided_wordlines && (params.banks != 2 || params.split_array) {
+            return Err(error::Error::InvalidParams.into());
+        }
+        if params.banks > 1 {
+            // The output mux sits beside the guard ring like the repair logic.
+            let bank_words = params.num_words / params.mux_ratio() / params.banks;
+            if !matches!(params.banks, 2 | 4)
+                || !bank_words.is_power_of_two()
+                || bank_words < MIN_ROWS
+                || params.redundancy.is_some()
+                || params.power_gating
+                || params.split_array
+                || params.pins.is_some()
+                || params.power_straps.is_some()
+            {
+                return Err(error::Error::InvalidParams.into());
+            }
+        }
         Ok(Self { params: *params })
     }
     fn name(&self) -> arcstr::ArcStr {
@@ -561,9 +1398,57 @@ impl Component for Sram {
         &self,
         ctx: &mut substrate::schematic::context::SchematicCtx,
     ) -> substrate::error::Result<()> {
-        let mut inner = ctx.instantiate::<SramInner>(&self.params)?;
-        ctx.bubble_all_ports(&mut inner);
+        if self.params.redundancy.is_some() {
+            return redundancy::draw_repair_schematic(ctx, &self.params);
+        }
+        if self.params.banks > 1 {
+            return banked::draw_bank_schematic(ctx, &self.params);
+        }
+        if !self.params.power_gating() {
+            let mut inner = ctx.instantiate::<SramInner>(&self.params)?;
+            ctx.bubble_all_ports(&mut inner);
+            ctx.add_instance(inner);
+            return Ok(());
+        }
+
+        let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+        let [clk, rstb, sleep, retain] =
+            ctx.ports(["clk", "rstb", "sleep", "retain"], Direction::Input);
+        let [vdd_periph, vdd_array, rstb_gated] =
+            ctx.signals(["vdd_periph", "vdd_array", "rstb_gated"]);
+
+        const GATED_PORTS: [&str; 6] = ["vdd", "vdd_array", "vss", "clk", "rstb", "sleep"];
+        let mut inner = ctx
+            .instantiate::<SramInner>(&self.params)?
+            .with_connections([
+                ("vdd", vdd_periph),
+                ("vdd_array", vdd_array),
+                ("vss", vss),
+                ("clk", clk),
+                ("rstb", rstb_gated),
+                ("sleep", sleep),
+            ])
+            .named("sram");
+        ctx.bubble_filter_map(&mut inner, |port| {
+            (!GATED_PORTS.contains(&port.name().as_ref())).then_some(port.name().into())
+        });
         ctx.add_instance(inner);
+
+        ctx.instantiate::<PowerSwitches>(&self.params.power_switch_params())?
+            .with_connections([
+                ("vdd", vdd),
+                ("vss", vss),
+                ("vdd_periph", vdd_periph),
+                ("vdd_array", vdd_array),
+                ("clk", clk),
+                ("rstb", rstb),
+                ("sleep", sleep),
+                ("retain", retain),
+                ("rstb_gated", rstb_gated),
+            ])
+            .named("power_switches")
+            .add_to(ctx);
+
         Ok(())
     }
 
@@ -573,11 +1458,11 @@ impl Component for Sram {
         ctx: &mut substrate::layout::context::LayoutCtx,
     ) -> substrate::error::Result<()> {
         let mut group = Group::new();
-        let sram = ctx.instantiate::<SramInner>(&self.params)?;
+        let core = self.params.core();
+        let sram = ctx.instantiate::<SramInner>(&core)?;
         ctx.set_metadata(*sram.cell().get_metadata::<columns::layout::Metadata>());
         let brect = sram.brect();
 
-        let m0 = ctx.layers().get(Selector::Metal(0))?;
         let m1 = ctx.layers().get(Selector::Metal(1))?;
         let m2 = ctx.layers().get(Selector::Metal(2))?;
         let params = GuardRingParams {
@@ -586,127 +1471,99 @@ impl Component for Sram {
             v_metal: m1,
             h_width: 1_360,
             v_width: 1_360,
+            switches: self
+                .params
+                .power_gating
+                .then(|| self.params.power_switch_params()),
         };
         let ring = ctx.instantiate::<GuardRing>(&params)?;
         let rings = ring.cell().get_metadata::<SupplyRings>();
         let straps = sram.cell().get_metadata::<PlacedStraps>();
 
-        for (layer, dir) in [(m1, Dir::Vert), (m2, Dir::Horiz)] {
-            for strap in straps.on_layer(layer) {
-                let strap_rect = strap.rect;
-                let ring = match strap.net {
-                    SingleSupplyNet::Vss => rings.vss,
-                    SingleSupplyNet::Vdd => rings.vdd,
-                };
-                assert_ne!(strap_rect.area(), 0);
-                let lower = if strap.lower_boundary {
-                    ring.outer().span(dir).start()
-                } else {
-                    strap_rect.span(dir).start()
-                };
-                let upper = if strap.upper_boundary {
-                    ring.outer().span(dir).stop()
-                } else {
-                    strap_rect.span(dir).stop()
-                };
-
-                let r = Rect::span_builder()
-                    .with(dir, Span::new(lower, upper))
-                    .with(!dir, strap_rect.span(!dir))
-                    .build();
-                if layer == m2 {
-                    group.add_port_with_strategy(
-                        CellPort::with_shape(
-                            match strap.net {
-                                SingleSupplyNet::Vdd => "vdd",
-                                SingleSupplyNet::Vss => "vss",
-                            },
-                            m2,
-                            r,
-                        ),
-                        PortConflictStrategy::Merge,
-                    )?;
-                }
-
-                let mut targets = Vec::new();
-                if strap.upper_boundary {
-                    targets.push(ring.dir_rects(!dir)[1]);
-                }
-                if strap.lower_boundary {
-                    targets.push(ring.dir_rects(!dir)[0]);
-                }
-                for target in targets {
-                    let (below_rect, above_rect) = if layer == m2 {
-                        (target, r)
-                    } else {
-                        (r, target)
-                    };
-                    let viap = ViaParams::builder()
-                        .layers(m1, m2)
-                        .geometry(below_rect, above_rect)
-                        .expand(ViaExpansion::LongerDirection)
-                        .build();
-                    group.add_instance(ctx.instantiate::<Via>(&viap)?);
-                }
-                if layer == m1 {
-                    let mut targets = Vec::new();
-                    if strap.upper_boundary {
-                        targets.push(ring.inner_hrects()[1]);
-                    }
-                    if strap.lower_boundary {
-                        targets.push(ring.inner_hrects()[0]);
-                    }
-                    for target in targets {
-                        let viap = ViaParams::builder()
-                            .layers(m0, m1)
-                            .geometry(target, r)
-                            .expand(ViaExpansion::LongerDirection)
-                            .build();
-                        group.add_instance(ctx.instantiate::<Via>(&viap)?);
-                    }
-                }
-                group.add(Element::new(LayerSpec::drawing(layer), r));
-            }
-        }
-
-        for port in ["vdd", "vss"] {
+        let straps = [m1, m2].into_iter().flat_map(|layer| {
+            straps.on_layer(layer).map(move |strap| CoreStrap {
+                layer,
+                rect: strap.rect,
+                net: strap.net,
+                lower_boundary: strap.lower_boundary,
+                upper_boundary: strap.upper_boundary,
+            })
+        });
+        connect_straps_to_rings(ctx, &mut group, straps, rings, self.params.power_gating)?;
+
+        let supplies: &[&str] = if self.params.power_gating {
+            group.add_port(ring.port("vdd")?.into_cell_port())?;
+            &["vss"]
+        } else {
+            &["vdd", "vss"]
+        };
+        for &port in supplies {
             group.add_port_with_strategy(
                 ring.port(format!("ring_{port}"))?
                     .into_cell_port()
                     .named(port),
                 PortConflictStrategy::Merge,
             )?;
+            group.add_port_with_strategy(sram.port(port)?, PortConflictStrategy::Merge)?;
         }
 
-        group.add_port_with_strategy(sram.port("vdd")?, PortConflictStrategy::Merge)?;
-        group.add_port_with_strategy(sram.port("vss")?, PortConflictStrategy::Merge)?;
         // Route pins to edge of guard ring
-        for (pin, width) in [
-            ("dout", self.params.data_width()),
-            ("din", self.params.data_width()),
-            ("wmask", self.params.wmask_width()),
-            ("addr", self.params.addr_width()),
-            ("we", 1),
-            ("ce", 1),
-            ("clk", 1),
-            ("rstb", 1),
-        ] {
-            for i in 0..width {
-                let port_id = PortId::new(pin, i);
-                let rect = sram.port(port_id.clone())?.largest_rect(m1)?;
-                let rect = rect.with_vspan(
-                    rect.vspan()
-                        .add_point(ring.bbox().into_rect().side(subgeom::Side::Bot)),
-                );
-                group.add(Element::new(LayerSpec::drawing(m1), rect));
-                group.add_port(CellPort::builder().id(port_id).add(m1, rect).build())?;
+        let ring_bbox = ring.bbox().into_rect();
+        let pin_bottom = if self.params.power_gating {
+            ring_bbox.bottom() - POWER_SWITCH_CHANNEL
+        } else {
+            ring_bbox.bottom()
+        };
+        if let Some(placement) = self.params.pins {
+            pins::route_pins(ctx, &mut group, &sram, ring_bbox, &self.params, &placement)?;
+        } else {
+            let trim_bits = self.params.timing_trim().unwrap_or_default();
+            // Each bank of the core has its own data and write mask pins.
+            let data_width = core.sections() * core.section_data_width();
+            for (pin, width) in [
+                ("dout", data_width),
+                ("din", data_width),
+                ("wmask", data_width / core.wmask_granularity()),
+                ("addr", core.addr_width()),
+                (self.params.port_style().write_pin(), 1),
+                (self.params.port_style().enable_pin(), 1),
+                ("clk", 1),
+                ("rstb", 1),
+                ("trim_saen", trim_bits),
+                ("trim_wl", trim_bits),
+            ] {
+                if pin == "rstb" && self.params.power_gating {
+                    // Gated by the power switches.
+                    continue;
+                }
+                for i in 0..width {
+                    let port_id = PortId::new(pin, i);
+                    let rect = sram.port(port_id.clone())?.largest_rect(m1)?;
+                    let rect = rect.with_vspan(rect.vspan().add_point(pin_bottom));
+                    group.add(Element::new(LayerSpec::drawing(m1), rect));
+                    group.add_port(CellPort::builder().id(port_id).add(m1, rect).build())?;
+                }
             }
         }
 
+        if self.params.power_gating {
+            route_power_switches(ctx, &mut group, &sram, &ring, pin_bottom)?;
+        }
+
+        if let Some(power_straps) = self.params.power_straps {
+            draw_power_straps(ctx, &mut group, &[&sram, &ring], rings, power_straps)?;
+        }
+
         group.add_instance(sram);
         group.add_instance(ring);
 
         group.place(Corner::LowerLeft, Point::zero());
+        if self.params.redundancy.is_some() {
+            return redundancy::draw_repair_logic(ctx, group, &self.params);
+        }
+        if self.params.banks > 1 {
+            return banked::draw_bank_logic(ctx, group, &self.params);
+        }
         ctx.add_ports(group.ports())?;
         ctx.draw(group)?;
 
@@ -714,6 +1571,463 @@ impl Component for Sram {
     }
 }
 
+/// A power strap placed by the router of an SRAM core,
+/// in the coordinates of the cell that instantiates the core.
+pub(crate) struct CoreStrap {
+    /// The layer of the strap, m1 for vertical straps or m2 for horizontal straps.
+    pub(crate) layer: LayerKey,
+    pub(crate) rect: Rect,
+    pub(crate) net: SingleSupplyNet,
+    /// Whether the strap reaches the lower boundary of the router.
+    pub(crate) lower_boundary: bool,
+    /// Whether the strap reaches the upper boundary of the router.
+    pub(crate) upper_boundary: bool,
+}
+
+/// Extends the power straps of an SRAM core that reach the boundary of its router
+/// to the supply rings around it.
+///
+/// The m2 straps are exposed as `vdd` and `vss` ports, except for the `vdd` straps
+/// of a power-gated SRAM, which carry `vdd_periph`.
+pub(crate) fn connect_straps_to_rings(
+    ctx: &mut substrate::layout::context::LayoutCtx,
+    group: &mut Group,
+    straps: impl IntoIterator<Item = CoreStrap>,
+    rings: &SupplyRings,
+    power_gating: bool,
+) -> substrate::error::Result<()> {
+    let m0 = ctx.layers().get(Selector::Metal(0))?;
+    let m1 = ctx.layers().get(Selector::Metal(1))?;
+    let m2 = ctx.layers().get(Selector::Metal(2))?;
+
+    for strap in straps {
+        let layer = strap.layer;
+        let dir = if layer == m1 { Dir::Vert } else { Dir::Horiz };
+        let strap_rect = strap.rect;
+        let ring = match strap.net {
+            SingleSupplyNet::Vss => rings.vss,
+            SingleSupplyNet::Vdd => rings.vdd,
+        };
+        assert_ne!(strap_rect.area(), 0);
+        let lower = if strap.lower_boundary {
+            ring.outer().span(dir).start()
+        } else {
+            strap_rect.span(dir).start()
+        };
+        let upper = if strap.upper_boundary {
+            ring.outer().span(dir).stop()
+        } else {
+            strap_rect.span(dir).stop()
+        };
+
+        let r = Rect::span_builder()
+            .with(dir, Span::new(lower, upper))
+            .with(!dir, strap_rect.span(!dir))
+            .build();
+        // With power gating, the `vdd` ring and straps carry `vdd_periph`.
+        if layer == m2 && !(power_gating && strap.net == SingleSupplyNet::Vdd) {
+            group.add_port_with_strategy(
+                CellPort::with_shape(
+                    match strap.net {
+                        SingleSupplyNet::Vdd => "vdd",
+                        SingleSupplyNet::Vss => "vss",
+                    },
+                    m2,
+                    r,
+                ),
+                PortConflictStrategy::Merge,
+            )?;
+        }
+
+        let mut targets = Vec::new();
+        if strap.upper_boundary {
+            targets.push(ring.dir_rects(!dir)[1]);
+        }
+        if strap.lower_boundary {
+            targets.push(ring.dir_rects(!dir)[0]);
+        }
+        for target in targets {
+            let (below_rect, above_rect) = if layer == m2 {
+                (target, r)
+            } else {
+                (r, target)
+            };
+            let viap = ViaParams::builder()
+                .layers(m1, m2)
+                .geometry(below_rect, above_rect)
+                .expand(ViaExpansion::LongerDirection)
+                .build();
+            group.add_instance(ctx.instantiate::<Via>(&viap)?);
+        }
+        if layer == m1 {
+            let mut targets = Vec::new();
+            if strap.upper_boundary {
+                targets.push(ring.inner_hrects()[1]);
+            }
+            if strap.lower_boundary {
+                targets.push(ring.inner_hrects()[0]);
+            }
+            for target in targets {
+                let viap = ViaParams::builder()
+                    .layers(m0, m1)
+                    .geometry(target, r)
+                    .expand(ViaExpansion::LongerDirection)
+                    .build();
+                group.add_instance(ctx.instantiate::<Via>(&viap)?);
+            }
+        }
+        group.add(Element::new(LayerSpec::drawing(layer), r));
+    }
+    Ok(())
+}
+
+/// Height of the routing channel beneath the guard ring of a power-gated SRAM.
+const POWER_SWITCH_CHANNEL: i64 = 4_000;
+
+/// Connects the SRAM to the header switches placed by its guard ring.
+///
+/// The `vdd_array` collectors of the bitcell array run right to a bus between
+/// the supply rings and the switches. `clk`, `sleep` and `rstb_gated` run along
+/// m2 tracks in a channel beneath the guard ring, whose bottom is `pin_bottom`.
+fn route_power_switches(
+    ctx: &mut substrate::layout::context::LayoutCtx,
+    group: &mut Group,
+    sram: &Instance,
+    ring: &Instance,
+    pin_bottom: i64,
+) -> substrate::error::Result<()> {
+    let m1 = ctx.layers().get(Selector::Metal(1))?;
+    let m2 = ctx.layers().get(Selector::Metal(2))?;
+    let rings = ring.cell().get_metadata::<SupplyRings>();
+
+    let bus_hspan = Span::with_start_and_length(rings.vdd.outer().right() + 1_000, 1_000);
+    let mut rails = sram
+        .port("vdd_array")?
+        .shapes(m2)
+        .filter_map(|shape| shape.as_rect())
+        .collect::<Vec<_>>();
+    rails.push(ring.port("vdd_array")?.largest_rect(m2)?);
+    let bus = Rect::from_spans(
+        bus_hspan,
+        rails
+            .iter()
+            .map(|rail| rail.vspan())
+            .reduce(|a, b| a.union(b))
+            .unwrap(),
+    );
+    group.add(Element::new(LayerSpec::drawing(m1), bus));
+    for rail in rails {
+        let rail = rail.with_hspan(rail.hspan().union(bus_hspan));
+        group.add(Element::new(LayerSpec::drawing(m2), rail));
+        let viap = ViaParams::builder()
+            .layers(m1, m2)
+            .geometry(bus, rail)
+            .build();
+        group.add_instance(ctx.instantiate::<Via>(&viap)?);
+    }
+
+    // `sleep` is a pin of the SRAM, while `rstb` is only connected through the switches.
+    let ring_bottom = ring.brect().bottom();
+    for (i, (src, dst)) in [("clk", "clk"), ("sleep", "sleep"), ("rstb", "rstb_gated")]
+        .into_iter()
+        .enumerate()
+    {
+        let track = Span::with_stop_and_length(ring_bottom - 600 - 1_000 * i as i64, 400);
+        let src = sram.port(src)?.largest_rect(m1)?;
+        let src_bottom = if dst == "rstb_gated" {
+            track.start()
+        } else {
+            pin_bottom
+        };
+        let src = src.with_vspan(src.vspan().add_point(src_bottom));
+        if dst == "sleep" {
+            group.add_port(CellPort::with_shape("sleep", m1, src))?;
+        }
+        let dst = ring.port(dst)?.largest_rect(m1)?;
+        let dst = dst.with_vspan(dst.vspan().union(track));
+        let track = Rect::from_spans(src.hspan().union(dst.hspan()), track);
+        for rect in [src, dst] {
+            group.add(Element::new(LayerSpec::drawing(m1), rect));
+            let viap = ViaParams::builder()
+                .layers(m1, m2)
+                .geometry(rect, track)
+                .build();
+            group.add_instance(ctx.instantiate::<Via>(&viap)?);
+        }
+        group.add(Element::new(LayerSpec::drawing(m2), track));
+    }
+
+    for pin in ["rstb", "retain"] {
+        let rect = ring.port(pin)?.largest_rect(m1)?;
+        let rect = rect.with_vspan(rect.vspan().add_point(pin_bottom));
+        group.add(Element::new(LayerSpec::drawing(m1), rect));
+        group.add_port(CellPort::with_shape(pin, m1, rect))?;
+    }
+
+    Ok(())
+}
+
+/// Positions of the straps of one net along `span`.
+///
+/// Straps are centered `offset` past each multiple of `pitch` from the start of `span`
+/// and lie entirely within `span`.
+fn strap_spans(span: Span, pitch: i64, width: i64, offset: i64) -> Vec<Span> {
+    (0..)
+        .map(|i| snap_to_grid(span.start() + i * pitch + offset - width / 2, 5))
+        .take_while(|&start| start + width <= span.stop())
+        .map(|start| Span::new(start, start + width))
+        .collect()
+}
+
+/// Spacing kept between power straps and other shapes on the same layer, in nm.
+const POWER_STRAP_SPACE: i64 = 400;
+
+/// Smallest overlap of a power strap and an internal rail that gets a via stack, in nm.
+const POWER_STRAP_MIN_VIA: i64 = 400;
+
+/// Splits `strap`, which runs in direction `dir`, into the pieces that keep
+/// [`POWER_STRAP_SPACE`] from every rect of `obstacles`.
+///
+/// Pieces shorter than twice the strap width are dropped.
+fn split_strap(strap: Rect, dir: Dir, obstacles: &[Rect]) -> Vec<Rect> {
+    let mut cuts = obstacles
+        .iter()
+        .map(|rect| rect.expand(POWER_STRAP_SPACE))
+        .filter(|rect| {
+            rect.span(dir).intersects(&strap.span(dir))
+                && rect.span(!dir).intersects(&strap.span(!dir))
+        })
+        .map(|rect| rect.span(dir))
+        .collect::<Vec<_>>();
+    cuts.sort_by_key(|span| span.start());
+
+    let span = strap.span(dir);
+    let mut pieces = Vec::new();
+    let mut start = span.start();
+    for cut in cuts {
+        if cut.start() > start {
+            pieces.push(Span::new(start, cut.start().min(span.stop())));
+        }
+        start = start.max(cut.stop());
+    }
+    if start < span.stop() {
+        pieces.push(Span::new(start, span.stop()));
+    }
+    pieces
+        .into_iter()
+        .filter(|piece| piece.length() >= 2 * strap.span(!dir).length())
+        .map(|piece| {
+            Rect::span_builder()
+                .with(dir, piece)
+                .with(!dir, strap.span(!dir))
+                .build()
+        })
+        .collect()
+}
+
+/// Draws the power straps of `params` over the supply rings.
+///
+/// Horizontal straps cover the horizontal sides of the supply rings,
+/// which they connect to through via stacks, and are repeated at the strap pitch
+/// inside the rings. Vertical straps cross the entire SRAM and connect to every
+/// horizontal strap of the same net. Straps inside the rings are broken around the
+/// existing shapes on their layer, and drop via stacks onto the `vdd` and `vss` rails
+/// of `group` and `insts` that they cross, wherever the layers in between are clear.
+/// Pieces of straps that end up connected to neither are left out.
+fn draw_power_straps(
+    ctx: &mut substrate::layout::context::LayoutCtx,
+    group: &mut Group,
+    insts: &[&Instance],
+    rings: &SupplyRings,
+    params: PowerStrapParams,
+) -> substrate::error::Result<()> {
+    let PowerStrapParams {
+        metal,
+        width,
+        pitch,
+    } = params;
+    let layers = (0..=metal + 1)
+        .map(|i| ctx.layers().get(Selector::Metal(i)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let h_metal = layers[metal];
+    let v_metal = layers[metal + 1];
+    let outer = rings.vdd.outer();
+    let inner = rings.vss.inner();
+
+    // Everything drawn so far on the layers from m1 up to the straps.
+    let shapes = (1..=metal + 1)
+        .map(|i| {
+            group
+                .shapes_on(layers[i])
+                .map(|shape| shape.brect())
+                .chain(
+                    insts
+                        .iter()
+                        .flat_map(|inst| inst.shapes_on(layers[i]).map(|shape| shape.brect())),
+                )
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let shapes = |i: usize| &shapes[i - 1];
+
+    for (net, ring, offset) in [
+        ("vdd", rings.vdd, pitch / 4),
+        ("vss", rings.vss, 3 * pitch / 4),
+    ] {
+        let rails = (1..=metal)
+            .map(|i| {
+                group
+                    .port_map()
+                    .port(net)
+                    .map(|port| port.shapes(layers[i]).map(|shape| shape.brect()).collect())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<Vec<Rect>>>();
+        let rails = |i: usize| &rails[i - 1];
+        // Shapes of any other net, or of this net but not exposed as a rail.
+        let obstacles = |i: usize, near: Rect| {
+            let near = near.expand(POWER_STRAP_SPACE);
+            shapes(i)
+                .iter()
+                .copied()
+                .filter(|rect| {
+                    !rect.bbox().intersection(near.bbox()).is_empty()
+                        && (i > metal || !rails(i).contains(rect))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut h_straps = Vec::new();
+        for side in ring.dir_rects(Dir::Horiz) {
+            for layer in 2..metal {
+                let below = layers[layer];
+                let above = layers[layer + 1];
+                if layer > 2 {
+                    group.add(Element::new(LayerSpec::drawing(below), side));
+                }
+                let viap = ViaParams::builder()
+                    .layers(below, above)
+                    .geometry(side, side)
+                    .expand(ViaExpansion::LongerDirection)
+                    .build();
+                group.add_instance(ctx.instantiate::<Via>(&viap)?);
+            }
+            h_straps.push((side.with_hspan(outer.hspan()), true));
+        }
+        for vspan in strap_spans(inner.vspan(), pitch, width, offset) {
+            let strap = Rect::from_spans(outer.hspan(), vspan);
+            h_straps.extend(
+                split_strap(strap, Dir::Horiz, &obstacles(metal, strap))
+                    .into_iter()
+                    .map(|piece| (piece, false)),
+            );
+        }
+        let mut v_straps = Vec::new();
+        for hspan in strap_spans(outer.hspan(), pitch, width, offset) {
+            let strap = Rect::from_spans(hspan, outer.vspan());
+            v_straps.extend(
+                split_strap(strap, Dir::Vert, &obstacles(metal + 1, strap))
+                    .into_iter()
+                    .map(|piece| (piece, false)),
+            );
+        }
+
+        // Via stacks from the rails crossed by each strap, through clear layers only.
+        for (straps, top) in [(&mut h_straps, metal), (&mut v_straps, metal + 1)] {
+            for (strap, connected) in straps.iter_mut() {
+                for bottom in 1..top {
+                    let between = (bottom + 1..top)
+                        .map(|i| (i, obstacles(i, *strap)))
+                        .collect::<Vec<_>>();
+                    for rail in rails(bottom) {
+                        let overlap = strap.bbox().intersection(rail.bbox());
+                        if overlap.is_empty() {
+                            continue;
+                        }
+                        let overlap = overlap.into_rect();
+                        if overlap.width() < POWER_STRAP_MIN_VIA
+                            || overlap.height() < POWER_STRAP_MIN_VIA
+                        {
+                            continue;
+                        }
+                        let keepout = overlap.expand(POWER_STRAP_SPACE);
+                        if between.iter().any(|(_, rects)| {
+                            rects
+                                .iter()
+                                .any(|rect| !rect.bbox().intersection(keepout.bbox()).is_empty())
+                        }) {
+                            continue;
+                        }
+                        for i in bottom..top {
+                            if i > bottom {
+                                group.add(Element::new(LayerSpec::drawing(layers[i]), overlap));
+                            }
+                            let viap = ViaParams::builder()
+                                .layers(layers[i], layers[i + 1])
+                                .geometry(overlap, overlap)
+                                .build();
+                            group.add_instance(ctx.instantiate::<Via>(&viap)?);
+                        }
+                        *connected = true;
+                    }
+                }
+            }
+        }
+
+        // Keep the straps reachable from a ring side or a rail through the vias
+        // between horizontal and vertical straps.
+        let crossings = h_straps
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (h, _))| {
+                v_straps
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (v, _))| !h.bbox().intersection(v.bbox()).is_empty())
+                    .map(move |(j, _)| (i, j))
+            })
+            .collect::<Vec<_>>();
+        loop {
+            let mut changed = false;
+            for &(i, j) in crossings.iter() {
+                if h_straps[i].1 != v_straps[j].1 {
+                    h_straps[i].1 = true;
+                    v_straps[j].1 = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        for &(i, j) in crossings.iter() {
+            if !h_straps[i].1 {
+                continue;
+            }
+            let viap = ViaParams::builder()
+                .layers(h_metal, v_metal)
+                .geometry(h_straps[i].0, v_straps[j].0)
+                .build();
+            group.add_instance(ctx.instantiate::<Via>(&viap)?);
+        }
+        for (layer, straps) in [(h_metal, h_straps), (v_metal, v_straps)] {
+            for (strap, connected) in straps {
+                if !connected {
+                    continue;
+                }
+                group.add(Element::new(LayerSpec::drawing(layer), strap));
+                group.add_port_with_strategy(
+                    CellPort::with_shape(net, layer, strap),
+                    PortConflictStrategy::Merge,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 impl Component for SramPex {
     type Params = SramPexParams;
     fn new(
@@ -781,8 +2095,9 @@ impl Component for SramAggregator {
         let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
         let [clk, rstb] = ctx.ports(["clk", "rstb"], Direction::Input);
         for (i, sram) in self.params.iter().enumerate() {
-            let we = ctx.port(format!("we_{i}"), Direction::Input);
-            let ce = ctx.port(format!("ce_{i}"), Direction::Input);
+            let style = sram.port_style();
+            let we = ctx.port(format!("{}_{i}", style.write_pin()), Direction::Input);
+            let ce = ctx.port(format!("{}_{i}", style.enable_pin()), Direction::Input);
             let addr = ctx.bus_port(format!("addr_{i}"), sram.addr_width(), Direction::Input);
             let wmask = ctx.bus_port(format!("wmask_{i}"), sram.wmask_width(), Direction::Input);
             let din = ctx.bus_port(format!("din_{i}"), sram.data_width(), Direction::Input);
@@ -792,8 +2107,8 @@ impl Component for SramAggregator {
                     ("vdd", vdd),
                     ("vss", vss),
                     ("clk", clk),
-                    ("we", we),
-                    ("ce", ce),
+                    (style.write_pin(), we),
+                    (style.enable_pin(), ce),
                     ("rstb", rstb),
                     ("addr", addr),
                     ("wmask", wmask),
@@ -812,8 +2127,9 @@ pub(crate) mod tests {
     use crate::paths::*;
     use crate::setup_ctx;
     use crate::tests::test_work_dir;
-    use crate::verilog::save_1rw_verilog;
+    use crate::verilog::{generate_1rw_verilog, save_1rw_verilog};
     use layout::{ReplicaColumnMos, ReplicaColumnMosParams};
+    use pins::{BitOrder, PinGroupPlacement, PinSide};
 
     use super::*;
 
@@ -861,6 +2177,200 @@ pub(crate) mod tests {
 
     pub(crate) const SRAM22_8192X32M8W8: SramParams = SramParams::new(8, MuxRatio::M8, 8192, 32);
 
+    pub(crate) const SRAM22_1024X16M16W8: SramParams = SramParams::new(8, MuxRatio::M16, 1024, 16);
+
+    pub(crate) const SRAM22_8192X8M32W8: SramParams = SramParams::new(8, MuxRatio::M32, 8192, 8);
+
+    pub(crate) const SRAM22_96X32M4W8: SramParams = SramParams::new(8, MuxRatio::M4, 96, 32);
+
+    pub(crate) const SRAM22_48X32M4W8: SramParams = SramParams::new(8, MuxRatio::M4, 48, 32);
+
+    pub(crate) const SRAM22_64X32M4W8_POWER_STRAPS: SramParams = SRAM22_64X32M4W8
+        .with_power_straps(PowerStrapParams {
+            metal: 3,
+            width: 1_600,
+            pitch: 20_000,
+        });
+
+    pub(crate) const SRAM22_64X32M4W8_PIN_PLACEMENT: SramParams = SRAM22_64X32M4W8
+        .with_pin_placement(PinPlacement {
+            control: PinGroupPlacement {
+                side: PinSide::Left,
+                layer: 3,
+                pitch: 2_000,
+                order: BitOrder::Ascending,
+            },
+            addr: PinGroupPlacement {
+                side: PinSide::Left,
+                layer: 4,
+                pitch: 2_000,
+                order: BitOrder::Descending,
+            },
+            wmask: PinGroupPlacement {
+                side: PinSide::Top,
+                layer: 2,
+                pitch: 2_000,
+                order: BitOrder::Ascending,
+            },
+            din: PinGroupPlacement {
+                side: PinSide::Top,
+                layer: 3,
+                pitch: 1_000,
+                order: BitOrder::Ascending,
+            },
+            dout: PinGroupPlacement {
+                side: PinSide::Bottom,
+                layer: 3,
+                pitch: 1_000,
+                order: BitOrder::Ascending,
+            },
+        });
+
+    pub(crate) const SRAM22_64X32M4W8_OPT1A: SramParams =
+        SRAM22_64X32M4W8.with_bitcell(BitcellType::SpOpt1a);
+
+    pub(crate) const SRAM22_64X32M4W8_RENWEN: SramParams =
+        SRAM22_64X32M4W8.with_port_style(PortStyle::RenWen);
+
+    pub(crate) const SRAM22_64X32M4W8_CSBWEB: SramParams =
+        SRAM22_64X32M4W8.with_port_style(PortStyle::CsbWeb);
+
+    pub(crate) const SRAM22_64X32M4W8_WT: SramParams =
+        SRAM22_64X32M4W8.with_write_mode(WriteMode::WriteThrough);
+
+    pub(crate) const SRAM22_64X32M4W8_REG: SramParams =
+        SRAM22_64X32M4W8.with_output(OutputMode::Registered);
+
+    pub(crate) const SRAM22_64X32M4W8_PG: SramParams = SRAM22_64X32M4W8.with_power_gating();
+
+    pub(crate) const SRAM22_64X32M4W8_WLUD: SramParams =
+        SRAM22_64X32M4W8.with_wordline_underdrive();
+
+    pub(crate) const SRAM22_64X32M4W8_TRIM: SramParams = SRAM22_64X32M4W8.with_timing_trim(2);
+
+    pub(crate) const SRAM22_64X32M4W8_SPLIT: SramParams = SRAM22_64X32M4W8.with_split_array();
+
+    pub(crate) const SRAM22_256X128M4W8_SPLIT: SramParams = SRAM22_256X128M4W8.with_split_array();
+
+    /// A 256x128 SRAM with a mux ratio of 8, divided into two halves with a mux ratio of 4.
+    pub(crate) const SRAM22_256X128M8W8_DWL: SramParams =
+        SRAM22_256X128M4W8.with_divided_wordlines();
+
+    pub(crate) const SRAM22_128X32M4W8_B2: SramParams = SRAM22_128X32M4W8.with_banks(2);
+
+    pub(crate) const SRAM22_256X32M4W8_B4: SramParams = SRAM22_256X32M4W8.with_banks(4);
+
+    #[test]
+    fn test_port_style_verilog() {
+        for params in [
+            SRAM22_64X32M4W8,
+            SRAM22_64X32M4W8_RENWEN,
+            SRAM22_64X32M4W8_CSBWEB,
+        ] {
+            let style = params.port_style();
+            let verilog = generate_1rw_verilog(&params).expect("failed to generate verilog");
+            assert!(verilog.contains(&format!(
+                "clk,rstb,{},{},wmask,addr,din,dout",
+                style.enable_pin(),
+                style.write_pin()
+            )));
+        }
+        assert_eq!(PortStyle::RenWen.pin_levels(true, true), (false, true));
+        assert_eq!(PortStyle::CsbWeb.pin_levels(true, false), (false, true));
+    }
+
+    #[test]
+    fn test_sram_wide_mux_schematic() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sram_wide_mux_schematic");
+        for (params, rows, col_select_bits) in
+            [(SRAM22_1024X16M16W8, 64, 4), (SRAM22_8192X8M32W8, 256, 5)]
+        {
+            assert_eq!(params.rows(), rows);
+            assert_eq!(params.cols(), 256);
+            assert_eq!(params.col_select_bits(), col_select_bits);
+            ctx.run_script::<SramPhysicalDesignScript>(&params)
+                .expect("failed to run design script");
+            ctx.write_schematic_to_file::<Sram>(&params, out_spice(&work_dir, &*params.name()))
+                .expect("failed to write schematic");
+        }
+    }
+
+    #[test]
+    fn test_sram_padded_rows_schematic() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sram_padded_rows_schematic");
+        let params = SRAM22_48X32M4W8;
+        assert_eq!(params.rows(), MIN_ROWS);
+        let dsn = ctx
+            .run_script::<SramPhysicalDesignScript>(&params)
+            .expect("failed to run design script");
+        assert_eq!(dsn.row_decoder.tree.root.num, MIN_ROWS);
+        ctx.write_schematic_to_file::<Sram>(&params, out_spice(&work_dir, &*params.name()))
+            .expect("failed to write schematic");
+    }
+
+    #[test]
+    fn test_read_latency() {
+        assert_eq!(SRAM22_64X32M4W8.read_latency(), 1);
+        assert_eq!(SRAM22_64X32M4W8_WT.read_latency(), 1);
+        assert_eq!(SRAM22_64X32M4W8_REG.read_latency(), 2);
+    }
+
+    #[test]
+    fn test_sram_wordline_underdrive() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sram_wordline_underdrive");
+        let params = SRAM22_64X32M4W8_WLUD;
+        let dsn = ctx
+            .run_script::<SramPhysicalDesignScript>(&params)
+            .expect("failed to run design script");
+        let suppression = dsn
+            .row_decoder
+            .suppression
+            .expect("wordlines not suppressed");
+        assert!((420..=SUPPRESSION_FINGER_WIDTH).contains(&suppression.width));
+        assert!(dsn.rwl_suppression.is_some());
+
+        // Only the drivers of each half of a split array drive the wordlines.
+        let split = ctx
+            .run_script::<SramPhysicalDesignScript>(
+                &SRAM22_256X128M4W8_SPLIT.with_wordline_underdrive(),
+            )
+            .expect("failed to run design script");
+        assert!(split.row_decoder.suppression.is_none());
+        assert!(split.wl_driver.as_ref().unwrap().suppression.is_some());
+
+        ctx.write_schematic_to_file::<Sram>(&params, out_spice(&work_dir, &*params.name()))
+            .expect("failed to write schematic");
+    }
+
+    #[test]
+    fn test_split_array_wordline_delay() {
+        let ctx = setup_ctx();
+        let unsplit = ctx
+            .run_script::<SramPhysicalDesignScript>(&SRAM22_256X128M4W8)
+            .expect("failed to run design script");
+        let dsn = ctx
+            .run_script::<SramPhysicalDesignScript>(&SRAM22_256X128M4W8_SPLIT)
+            .expect("failed to run design script");
+        assert_eq!(dsn.bitcells.cols, SRAM22_256X128M4W8.cols() / 2);
+        assert_eq!(dsn.col_params.cols, SRAM22_256X128M4W8.cols() / 2);
+        assert!(dsn.time_constants.row_decoder < unsplit.time_constants.row_decoder);
+
+        // Each half of a divided-wordline array holds half of the columns of every bit,
+        // and only the drivers of one half are enabled.
+        let params = SRAM22_256X128M8W8_DWL;
+        let dsn = ctx
+            .run_script::<SramPhysicalDesignScript>(&params)
+            .expect("failed to run design script");
+        assert_eq!(&*params.name(), "sram22_256x128m8w8_dwl");
+        assert_eq!(params.rows(), 32);
+        assert_eq!(dsn.bitcells.cols, 128 * 8 / 2);
+        assert_eq!(dsn.col_params.cols, 128 * 8 / 2);
+        assert!(dsn.bank_select.is_some());
+    }
+
     #[test]
     fn test_replica_column_nmos() {
         let ctx = setup_ctx();
@@ -1130,4 +2640,87 @@ pub(crate) mod tests {
     test_sram!(test_sram22_4096x8m8w1, SRAM22_4096X8M8W1, ignore = "slow");
     test_sram!(test_sram22_4096x32m8w8, SRAM22_4096X32M8W8, ignore = "slow");
     test_sram!(test_sram22_8192x32m8w8, SRAM22_8192X32M8W8, ignore = "slow");
+    test_sram!(
+        test_sram22_1024x16m16w8,
+        SRAM22_1024X16M16W8,
+        ignore = "slow"
+    );
+    test_sram!(test_sram22_8192x8m32w8, SRAM22_8192X8M32W8, ignore = "slow");
+    test_sram!(test_sram22_96x32m4w8, SRAM22_96X32M4W8, ignore = "slow");
+    test_sram!(test_sram22_48x32m4w8, SRAM22_48X32M4W8, ignore = "slow");
+    test_sram!(
+        test_sram22_64x32m4w8_power_straps,
+        SRAM22_64X32M4W8_POWER_STRAPS,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_pin_placement,
+        SRAM22_64X32M4W8_PIN_PLACEMENT,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_opt1a,
+        SRAM22_64X32M4W8_OPT1A,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_renwen,
+        SRAM22_64X32M4W8_RENWEN,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_csbweb,
+        SRAM22_64X32M4W8_CSBWEB,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_wt,
+        SRAM22_64X32M4W8_WT,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_reg,
+        SRAM22_64X32M4W8_REG,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_wlud,
+        SRAM22_64X32M4W8_WLUD,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_trim,
+        SRAM22_64X32M4W8_TRIM,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_pg,
+        SRAM22_64X32M4W8_PG,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_64x32m4w8_split,
+        SRAM22_64X32M4W8_SPLIT,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_256x128m4w8_split,
+        SRAM22_256X128M4W8_SPLIT,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_256x128m8w8_dwl,
+        SRAM22_256X128M8W8_DWL,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_128x32m4w8_b2,
+        SRAM22_128X32M4W8_B2,
+        ignore = "slow"
+    );
+    test_sram!(
+        test_sram22_256x32m4w8_b4,
+        SRAM22_256X32M4W8_B4,
+        ignore = "slow"
+    );
 }
@@ -0,0 +1,370 @@
+//! Placement of the SRAM's signal pins on the macro boundary.
+//!
+//! Each signal leaves the SRAM through an m1 stub below the guard ring, as in the default
+//! pin placement, and is given a dedicated m2 track in a channel below the ring.
+//! Signals with pins on the left, right or top of the macro continue up m3 columns in
+//! channels beside the ring, and signals with pins on the top cross a second m2 channel
+//! above the ring. Pins on the top and bottom are reached by m3 wires and pins on the
+//! left and right by m2 wires, with via stacks up to the pin layer.
+
+use serde::{Deserialize, Serialize};
+use subgeom::bbox::BoundBox;
+use subgeom::{snap_to_grid, Rect, Span};
+use substrate::component::error;
+use substrate::layout::cell::{CellPort, Element, Instance, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::elements::via::{Via, ViaParams};
+use substrate::layout::group::Group;
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::layers::{LayerKey, LayerSpec};
+
+use super::SramParams;
+
+/// Width of the wires routing signals to their pins, and of the pins themselves.
+const ROUTE_WIDTH: i64 = 400;
+/// Pitch of the routing tracks in the channels around the guard ring.
+const TRACK_PITCH: i64 = 1_000;
+/// Length of each pin, measured inward from the macro boundary.
+const PIN_LENGTH: i64 = 1_000;
+/// Space between the guard ring, the routing channels and the pins.
+const CHANNEL_MARGIN: i64 = 1_000;
+
+/// The minimum distance between adjacent pins in nm.
+pub const MIN_PIN_PITCH: i64 = TRACK_PITCH;
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PinSide {
+    Left,
+    Right,
+    Top,
+    #[default]
+    Bottom,
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitOrder {
+    /// Bit 0 is placed closest to the left or bottom of the macro.
+    #[default]
+    Ascending,
+    /// Bit 0 is placed closest to the right or top of the macro.
+    Descending,
+}
+
+/// The placement of a group of pins.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PinGroupPlacement {
+    pub side: PinSide,
+    /// Metal layer of the pins. Must be 2, 3 or 4.
+    pub layer: usize,
+    /// Distance between adjacent pins in nm.
+    pub pitch: i64,
+    pub order: BitOrder,
+}
+
+impl Default for PinGroupPlacement {
+    fn default() -> Self {
+        Self {
+            side: PinSide::Bottom,
+            layer: 3,
+            pitch: 2_000,
+            order: BitOrder::Ascending,
+        }
+    }
+}
+
+/// The placement of the SRAM's signal pins.
+///
+/// Groups sharing a side are placed in the order of the fields below,
+/// separated by one pitch of the preceding group.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PinPlacement {
+    /// The `clk` and `rstb` pins, the enable and write enable pins of the port style,
+    /// and the trim pins, if any.
+    pub control: PinGroupPlacement,
+    pub addr: PinGroupPlacement,
+    pub wmask: PinGroupPlacement,
+    pub din: PinGroupPlacement,
+    pub dout: PinGroupPlacement,
+}
+
+impl PinPlacement {
+    /// The placement of each pin group.
+    pub fn groups(&self) -> [PinGroupPlacement; 5] {
+        [self.control, self.addr, self.wmask, self.din, self.dout]
+    }
+
+    /// The placement of each pin group along with the name and width of each bus in the group.
+    fn buses(&self, params: &SramParams) -> [(PinGroupPlacement, Vec<(&'static str, usize)>); 5] {
+        [
+            (
+                self.control,
+                [
+                    ("clk", 1),
+                    ("rstb", 1),
+                    (params.port_style().enable_pin(), 1),
+                    (params.port_style().write_pin(), 1),
+                ]
+                .into_iter()
+                .chain(
+                    params
+                        .timing_trim()
+                        .into_iter()
+                        .flat_map(|bits| [("trim_saen", bits), ("trim_wl", bits)]),
+                )
+                .collect(),
+            ),
+            (self.addr, vec![("addr", params.addr_width())]),
+            (self.wmask, vec![("wmask", params.wmask_width())]),
+            (self.din, vec![("din", params.data_width())]),
+            (self.dout, vec![("dout", params.data_width())]),
+        ]
+    }
+}
+
+struct PinRoute {
+    id: PortId,
+    /// The m1 stub of the SRAM port.
+    stub: Rect,
+    side: PinSide,
+    layer: usize,
+    /// Center of the pin along its side.
+    pos: i64,
+}
+
+fn centered(center: i64, width: i64) -> Span {
+    Span::new(center - width / 2, center + width / 2)
+}
+
+/// The smallest span containing both `a` and `b`.
+fn hull(a: Span, b: Span) -> Span {
+    Span::new(a.start().min(b.start()), a.stop().max(b.stop()))
+}
+
+fn draw(group: &mut Group, layer: LayerKey, rect: Rect) {
+    group.add(Element::new(LayerSpec::drawing(layer), rect));
+}
+
+fn draw_via(
+    ctx: &mut LayoutCtx,
+    group: &mut Group,
+    layers: (LayerKey, LayerKey),
+    below: Rect,
+    above: Rect,
+) -> substrate::error::Result<()> {
+    let viap = ViaParams::builder()
+        .layers(layers.0, layers.1)
+        .geometry(below, above)
+        .build();
+    group.add_instance(ctx.instantiate::<Via>(&viap)?);
+    Ok(())
+}
+
+/// Routes the signal ports of `sram` to pins on the boundary of the macro
+/// according to `placement`.
+///
+/// `ring` is the bounding box of the guard ring surrounding `sram`.
+pub(crate) fn route_pins(
+    ctx: &mut LayoutCtx,
+    group: &mut Group,
+    sram: &Instance,
+    ring: Rect,
+    params: &SramParams,
+    placement: &PinPlacement,
+) -> substrate::error::Result<()> {
+    let metals = (0..=4)
+        .map(|i| ctx.layers().get(Selector::Metal(i)))
+        .collect::<substrate::error::Result<Vec<_>>>()?;
+    let (m1, m2, m3) = (metals[1], metals[2], metals[3]);
+
+    let mut pins = Vec::new();
+    for side in [PinSide::Left, PinSide::Right, PinSide::Top, PinSide::Bottom] {
+        let span = match side {
+            PinSide::Left | PinSide::Right => ring.vspan(),
+            PinSide::Top | PinSide::Bottom => ring.hspan(),
+        };
+        let mut pos = span.start() + CHANNEL_MARGIN + ROUTE_WIDTH / 2;
+        for (group_placement, buses) in placement.buses(params) {
+            if group_placement.side != side {
+                continue;
+            }
+            let mut ids = buses
+                .into_iter()
+                .flat_map(|(name, width)| (0..width).map(move |i| PortId::new(name, i)))
+                .collect::<Vec<_>>();
+            if group_placement.order == BitOrder::Descending {
+                ids.reverse();
+            }
+            for id in ids {
+                if pos + ROUTE_WIDTH / 2 > span.stop() - CHANNEL_MARGIN {
+                    // The pins on this side do not fit along the guard ring.
+                    return Err(error::Error::InvalidParams.into());
+                }
+                let stub = sram.port(id.clone())?.largest_rect(m1)?;
+                pins.push(PinRoute {
+                    id,
+                    stub,
+                    side,
+                    layer: group_placement.layer,
+                    pos: snap_to_grid(pos, 5),
+                });
+                pos += group_placement.pitch;
+            }
+            pos += group_placement.pitch;
+        }
+    }
+
+    // Pins on the top route through the side channel nearest their stub.
+    let column_side = |pin: &PinRoute| match pin.side {
+        PinSide::Top if pin.stub.center().x < ring.center().x => Some(PinSide::Left),
+        PinSide::Top => Some(PinSide::Right),
+        PinSide::Bottom => None,
+        side => Some(side),
+    };
+    let num_left = pins
+        .iter()
+        .filter(|pin| column_side(pin) == Some(PinSide::Left))
+        .count() as i64;
+    let num_right = pins
+        .iter()
+        .filter(|pin| column_side(pin) == Some(PinSide::Right))
+        .count() as i64;
+    let num_top = pins.iter().filter(|pin| pin.side == PinSide::Top).count() as i64;
+    let channel = |count: i64| {
+        if count > 0 {
+            2 * CHANNEL_MARGIN + count * TRACK_PITCH + PIN_LENGTH
+        } else {
+            0
+        }
+    };
+    let boundary = Rect::from_spans(
+        Span::new(
+            ring.left() - channel(num_left),
+            ring.right() + channel(num_right),
+        ),
+        Span::new(
+            ring.bottom() - channel(pins.len() as i64),
+            ring.top() + channel(num_top),
+        ),
+    );
+
+    let (mut left, mut right, mut top) = (0, 0, 0);
+    for (k, pin) in pins.into_iter().enumerate() {
+        let track = centered(
+            ring.bottom() - CHANNEL_MARGIN - ROUTE_WIDTH / 2 - k as i64 * TRACK_PITCH,
+            ROUTE_WIDTH,
+        );
+        let stub = pin
+            .stub
+            .with_vspan(Span::new(track.start(), pin.stub.top()));
+        draw(group, m1, stub);
+
+        let column = column_side(&pin).map(|side| {
+            let (index, sign, edge) = match side {
+                PinSide::Left => (&mut left, -1, ring.left()),
+                _ => (&mut right, 1, ring.right()),
+            };
+            let x = edge + sign * (CHANNEL_MARGIN + ROUTE_WIDTH / 2 + *index * TRACK_PITCH);
+            *index += 1;
+            centered(x, ROUTE_WIDTH)
+        });
+        let pin_span = centered(pin.pos, ROUTE_WIDTH);
+
+        // The wire reaching the pin, its layer, and the pin itself.
+        let (route_layer, pin_rect) = match pin.side {
+            PinSide::Bottom => {
+                let pin_rect = Rect::from_spans(
+                    pin_span,
+                    Span::new(boundary.bottom(), boundary.bottom() + PIN_LENGTH),
+                );
+                let drop = pin_rect.with_vspan(Span::new(boundary.bottom(), track.stop()));
+                let track_rect = Rect::from_spans(hull(stub.hspan(), pin_span), track);
+                draw(group, m2, track_rect);
+                draw(group, m3, drop);
+                draw_via(ctx, group, (m1, m2), stub, track_rect)?;
+                draw_via(ctx, group, (m2, m3), track_rect, drop)?;
+                (3, pin_rect)
+            }
+            PinSide::Left | PinSide::Right => {
+                let column = column.unwrap();
+                let pin_rect = if pin.side == PinSide::Left {
+                    Rect::from_spans(
+                        Span::new(boundary.left(), boundary.left() + PIN_LENGTH),
+                        pin_span,
+                    )
+                } else {
+                    Rect::from_spans(
+                        Span::new(boundary.right() - PIN_LENGTH, boundary.right()),
+                        pin_span,
+                    )
+                };
+                let track_rect = Rect::from_spans(hull(stub.hspan(), column), track);
+                let column_rect =
+                    Rect::from_spans(column, Span::new(track.start(), pin_span.stop()));
+                let wire = Rect::from_spans(hull(pin_rect.hspan(), column), pin_span);
+                draw(group, m2, track_rect);
+                draw(group, m3, column_rect);
+                draw(group, m2, wire);
+                draw_via(ctx, group, (m1, m2), stub, track_rect)?;
+                draw_via(ctx, group, (m2, m3), track_rect, column_rect)?;
+                draw_via(ctx, group, (m2, m3), wire, column_rect)?;
+                (2, pin_rect)
+            }
+            PinSide::Top => {
+                let column = column.unwrap();
+                let top_track = centered(
+                    ring.top() + CHANNEL_MARGIN + ROUTE_WIDTH / 2 + top * TRACK_PITCH,
+                    ROUTE_WIDTH,
+                );
+                top += 1;
+                let pin_rect = Rect::from_spans(
+                    pin_span,
+                    Span::new(boundary.top() - PIN_LENGTH, boundary.top()),
+                );
+                let track_rect = Rect::from_spans(hull(stub.hspan(), column), track);
+                let column_rect =
+                    Rect::from_spans(column, Span::new(track.start(), top_track.stop()));
+                let top_track_rect = Rect::from_spans(hull(column, pin_span), top_track);
+                let rise = pin_rect.with_vspan(Span::new(top_track.start(), boundary.top()));
+                draw(group, m2, track_rect);
+                draw(group, m3, column_rect);
+                draw(group, m2, top_track_rect);
+                draw(group, m3, rise);
+                draw_via(ctx, group, (m1, m2), stub, track_rect)?;
+                draw_via(ctx, group, (m2, m3), track_rect, column_rect)?;
+                draw_via(ctx, group, (m2, m3), top_track_rect, column_rect)?;
+                draw_via(ctx, group, (m2, m3), top_track_rect, rise)?;
+                (3, pin_rect)
+            }
+        };
+
+        // Stack vias from the routing layer to the pin layer.
+        let (lower, upper) = if pin.layer < route_layer {
+            (pin.layer, route_layer)
+        } else {
+            (route_layer, pin.layer)
+        };
+        for layer in lower..upper {
+            draw(group, metals[layer], pin_rect);
+            draw_via(
+                ctx,
+                group,
+                (metals[layer], metals[layer + 1]),
+                pin_rect,
+                pin_rect,
+            )?;
+        }
+        draw(group, metals[pin.layer], pin_rect);
+        group.add_port(
+            CellPort::builder()
+                .id(pin.id)
+                .add(metals[pin.layer], pin_rect)
+                .build(),
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,436 @@
+//! Spare rows and columns that replace faulty rows and data bits of an [`Sram`](super::Sram).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use subgeom::bbox::BoundBox;
+use subgeom::{Rect, Span};
+use substrate::error::Result;
+use substrate::layout::cell::{Port, PortId};
+use substrate::layout::context::LayoutCtx;
+use substrate::layout::group::Group;
+use substrate::layout::layers::selector::Selector;
+use substrate::layout::placement::align::AlignRect;
+use substrate::schematic::circuit::Direction;
+use substrate::schematic::context::SchematicCtx;
+
+use crate::blocks::logic::channel::{strap_supplies, Channel, STRAP_SPACE};
+use crate::blocks::logic::{LogicBlock, LogicBlockParams, LogicBuilder, LogicCell, VDD, VSS};
+
+use super::{PortStyle, SramInner, SramParams};
+
+/// Spare rows and columns of an SRAM.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Redundancy {
+    /// Spare rows, which are added below the regular rows of the bitcell array.
+    pub spare_rows: usize,
+    /// Spare data bits, each of which adds `mux_ratio` columns to the bitcell array.
+    pub spare_cols: usize,
+}
+
+/// Ports of the SRAM core that are connected to the repair logic rather than to pins,
+/// besides the pins that enable an access.
+const CORE_PORTS: [&str; 4] = ["addr", "wmask", "din", "dout"];
+
+/// The pins that enable an access with `port_style`, which the repair logic
+/// also connects to the SRAM core.
+fn enable_pins(port_style: PortStyle) -> Vec<&'static str> {
+    match port_style {
+        PortStyle::RenWen => vec![port_style.enable_pin(), port_style.write_pin()],
+        _ => vec![port_style.enable_pin()],
+    }
+}
+
+/// The repair register and the logic that steers accesses to spare rows and columns.
+///
+/// A row address that matches a valid spare row entry is replaced by the address of
+/// that spare row before it reaches the row decoder, which decodes the spare rows after
+/// the regular rows. Data bits that match a valid spare column entry are written to and
+/// read from that spare column as well, and read data from the spare column replaces
+/// the regular column's data on `dout`.
+///
+/// Accesses to rows beyond the regular rows are disabled, since their addresses
+/// would otherwise reach the spare rows.
+pub fn repair_logic(params: &SramParams) -> LogicBlockParams {
+    let redundancy = params
+        .redundancy()
+        .expect("SRAM has no spare rows or columns");
+    let core = params.core();
+    let dw = params.data_width();
+    let csb = params.col_select_bits();
+    let rw = params.row_addr_width();
+    let cw = params.col_index_width();
+    let n = params.repair_width();
+
+    let mut logic = LogicBuilder::new(arcstr::format!("{}_repair", params.name()));
+    let [clk, rstb, repair_se, repair_si] =
+        ["clk", "rstb", "repair_se", "repair_si"].map(|name| logic.input(name, 1)[0]);
+    let enable = enable_pins(params.port_style())
+        .into_iter()
+        .map(|pin| logic.input(pin, 1)[0])
+        .collect::<Vec<_>>();
+    let addr = logic.input("addr", params.addr_width());
+    let wmask = logic.input("wmask", params.wmask_width());
+    let din = logic.input("din", dw);
+    let core_dout = logic.input("core_dout", core.data_width());
+    let repair_so = logic.output("repair_so", 1)[0];
+    let core_enable = enable_pins(params.port_style())
+        .into_iter()
+        .map(|pin| logic.output(format!("core_{pin}"), 1)[0])
+        .collect::<Vec<_>>();
+    let core_addr = logic.output("core_addr", core.addr_width());
+    let core_wmask = logic.output("core_wmask", core.wmask_width());
+    let core_din = logic.output("core_din", core.data_width());
+    let dout = logic.output("dout", dw);
+
+    // Repair register.
+    let repair = (0..n).map(|_| logic.net()).collect::<Vec<_>>();
+    for p in 0..n {
+        let prev = if p == 0 { repair_si } else { repair[p - 1] };
+        let d = logic.mux2(repair_se, repair[p], prev);
+        logic.gate_to(LogicCell::Dff, &[clk, rstb, d], repair[p]);
+    }
+    logic.buf(repair[n - 1], repair_so);
+
+    // Row repair: steer matching row addresses to the corresponding spare row.
+    let row_matches = (0..redundancy.spare_rows)
+        .map(|i| {
+            let base = i * (1 + rw);
+            let mut terms = vec![repair[base]];
+            for b in 0..rw {
+                let diff = logic.xor2(addr[csb + b], repair[base + 1 + b]);
+                terms.push(logic.inv(diff));
+            }
+            logic.and_all(&terms)
+        })
+        .collect::<Vec<_>>();
+
+    // `in_range` is high if the row address is less than the number of regular rows.
+    let mut in_range = if params.rows() >= 1 << rw { VDD } else { VSS };
+    if in_range == VSS {
+        for b in 0..rw {
+            let a_b = logic.inv(addr[csb + b]);
+            in_range = match (params.rows() & (1 << b) != 0, in_range) {
+                (true, VSS) => a_b,
+                (true, lt) => logic.or2(a_b, lt),
+                (false, VSS) => VSS,
+                (false, lt) => logic.and2(a_b, lt),
+            };
+        }
+    }
+    for (&pin, &core_pin) in enable.iter().zip(&core_enable) {
+        let y = if in_range == VDD {
+            pin
+        } else if params.port_style().active_low() {
+            let out_of_range = logic.inv(in_range);
+            logic.or2(pin, out_of_range)
+        } else {
+            logic.and2(pin, in_range)
+        };
+        logic.buf(y, core_pin);
+    }
+
+    let row_repaired = logic.or_all(&row_matches);
+    let row_repaired_b = logic.inv(row_repaired);
+    for b in 0..csb {
+        logic.buf(addr[b], core_addr[b]);
+    }
+    for b in 0..core.addr_width() - csb {
+        let mut terms = row_matches
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (params.rows() + i) & (1 << b) != 0)
+            .map(|(_, &m)| m)
+            .collect::<Vec<_>>();
+        if b < rw {
+            terms.push(logic.and2(addr[csb + b], row_repaired_b));
+        }
+        let bit = logic.or_all(&terms);
+        logic.buf(bit, core_addr[csb + b]);
+    }
+
+    // Column repair: `sel[k][j]` is high if spare column `k` replaces data bit `j`.
+    let col_base = redundancy.spare_rows * (1 + rw);
+    let sel = (0..redundancy.spare_cols)
+        .map(|k| {
+            let base = col_base + k * (1 + cw);
+            let index_b = (0..cw)
+                .map(|b| logic.inv(repair[base + 1 + b]))
+                .collect::<Vec<_>>();
+            (0..dw)
+                .map(|j| {
+                    let mut terms = vec![repair[base]];
+                    for b in 0..cw {
+                        terms.push(if j & (1 << b) != 0 {
+                            repair[base + 1 + b]
+                        } else {
+                            index_b[b]
+                        });
+                    }
+                    logic.and_all(&terms)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let wmask_bit = |j: usize| wmask[j / params.wmask_granularity()];
+    for j in 0..dw {
+        logic.buf(din[j], core_din[j]);
+        logic.buf(wmask_bit(j), core_wmask[j]);
+    }
+    for (k, sel_k) in sel.iter().enumerate() {
+        let din_terms = (0..dw)
+            .map(|j| logic.and2(sel_k[j], din[j]))
+            .collect::<Vec<_>>();
+        let spare_din = logic.or_all(&din_terms);
+        logic.buf(spare_din, core_din[dw + k]);
+        let wmask_terms = (0..dw)
+            .map(|j| logic.and2(sel_k[j], wmask_bit(j)))
+            .collect::<Vec<_>>();
+        let spare_wmask = logic.or_all(&wmask_terms);
+        logic.buf(spare_wmask, core_wmask[dw + k]);
+    }
+    for j in 0..dw {
+        let spare_terms = sel
+            .iter()
+            .enumerate()
+            .map(|(k, sel_k)| logic.and2(sel_k[j], core_dout[dw + k]))
+            .collect::<Vec<_>>();
+        let replaced = logic.or_all(&sel.iter().map(|sel_k| sel_k[j]).collect::<Vec<_>>());
+        let spare = logic.or_all(&spare_terms);
+        let y = logic.mux2(replaced, core_dout[j], spare);
+        logic.buf(y, dout[j]);
+    }
+
+    logic.build()
+}
+
+/// Draws the schematic of an SRAM with spare rows and columns.
+///
+/// The SRAM core stores the spares, and its address and data ports
+/// are connected through the [`repair_logic`].
+pub(crate) fn draw_repair_schematic(ctx: &mut SchematicCtx, params: &SramParams) -> Result<()> {
+    let core = params.core();
+    let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
+    let [clk, rstb] = ctx.ports(["clk", "rstb"], Direction::Input);
+    let core_addr = ctx.bus("core_addr", core.addr_width());
+    let core_wmask = ctx.bus("core_wmask", core.wmask_width());
+    let core_din = ctx.bus("core_din", core.data_width());
+    let core_dout = ctx.bus("core_dout", core.data_width());
+
+    let enable = enable_pins(params.port_style());
+    let core_enable = enable
+        .iter()
+        .map(|pin| ctx.signal(format!("core_{pin}")))
+        .collect::<Vec<_>>();
+
+    let mut inner = ctx
+        .instantiate::<SramInner>(&core)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("clk", clk),
+            ("rstb", rstb),
+            ("addr", core_addr),
+            ("wmask", core_wmask),
+            ("din", core_din),
+            ("dout", core_dout),
+        ])
+        .with_connections(enable.iter().copied().zip(core_enable.iter().copied()))
+        .named("sram");
+    ctx.bubble_filter_map(&mut inner, |port| {
+        let name = port.name().as_ref();
+        (!CORE_PORTS.contains(&name)
+            && !enable.contains(&name)
+            && !["vdd", "vss", "clk", "rstb"].contains(&name))
+        .then_some(port.name().into())
+    });
+    ctx.add_instance(inner);
+
+    let addr = ctx.bus_port("addr", params.addr_width(), Direction::Input);
+    let wmask = ctx.bus_port("wmask", params.wmask_width(), Direction::Input);
+    let din = ctx.bus_port("din", params.data_width(), Direction::Input);
+    let dout = ctx.bus_port("dout", params.data_width(), Direction::Output);
+    let [repair_se, repair_si] = ctx.ports(["repair_se", "repair_si"], Direction::Input);
+    let repair_so = ctx.port("repair_so", Direction::Output);
+    let enable_ports = enable
+        .iter()
+        .map(|&pin| ctx.port(pin, Direction::Input))
+        .collect::<Vec<_>>();
+
+    ctx.instantiate::<LogicBlock>(&repair_logic(params))?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("clk", clk),
+            ("rstb", rstb),
+            ("repair_se", repair_se),
+            ("repair_si", repair_si),
+            ("repair_so", repair_so),
+            ("addr", addr),
+            ("wmask", wmask),
+            ("din", din),
+            ("dout", dout),
+            ("core_addr", core_addr),
+            ("core_wmask", core_wmask),
+            ("core_din", core_din),
+            ("core_dout", core_dout),
+        ])
+        .with_connections(enable.iter().copied().zip(enable_ports))
+        .with_connections(
+            enable
+                .iter()
+                .map(|pin| format!("core_{pin}"))
+                .zip(core_enable),
+        )
+        .named("repair")
+        .add_to(ctx);
+
+    Ok(())
+}
+
+/// Places the [`repair_logic`] to the right of `group` and connects it through a channel
+/// beneath both, drawing `group` and adding the macro pins.
+///
+/// `group` holds the SRAM core and its guard ring, with the core's pins
+/// extended to the bottom of the ring.
+pub(crate) fn draw_repair_logic(
+    ctx: &mut LayoutCtx,
+    group: Group,
+    params: &SramParams,
+) -> Result<()> {
+    let m1 = ctx.layers().get(Selector::Metal(1))?;
+    let core = params.core();
+    let trim_bits = params.timing_trim().unwrap_or_default();
+
+    let mut stubs = HashMap::new();
+    for port in group.ports() {
+        if let "vdd" | "vss" = port.name().as_str() {
+            ctx.merge_port(port);
+        } else {
+            stubs.insert(port.id().clone(), port.largest_rect(m1)?);
+        }
+    }
+    let ring_vdd = group
+        .port_map()
+        .port("vdd")?
+        .shapes(m1)
+        .filter_map(|shape| shape.as_rect())
+        .max_by_key(|rect| rect.right())
+        .unwrap();
+    let brect = group.brect();
+    ctx.draw(group)?;
+
+    let mut logic = ctx.instantiate::<LogicBlock>(&repair_logic(params))?;
+    logic.align_to_the_right_of(brect, STRAP_SPACE);
+    logic.align_bottom(brect);
+    strap_supplies(ctx, &logic, Some(ring_vdd.left()))?;
+
+    let enable = enable_pins(params.port_style());
+    let mut passthrough = vec![("trim_saen", trim_bits), ("trim_wl", trim_bits)];
+    if !enable.contains(&params.port_style().write_pin()) {
+        passthrough.push((params.port_style().write_pin(), 1));
+    }
+    let nets = 2 * (params.addr_width() + params.wmask_width() + 2 * params.data_width())
+        + 2 * (core.addr_width() + core.wmask_width() + 2 * core.data_width())
+        + passthrough.iter().map(|(_, width)| width).sum::<usize>();
+    let mut channel = Channel::new(
+        ctx,
+        Span::new(brect.left(), logic.brect().right()),
+        brect.bottom(),
+        nets + 16,
+    )?;
+    let logic_pin = |port: &str, i: usize| -> Result<Rect> {
+        logic.port(PortId::new(port, i))?.largest_rect(m1)
+    };
+
+    // The clock and reset go to both the SRAM core and the repair register.
+    for port in ["clk", "rstb"] {
+        let net = format!("{port}_0");
+        let sram = channel.pin(ctx, stubs[&PortId::new(port, 0)], &net)?;
+        let repair = channel.pin(ctx, logic_pin(port, 0)?, &net)?;
+        let pin = channel.macro_pin(ctx, PortId::new(port, 0), sram, &net)?;
+        channel.connect(pin, sram, &net);
+        channel.connect(pin, repair, &net);
+    }
+    for (port, width) in passthrough {
+        for i in 0..width {
+            let net = format!("{port}_{i}");
+            let sram = channel.pin(ctx, stubs[&PortId::new(port, i)], &net)?;
+            let pin = channel.macro_pin(ctx, PortId::new(port, i), sram, &net)?;
+            channel.connect(pin, sram, &net);
+        }
+    }
+
+    // Enables, address and data pass through the repair logic.
+    let core_inputs = enable.iter().map(|&pin| (pin, 1)).chain([
+        ("addr", core.addr_width()),
+        ("wmask", core.wmask_width()),
+        ("din", core.data_width()),
+    ]);
+    for (port, width) in core_inputs {
+        for i in 0..width {
+            let net = format!("core_{port}_{i}");
+            let src = channel.pin(ctx, logic_pin(&format!("core_{port}"), i)?, &net)?;
+            let dst = channel.pin(ctx, stubs[&PortId::new(port, i)], &net)?;
+            channel.connect(src, dst, &net);
+        }
+    }
+    for i in 0..core.data_width() {
+        let net = format!("core_dout_{i}");
+        let src = channel.pin(ctx, stubs[&PortId::new("dout", i)], &net)?;
+        let dst = channel.pin(ctx, logic_pin("core_dout", i)?, &net)?;
+        channel.connect(src, dst, &net);
+    }
+    let inputs = enable.iter().map(|&pin| (pin, 1, Direction::Input));
+    for (port, width, direction) in inputs.chain([
+        ("addr", params.addr_width(), Direction::Input),
+        ("wmask", params.wmask_width(), Direction::Input),
+        ("din", params.data_width(), Direction::Input),
+        ("repair_se", 1, Direction::Input),
+        ("repair_si", 1, Direction::Input),
+        ("dout", params.data_width(), Direction::Output),
+        ("repair_so", 1, Direction::Output),
+    ]) {
+        for i in 0..width {
+            let net = format!("{port}_{i}");
+            let repair = channel.pin(ctx, logic_pin(port, i)?, &net)?;
+            let pin = channel.macro_pin(ctx, PortId::new(port, i), repair, &net)?;
+            match direction {
+                Direction::Input => channel.connect(pin, repair, &net),
+                _ => channel.connect(repair, pin, &net),
+            }
+        }
+    }
+    channel.draw(ctx)?;
+    ctx.draw(logic)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::sram::{MuxRatio, Sram};
+    use crate::paths::{out_gds, out_lef, out_spice, out_verilog};
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+    use crate::verilog::save_1rw_verilog;
+
+    use super::*;
+
+    #[test]
+    fn test_sram_redundant() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_sram_redundant");
+        let params = SramParams::new(8, MuxRatio::M4, 64, 32).with_redundancy(4, 2);
+        assert_eq!(params.repair_width(), 4 * 5 + 2 * 6);
+        ctx.write_schematic_to_file::<Sram>(&params, out_spice(&work_dir, "schematic"))
+            .expect("failed to write schematic");
+        save_1rw_verilog(out_verilog(&work_dir, &*params.name()), &params)
+            .expect("failed to write behavioral model");
+        ctx.write_layout::<Sram>(&params, out_gds(&work_dir, &*params.name()))
+            .expect("failed to write layout");
+        crate::abs::write_abstract(&ctx, &params, out_lef(&work_dir, &*params.name()))
+            .expect("failed to write abstract");
+    }
+}
@@ -1,8 +1,12 @@
 use substrate::error::Result;
 use substrate::index::IndexOwned;
+use substrate::pdk::mos::query::Query;
+use substrate::pdk::mos::spec::MosKind;
+use substrate::pdk::mos::MosParams;
 use substrate::schematic::circuit::Direction;
 use substrate::schematic::context::SchematicCtx;
-use substrate::schematic::signal::Signal;
+use substrate::schematic::elements::mos::SchematicMos;
+use substrate::schematic::signal::{Signal, Slice};
 
 use crate::blocks::bitcell_array::replica::ReplicaCellArray;
 use crate::blocks::bitcell_array::SpCellArray;
@@ -17,7 +21,7 @@ use crate::blocks::gate::GateParams;
 use crate::blocks::precharge::Precharge;
 
 use super::layout::ReplicaColumnMos;
-use super::{SramInner, SramPhysicalDesignScript};
+use super::{SramInner, SramParams, SramPhysicalDesign, SramPhysicalDesignScript};
 
 impl SramInner {
     pub(crate) fn schematic(&self, ctx: &mut SchematicCtx) -> Result<()> {
@@ -25,205 +29,487 @@ impl SramInner {
             .inner()
             .run_script::<SramPhysicalDesignScript>(&self.params)?;
         let [vdd, vss] = ctx.ports(["vdd", "vss"], Direction::InOut);
-        let [clk, we, ce, rstb] = ctx.ports(["clk", "we", "ce", "rstb"], Direction::Input);
+        let style = self.params.port_style();
+        let [clk, we, ce, rstb] = ctx.ports(
+            ["clk", style.write_pin(), style.enable_pin(), "rstb"],
+            Direction::Input,
+        );
 
+        // Each bank has its own data ports, which are connected to the pins
+        // through the output mux of the enclosing SRAM.
+        let sections = self.params.sections();
+        let section_data_width = self.params.section_data_width();
+        let section_wmask_width = section_data_width / self.params.wmask_granularity();
         let addr = ctx.bus_port("addr", self.params.addr_width(), Direction::Input);
-        let wmask = ctx.bus_port("wmask", self.params.wmask_width(), Direction::Input);
-        let din = ctx.bus_port("din", self.params.data_width(), Direction::Input);
-        let dout = ctx.bus_port("dout", self.params.data_width(), Direction::Output);
+        let wmask = ctx.bus_port("wmask", sections * section_wmask_width, Direction::Input);
+        let din = ctx.bus_port("din", sections * section_data_width, Direction::Input);
+        let dout = ctx.bus_port("dout", sections * section_data_width, Direction::Output);
+        let trim = self.params.timing_trim().map(|bits| {
+            [
+                ctx.bus_port("trim_saen", bits, Direction::Input),
+                ctx.bus_port("trim_wl", bits, Direction::Input),
+            ]
+        });
 
-        let [addr_in, addr_in_b] = ctx.buses(["addr_in", "addr_in_b"], self.params.addr_width());
+        let section_cols = self.params.section_cols();
+        let bl = ctx.bus("bl", sections * section_cols);
+        let br = ctx.bus("br", sections * section_cols);
+        let wl = ctx.bus("wl", self.params.rows());
+        let bank_en = (self.params.banks() > 1).then(|| ctx.bus("bank_en", self.params.banks()));
 
-        let [addr_gated, addr_b_gated] =
-            ctx.buses(["addr_gated", "addr_b_gated"], self.params.row_bits());
-        let addr_gate_y_b_noconn = ctx.bus("addr_gate_y_b_noconn", 2 * self.params.row_bits());
+        // With power gating, `vdd` supplies only the periphery,
+        // and the final wordline drivers clamp the wordlines low while they are off.
+        let (vdd_array, sleep) = if self.params.power_gating() {
+            (
+                ctx.port("vdd_array", Direction::InOut),
+                Some(ctx.port("sleep", Direction::Input)),
+            )
+        } else {
+            (vdd, None)
+        };
 
-        let bl = ctx.bus("bl", self.params.cols());
-        let br = ctx.bus("br", self.params.cols());
-        let wl = ctx.bus("wl", self.params.rows());
-        let wl_b = ctx.bus("wl_b", self.params.rows());
-
-        let col_sel = ctx.bus("col_sel", self.params.mux_ratio());
-        let col_sel_b = ctx.bus("col_sel_b", self.params.mux_ratio());
-
-        let [we_in, we_in_b, ce_in, ce_in_b, rwl, rbl, rbr, pc_b0, pc, pc_b, wl_en0, wl_en_b, wl_en, write_driver_en0, write_driver_en_b, write_driver_en, sense_en0, sense_en_b, sense_en] =
-            ctx.signals([
-                "we_in",
-                "we_in_b",
-                "ce_in",
-                "ce_in_b",
-                "rwl",
-                "rbl",
-                "rbr",
-                "pc_b0",
-                "pc",
-                "pc_b",
-                "wl_en0",
-                "wl_en_b",
-                "wl_en",
-                "write_driver_en0",
-                "write_driver_en_b",
-                "write_driver_en",
-                "sense_en0",
-                "sense_en_b",
-                "sense_en",
-            ]);
-
-        ctx.instantiate::<DecoderStage>(&dsn.addr_gate)?
-            .with_connections([
-                ("vdd", vdd),
-                ("vss", vss),
-                ("wl_en", wl_en),
-                ("y_b", addr_gate_y_b_noconn),
-            ])
-            .with_connections([
-                (
-                    "in",
-                    Signal::new(vec![
-                        addr_in.index(self.params.col_select_bits()..),
-                        addr_in_b.index(self.params.col_select_bits()..),
-                    ]),
-                ),
-                ("y", Signal::new(vec![addr_gated, addr_b_gated])),
-            ])
-            .named("addr_gate")
-            .add_to(ctx);
+        // Each half of a split array holds a contiguous half of the columns,
+        // and so a contiguous half of the data bits and write mask bits.
+        // Each bank holds all data bits of its words.
+        let columns = (0..sections)
+            .map(|i| {
+                let cols = section_cols;
+                let data_bits = section_data_width;
+                let wmask_bits = section_wmask_width;
+                ColumnSignals {
+                    bl: bl.index(i * cols..(i + 1) * cols),
+                    br: br.index(i * cols..(i + 1) * cols),
+                    wmask: wmask.index(i * wmask_bits..(i + 1) * wmask_bits).into(),
+                    din: din.index(i * data_bits..(i + 1) * data_bits).into(),
+                    dout: dout.index(i * data_bits..(i + 1) * data_bits).into(),
+                }
+            })
+            .collect::<Vec<_>>();
 
-        let mut row_decoder = ctx
-            .instantiate::<Decoder>(&dsn.row_decoder)?
-            .with_connections([("vdd", vdd), ("vss", vss), ("y", wl), ("y_b", wl_b)])
-            .named("decoder");
-        for i in 0..self.params.row_bits() {
-            for j in 0..2 {
-                row_decoder.connect(
-                    format!("predecode_{i}_{j}"),
-                    if j == 0 {
-                        addr_b_gated.index(i)
-                    } else {
-                        addr_gated.index(i)
-                    },
-                );
-            }
+        let read = port_periphery(
+            ctx,
+            &self.params,
+            &dsn,
+            "",
+            PeripherySignals {
+                vdd,
+                vss,
+                clk,
+                we,
+                ce,
+                rstb,
+                addr,
+                wl,
+                columns: columns.clone(),
+                trim,
+                sleep,
+                bank_en,
+                complement_addr: false,
+            },
+        )?;
+
+        if sections == 1 {
+            ctx.instantiate::<SpCellArray>(&dsn.bitcells)?
+                .with_connections([
+                    ("vdd", vdd_array),
+                    ("vss", vss),
+                    ("dummy_bl", vdd_array),
+                    ("dummy_br", vdd_array),
+                    ("bl", bl),
+                    ("br", br),
+                    ("wl", wl),
+                ])
+                .named("bitcell_array")
+                .add_to(ctx);
+            return Ok(());
         }
-        ctx.add_instance(row_decoder);
 
-        let mut col_decoder = ctx
-            .instantiate::<Decoder>(&dsn.col_decoder)?
-            .with_connections([
-                ("vdd", vdd),
-                ("vss", vss),
-                ("y", col_sel),
-                ("y_b", col_sel_b),
-            ])
-            .named("column_decoder");
-        for i in 0..self.params.col_select_bits() {
-            for j in 0..2 {
-                col_decoder.connect(
-                    format!("predecode_{i}_{j}"),
-                    if j == 0 {
-                        addr_in_b.index(i)
-                    } else {
-                        addr_in.index(i)
-                    },
-                );
+        // `wl` carries the global wordlines from the row decoder to the drivers of each array.
+        // The drivers of a split array are always enabled, while those of a bank, or of a half
+        // of a divided-wordline array, are enabled by the bank select decoder.
+        for (i, columns) in columns.into_iter().enumerate() {
+            let name = section_name(&self.params, i);
+            let [local_wl, local_wl_b] = ctx.buses(
+                [arcstr::format!("wl_{name}"), arcstr::format!("wl_b_{name}")],
+                self.params.rows(),
+            );
+            let wl_driver = dsn.wl_driver.as_ref().unwrap();
+            let wl_en = match bank_en {
+                Some(bank_en) => bank_en.index(i),
+                None => vdd,
+            };
+            let mut inst = ctx
+                .instantiate::<DecoderStage>(wl_driver)?
+                .with_connections([
+                    ("vdd", vdd),
+                    ("vss", vss),
+                    ("wl_en", wl_en),
+                    ("in", wl),
+                    ("y", local_wl),
+                    ("y_b", local_wl_b),
+                ])
+                .named(format!("wl_driver_{name}"));
+            if wl_driver.suppression.is_some() {
+                inst.connect("suppress", read);
+            }
+            if let Some(sleep) = sleep {
+                inst.connect("clamp", sleep);
             }
+            ctx.add_instance(inst);
+
+            ctx.instantiate::<SpCellArray>(&dsn.bitcells)?
+                .with_connections([
+                    ("vdd", vdd_array),
+                    ("vss", vss),
+                    ("dummy_bl", vdd_array),
+                    ("dummy_br", vdd_array),
+                    ("bl", columns.bl),
+                    ("br", columns.br),
+                    ("wl", local_wl),
+                ])
+                .named(format!("bitcell_array_{name}"))
+                .add_to(ctx);
         }
-        ctx.add_instance(col_decoder);
 
-        let control_logic = ctx
-            .instantiate::<ControlLogicReplicaV2>(&dsn.control)?
-            .with_connections([
-                ("clk", clk),
-                ("we", we_in),
-                ("ce", ce_in),
-                ("rstb", rstb),
-                ("rbl", rbl),
-                ("rwl", rwl),
-                ("pc_b", pc_b0),
-                ("wlen", wl_en0),
-                ("wrdrven", write_driver_en0),
-                ("saen", sense_en0),
-                ("vdd", vdd),
-                ("vss", vss),
-            ])
-            .named("control_logic");
-        control_logic.add_to(ctx);
+        Ok(())
+    }
+}
 
-        ctx.instantiate::<DecoderStage>(&dsn.pc_b_buffer)?
-            .with_connections([
-                ("vdd", vdd),
-                ("vss", vss),
-                ("y", pc_b),
-                ("y_b", pc),
-                ("predecode_0_0", pc_b0),
-            ])
-            .named("pc_b_buffer")
-            .add_to(ctx);
+/// The name of array `i` of a split or banked SRAM, from left to right.
+pub(crate) fn section_name(params: &SramParams, i: usize) -> String {
+    if params.central_decoder() {
+        ["left", "right"][i].to_string()
+    } else {
+        format!("bank_{i}")
+    }
+}
 
-        ctx.instantiate::<DecoderStage>(&dsn.wlen_buffer)?
-            .with_connections([
-                ("vdd", vdd),
-                ("vss", vss),
-                ("y", wl_en),
-                ("y_b", wl_en_b),
-                ("predecode_0_0", wl_en0),
-            ])
-            .named("wlen_buffer")
-            .add_to(ctx);
+/// Signals connected to the periphery of one SRAM port.
+pub(crate) struct PeripherySignals {
+    pub(crate) vdd: Slice,
+    pub(crate) vss: Slice,
+    pub(crate) clk: Slice,
+    pub(crate) we: Slice,
+    pub(crate) ce: Slice,
+    pub(crate) rstb: Slice,
+    pub(crate) addr: Slice,
+    pub(crate) wl: Slice,
+    /// The signals of each column circuitry instance, from left to right.
+    pub(crate) columns: Vec<ColumnSignals>,
+    /// The `trim_saen` and `trim_wl` inputs of the control logic, if it has timing trim.
+    pub(crate) trim: Option<[Slice; 2]>,
+    /// The `sleep` input, if the row decoder clamps the wordlines.
+    pub(crate) sleep: Option<Slice>,
+    /// The outputs of the bank select decoder, if the array is divided into banks.
+    pub(crate) bank_en: Option<Slice>,
+    /// Whether the address is complemented after it is registered,
+    /// for a port that sees the rows and columns of its array in reverse.
+    pub(crate) complement_addr: bool,
+}
 
-        ctx.instantiate::<DecoderStage>(&dsn.write_driver_en_buffer)?
-            .with_connections([
-                ("vdd", vdd),
-                ("vss", vss),
-                ("y", write_driver_en),
-                ("y_b", write_driver_en_b),
-                ("predecode_0_0", write_driver_en0),
-            ])
-            .named("write_driver_en_buffer")
-            .add_to(ctx);
-        ctx.instantiate::<DecoderStage>(&dsn.sense_en_buffer)?
-            .with_connections([
-                ("vdd", vdd),
-                ("vss", vss),
-                ("y", sense_en),
-                ("y_b", sense_en_b),
-                ("predecode_0_0", sense_en0),
-            ])
-            .named("sense_en_buffer")
-            .add_to(ctx);
+/// Signals connected to one column circuitry instance.
+#[derive(Clone)]
+pub(crate) struct ColumnSignals {
+    pub(crate) bl: Slice,
+    pub(crate) br: Slice,
+    pub(crate) wmask: Signal,
+    pub(crate) din: Signal,
+    pub(crate) dout: Signal,
+}
 
-        ctx.instantiate::<DffArray>(&dsn.num_dffs)?
-            .with_connections([("vdd", vdd), ("vss", vss), ("clk", clk), ("rb", rstb)])
-            .with_connection("d", Signal::new(vec![addr, we, ce]))
-            .with_connection("q", Signal::new(vec![addr_in, we_in, ce_in]))
-            .with_connection("qn", Signal::new(vec![addr_in_b, we_in_b, ce_in_b]))
-            .named("addr_we_ce_dffs")
-            .add_to(ctx);
+/// Instantiates everything needed to access a bitcell array through one port:
+/// input registers, decoders, control logic, replica column and column circuitry.
+///
+/// Internal signal and instance names are prefixed with `prefix`.
+/// Returns a registered signal that is high during reads.
+pub(crate) fn port_periphery(
+    ctx: &mut SchematicCtx,
+    params: &SramParams,
+    dsn: &SramPhysicalDesign,
+    prefix: &str,
+    signals: PeripherySignals,
+) -> Result<Slice> {
+    let PeripherySignals {
+        vdd,
+        vss,
+        clk,
+        we,
+        ce,
+        rstb,
+        addr,
+        wl,
+        columns,
+        trim,
+        sleep,
+        bank_en,
+        complement_addr,
+    } = signals;
+    let [addr_in, addr_in_b] = ctx.buses(
+        [
+            arcstr::format!("{prefix}addr_in"),
+            arcstr::format!("{prefix}addr_in_b"),
+        ],
+        params.addr_width(),
+    );
 
-        ctx.instantiate::<SpCellArray>(&dsn.bitcells)?
-            .with_connections([
-                ("vdd", vdd),
-                ("vss", vss),
-                ("dummy_bl", vdd),
-                ("dummy_br", vdd),
-                ("bl", bl),
-                ("br", br),
-                ("wl", wl),
-            ])
-            .named("bitcell_array")
-            .add_to(ctx);
+    let [addr_gated, addr_b_gated] = ctx.buses(
+        [
+            arcstr::format!("{prefix}addr_gated"),
+            arcstr::format!("{prefix}addr_b_gated"),
+        ],
+        params.row_bits(),
+    );
+    let addr_gate_y_b_noconn = ctx.bus(
+        arcstr::format!("{prefix}addr_gate_y_b_noconn"),
+        2 * params.row_bits(),
+    );
+
+    let wl_b = ctx.bus(arcstr::format!("{prefix}wl_b"), params.rows());
+
+    let col_sel = ctx.bus(arcstr::format!("{prefix}col_sel"), params.mux_ratio());
+    let col_sel_b = ctx.bus(arcstr::format!("{prefix}col_sel_b"), params.mux_ratio());
+
+    let [we_in, we_in_b, ce_in, ce_in_b, rwl, rbl, rbr, pc_b0, pc, pc_b, wl_en0, wl_en_b, wl_en, write_driver_en0, write_driver_en_b, write_driver_en, sense_en0, sense_en_b, sense_en] =
+        ctx.signals([
+            arcstr::format!("{prefix}we_in"),
+            arcstr::format!("{prefix}we_in_b"),
+            arcstr::format!("{prefix}ce_in"),
+            arcstr::format!("{prefix}ce_in_b"),
+            arcstr::format!("{prefix}rwl"),
+            arcstr::format!("{prefix}rbl"),
+            arcstr::format!("{prefix}rbr"),
+            arcstr::format!("{prefix}pc_b0"),
+            arcstr::format!("{prefix}pc"),
+            arcstr::format!("{prefix}pc_b"),
+            arcstr::format!("{prefix}wl_en0"),
+            arcstr::format!("{prefix}wl_en_b"),
+            arcstr::format!("{prefix}wl_en"),
+            arcstr::format!("{prefix}write_driver_en0"),
+            arcstr::format!("{prefix}write_driver_en_b"),
+            arcstr::format!("{prefix}write_driver_en"),
+            arcstr::format!("{prefix}sense_en0"),
+            arcstr::format!("{prefix}sense_en_b"),
+            arcstr::format!("{prefix}sense_en"),
+        ]);
+
+    // The row address lies between the column select bits and any bank select bits.
+    let row_addr = params.col_select_bits()..params.col_select_bits() + params.row_bits();
+    ctx.instantiate::<DecoderStage>(&dsn.addr_gate)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("wl_en", wl_en),
+            ("y_b", addr_gate_y_b_noconn),
+        ])
+        .with_connections([
+            (
+                "in",
+                Signal::new(vec![
+                    addr_in.index(row_addr.clone()),
+                    addr_in_b.index(row_addr.clone()),
+                ]),
+            ),
+            ("y", Signal::new(vec![addr_gated, addr_b_gated])),
+        ])
+        .named(format!("{prefix}addr_gate"))
+        .add_to(ctx);
+
+    let read = if params.port_style().active_low() {
+        we_in
+    } else {
+        we_in_b
+    };
+
+    let mut row_decoder = ctx
+        .instantiate::<Decoder>(&dsn.row_decoder)?
+        .with_connections([("vdd", vdd), ("vss", vss), ("y", wl), ("y_b", wl_b)])
+        .named(format!("{prefix}decoder"));
+    if dsn.row_decoder.suppression.is_some() {
+        row_decoder.connect("suppress", read);
+    }
+    if let (Some(_), Some(sleep)) = (dsn.row_decoder.clamp, sleep) {
+        row_decoder.connect("clamp", sleep);
+    }
+    for i in 0..params.row_bits() {
+        for j in 0..2 {
+            row_decoder.connect(
+                format!("predecode_{i}_{j}"),
+                if j == 0 {
+                    addr_b_gated.index(i)
+                } else {
+                    addr_gated.index(i)
+                },
+            );
+        }
+    }
+    ctx.add_instance(row_decoder);
+
+    let mut col_decoder = ctx
+        .instantiate::<Decoder>(&dsn.col_decoder)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("y", col_sel),
+            ("y_b", col_sel_b),
+        ])
+        .named(format!("{prefix}column_decoder"));
+    for i in 0..params.col_select_bits() {
+        for j in 0..2 {
+            col_decoder.connect(
+                format!("predecode_{i}_{j}"),
+                if j == 0 {
+                    addr_in_b.index(i)
+                } else {
+                    addr_in.index(i)
+                },
+            );
+        }
+    }
+    ctx.add_instance(col_decoder);
+
+    let mut control_logic = ctx
+        .instantiate::<ControlLogicReplicaV2>(&dsn.control)?
+        .with_connections([
+            ("clk", clk),
+            (params.port_style().write_pin(), we_in),
+            (params.port_style().enable_pin(), ce_in),
+            ("rstb", rstb),
+            ("rbl", rbl),
+            ("rwl", rwl),
+            ("pc_b", pc_b0),
+            ("wlen", wl_en0),
+            ("wrdrven", write_driver_en0),
+            ("saen", sense_en0),
+            ("vdd", vdd),
+            ("vss", vss),
+        ])
+        .named(format!("{prefix}control_logic"));
+    if let Some([trim_saen, trim_wl]) = trim {
+        control_logic.connect_all([("trim_saen", &trim_saen), ("trim_wl", &trim_wl)]);
+    }
+    control_logic.add_to(ctx);
 
-        ctx.instantiate::<ReplicaCellArray>(&dsn.rbl)?
+    ctx.instantiate::<DecoderStage>(&dsn.pc_b_buffer)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("y", pc_b),
+            ("y_b", pc),
+            ("predecode_0_0", pc_b0),
+        ])
+        .named(format!("{prefix}pc_b_buffer"))
+        .add_to(ctx);
+
+    ctx.instantiate::<DecoderStage>(&dsn.wlen_buffer)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("y", wl_en),
+            ("y_b", wl_en_b),
+            ("predecode_0_0", wl_en0),
+        ])
+        .named(format!("{prefix}wlen_buffer"))
+        .add_to(ctx);
+
+    ctx.instantiate::<DecoderStage>(&dsn.write_driver_en_buffer)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("y", write_driver_en),
+            ("y_b", write_driver_en_b),
+            ("predecode_0_0", write_driver_en0),
+        ])
+        .named(format!("{prefix}write_driver_en_buffer"))
+        .add_to(ctx);
+    ctx.instantiate::<DecoderStage>(&dsn.sense_en_buffer)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("y", sense_en),
+            ("y_b", sense_en_b),
+            ("predecode_0_0", sense_en0),
+        ])
+        .named(format!("{prefix}sense_en_buffer"))
+        .add_to(ctx);
+
+    if let (Some(bank_select), Some(bank_en)) = (&dsn.bank_select, bank_en) {
+        let bank_en_b = ctx.bus(arcstr::format!("{prefix}bank_en_b"), params.banks());
+        let mut inst = ctx
+            .instantiate::<DecoderStage>(bank_select)?
             .with_connections([
                 ("vdd", vdd),
                 ("vss", vss),
-                ("rbl", rbl),
-                ("rbr", rbr),
-                ("rwl", rwl),
+                ("y", bank_en),
+                ("y_b", bank_en_b),
             ])
-            .named("replica_bitcell_array")
-            .add_to(ctx);
+            .named(format!("{prefix}bank_select"));
+        for i in 0..params.bank_select_bits() {
+            let bit = row_addr.end + i;
+            inst.connect(format!("predecode_{i}_0"), addr_in_b.index(bit));
+            inst.connect(format!("predecode_{i}_1"), addr_in.index(bit));
+        }
+        ctx.add_instance(inst);
+    }
 
+    let (addr_q, addr_qn) = if complement_addr {
+        (addr_in_b, addr_in)
+    } else {
+        (addr_in, addr_in_b)
+    };
+    ctx.instantiate::<DffArray>(&dsn.num_dffs)?
+        .with_connections([("vdd", vdd), ("vss", vss), ("clk", clk), ("rb", rstb)])
+        .with_connection("d", Signal::new(vec![addr, we, ce]))
+        .with_connection("q", Signal::new(vec![addr_q, we_in, ce_in]))
+        .with_connection("qn", Signal::new(vec![addr_qn, we_in_b, ce_in_b]))
+        .named(format!("{prefix}addr_we_ce_dffs"))
+        .add_to(ctx);
+
+    ctx.instantiate::<ReplicaCellArray>(&dsn.rbl)?
+        .with_connections([
+            ("vdd", vdd),
+            ("vss", vss),
+            ("rbl", rbl),
+            ("rbr", rbr),
+            ("rwl", rwl),
+        ])
+        .named(format!("{prefix}replica_bitcell_array"))
+        .add_to(ctx);
+
+    // The replica wordline is underdriven like the wordlines,
+    // so that the replica bitline tracks the reduced read current.
+    if let Some(suppression) = dsn.rwl_suppression {
+        let nmos_id = ctx
+            .mos_db()
+            .query(Query::builder().kind(MosKind::Nmos).build().unwrap())?
+            .id();
+        for i in 0..suppression.nf {
+            let mut mn = ctx.instantiate::<SchematicMos>(&MosParams {
+                w: suppression.width,
+                l: suppression.length,
+                m: 1,
+                nf: 1,
+                id: nmos_id,
+            })?;
+            mn.connect_all([("d", &rwl), ("g", &read), ("s", &vss), ("b", &vss)]);
+            mn.set_name(format!("{prefix}rwl_suppress_{i}"));
+            ctx.add_instance(mn);
+        }
+    }
+
+    let num_columns = columns.len();
+    for (i, columns) in columns.into_iter().enumerate() {
+        let ColumnSignals {
+            bl,
+            br,
+            wmask,
+            din,
+            dout,
+        } = columns;
+        let name = if num_columns > 1 {
+            format!("{prefix}col_circuitry_{i}")
+        } else {
+            format!("{prefix}col_circuitry")
+        };
         ctx.instantiate::<ColPeripherals>(&dsn.col_params)?
             .with_connections([
                 ("clk", clk),
@@ -236,27 +522,25 @@ impl SramInner {
                 ("sel", col_sel),
                 ("sel_b", col_sel_b),
                 ("we", write_driver_en),
-                ("wmask", wmask),
-                ("din", din),
-                ("dout", dout),
                 ("sense_en", sense_en),
             ])
-            .named("col_circuitry")
+            .with_connections([("wmask", wmask), ("din", din), ("dout", dout)])
+            .named(name)
             .add_to(ctx);
+    }
 
-        for i in 0..dsn.replica_pc.cols {
-            ctx.instantiate::<Precharge>(&dsn.replica_pc.inner)?
-                .with_connections([("vdd", vdd), ("bl", rbl), ("br", rbr), ("en_b", pc_b0)])
-                .named(format!("replica_precharge_{i}"))
-                .add_to(ctx);
-        }
-        ctx.instantiate::<ReplicaColumnMos>(&dsn.replica_nmos)?
-            .with_connections([("vdd", vdd), ("vss", vss), ("bl", rbl)])
-            .named("replica_mos")
+    for i in 0..dsn.replica_pc.cols {
+        ctx.instantiate::<Precharge>(&dsn.replica_pc.inner)?
+            .with_connections([("vdd", vdd), ("bl", rbl), ("br", rbr), ("en_b", pc_b0)])
+            .named(format!("{prefix}replica_precharge_{i}"))
             .add_to(ctx);
-
-        Ok(())
     }
+    ctx.instantiate::<ReplicaColumnMos>(&dsn.replica_nmos)?
+        .with_connections([("vdd", vdd), ("vss", vss), ("bl", rbl)])
+        .named(format!("{prefix}replica_mos"))
+        .add_to(ctx);
+
+    Ok(read)
 }
 
 pub(crate) fn buffer_chain_num_stages(cl: f64) -> usize {
@@ -300,6 +584,8 @@ pub fn fanout_buffer_stage(pd: DecoderPhysicalDesignParams, cl: f64) -> DecoderS
         use_multi_finger_invs: true,
         dont_connect_outputs: false,
         child_sizes: vec![1],
+        suppression: None,
+        clamp: None,
     }
 }
 
@@ -323,6 +609,8 @@ pub fn fanout_buffer_stage_with_inverted_output(
         use_multi_finger_invs: true,
         dont_connect_outputs: false,
         child_sizes: vec![1],
+        suppression: None,
+        clamp: None,
     }
 }
 
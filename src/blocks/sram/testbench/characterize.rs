@@ -0,0 +1,949 @@
+//! Liberty characterization of SRAMs using transient simulations of [`SramTestbench`].
+//!
+//! Characterization runs with whichever simulator the [`SubstrateCtx`] is configured with,
+//! so it is available in open-source builds through ngspice.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail};
+use arcstr::ArcStr;
+use derive_builder::Builder;
+use substrate::data::SubstrateCtx;
+use substrate::verification::simulation::bits::BitSignal;
+use substrate::verification::simulation::waveform::TimeWaveform;
+use substrate::verification::simulation::TranData;
+
+use super::verify::verify_reads;
+use super::{
+    bits0101, bits1010, Op, SramTestbench, TbInput, TbParams, TbSignals, SUPPLY_NODE,
+    SUPPLY_SENSE_RES,
+};
+use crate::blocks::sram::{OutputMode, PortStyle, Sram, SramParams, SramPhysicalDesign, WriteMode};
+use crate::liberty::{
+    Bus, Cell, Direction, InternalPower, Library, Pin, TableKind, TableTemplate, Timing,
+    TimingSense, TimingType,
+};
+use crate::measure::cap::{self, CapTestbench, TbNode};
+use crate::Result;
+
+/// Current used to charge pins when measuring pin capacitance, in nano amperes.
+const PIN_CAP_IDC: i64 = 20;
+
+/// The temperature at which a process corner is characterized, in degrees Celsius.
+///
+/// Slow corners are characterized hot and fast corners cold,
+/// matching the corners of the commercial flow.
+pub fn corner_temp(corner: &str) -> f64 {
+    match corner {
+        "ss" => 100.,
+        "ff" => -40.,
+        _ => 25.,
+    }
+}
+
+#[derive(Debug, Clone, Builder)]
+#[builder(derive(Debug))]
+pub struct CharacterizeParams {
+    /// Directory in which to run simulations.
+    pub work_dir: PathBuf,
+    /// SRAM configuration to characterize.
+    pub sram: SramParams,
+    pub dsn: Arc<SramPhysicalDesign>,
+    /// Name of the process corner to simulate.
+    #[builder(setter(into))]
+    pub corner: ArcStr,
+    /// Supply voltage.
+    pub vdd: f64,
+    /// Temperature in degrees Celsius.
+    ///
+    /// Defaults to the [`corner_temp`] of `corner`.
+    #[builder(default = "corner_temp(self.corner.as_deref().unwrap_or_default())")]
+    pub temp: f64,
+    /// Area of the SRAM in square microns.
+    #[builder(default)]
+    pub area: f64,
+    /// Clock period in seconds.
+    #[builder(default = "20e-9")]
+    pub clk_period: f64,
+    /// Transition times of the clock and inputs in seconds.
+    #[builder(default = "crate::liberty::DEFAULT_SLEWS.to_vec()")]
+    pub slews: Vec<f64>,
+    /// Capacitive loads on the outputs in farads.
+    #[builder(default = "crate::liberty::DEFAULT_LOADS.to_vec()")]
+    pub loads: Vec<f64>,
+    /// Resolution of setup and hold time measurements in seconds.
+    #[builder(default = "20e-12")]
+    pub resolution: f64,
+}
+
+impl CharacterizeParams {
+    #[inline]
+    pub fn builder() -> CharacterizeParamsBuilder {
+        CharacterizeParamsBuilder::default()
+    }
+
+    /// The name of the Liberty library for these parameters.
+    pub fn lib_name(&self) -> String {
+        let vdd = format!("{:.2}", self.vdd).replace('.', "v");
+        let temp = self.temp.round() as i64;
+        let temp = if temp < 0 {
+            format!("n{:02}C", -temp)
+        } else {
+            format!("{temp:03}C")
+        };
+        format!("{}_{}_{temp}_{vdd}", self.sram.name(), self.corner)
+    }
+
+    /// The SPICE file that sets the simulation temperature, written by [`characterize`].
+    fn temp_include(&self) -> PathBuf {
+        self.work_dir.join("temp.spice")
+    }
+
+    /// The inputs whose setup and hold times are characterized.
+    pub(crate) fn constrained_inputs(&self) -> Vec<TbInput> {
+        let mut inputs = vec![TbInput::Ce, TbInput::We, TbInput::Addr, TbInput::Din];
+        if self.sram.wmask_width() > 1 {
+            inputs.push(TbInput::Wmask);
+        }
+        inputs
+    }
+}
+
+/// Clock to output delay and output transition tables.
+///
+/// Each table has one row per input slew and one column per output load.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DelayTables {
+    pub cell_rise: Vec<Vec<f64>>,
+    pub cell_fall: Vec<Vec<f64>>,
+    pub rise_transition: Vec<Vec<f64>>,
+    pub fall_transition: Vec<Vec<f64>>,
+}
+
+/// Setup and hold times of an input relative to the rising edge of the clock.
+///
+/// Each vector has one entry per input slew.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Constraint {
+    pub setup: Vec<f64>,
+    pub hold: Vec<f64>,
+}
+
+/// Energy drawn from `vdd` by each kind of clock cycle, excluding leakage.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct CycleEnergy {
+    /// Energy of a read in joules.
+    pub read: f64,
+    /// Energy of a write in joules.
+    pub write: f64,
+    /// Energy of a cycle without an access in joules.
+    pub idle: f64,
+}
+
+/// The measured power of an SRAM.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct SramPower {
+    pub energy: CycleEnergy,
+    /// Leakage power in watts.
+    pub leakage: f64,
+}
+
+/// The measured timing of an SRAM.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SramTiming {
+    pub clk_to_dout: DelayTables,
+    pub constraints: HashMap<TbInput, Constraint>,
+    /// Capacitance of each bit of each input pin in farads.
+    ///
+    /// Buses are keyed by the name of the bus. Single pins have one entry.
+    pub pin_caps: HashMap<String, Vec<f64>>,
+    /// Minimum clock period in seconds, if known.
+    pub min_period: Option<f64>,
+    /// Power of the SRAM, if known.
+    pub power: Option<SramPower>,
+}
+
+impl TbInput {
+    /// The name of the input's pin on an SRAM with the given port style.
+    pub(crate) fn pin_name(&self, port_style: PortStyle) -> &'static str {
+        match self {
+            Self::Ce => port_style.enable_pin(),
+            Self::We => port_style.write_pin(),
+            Self::Addr => "addr",
+            Self::Wmask => "wmask",
+            Self::Din => "din",
+        }
+    }
+}
+
+fn tb_params(
+    params: &CharacterizeParams,
+    ops: Vec<Op>,
+    slew: f64,
+    c_load: f64,
+    t_hold_overrides: HashMap<TbInput, f64>,
+) -> TbParams {
+    let mut tb = TbParams::builder();
+    let tb = tb
+        .ops(ops)
+        .clk_period(params.clk_period)
+        .tr(slew)
+        .tf(slew)
+        .vdd(params.vdd)
+        .c_load(c_load)
+        .t_hold(params.clk_period / 4.0)
+        .t_hold_overrides(t_hold_overrides)
+        .sram(params.sram)
+        .dsn(params.dsn.clone())
+        .includes(vec![params.temp_include()]);
+
+    #[cfg(feature = "commercial")]
+    let tb = tb.pex_netlist(None);
+
+    tb.build().unwrap()
+}
+
+fn simulate(
+    ctx: &SubstrateCtx,
+    params: &CharacterizeParams,
+    tb: &TbParams,
+    name: &str,
+) -> Result<TranData> {
+    let corner = ctx
+        .corner_db()
+        .corner_named(&params.corner)
+        .ok_or_else(|| anyhow!("Unknown corner `{}`", params.corner))?
+        .clone();
+    Ok(ctx.write_simulation_with_corner::<SramTestbench>(tb, params.work_dir.join(name), corner)?)
+}
+
+/// The time of the rising clock edge that captures operation `k` of a [`TbParams`].
+///
+/// The inputs of operation `k` are driven after the falling clock edge
+/// that ends cycle `k`, and captured half a cycle later.
+fn capture_edge(k: usize, period: f64) -> f64 {
+    (k as f64 + 1.5) * period
+}
+
+/// Operations that make every bit of `dout` rise and fall.
+///
+/// The last [`toggling_ops`] operations toggle every output bit.
+fn delay_ops(sram: &SramParams) -> Vec<Op> {
+    let addr0 = BitSignal::zeros(sram.addr_width());
+    let addr1 = BitSignal::from_u64(sram.num_words() as u64 - 1, sram.addr_width());
+    let data0 = BitSignal::from_vec(bits0101(sram.data_width()));
+    let data1 = BitSignal::from_vec(bits1010(sram.data_width()));
+    let mut ops = vec![
+        Op::Reset,
+        Op::Write {
+            addr: addr0.clone(),
+            data: data0.clone(),
+        },
+        Op::Write {
+            addr: addr1.clone(),
+            data: data1.clone(),
+        },
+        Op::Read {
+            addr: addr0.clone(),
+        },
+        Op::Read {
+            addr: addr1.clone(),
+        },
+        Op::Read {
+            addr: addr0.clone(),
+        },
+    ];
+    if sram.write_mode() == WriteMode::WriteThrough {
+        // Writes also drive `dout`, so they are measured as well.
+        ops.push(Op::Write {
+            addr: addr1,
+            data: data1,
+        });
+        ops.push(Op::Write {
+            addr: addr0,
+            data: data0,
+        });
+    }
+    ops
+}
+
+/// The number of operations at the end of [`delay_ops`] that toggle every bit of `dout`.
+fn toggling_ops(sram: &SramParams) -> usize {
+    match sram.write_mode() {
+        WriteMode::NoChange => 2,
+        WriteMode::WriteThrough => 4,
+    }
+}
+
+/// Operations in which every input toggles between consecutive operations
+/// in a way that changes the data subsequently read back.
+fn constraint_ops(sram: &SramParams) -> Vec<Op> {
+    let addr0 = BitSignal::zeros(sram.addr_width());
+    let addr1 = BitSignal::from_u64(sram.num_words() as u64 - 1, sram.addr_width());
+    let data0 = BitSignal::from_vec(bits0101(sram.data_width()));
+    let data1 = BitSignal::from_vec(bits1010(sram.data_width()));
+    let mut ops = vec![
+        Op::Reset,
+        Op::Write {
+            addr: addr0.clone(),
+            data: data0.clone(),
+        },
+        Op::Write {
+            addr: addr1.clone(),
+            data: data1.clone(),
+        },
+        Op::Read {
+            addr: addr0.clone(),
+        },
+        Op::Read { addr: addr1 },
+        Op::None,
+        Op::Write {
+            addr: addr0.clone(),
+            data: data1,
+        },
+    ];
+    if sram.wmask_width() > 1 {
+        ops.push(Op::WriteMasked {
+            addr: addr0.clone(),
+            data: data0,
+            mask: BitSignal::from_vec(
+                std::iter::once(true)
+                    .chain(std::iter::repeat(false).take(sram.wmask_width() - 1))
+                    .collect(),
+            ),
+        });
+    }
+    ops.push(Op::Read { addr: addr0 });
+    ops
+}
+
+/// Measures clock to output delays and transitions for one input slew and output load.
+///
+/// Returns the worst case `[cell_rise, cell_fall, rise_transition, fall_transition]`
+/// over all bits of `dout`.
+fn measure_delays(data: &TranData, tb: &TbParams) -> Result<[f64; 4]> {
+    let lo = 0.1 * tb.vdd;
+    let hi = 0.9 * tb.vdd;
+    let clk = data
+        .waveform(&tb.sram_signal_path(TbSignals::Clk))
+        .ok_or_else(|| anyhow!("Unable to find signal clk"))?
+        .transitions(lo, hi)
+        .filter(|t| t.dir().is_rising())
+        .collect::<Vec<_>>();
+
+    let mut worst = [0f64; 4];
+    for k in tb.ops.len() - toggling_ops(&tb.sram)..tb.ops.len() {
+        // A latched output is updated after the clock edge that captures operation `k`,
+        // and a registered output after the following edge.
+        let t_edge = capture_edge(k + tb.sram.read_latency() - 1, tb.clk_period);
+        let edge = clk
+            .iter()
+            .find(|t| (t.center_time() - t_edge).abs() < tb.clk_period / 4.0)
+            .ok_or_else(|| anyhow!("Unable to find clock edge at time {t_edge}"))?;
+        for i in 0..tb.sram.data_width() {
+            let trans = data
+                .waveform(&tb.sram_signal_path(TbSignals::Dout(i)))
+                .ok_or_else(|| anyhow!("Unable to find signal dout[{i}]"))?
+                .transitions(lo, hi)
+                .find(|t| t.center_time() > edge.center_time())
+                .filter(|t| t.center_time() < edge.center_time() + tb.clk_period)
+                .ok_or_else(|| {
+                    anyhow!("dout[{i}] did not toggle after clock edge at time {t_edge}")
+                })?;
+            let delay = trans.center_time() - edge.center_time();
+            let (delay_idx, transition_idx) = if trans.dir().is_rising() {
+                (0, 2)
+            } else {
+                (1, 3)
+            };
+            worst[delay_idx] = worst[delay_idx].max(delay);
+            worst[transition_idx] = worst[transition_idx].max(trans.duration());
+        }
+    }
+    Ok(worst)
+}
+
+fn characterize_delays(ctx: &SubstrateCtx, params: &CharacterizeParams) -> Result<DelayTables> {
+    let mut tables = DelayTables::default();
+    for (i, &slew) in params.slews.iter().enumerate() {
+        let mut rows = [(); 4].map(|_| Vec::with_capacity(params.loads.len()));
+        for (j, &c_load) in params.loads.iter().enumerate() {
+            let tb = tb_params(
+                params,
+                delay_ops(&params.sram),
+                slew,
+                c_load,
+                HashMap::new(),
+            );
+            let data = simulate(ctx, params, &tb, &format!("delay_{i}_{j}"))?;
+            verify_reads(&data, &tb)?;
+            for (row, value) in rows.iter_mut().zip(measure_delays(&data, &tb)?) {
+                row.push(value);
+            }
+        }
+        let [cell_rise, cell_fall, rise_transition, fall_transition] = rows;
+        tables.cell_rise.push(cell_rise);
+        tables.cell_fall.push(cell_fall);
+        tables.rise_transition.push(rise_transition);
+        tables.fall_transition.push(fall_transition);
+    }
+    Ok(tables)
+}
+
+/// Finds the boundary between hold times for which `passes` is `true` and `false`.
+///
+/// Requires `passes(pass)` to be `true` and `passes(fail)` to be `false`.
+/// Returns the passing hold time closest to the boundary.
+fn bisect(
+    mut pass: f64,
+    mut fail: f64,
+    resolution: f64,
+    mut passes: impl FnMut(f64) -> Result<bool>,
+) -> Result<f64> {
+    while (pass - fail).abs() > resolution {
+        let mid = (pass + fail) / 2.0;
+        if passes(mid)? {
+            pass = mid;
+        } else {
+            fail = mid;
+        }
+    }
+    Ok(pass)
+}
+
+fn characterize_constraint(
+    ctx: &SubstrateCtx,
+    params: &CharacterizeParams,
+    input: TbInput,
+) -> Result<Constraint> {
+    let period = params.clk_period;
+    let mut constraint = Constraint::default();
+    for (i, &slew) in params.slews.iter().enumerate() {
+        let mut run = 0;
+        let mut passes = |t_hold: f64| -> Result<bool> {
+            let tb = tb_params(
+                params,
+                constraint_ops(&params.sram),
+                slew,
+                params.loads[0],
+                HashMap::from_iter([(input, t_hold)]),
+            );
+            run += 1;
+            let name = format!("{}_{i}_{run}", input.pin_name(params.sram.port_style()));
+            let data = simulate(ctx, params, &tb, &name)?;
+            Ok(verify_reads(&data, &tb).is_ok())
+        };
+
+        // The input changes `t_hold` after the falling clock edge,
+        // so `period / 2 + t_hold` after the rising edge that captures its previous value
+        // and `period / 2 - t_hold` before the rising edge that captures its new value.
+        let nominal = period / 4.0;
+        if !passes(nominal)? {
+            bail!(
+                "SRAM failed with nominal timing on input `{}`",
+                input.pin_name(params.sram.port_style())
+            );
+        }
+        let hold =
+            period / 2.0 + bisect(nominal, -3.0 * period / 4.0, params.resolution, &mut passes)?;
+        let setup =
+            period / 2.0 - bisect(nominal, 3.0 * period / 4.0, params.resolution, &mut passes)?;
+        constraint.hold.push(hold);
+        constraint.setup.push(setup);
+    }
+    Ok(constraint)
+}
+
+fn characterize_pin_caps(
+    ctx: &SubstrateCtx,
+    params: &CharacterizeParams,
+) -> Result<HashMap<String, Vec<f64>>> {
+    let sram = &params.sram;
+    let corner = ctx
+        .corner_db()
+        .corner_named(&params.corner)
+        .ok_or_else(|| anyhow!("Unknown corner `{}`", params.corner))?
+        .clone();
+
+    let style = sram.port_style();
+    let mut pins = vec![
+        ("clk", 1),
+        (style.enable_pin(), 1),
+        (style.write_pin(), 1),
+        ("rstb", 1),
+    ];
+    pins.extend([("addr", sram.addr_width()), ("din", sram.data_width())]);
+    if sram.wmask_width() > 1 {
+        pins.push(("wmask", sram.wmask_width()));
+    }
+
+    let mut caps = HashMap::new();
+    for &(pin, width) in pins.iter() {
+        let mut bit_caps = Vec::with_capacity(width);
+        for i in 0..width {
+            let mut connections = HashMap::from_iter([
+                (arcstr::literal!("vdd"), vec![TbNode::Vdd]),
+                (arcstr::literal!("vss"), vec![TbNode::Vss]),
+                (arcstr::literal!("rstb"), vec![TbNode::Vdd]),
+                (
+                    arcstr::literal!("dout"),
+                    vec![TbNode::Floating; sram.data_width()],
+                ),
+                (
+                    arcstr::literal!("wmask"),
+                    vec![TbNode::Vss; sram.wmask_width()],
+                ),
+            ]);
+            for &(other, width) in pins.iter().filter(|(other, _)| *other != "rstb") {
+                connections.insert(ArcStr::from(other), vec![TbNode::Vss; width]);
+            }
+            connections.get_mut(pin).unwrap()[i] = TbNode::Vmeas;
+
+            let tb = cap::TbParams::<SramParams>::builder()
+                .idc(PIN_CAP_IDC)
+                .vdd(params.vdd)
+                .dut(*sram)
+                .pex_netlist(None)
+                .connections(connections)
+                .includes(vec![params.temp_include()])
+                .build()
+                .unwrap();
+            let cap = ctx.write_simulation_with_corner::<CapTestbench<Sram>>(
+                &tb,
+                params.work_dir.join(format!("cap_{pin}_{i}")),
+                corner.clone(),
+            )?;
+            bit_caps.push(cap.cnode);
+        }
+        caps.insert(pin.to_string(), bit_caps);
+    }
+    Ok(caps)
+}
+
+/// Operations whose supply energy is measured by [`characterize_power`].
+///
+/// Every write and read uses the same data so that `dout` does not toggle
+/// after the first access, keeping the energy of the output loads out of the measurement.
+fn power_ops(sram: &SramParams) -> Vec<Op> {
+    let addr0 = BitSignal::zeros(sram.addr_width());
+    let addr1 = BitSignal::from_u64(sram.num_words() as u64 - 1, sram.addr_width());
+    let data = BitSignal::from_vec(bits0101(sram.data_width()));
+    let mut ops = vec![Op::Reset];
+    for addr in [&addr0, &addr1, &addr0] {
+        ops.push(Op::Write {
+            addr: addr.clone(),
+            data: data.clone(),
+        });
+    }
+    for addr in [&addr1, &addr0, &addr1] {
+        ops.push(Op::Read { addr: addr.clone() });
+    }
+    ops.extend([Op::None, Op::None]);
+    ops
+}
+
+/// The energy drawn from the supply of a testbench with [`TbParams::measure_supply`] set
+/// between times `t0` and `t1`, in joules.
+fn supply_energy(data: &TranData, t0: f64, t1: f64) -> Result<f64> {
+    let signal = |name: &str| {
+        data.data
+            .get(name)
+            .ok_or_else(|| anyhow!("Unable to find signal {name}"))
+    };
+    let supply = &signal(SUPPLY_NODE)?.values;
+    let vdd = &signal("vdd")?.values;
+    let power = |i: usize| supply[i] * (supply[i] - vdd[i]) / SUPPLY_SENSE_RES;
+
+    let t = &data.time.values;
+    let mut energy = 0.;
+    for i in 1..t.len() {
+        if t[i - 1] >= t0 && t[i] <= t1 {
+            energy += 0.5 * (power(i - 1) + power(i)) * (t[i] - t[i - 1]);
+        }
+    }
+    Ok(energy)
+}
+
+/// Measures the energy of each kind of clock cycle and the leakage power
+/// at the smallest input slew and output load.
+fn characterize_power(ctx: &SubstrateCtx, params: &CharacterizeParams) -> Result<SramPower> {
+    let period = params.clk_period;
+    let mut tb = tb_params(
+        params,
+        power_ops(&params.sram),
+        params.slews[0],
+        params.loads[0],
+        HashMap::new(),
+    );
+    tb.measure_supply = true;
+    let data = simulate(ctx, params, &tb, "power")?;
+    verify_reads(&data, &tb)?;
+
+    // Leakage is measured during the high phase of the clock in the second idle cycle,
+    // once the clock edge has settled and before any input changes.
+    let idle = 8;
+    let t0 = capture_edge(idle, period) + period / 4.0;
+    let t1 = capture_edge(idle, period) + 7.0 * period / 16.0;
+    let leakage = supply_energy(&data, t0, t1)? / (t1 - t0);
+
+    let cycle_energy = |k: usize| -> Result<f64> {
+        let t = capture_edge(k, period);
+        Ok(supply_energy(&data, t, t + period)? - leakage * period)
+    };
+    Ok(SramPower {
+        energy: CycleEnergy {
+            // The first write and read may toggle `dout`, so only the later ones are used.
+            write: (cycle_energy(2)? + cycle_energy(3)?) / 2.0,
+            read: (cycle_energy(5)? + cycle_energy(6)?) / 2.0,
+            idle: cycle_energy(idle)?,
+        },
+        leakage,
+    })
+}
+
+/// Measures the timing and power of an SRAM.
+///
+/// Runs one simulation per input slew and output load to measure clock to output delays,
+/// a bisection per input slew and constrained input to measure setup and hold times,
+/// one simulation per input bit to measure pin capacitances,
+/// and one simulation with a sensed supply current to measure power.
+///
+/// Every simulation runs at the temperature [`CharacterizeParams::temp`].
+pub fn characterize(ctx: &SubstrateCtx, params: &CharacterizeParams) -> Result<SramTiming> {
+    std::fs::create_dir_all(&params.work_dir)?;
+    std::fs::write(params.temp_include(), format!(".temp {}\n", params.temp))?;
+
+    let clk_to_dout = characterize_delays(ctx, params)?;
+    let constraints = params
+        .constrained_inputs()
+        .into_iter()
+        .map(|input| Ok((input, characterize_constraint(ctx, params, input)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+    let pin_caps = characterize_pin_caps(ctx, params)?;
+    let power = characterize_power(ctx, params)?;
+
+    Ok(SramTiming {
+        clk_to_dout,
+        constraints,
+        pin_caps,
+        min_period: None,
+        power: Some(power),
+    })
+}
+
+/// A Liberty condition on the enable and write pins of `style`
+/// that selects a write if `we` is set, a read if only `ce` is set, and no access otherwise.
+fn access_condition(style: PortStyle, ce: bool, we: bool) -> String {
+    let level = |pin: &str, high: bool| {
+        if high {
+            pin.to_string()
+        } else {
+            format!("!{pin}")
+        }
+    };
+    let (enable, write) = (style.enable_pin(), style.write_pin());
+    match (style, ce, we) {
+        // A write is selected by the write enable alone.
+        (PortStyle::RenWen, true, true) => write.to_string(),
+        // No access is selected by the chip enable alone.
+        (PortStyle::CeWe | PortStyle::CsbWeb, false, _) => {
+            level(enable, style.pin_levels(false, false).0)
+        }
+        _ => {
+            let (enable_level, write_level) = style.pin_levels(ce, we);
+            format!(
+                "{} & {}",
+                level(enable, enable_level),
+                level(write, write_level)
+            )
+        }
+    }
+}
+
+/// Builds an NLDM Liberty library from the measured timing of an SRAM.
+pub fn sram_library(params: &CharacterizeParams, timing: &SramTiming) -> Library {
+    let sram = &params.sram;
+    let delay_template = TableTemplate::delay(&params.slews, &params.loads);
+    let constraint_template = TableTemplate::constraint(&params.slews);
+
+    let delay_table = |values: &Vec<Vec<f64>>| delay_template.table(values.clone());
+    let constraint_table = |values: &Vec<f64>| constraint_template.table(vec![values.clone()]);
+
+    let max_transition = params.slews.iter().copied().reduce(f64::max);
+    let bit_caps = |name: &str| timing.pin_caps.get(name).cloned().unwrap_or_default();
+    let input = |name: &str| {
+        let mut pin = Pin::new(name, Direction::Input);
+        // Buses list the capacitance of each bit separately, so this is only a default.
+        pin.capacitance = bit_caps(name).into_iter().reduce(f64::max);
+        pin.max_transition = max_transition;
+        if let Some(input) = params
+            .constrained_inputs()
+            .into_iter()
+            .find(|input| input.pin_name(params.sram.port_style()) == name)
+        {
+            let constraint = &timing.constraints[&input];
+            for (timing_type, values) in [
+                (TimingType::SetupRising, &constraint.setup),
+                (TimingType::HoldRising, &constraint.hold),
+            ] {
+                pin.timing.push(Timing {
+                    related_pin: "clk".to_string(),
+                    timing_type,
+                    timing_sense: None,
+                    tables: vec![
+                        (TableKind::RiseConstraint, constraint_table(values)),
+                        (TableKind::FallConstraint, constraint_table(values)),
+                    ],
+                });
+            }
+        }
+        pin
+    };
+
+    let mut clk = input("clk");
+    clk.clock = true;
+    clk.min_period = timing.min_period;
+
+    // The energy of each cycle is dissipated on the rising clock edge.
+    let power_template = TableTemplate::power(&params.slews);
+    if let Some(power) = &timing.power {
+        let style = sram.port_style();
+        for (ce, we, energy) in [
+            (true, false, power.energy.read),
+            (true, true, power.energy.write),
+            (false, false, power.energy.idle),
+        ] {
+            clk.internal_power.push(InternalPower::constant(
+                None,
+                Some(access_condition(style, ce, we)),
+                &power_template,
+                energy,
+                0.,
+            ));
+        }
+    }
+
+    let mut dout = Pin::new("dout", Direction::Output);
+    let delays = &timing.clk_to_dout;
+    dout.timing.push(Timing {
+        related_pin: "clk".to_string(),
+        timing_type: TimingType::RisingEdge,
+        timing_sense: Some(TimingSense::NonUnate),
+        tables: vec![
+            (TableKind::CellRise, delay_table(&delays.cell_rise)),
+            (TableKind::CellFall, delay_table(&delays.cell_fall)),
+            (
+                TableKind::RiseTransition,
+                delay_table(&delays.rise_transition),
+            ),
+            (
+                TableKind::FallTransition,
+                delay_table(&delays.fall_transition),
+            ),
+        ],
+    });
+    // Registered outputs are cleared by `rstb` through the same flip-flop output stage
+    // as a falling clock-to-output transition.
+    if sram.output() == OutputMode::Registered {
+        dout.timing.push(Timing {
+            related_pin: "rstb".to_string(),
+            timing_type: TimingType::Clear,
+            timing_sense: Some(TimingSense::PositiveUnate),
+            tables: vec![
+                (TableKind::CellFall, delay_table(&delays.cell_fall)),
+                (
+                    TableKind::FallTransition,
+                    delay_table(&delays.fall_transition),
+                ),
+            ],
+        });
+    }
+
+    let mut buses = vec![
+        Bus {
+            width: sram.addr_width(),
+            pin: input("addr"),
+            bit_capacitance: bit_caps("addr"),
+        },
+        Bus {
+            width: sram.data_width(),
+            pin: input("din"),
+            bit_capacitance: bit_caps("din"),
+        },
+    ];
+    if sram.wmask_width() > 1 {
+        buses.push(Bus {
+            width: sram.wmask_width(),
+            pin: input("wmask"),
+            bit_capacitance: bit_caps("wmask"),
+        });
+    }
+    buses.push(Bus {
+        width: sram.data_width(),
+        pin: dout,
+        bit_capacitance: Vec::new(),
+    });
+
+    Library {
+        name: params.lib_name(),
+        voltage: params.vdd,
+        temperature: params.temp,
+        templates: vec![delay_template.clone(), constraint_template.clone()],
+        power_templates: timing.power.map(|_| power_template).into_iter().collect(),
+        cells: vec![Cell {
+            name: sram.name().to_string(),
+            area: params.area,
+            leakage_power: timing.power.map(|power| power.leakage),
+            pins: vec![
+                clk,
+                input(sram.port_style().enable_pin()),
+                input(sram.port_style().write_pin()),
+                input("rstb"),
+            ],
+            buses,
+        }],
+    }
+}
+
+/// Characterizes an SRAM and writes the resulting Liberty library to `path`.
+pub fn generate_sram_lib(
+    ctx: &SubstrateCtx,
+    params: &CharacterizeParams,
+    path: impl AsRef<Path>,
+) -> Result<()> {
+    let timing = characterize(ctx, params)?;
+    sram_library(params, &timing).save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::sram::estimate::estimate;
+    use crate::blocks::sram::tests::SRAM22_64X32M4W8;
+    use crate::blocks::sram::SramPhysicalDesignScript;
+    use crate::paths::out_lib;
+    use crate::setup_ctx;
+    use crate::tests::test_work_dir;
+
+    use super::*;
+
+    #[test]
+    fn test_sram_library_corner_and_power() {
+        let ctx = setup_ctx();
+        let dsn = ctx
+            .run_script::<SramPhysicalDesignScript>(&SRAM22_64X32M4W8)
+            .expect("failed to run sram design script");
+        let params = CharacterizeParams::builder()
+            .work_dir(test_work_dir("test_sram_library_corner_and_power"))
+            .sram(SRAM22_64X32M4W8)
+            .dsn(dsn)
+            .corner("ss")
+            .vdd(1.6)
+            .build()
+            .unwrap();
+        let name = SRAM22_64X32M4W8.name();
+        assert_eq!(params.temp, 100.);
+        assert_eq!(params.lib_name(), format!("{name}_ss_100C_1v60"));
+        let ff = CharacterizeParams {
+            corner: arcstr::literal!("ff"),
+            temp: corner_temp("ff"),
+            vdd: 1.95,
+            ..params.clone()
+        };
+        assert_eq!(ff.lib_name(), format!("{name}_ff_n40C_1v95"));
+
+        let mut timing = estimate(&params);
+        let addr_caps = (1..=SRAM22_64X32M4W8.addr_width())
+            .map(|i| i as f64 * 1e-15)
+            .collect::<Vec<_>>();
+        timing
+            .pin_caps
+            .insert("addr".to_string(), addr_caps.clone());
+        timing.power = Some(SramPower {
+            energy: CycleEnergy {
+                read: 3e-12,
+                write: 4e-12,
+                idle: 1e-12,
+            },
+            leakage: 2e-9,
+        });
+
+        let lib = sram_library(&params, &timing);
+        assert_eq!(lib.temperature, 100.);
+        let cell = &lib.cells[0];
+        assert_eq!(cell.leakage_power, Some(2e-9));
+        let addr = cell
+            .buses
+            .iter()
+            .find(|bus| bus.pin.name == "addr")
+            .unwrap();
+        assert_eq!(addr.bit_capacitance, addr_caps);
+        assert_eq!(addr.pin.capacitance, addr_caps.last().copied());
+        let clk = &cell.pins[0];
+        let conditions = clk
+            .internal_power
+            .iter()
+            .map(|power| power.when.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(conditions, ["ce & !we", "ce & we", "!ce"]);
+
+        let lib = lib.to_string();
+        assert!(lib.contains("  nom_temperature : 100;\n"));
+        assert!(lib.contains("    cell_leakage_power : 2.000000;\n"));
+    }
+
+    #[test]
+    fn test_access_condition() {
+        assert_eq!(
+            access_condition(PortStyle::CsbWeb, true, false),
+            "!csb & web"
+        );
+        assert_eq!(access_condition(PortStyle::CsbWeb, false, false), "csb");
+        assert_eq!(
+            access_condition(PortStyle::RenWen, true, false),
+            "ren & !wen"
+        );
+        assert_eq!(access_condition(PortStyle::RenWen, true, true), "wen");
+        assert_eq!(
+            access_condition(PortStyle::RenWen, false, false),
+            "!ren & !wen"
+        );
+    }
+
+    #[test]
+    #[ignore = "slow"]
+    fn test_characterize_sram22_64x32m4w8() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_characterize_sram22_64x32m4w8");
+        let dsn = ctx
+            .run_script::<SramPhysicalDesignScript>(&SRAM22_64X32M4W8)
+            .expect("failed to run sram design script");
+        let params = CharacterizeParams::builder()
+            .work_dir(work_dir.clone())
+            .sram(SRAM22_64X32M4W8)
+            .dsn(dsn)
+            .corner("tt")
+            .vdd(1.8)
+            .build()
+            .unwrap();
+
+        let timing = characterize(&ctx, &params).expect("failed to characterize SRAM");
+        for table in [&timing.clk_to_dout.cell_rise, &timing.clk_to_dout.cell_fall] {
+            for row in table.iter() {
+                // Delay increases with output load.
+                assert!(row.windows(2).all(|w| w[0] < w[1]));
+            }
+        }
+        assert!(timing.pin_caps.values().flatten().all(|&c| c > 0.0));
+        assert_eq!(timing.pin_caps["addr"].len(), SRAM22_64X32M4W8.addr_width());
+        let power = timing.power.unwrap();
+        assert!(power.leakage > 0.0);
+        assert!(power.energy.read > power.energy.idle);
+        assert!(power.energy.write > power.energy.idle);
+
+        sram_library(&params, &timing)
+            .save(out_lib(&work_dir, &params.lib_name()))
+            .expect("failed to write lib");
+    }
+}
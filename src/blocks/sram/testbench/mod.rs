@@ -1,5 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use derive_builder::Builder;
@@ -8,8 +9,10 @@ use substrate::component::Component;
 use substrate::index::IndexOwned;
 use substrate::schematic::circuit::Direction;
 use substrate::schematic::elements::capacitor::Capacitor;
+use substrate::schematic::elements::resistor::Resistor;
 use substrate::schematic::elements::vdc::Vdc;
 use substrate::schematic::elements::vpwl::Vpwl;
+use substrate::schematic::signal::Signal;
 use substrate::units::{SiPrefix, SiValue};
 use substrate::verification::simulation::bits::BitSignal;
 use substrate::verification::simulation::testbench::Testbench;
@@ -17,17 +20,23 @@ use substrate::verification::simulation::waveform::{TimeWaveform, Waveform};
 use substrate::verification::simulation::{Save, TranAnalysis, TranData};
 
 use super::{Sram, SramParams, SramPhysicalDesign};
+use crate::blocks::bist::march::{AddrOrder, MarchElement, MarchOp, MARCH_CM};
 
 #[cfg(feature = "commercial")]
 use super::{SramPex, SramPexParams};
 #[cfg(feature = "commercial")]
 use calibre::pex::PexLevel;
-#[cfg(feature = "commercial")]
-use std::path::PathBuf;
 
+pub mod characterize;
 pub mod plot;
 pub mod verify;
 
+/// Resistance in ohms between the supply and `vdd` of the SRAM
+/// when [`TbParams::measure_supply`] is set.
+pub const SUPPLY_SENSE_RES: f64 = 1.;
+/// The node driven by the supply when [`TbParams::measure_supply`] is set.
+pub const SUPPLY_NODE: &str = "vdd_supply";
+
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 #[builder(derive(Debug))]
 pub struct TbParams {
@@ -48,6 +57,20 @@ pub struct TbParams {
     ///
     /// Specifies how long data should be held after the clock edge.
     pub t_hold: f64,
+    /// Hold times that override [`TbParams::t_hold`] for specific inputs.
+    ///
+    /// Used to move the transitions of one input relative to the clock edge
+    /// while all other inputs keep the nominal hold time.
+    #[builder(default)]
+    pub t_hold_overrides: HashMap<TbInput, f64>,
+    /// Whether to supply `vdd` through a [`SUPPLY_SENSE_RES`] resistor from [`SUPPLY_NODE`].
+    ///
+    /// The supply current is the voltage across the resistor divided by its resistance.
+    #[builder(default)]
+    pub measure_supply: bool,
+    /// SPICE files to include in the simulation, such as simulator options.
+    #[builder(default)]
+    pub includes: Vec<PathBuf>,
 
     /// SRAM configuration to test.
     pub sram: SramParams,
@@ -56,6 +79,16 @@ pub struct TbParams {
     pub pex_netlist: Option<(PathBuf, PexLevel)>,
 }
 
+/// An input bus of the SRAM whose timing can be adjusted in [`TbParams`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TbInput {
+    Ce,
+    We,
+    Addr,
+    Wmask,
+    Din,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum TbSignals {
     Clk,
@@ -128,6 +161,14 @@ impl TbParams {
         TbParamsBuilder::default()
     }
 
+    /// The time for which `input` is held after each clock edge.
+    pub fn hold_time(&self, input: TbInput) -> f64 {
+        self.t_hold_overrides
+            .get(&input)
+            .copied()
+            .unwrap_or(self.t_hold)
+    }
+
     pub fn sram_signal_path(&self, signal: TbSignals) -> String {
         #[allow(unused_variables)]
         let mut last_stage_decoder_depth = 0;
@@ -142,8 +183,8 @@ impl TbParams {
 
         match signal {
             TbSignals::Clk => "clk".to_string(),
-            TbSignals::We => "we".to_string(),
-            TbSignals::Ce => "ce".to_string(),
+            TbSignals::We => self.sram.port_style().write_pin().to_string(),
+            TbSignals::Ce => self.sram.port_style().enable_pin().to_string(),
             TbSignals::RstB => "rstb".to_string(),
             TbSignals::Addr(i) => format!("addr[{i}]"),
             TbSignals::Wmask(i) => format!("wmask[{i}]"),
@@ -152,7 +193,7 @@ impl TbParams {
             _ => {
                 #[cfg(feature = "commercial")]
                 if let Some((_, ref level)) = self.pex_netlist {
-                    format!(
+                    return format!(
                         "Xdut.Xdut.{}",
                         match level {
                             PexLevel::Rc => {
@@ -353,103 +394,91 @@ impl TbParams {
                             }
                             _ => unimplemented!(),
                         }
-                    )
-                } else {
-                    format!(
-                        "Xdut.X0.{}",
-                        match signal {
-                            TbSignals::Clk
-                            | TbSignals::We
-                            | TbSignals::Ce
-                            | TbSignals::RstB
-                            | TbSignals::Addr(_)
-                            | TbSignals::Wmask(_)
-                            | TbSignals::Din(_)
-                            | TbSignals::Dout(_) => unreachable!(),
-                            TbSignals::Wlen => "wl_en".to_string(),
-                            TbSignals::Decrepstart => "Xcontrol_logic.decrepstart".to_string(),
-                            TbSignals::Decrepend => "Xcontrol_logic.decrepend".to_string(),
-                            TbSignals::PcBStart | TbSignals::PcBEnd => "pc_b".to_string(),
-                            TbSignals::SenseEnStart | TbSignals::SenseEnEnd =>
-                                "sense_en".to_string(),
-                            TbSignals::Rwl => "rwl".to_string(),
-                            TbSignals::Rbl => "rbl".to_string(),
-                            TbSignals::WriteDriverEnStart | TbSignals::WriteDriverEnEnd =>
-                                "write_driver_en".to_string(),
-                            TbSignals::WlStart(i) | TbSignals::WlEnd(i) => format!("wl[{i}]"),
-                            TbSignals::WeI(i) => format!(
-                                "Xcol_circuitry.we_i{}",
-                                if self.sram.wmask_width() > 1 {
-                                    format!("[{i}]")
-                                } else {
-                                    "".to_string()
-                                }
-                            ),
-                            TbSignals::WeIb(i) => format!(
-                                "Xcol_circuitry.we_ib{}",
-                                if self.sram.wmask_width() > 1 {
-                                    format!("[{i}]")
-                                } else {
-                                    "".to_string()
-                                }
-                            ),
-                            TbSignals::Bl(i) => format!("bl[{i}]"),
-                            TbSignals::Br(i) => format!("br[{i}]"),
-                            TbSignals::BitcellQ(i, j) =>
-                                format!("Xbitcell_array.Xcell_{i}_{j}.X0.Q"),
-                            TbSignals::BitcellQB(i, j) =>
-                                format!("Xbitcell_array.Xcell_{i}_{j}.X0.QB"),
-                            TbSignals::WlCtlQ => "Xcontrol_logic.Xwl_ctl.q0".to_string(),
-                            TbSignals::WlCtlQB => "Xcontrol_logic.Xwl_ctl.q0b".to_string(),
-                            TbSignals::SaenCtlQ => "Xcontrol_logic.Xsaen_ctl.q0".to_string(),
-                            TbSignals::SaenCtlQB => "Xcontrol_logic.Xsaen_ctl.q0b".to_string(),
-                            TbSignals::PcCtlQ => "Xcontrol_logic.Xpc_ctl.q0".to_string(),
-                            TbSignals::PcCtlQB => "Xcontrol_logic.Xpc_ctl.q0b".to_string(),
-                            TbSignals::WrdrvenCtlQ => "Xcontrol_logic.Xwrdrven_ctl.q0".to_string(),
-                            TbSignals::WrdrvenCtlQB =>
-                                "Xcontrol_logic.Xwrdrven_ctl.q0b".to_string(),
-                            TbSignals::DffsQ1(i) =>
-                                format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_331_392#"),
-                            TbSignals::DffsQ1B(i) =>
-                                format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_298_294#"),
-                            TbSignals::DffsQ2(i) =>
-                                format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_1586_149#"),
-                            TbSignals::DffsQ2B(i) =>
-                                format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_1800_291#"),
-                            TbSignals::Wlen0 => "wl_en0".to_string(),
-                            TbSignals::PcB0 => "pc_b0".to_string(),
-                            TbSignals::SenseEn0 => "sense_en0".to_string(),
-                            TbSignals::WriteDriverEn0 => "write_driver_en0".to_string(),
-                            TbSignals::WlBs => "wl_b[*]".to_string(),
-                            TbSignals::BlOut(i) => format!("Xcol_circuitry.Xcol_group_{i}.bl_out"),
-                            TbSignals::BrOut(i) => format!("Xcol_circuitry.Xcol_group_{i}.br_out"),
-                            TbSignals::AddrGated(i) => format!("addr_gated[{i}]"),
-                            TbSignals::AddrBGated(i) => format!("addr_b_gated[{i}]"),
-                            TbSignals::ColSelStart(i) | TbSignals::ColSelEnd(i) =>
-                                format!("col_sel[{i}]"),
-                            TbSignals::ColSelBStart(i) | TbSignals::ColSelBEnd(i) =>
-                                format!("col_sel_b[{i}]"),
-                            TbSignals::ChildConnsStart | TbSignals::ChildConnsEnd =>
-                                "Xdecoder.*child_conn_*".to_string(),
-                            TbSignals::LastStageDecoderXs => "*.x_*".to_string(),
-                            TbSignals::LastStageDecoderVdds => "vdd".to_string(),
-                            TbSignals::LastStageDecoderVsss => "vdd".to_string(), // Hack, nothing
-                            // to save for vss
-                            TbSignals::ColumnVdds => "vdd".to_string(),
-                            TbSignals::ColumnVsss => "vdd".to_string(), // Hack, nothing to
-                            // save for vss
-                            TbSignals::WriteDriverQ(i) =>
-                                format!("Xcol_circuitry.Xcol_group_{i}.q"),
-                            TbSignals::WriteDriverQB(i) =>
-                                format!("Xcol_circuitry.Xcol_group_{i}.q_b"),
-                            TbSignals::WmaskQ(i) => {
-                                format!("Xcol_circuitry.wmask_in[{i}]")
+                    );
+                }
+                format!(
+                    "Xdut.X0.{}",
+                    match signal {
+                        TbSignals::Clk
+                        | TbSignals::We
+                        | TbSignals::Ce
+                        | TbSignals::RstB
+                        | TbSignals::Addr(_)
+                        | TbSignals::Wmask(_)
+                        | TbSignals::Din(_)
+                        | TbSignals::Dout(_) => unreachable!(),
+                        TbSignals::Wlen => "wl_en".to_string(),
+                        TbSignals::Decrepstart => "Xcontrol_logic.decrepstart".to_string(),
+                        TbSignals::Decrepend => "Xcontrol_logic.decrepend".to_string(),
+                        TbSignals::PcBStart | TbSignals::PcBEnd => "pc_b".to_string(),
+                        TbSignals::SenseEnStart | TbSignals::SenseEnEnd => "sense_en".to_string(),
+                        TbSignals::Rwl => "rwl".to_string(),
+                        TbSignals::Rbl => "rbl".to_string(),
+                        TbSignals::WriteDriverEnStart | TbSignals::WriteDriverEnEnd =>
+                            "write_driver_en".to_string(),
+                        TbSignals::WlStart(i) | TbSignals::WlEnd(i) => format!("wl[{i}]"),
+                        TbSignals::WeI(i) => format!(
+                            "Xcol_circuitry.we_i{}",
+                            if self.sram.wmask_width() > 1 {
+                                format!("[{i}]")
+                            } else {
+                                "".to_string()
+                            }
+                        ),
+                        TbSignals::WeIb(i) => format!(
+                            "Xcol_circuitry.we_ib{}",
+                            if self.sram.wmask_width() > 1 {
+                                format!("[{i}]")
+                            } else {
+                                "".to_string()
                             }
+                        ),
+                        TbSignals::Bl(i) => format!("bl[{i}]"),
+                        TbSignals::Br(i) => format!("br[{i}]"),
+                        TbSignals::BitcellQ(i, j) => format!("Xbitcell_array.Xcell_{i}_{j}.X0.Q"),
+                        TbSignals::BitcellQB(i, j) => format!("Xbitcell_array.Xcell_{i}_{j}.X0.QB"),
+                        TbSignals::WlCtlQ => "Xcontrol_logic.Xwl_ctl.q0".to_string(),
+                        TbSignals::WlCtlQB => "Xcontrol_logic.Xwl_ctl.q0b".to_string(),
+                        TbSignals::SaenCtlQ => "Xcontrol_logic.Xsaen_ctl.q0".to_string(),
+                        TbSignals::SaenCtlQB => "Xcontrol_logic.Xsaen_ctl.q0b".to_string(),
+                        TbSignals::PcCtlQ => "Xcontrol_logic.Xpc_ctl.q0".to_string(),
+                        TbSignals::PcCtlQB => "Xcontrol_logic.Xpc_ctl.q0b".to_string(),
+                        TbSignals::WrdrvenCtlQ => "Xcontrol_logic.Xwrdrven_ctl.q0".to_string(),
+                        TbSignals::WrdrvenCtlQB => "Xcontrol_logic.Xwrdrven_ctl.q0b".to_string(),
+                        TbSignals::DffsQ1(i) => format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_331_392#"),
+                        TbSignals::DffsQ1B(i) => format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_298_294#"),
+                        TbSignals::DffsQ2(i) => format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_1586_149#"),
+                        TbSignals::DffsQ2B(i) =>
+                            format!("Xaddr_we_ce_dffs.Xdff_{i}.X0.a_1800_291#"),
+                        TbSignals::Wlen0 => "wl_en0".to_string(),
+                        TbSignals::PcB0 => "pc_b0".to_string(),
+                        TbSignals::SenseEn0 => "sense_en0".to_string(),
+                        TbSignals::WriteDriverEn0 => "write_driver_en0".to_string(),
+                        TbSignals::WlBs => "wl_b[*]".to_string(),
+                        TbSignals::BlOut(i) => format!("Xcol_circuitry.Xcol_group_{i}.bl_out"),
+                        TbSignals::BrOut(i) => format!("Xcol_circuitry.Xcol_group_{i}.br_out"),
+                        TbSignals::AddrGated(i) => format!("addr_gated[{i}]"),
+                        TbSignals::AddrBGated(i) => format!("addr_b_gated[{i}]"),
+                        TbSignals::ColSelStart(i) | TbSignals::ColSelEnd(i) =>
+                            format!("col_sel[{i}]"),
+                        TbSignals::ColSelBStart(i) | TbSignals::ColSelBEnd(i) =>
+                            format!("col_sel_b[{i}]"),
+                        TbSignals::ChildConnsStart | TbSignals::ChildConnsEnd =>
+                            "Xdecoder.*child_conn_*".to_string(),
+                        TbSignals::LastStageDecoderXs => "*.x_*".to_string(),
+                        TbSignals::LastStageDecoderVdds => "vdd".to_string(),
+                        TbSignals::LastStageDecoderVsss => "vdd".to_string(), // Hack, nothing
+                        // to save for vss
+                        TbSignals::ColumnVdds => "vdd".to_string(),
+                        TbSignals::ColumnVsss => "vdd".to_string(), // Hack, nothing to
+                        // save for vss
+                        TbSignals::WriteDriverQ(i) => format!("Xcol_circuitry.Xcol_group_{i}.q"),
+                        TbSignals::WriteDriverQB(i) => format!("Xcol_circuitry.Xcol_group_{i}.q_b"),
+                        TbSignals::WmaskQ(i) => {
+                            format!("Xcol_circuitry.wmask_in[{i}]")
                         }
-                    )
-                }
-                #[cfg(not(feature = "commercial"))]
-                unimplemented!()
+                    }
+                )
             }
         }
     }
@@ -488,15 +517,20 @@ pub struct TbWaveforms {
     /// Clock.
     clk: Waveform,
 
-    /// Chip enable.
+    /// Chip enable, or the enable pin of the SRAM's port style.
     ce: Waveform,
 
-    /// Write enable.
+    /// Write enable, or the write pin of the SRAM's port style.
     we: Waveform,
 
     /// Reset.
     rstb: Waveform,
 
+    /// Sleep, for power-gated SRAMs.
+    ///
+    /// Falls before the first clock edge, so the SRAM wakes up during reset.
+    sleep: Waveform,
+
     /// One [`Waveform`] per write mask bit.
     ///
     /// Empty if no write mask is enabled.
@@ -513,11 +547,15 @@ pub fn push_bus(
 ) {
     assert_eq!(waveforms.len(), signal.width());
     for (i, bit) in signal.bits().enumerate() {
-        if bit {
-            waveforms[i].push_high(until, vdd, tr);
-        } else {
-            waveforms[i].push_low(until, vdd, tf);
-        }
+        push_bit(&mut waveforms[i], bit, until, vdd, tr, tf);
+    }
+}
+
+fn push_bit(waveform: &mut Waveform, bit: bool, until: f64, vdd: f64, tr: f64, tf: f64) {
+    if bit {
+        waveform.push_high(until, vdd, tr);
+    } else {
+        waveform.push_low(until, vdd, tf);
     }
 }
 
@@ -526,71 +564,77 @@ fn generate_waveforms(params: &TbParams) -> TbWaveforms {
     let mut din = vec![Waveform::with_initial_value(0f64); params.sram.data_width()];
     let wmask_bits = params.sram.wmask_width();
     let mut wmask = vec![Waveform::with_initial_value(0f64); wmask_bits];
-    let mut clk = Waveform::with_initial_value(0f64);
-    let mut ce = Waveform::with_initial_value(0f64);
-    let mut we = Waveform::with_initial_value(0f64);
-    let mut rstb = Waveform::with_initial_value(0f64);
-
     let period = params.clk_period;
     let vdd = params.vdd;
     let tr = params.tr;
     let tf = params.tf;
+    let style = params.sram.port_style();
+    let (ce_idle, we_idle) = style.pin_levels(false, false);
+    let level = |bit: bool| if bit { vdd } else { 0f64 };
+
+    let mut clk = Waveform::with_initial_value(0f64);
+    let mut ce = Waveform::with_initial_value(level(ce_idle));
+    let mut we = Waveform::with_initial_value(level(we_idle));
+    let mut rstb = Waveform::with_initial_value(0f64);
+    let mut sleep = Waveform::with_initial_value(vdd);
+    sleep.push_low(period / 4., vdd, tf);
 
     let mut t = 0f64;
-    let mut t_end;
 
     let wmask_all = BitSignal::ones(params.sram.wmask_width());
 
     for op in params.ops.iter() {
-        t_end = t + period;
+        let t_end = t + period;
         let t_data = t_end + params.t_hold;
+        let [t_ce, t_we, t_addr, t_wmask, t_din] = [
+            TbInput::Ce,
+            TbInput::We,
+            TbInput::Addr,
+            TbInput::Wmask,
+            TbInput::Din,
+        ]
+        .map(|input| t_end + params.hold_time(input));
         // Toggle the clock
         clk.push_high(t + (period / 2.0), vdd, tr);
         clk.push_low(t + period, vdd, tf);
 
+        // Drive the enable pins of the SRAM's port style to select the operation.
+        let (ce_level, we_level) = match op {
+            Op::Reset | Op::None => (false, false),
+            Op::Read { .. } => (true, false),
+            Op::Write { .. } | Op::WriteMasked { .. } => (true, true),
+        };
+        let (ce_level, we_level) = style.pin_levels(ce_level, we_level);
+        push_bit(&mut ce, ce_level, t_ce, vdd, tr, tf);
+        push_bit(&mut we, we_level, t_we, vdd, tr, tf);
+
         match op {
             Op::Reset => {
-                // Set write enable low
-                we.push_low(t_data, vdd, tf);
-                // Set chip enable low
-                ce.push_low(t_data, vdd, tf);
                 // Set reset high
                 rstb.push_low(t_data + period / 2., vdd, tf);
             }
             Op::None => {
-                // Set write enable low
-                we.push_low(t_data, vdd, tf);
-                // Set chip enable low
-                ce.push_low(t_data, vdd, tf);
                 // Set reset low
                 rstb.push_high(t_data, vdd, tr);
             }
             Op::Read { addr: addrv } => {
-                // Set write enable low
-                we.push_low(t_data, vdd, tf);
-                // Set chip enable high
-                ce.push_high(t_data, vdd, tr);
                 // Set reset low
                 rstb.push_high(t_data, vdd, tr);
 
                 assert_eq!(addrv.width(), params.sram.addr_width());
-                push_bus(&mut addr, addrv, t_data, vdd, tr, tf);
+                push_bus(&mut addr, addrv, t_addr, vdd, tr, tf);
             }
             Op::Write { addr: addrv, data } => {
-                // Set write enable high
-                we.push_high(t_data, vdd, tr);
-                // Set chip enable high
-                ce.push_high(t_data, vdd, tr);
                 // Set reset low
                 rstb.push_high(t_data, vdd, tr);
 
                 assert_eq!(addrv.width(), params.sram.addr_width());
-                push_bus(&mut addr, addrv, t_data, vdd, tr, tf);
+                push_bus(&mut addr, addrv, t_addr, vdd, tr, tf);
 
                 assert_eq!(data.width(), params.sram.data_width);
-                push_bus(&mut din, data, t_data, vdd, tr, tf);
+                push_bus(&mut din, data, t_din, vdd, tr, tf);
 
-                push_bus(&mut wmask, &wmask_all, t_data, vdd, tr, tf);
+                push_bus(&mut wmask, &wmask_all, t_wmask, vdd, tr, tf);
             }
 
             Op::WriteMasked {
@@ -598,37 +642,34 @@ fn generate_waveforms(params: &TbParams) -> TbWaveforms {
                 data,
                 mask,
             } => {
-                // Set write enable high
-                we.push_high(t_data, vdd, tr);
-                // Set chip enable high
-                ce.push_high(t_data, vdd, tr);
                 // Set reset low
                 rstb.push_high(t_data, vdd, tr);
 
                 assert_eq!(addrv.width(), params.sram.addr_width());
-                push_bus(&mut addr, addrv, t_data, vdd, tr, tf);
+                push_bus(&mut addr, addrv, t_addr, vdd, tr, tf);
 
                 assert_eq!(data.width(), params.sram.data_width);
-                push_bus(&mut din, data, t_data, vdd, tr, tf);
+                push_bus(&mut din, data, t_din, vdd, tr, tf);
 
                 assert!(params.sram.wmask_width() > 1);
                 assert_eq!(mask.width(), params.sram.wmask_width());
-                push_bus(&mut wmask, mask, t_data, vdd, tr, tf);
+                push_bus(&mut wmask, mask, t_wmask, vdd, tr, tf);
             }
         }
 
         t += period;
     }
 
-    t_end = t + period;
-    let t_final = t + 2.0 * period + params.t_hold;
-
-    // One more clock cycle
-    clk.push_high(t + period / 2.0, vdd, tr);
-    clk.push_low(t_end, vdd, tf);
+    // One more clock cycle, and another for SRAMs with a registered output.
+    for _ in 0..params.sram.read_latency() {
+        clk.push_high(t + period / 2.0, vdd, tr);
+        clk.push_low(t + period, vdd, tf);
+        t += period;
+    }
+    let t_final = t + period + params.t_hold;
 
     // Turn off write enable
-    we.push_low(t_final, vdd, tf);
+    push_bit(&mut we, we_idle, t_final, vdd, tr, tf);
     clk.push_high(t_final, vdd, tr);
 
     TbWaveforms {
@@ -638,6 +679,7 @@ fn generate_waveforms(params: &TbParams) -> TbWaveforms {
         ce,
         we,
         rstb,
+        sleep,
         wmask,
     }
 }
@@ -662,7 +704,9 @@ impl Component for SramTestbench {
         ctx: &mut substrate::schematic::context::SchematicCtx,
     ) -> substrate::error::Result<()> {
         let vss = ctx.port("vss", Direction::InOut);
-        let [vdd, clk, ce, we, rstb] = ctx.signals(["vdd", "clk", "ce", "we", "rstb"]);
+        let style = self.params.sram.port_style();
+        let [vdd, clk, ce, we, rstb] =
+            ctx.signals(["vdd", "clk", style.enable_pin(), style.write_pin(), "rstb"]);
 
         let addr = ctx.bus("addr", self.params.sram.addr_width());
         let din = ctx.bus("din", self.params.sram.data_width());
@@ -672,6 +716,26 @@ impl Component for SramTestbench {
         let waveforms = generate_waveforms(&self.params);
         let output_cap = SiValue::with_precision(self.params.c_load, SiPrefix::Femto);
 
+        // Power-gated SRAMs are woken up at the start of the simulation and never retain.
+        let power_pins = if self.params.sram.power_gating() {
+            let sleep = ctx.signal("sleep");
+            ctx.instantiate::<Vpwl>(&Arc::new(waveforms.sleep.clone()))?
+                .with_connections([("p", sleep), ("n", vss)])
+                .named("Vsleep")
+                .add_to(ctx);
+            vec![("sleep", sleep), ("retain", vss)]
+        } else {
+            Vec::new()
+        };
+        // Trim pins are tied low, selecting the generated delays.
+        let trim_pins = match self.params.sram.timing_trim() {
+            Some(bits) => vec![
+                ("trim_saen", Signal::new(vec![vss; bits])),
+                ("trim_wl", Signal::new(vec![vss; bits])),
+            ],
+            None => Vec::new(),
+        };
+
         #[cfg(feature = "commercial")]
         if let Some((ref pex_netlist, _)) = self.params.pex_netlist {
             ctx.instantiate::<SramPex>(&SramPexParams {
@@ -682,14 +746,16 @@ impl Component for SramTestbench {
                 ("vdd", vdd),
                 ("vss", vss),
                 ("clk", clk),
-                ("ce", ce),
-                ("we", we),
+                (style.enable_pin(), ce),
+                (style.write_pin(), we),
                 ("rstb", rstb),
                 ("addr", addr),
                 ("wmask", wmask),
                 ("din", din),
                 ("dout", dout),
             ])
+            .with_connections(power_pins.clone())
+            .with_connections(trim_pins.clone())
             .named("dut")
             .add_to(ctx);
         } else {
@@ -698,14 +764,16 @@ impl Component for SramTestbench {
                     ("vdd", vdd),
                     ("vss", vss),
                     ("clk", clk),
-                    ("ce", ce),
-                    ("we", we),
+                    (style.enable_pin(), ce),
+                    (style.write_pin(), we),
                     ("rstb", rstb),
                     ("addr", addr),
                     ("wmask", wmask),
                     ("din", din),
                     ("dout", dout),
                 ])
+                .with_connections(power_pins.clone())
+                .with_connections(trim_pins.clone())
                 .named("dut")
                 .add_to(ctx);
         }
@@ -715,19 +783,34 @@ impl Component for SramTestbench {
                 ("vdd", vdd),
                 ("vss", vss),
                 ("clk", clk),
-                ("ce", ce),
-                ("we", we),
+                (style.enable_pin(), ce),
+                (style.write_pin(), we),
                 ("rstb", rstb),
                 ("addr", addr),
                 ("wmask", wmask),
                 ("din", din),
                 ("dout", dout),
             ])
+            .with_connections(power_pins.clone())
+            .with_connections(trim_pins.clone())
             .named("dut")
             .add_to(ctx);
 
+        let supply = if self.params.measure_supply {
+            let supply = ctx.signal(SUPPLY_NODE);
+            ctx.instantiate::<Resistor>(&SiValue::with_precision(
+                SUPPLY_SENSE_RES,
+                SiPrefix::Milli,
+            ))?
+            .with_connections([("p", supply), ("n", vdd)])
+            .named("Rsense")
+            .add_to(ctx);
+            supply
+        } else {
+            vdd
+        };
         ctx.instantiate::<Vdc>(&SiValue::with_precision(self.params.vdd, SiPrefix::Milli))?
-            .with_connections([("p", vdd), ("n", vss)])
+            .with_connections([("p", supply), ("n", vss)])
             .named("Vdd")
             .add_to(ctx);
 
@@ -792,63 +875,39 @@ fn alternating_bits(width: usize, start: bool) -> Vec<bool> {
     bits
 }
 
-pub fn march_cm_test(params: SramParams) -> Vec<Op> {
+/// Expands a march algorithm into a sequence of SRAM operations.
+pub fn march_test(params: SramParams, elements: &[MarchElement]) -> Vec<Op> {
     let n = params.num_words() as u64;
     let aw = params.addr_width();
     let dw = params.data_width();
-    (0..n)
-        .map(|i| Op::Write {
-            addr: BitSignal::from_u64(i, aw),
-            data: BitSignal::zeros(dw),
-        })
-        .chain((0..n).flat_map(|i| {
-            [
-                Op::Read {
-                    addr: BitSignal::from_u64(i, aw),
-                },
-                Op::Write {
-                    addr: BitSignal::from_u64(i, aw),
-                    data: BitSignal::ones(dw),
-                },
-            ]
-        }))
-        .chain((0..n).flat_map(|i| {
-            [
-                Op::Read {
-                    addr: BitSignal::from_u64(i, aw),
-                },
-                Op::Write {
-                    addr: BitSignal::from_u64(i, aw),
-                    data: BitSignal::zeros(dw),
-                },
-            ]
-        }))
-        .chain((0..n).rev().flat_map(|i| {
-            [
-                Op::Read {
-                    addr: BitSignal::from_u64(i, aw),
-                },
-                Op::Write {
-                    addr: BitSignal::from_u64(i, aw),
-                    data: BitSignal::ones(dw),
-                },
-            ]
-        }))
-        .chain((0..n).rev().flat_map(|i| {
-            [
-                Op::Read {
-                    addr: BitSignal::from_u64(i, aw),
-                },
-                Op::Write {
-                    addr: BitSignal::from_u64(i, aw),
-                    data: BitSignal::zeros(dw),
-                },
-            ]
-        }))
-        .chain((0..n).rev().map(|i| Op::Read {
-            addr: BitSignal::from_u64(i, aw),
-        }))
-        .collect::<Vec<_>>()
+    let mut ops = Vec::new();
+    for element in elements {
+        let addrs: Box<dyn Iterator<Item = u64>> = match element.order {
+            AddrOrder::Up => Box::new(0..n),
+            AddrOrder::Down => Box::new((0..n).rev()),
+        };
+        for i in addrs {
+            for op in element.ops {
+                let addr = BitSignal::from_u64(i, aw);
+                ops.push(match *op {
+                    MarchOp::Read(_) => Op::Read { addr },
+                    MarchOp::Write(value) => Op::Write {
+                        addr,
+                        data: if value {
+                            BitSignal::ones(dw)
+                        } else {
+                            BitSignal::zeros(dw)
+                        },
+                    },
+                });
+            }
+        }
+    }
+    ops
+}
+
+pub fn march_cm_test(params: SramParams) -> Vec<Op> {
+    march_test(params, MARCH_CM)
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -892,7 +951,7 @@ pub fn tb_params(
     let bit_pattern2 = 0xAAAAAAAAAAAAAAAAu128;
 
     let addr1 = BitSignal::zeros(addr_width);
-    let addr2 = BitSignal::ones(addr_width);
+    let addr2 = BitSignal::from_u64(params.num_words() as u64 - 1, addr_width);
     let mask1 = BitSignal::from_vec(
         std::iter::once(true)
             .chain(std::iter::repeat(false).take(wmask_width - 1))
@@ -915,7 +974,9 @@ pub fn tb_params(
         Op::Read {
             addr: addr1.clone(),
         },
-        Op::Read { addr: addr2 },
+        Op::Read {
+            addr: addr2.clone(),
+        },
         Op::Read {
             addr: addr1.clone(),
         },
@@ -962,6 +1023,13 @@ pub fn tb_params(
                 }
             }
 
+            // Write the inverse of the short sequence's data to the last valid address.
+            short_ops.push(Op::Write {
+                addr: addr2.clone(),
+                data: BitSignal::from_vec(bits0101(data_width)),
+            });
+            short_ops.push(Op::Read { addr: addr2 });
+
             short_ops
         }
         TestSequence::MarchCm => march_cm_test(params),
@@ -993,40 +1061,49 @@ impl Testbench for SramTestbench {
     ) -> substrate::error::Result<()> {
         let wav = generate_waveforms(&self.params);
         let step = self.params.clk_period / 8.0;
-        use std::collections::HashMap;
-        let opts = HashMap::from_iter([
-            ("write".to_string(), "initial.ic".to_string()),
-            ("readns".to_string(), "initial.ic".to_string()),
-        ]);
         #[cfg(feature = "commercial")]
         if let Some((ref netlist, _)) = self.params.pex_netlist {
             ctx.include(netlist);
         }
-        ctx.add_analysis(
-            TranAnalysis::builder()
-                .stop(wav.clk.last_t().unwrap() + 2.0 * step)
-                // .stop(80e-9)
-                .step(step)
-                // .strobe_period(step)
-                .opts(opts)
-                .build()
-                .unwrap(),
-        );
-
-        let signals = (0..self.params.sram.data_width)
-            .flat_map(|i| {
-                [
-                    TbSignals::Din(i),
-                    TbSignals::Dout(i),
-                    TbSignals::WriteDriverQ(i),
-                    TbSignals::WriteDriverQB(i),
-                ]
-            })
+        for path in self.params.includes.iter() {
+            ctx.include(path);
+        }
+        let mut tran = TranAnalysis::builder();
+        tran.stop(wav.clk.last_t().unwrap() + 2.0 * step)
+            // .stop(80e-9)
+            .step(step);
+        #[cfg(feature = "commercial")]
+        tran.opts(HashMap::from_iter([
+            ("write".to_string(), "initial.ic".to_string()),
+            ("readns".to_string(), "initial.ic".to_string()),
+        ]));
+        ctx.add_analysis(tran.build().unwrap());
+
+        let pins = (0..self.params.sram.data_width)
+            .flat_map(|i| [TbSignals::Din(i), TbSignals::Dout(i)])
             .chain([
                 TbSignals::Clk,
                 TbSignals::We,
                 TbSignals::Ce,
                 TbSignals::RstB,
+            ])
+            .chain((0..self.params.sram.addr_width()).map(TbSignals::Addr))
+            .chain((0..self.params.sram.wmask_width()).map(TbSignals::Wmask));
+
+        // Ngspice does not support wildcards in saved signal names,
+        // so only the SRAM pins are saved in open-source builds.
+        #[cfg(not(feature = "commercial"))]
+        let mut signals = pins
+            .map(|signal| self.params.sram_signal_path(signal))
+            .collect::<HashSet<_>>();
+
+        #[cfg(feature = "commercial")]
+        let mut signals = pins
+            .chain(
+                (0..self.params.sram.data_width)
+                    .flat_map(|i| [TbSignals::WriteDriverQ(i), TbSignals::WriteDriverQB(i)]),
+            )
+            .chain([
                 TbSignals::Wlen,
                 TbSignals::Wlen0,
                 TbSignals::Decrepstart,
@@ -1055,15 +1132,10 @@ impl Testbench for SramTestbench {
                 (0..self.params.sram.rows())
                     .flat_map(|i| [TbSignals::WlStart(i), TbSignals::WlEnd(i)]),
             )
-            .chain((0..self.params.sram.addr_width()).map(TbSignals::Addr))
-            .chain((0..self.params.sram.wmask_width()).flat_map(|i| {
-                [
-                    TbSignals::WeI(i),
-                    TbSignals::WeIb(i),
-                    TbSignals::Wmask(i),
-                    TbSignals::WmaskQ(i),
-                ]
-            }))
+            .chain(
+                (0..self.params.sram.wmask_width())
+                    .flat_map(|i| [TbSignals::WeI(i), TbSignals::WeIb(i), TbSignals::WmaskQ(i)]),
+            )
             .chain((0..self.params.sram.cols()).flat_map(|i| {
                 [
                     TbSignals::Bl(i),
@@ -1087,6 +1159,9 @@ impl Testbench for SramTestbench {
             .map(|signal| self.params.sram_signal_path(signal))
             .collect::<HashSet<_>>();
 
+        if self.params.measure_supply {
+            signals.extend(["vdd".to_string(), SUPPLY_NODE.to_string()]);
+        }
         ctx.save(Save::Signals(signals));
         // ctx.save(Save::All);
 
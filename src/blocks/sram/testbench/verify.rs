@@ -9,6 +9,7 @@ use substrate::verification::simulation::waveform::{TimeWaveform, Transition, Wa
 use substrate::verification::simulation::TranData;
 
 use super::{Op, TbParams, TbSignals};
+use crate::blocks::sram::WriteMode;
 use anyhow::{anyhow, bail, Result};
 
 /// Reports relevant behavior of internal signals for diagnostic purposes.
@@ -388,13 +389,40 @@ pub fn write_internal_rpt(
 }
 
 pub fn verify_simulation(work_dir: impl AsRef<Path>, data: &TranData, tb: &TbParams) -> Result<()> {
-    let mut state = HashMap::new();
-    let data_bits_per_wmask = tb.sram.data_width / tb.sram.wmask_width();
-
     if let Err(e) = write_internal_rpt(work_dir, data, tb) {
         println!("ERROR: Failed to write internal report ({:?})", e);
     }
 
+    verify_reads(data, tb)
+}
+
+/// Checks that every read returns the data most recently written to its address.
+///
+/// In write-through mode, also checks that every write forwards
+/// the bits it writes to `dout`.
+///
+/// Only requires the SRAM pins to be saved.
+pub fn verify_reads(data: &TranData, tb: &TbParams) -> Result<()> {
+    let mut state = HashMap::new();
+    let data_bits_per_wmask = tb.sram.data_width / tb.sram.wmask_width();
+    let write_through = tb.sram.write_mode() == WriteMode::WriteThrough;
+
+    let check_bit = |op: &str, addr: &BitSignal, cycle: usize, i: usize, ex: bool| -> Result<()> {
+        let rx_bit = dout_bit(data, tb, cycle, i)?;
+        if rx_bit != ex {
+            bail!(
+                "{} addr {}: expected bit {} to be {}; got {} at clock cycle {}",
+                op,
+                addr,
+                i,
+                ex,
+                rx_bit,
+                cycle - 1,
+            );
+        }
+        Ok(())
+    };
+
     // Clock cycle counter
     // Initialized to 1 instead of 0,
     // since nothing happens on the first cycle of our testbench.
@@ -408,33 +436,17 @@ pub fn verify_simulation(work_dir: impl AsRef<Path>, data: &TranData, tb: &TbPar
                     .get(addr)
                     .ok_or_else(|| anyhow!("Attempted to read an uninitialized address."))?;
 
-                let t = cycle as f64 * tb.clk_period;
-                let idx = data
-                    .time
-                    .idx_before_sorted(t)
-                    .ok_or_else(|| anyhow!("Time {} was out of simulation range", t))?;
                 for i in 0..tb.sram.data_width {
-                    let name = format!("{}[{}]", "dout", i);
-                    let rx_bit = data
-                        .data
-                        .get(&name)
-                        .ok_or_else(|| anyhow!("Unable to find signal {}", &name))?
-                        .get(idx)
-                        .ok_or_else(|| {
-                            anyhow!("Index {} was out of range for signal {}", idx, &name)
-                        })?;
-                    let rx_bit = to_bit(rx_bit, tb.vdd)?;
-                    let ex_bit = expected.bit(i);
-                    if rx_bit != ex_bit {
-                        bail!(
-                            "reading addr {}: expected bit {} to be {}; got {} at clock cycle {} (time {}, index {})",
-                            addr, i, ex_bit, rx_bit, cycle-1, t, idx
-                        );
-                    }
+                    check_bit("reading", addr, cycle, i, expected.bit(i))?;
                 }
             }
             Op::Write { addr, data } => {
                 state.insert(addr.to_owned(), data.to_owned());
+                if write_through {
+                    for i in 0..tb.sram.data_width {
+                        check_bit("writing", addr, cycle, i, data.bit(i))?;
+                    }
+                }
             }
             Op::WriteMasked { addr, data, mask } => {
                 // If performing a masked write, that address should already have been initialized.
@@ -445,6 +457,9 @@ pub fn verify_simulation(work_dir: impl AsRef<Path>, data: &TranData, tb: &TbPar
                     if bit {
                         for j in i * data_bits_per_wmask..(i + 1) * data_bits_per_wmask {
                             entry.assign(j, data.bit(j));
+                            if write_through {
+                                check_bit("writing", addr, cycle, j, data.bit(j))?;
+                            }
                         }
                     }
                 }
@@ -455,3 +470,22 @@ pub fn verify_simulation(work_dir: impl AsRef<Path>, data: &TranData, tb: &TbPar
 
     Ok(())
 }
+
+/// The value of bit `i` of `dout` for the operation in clock cycle `cycle`.
+///
+/// Outputs are sampled at the end of the cycle, or a cycle later if `dout` is registered.
+fn dout_bit(data: &TranData, tb: &TbParams, cycle: usize, i: usize) -> Result<bool> {
+    let t = (cycle + tb.sram.read_latency() - 1) as f64 * tb.clk_period;
+    let idx = data
+        .time
+        .idx_before_sorted(t)
+        .ok_or_else(|| anyhow!("Time {} was out of simulation range", t))?;
+    let name = format!("{}[{}]", "dout", i);
+    let rx_bit = data
+        .data
+        .get(&name)
+        .ok_or_else(|| anyhow!("Unable to find signal {}", &name))?
+        .get(idx)
+        .ok_or_else(|| anyhow!("Index {} was out of range for signal {}", idx, &name))?;
+    Ok(to_bit(rx_bit, tb.vdd)?)
+}
@@ -0,0 +1,114 @@
+//! Estimated Liberty models of time-to-digital converters.
+//!
+//! The output flip-flops are modeled using the timing of `sky130_fd_sc_hd__dfrtp_2`
+//! from the typical corner Liberty file of the standard cell library.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::decoder::inv_model;
+use crate::liberty::{
+    Bus, Cell, DffModel, Direction, InternalPower, Library, Pin, TableKind, TableTemplate, Timing,
+    TimingSense, TimingType, DEFAULT_LOADS, DEFAULT_SLEWS,
+};
+use crate::Result;
+
+use super::TdcParams;
+
+/// The standard cell library of the output flip-flops.
+const DFF_LIB: &str = "sky130_fd_sc_hd";
+/// The standard cell used for the output flip-flops.
+const DFF_CELL: &str = "sky130_fd_sc_hd__dfrtp_2";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TdcLibParams {
+    /// Name of the library and of the TDC cell.
+    pub name: String,
+    pub inner: TdcParams,
+    pub vdd: f64,
+    /// Area of the TDC in square microns.
+    pub area: f64,
+}
+
+/// An estimated Liberty library for a TDC.
+///
+/// `dout` is sampled on the rising edge of `b` and cleared while `reset_b` is low.
+pub fn tdc_library(params: &TdcLibParams, dff: &DffModel) -> Library {
+    let tdc = &params.inner;
+    let bits_out = tdc.bits_out();
+    let inv = inv_model(tdc.inv);
+    let delay_template = TableTemplate::delay(&DEFAULT_SLEWS, &DEFAULT_LOADS);
+    let power_template = TableTemplate::power(&DEFAULT_SLEWS);
+    let max_transition = DEFAULT_SLEWS.iter().copied().reduce(f64::max);
+
+    let input = |name: &str, cap: f64| {
+        let mut pin = Pin::new(name, Direction::Input);
+        pin.capacitance = Some(cap);
+        pin.max_transition = max_transition;
+        pin
+    };
+
+    // Every inverter of the delay chain and interpolation stages switches
+    // when `a` transitions.
+    let chain_invs = 2 * tdc.stages + 3 * (2 * tdc.stages - 1) + 3 * bits_out + 6;
+    let mut a = input("a", inv.cin);
+    a.internal_power.push(InternalPower::switching(
+        None,
+        &power_template,
+        chain_invs as f64 * inv.cin,
+        params.vdd,
+    ));
+    let mut b = input("b", bits_out as f64 * dff.clk_cap);
+    b.clock = true;
+    b.internal_power.push(InternalPower::switching(
+        None,
+        &power_template,
+        bits_out as f64 * dff.clk_cap,
+        params.vdd,
+    ));
+    let reset_b = input("reset_b", bits_out as f64 * dff.reset_b_cap);
+
+    let mut dout = Pin::new("dout", Direction::Output);
+    let tables = dff.driver.delay_tables(&delay_template);
+    dout.timing.push(Timing {
+        related_pin: "b".to_string(),
+        timing_type: TimingType::RisingEdge,
+        timing_sense: Some(TimingSense::NonUnate),
+        tables: tables.clone(),
+    });
+    dout.timing.push(Timing {
+        related_pin: "reset_b".to_string(),
+        timing_type: TimingType::Clear,
+        timing_sense: Some(TimingSense::PositiveUnate),
+        tables: tables
+            .into_iter()
+            .filter(|(kind, _)| matches!(kind, TableKind::CellFall | TableKind::FallTransition))
+            .collect(),
+    });
+
+    Library {
+        name: params.name.clone(),
+        voltage: params.vdd,
+        temperature: 25.,
+        templates: vec![delay_template],
+        power_templates: vec![power_template],
+        cells: vec![Cell {
+            name: params.name.clone(),
+            area: params.area,
+            leakage_power: None,
+            pins: vec![a, b, reset_b],
+            buses: vec![Bus {
+                width: bits_out,
+                pin: dout,
+                bit_capacitance: Vec::new(),
+            }],
+        }],
+    }
+}
+
+/// Writes the Liberty library of a TDC to `path`, modeling the output flip-flops
+/// with [`DffModel::load`].
+pub fn save_tdc_lib(path: impl AsRef<Path>, params: &TdcLibParams) -> Result<()> {
+    tdc_library(params, &DffModel::load(DFF_LIB, DFF_CELL)?).save(path)
+}
@@ -35,6 +35,7 @@ use super::decoder::layout::{DecoderGateParams, DecoderTap};
 use super::decoder::DecoderPhysicalDesign;
 use super::gate::{GateParams, Inv, PrimitiveGateParams};
 
+pub mod liberty;
 pub mod tb;
 
 pub struct Tdc {
@@ -1052,10 +1053,11 @@ impl Component for TappedRegisterN {
 #[cfg(test)]
 mod tests {
 
-    use crate::paths::{out_gds, out_spice};
+    use crate::paths::{out_gds, out_lib, out_spice};
     use crate::setup_ctx;
     use crate::tests::test_work_dir;
 
+    use super::liberty::{save_tdc_lib, TdcLibParams};
     use super::*;
 
     const INV_SIZING: PrimitiveGateParams = PrimitiveGateParams {
@@ -1117,6 +1119,18 @@ mod tests {
             .expect("failed to write layout");
     }
 
+    #[test]
+    fn test_tdc_abstract() {
+        let ctx = setup_ctx();
+        let work_dir = test_work_dir("test_tdc_abstract");
+        crate::abs::write_component_abstract::<Tdc>(
+            &ctx,
+            &TDC_PARAMS,
+            crate::paths::out_lef(&work_dir, "abstract"),
+        )
+        .expect("failed to write abstract");
+    }
+
     #[test]
     fn test_tdc() {
         let ctx = setup_ctx();
@@ -1126,27 +1140,28 @@ mod tests {
         let gds_path = out_gds(&work_dir, "layout");
         ctx.write_layout::<Tdc>(&TDC_PARAMS, &gds_path)
             .expect("failed to write layout");
+
+        let tdc = ctx.instantiate_layout::<Tdc>(&TDC_PARAMS).unwrap();
+        let name = tdc.cell().name();
+        let brect = tdc.brect();
+        save_tdc_lib(
+            out_lib(&work_dir, name),
+            &TdcLibParams {
+                name: name.to_string(),
+                inner: TDC_PARAMS,
+                vdd: 1.8,
+                area: brect.width() as f64 * brect.height() as f64 / 1e6,
+            },
+        )
+        .expect("failed to write lib");
+
         #[cfg(feature = "commercial")]
         {
-            use crate::liberate::save_tdc_lib;
             use crate::paths::out_verilog;
             use crate::verilog::save_tdc_verilog;
             use substrate::schematic::netlist::NetlistPurpose;
             use substrate::verification::pex::PexInput;
 
-            let tdc = ctx.instantiate_layout::<Tdc>(&TDC_PARAMS).unwrap();
-            let name = tdc.cell().name();
-
-            let lib_path = crate::paths::out_lib(&work_dir, name);
-            save_tdc_lib(
-                lib_path,
-                &crate::verilog::TdcParams {
-                    module_name: name.to_string(),
-                    data_width: TDC_PARAMS.bits_out(),
-                },
-            )
-            .expect("failed to write lib file from template");
-
             let verilog_path = out_verilog(&work_dir, name);
             save_tdc_verilog(
                 &verilog_path,
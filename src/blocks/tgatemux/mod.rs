@@ -181,7 +181,7 @@ impl Component for TappedTGateMux {
         let mut tiler = ArrayTiler::builder();
         tiler.push(tap);
 
-        for i in 0..4 {
+        for i in 0..self.params.mux_ratio {
             let params = TGateMuxParams {
                 idx: i,
                 ..self.params
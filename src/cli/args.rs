@@ -22,10 +22,17 @@ pub struct Args {
     pub output_dir: Option<PathBuf>,
 
     /// Generate LIB (setup, hold, and delay timing information).
-    #[cfg(feature = "commercial")]
     #[arg(long)]
     pub lib: bool,
 
+    /// Generate an estimated LIB from analytical delay models, without simulation.
+    #[arg(long)]
+    pub estimate_lib: bool,
+
+    /// Only generate the estimated LIB, skipping the netlist, layout, Verilog and LEF.
+    #[arg(long, conflicts_with = "lib")]
+    pub estimate_only: bool,
+
     /// Run DRC using Calibre.
     #[cfg(feature = "commercial")]
     #[arg(long)]
@@ -4,7 +4,7 @@ use std::path::PathBuf;
 
 use clap::Parser;
 
-use crate::blocks::sram::parse_sram_config;
+use crate::blocks::sram::{parse_sram_config, Ports};
 use crate::cli::args::Args;
 use crate::cli::progress::StepContext;
 use crate::plan::{execute_plan, generate_plan, ExecutePlanParams, TaskKey};
@@ -41,10 +41,25 @@ pub fn run() -> Result<()> {
     println!("SRAM parameters:");
     println!("\tNumber of words: {}", config.num_words);
     println!("\tData width: {}", config.data_width);
-    println!("\tMux ratio: {}", config.mux_ratio as usize);
+    if let Some(mux_ratio) = config.mux_ratio {
+        println!("\tMux ratio: {}", mux_ratio as usize);
+    }
     println!("\tWrite size: {}", config.write_size);
+    if config.ports == Ports::OneROneW {
+        println!("\tPorts: 1R1W");
+    }
+    println!("\tECC: {:?}", config.ecc);
+    if let Some(algorithm) = config.bist {
+        println!("\tBIST: {}", algorithm.name());
+    }
+    println!("\tSpare rows: {}", config.spare_rows);
+    println!("\tSpare columns: {}", config.spare_cols);
 
     let enabled_tasks = vec![
+        (
+            args.estimate_lib || args.estimate_only,
+            TaskKey::GenerateEstimateLib,
+        ),
         #[cfg(feature = "commercial")]
         (args.drc, TaskKey::RunDrc),
         #[cfg(feature = "commercial")]
@@ -54,7 +69,6 @@ pub fn run() -> Result<()> {
             args.pex || (args.lib && config.pex_level.is_some()),
             TaskKey::RunPex,
         ),
-        #[cfg(feature = "commercial")]
         (args.lib, TaskKey::GenerateLib),
         #[cfg(feature = "commercial")]
         (args.all, TaskKey::All),
@@ -69,10 +83,25 @@ pub fn run() -> Result<()> {
     let plan = ctx.check(generate_plan(&config))?;
     ctx.finish(TaskKey::GeneratePlan);
 
+    if let Some(floorplan) = plan.floorplan {
+        println!(
+            "Chose mux ratio {}{}, estimated at {:.1} x {:.1} um (aspect ratio {:.2})\n",
+            floorplan.mux_ratio as usize,
+            if floorplan.split {
+                " with a split array"
+            } else {
+                ""
+            },
+            floorplan.width_um(),
+            floorplan.height_um(),
+            floorplan.aspect_ratio(),
+        );
+    }
+
     let work_dir = if let Some(output_dir) = args.output_dir {
         output_dir
     } else {
-        PathBuf::from(plan.sram_params.name().as_str())
+        PathBuf::from(plan.name().as_str())
     };
     std::fs::create_dir_all(&work_dir)?;
     let work_dir = canonicalize(work_dir)?;
@@ -81,6 +110,7 @@ pub fn run() -> Result<()> {
         work_dir: &work_dir,
         plan: &plan,
         tasks: &tasks,
+        estimate_only: args.estimate_only,
         ctx: Some(&mut ctx),
         #[cfg(feature = "commercial")]
         pex_level: config.pex_level,
@@ -67,6 +67,12 @@ impl StepContext {
                 progress_bar: ProgressBar::new_spinner(),
                 disabled: false,
             },
+            Step {
+                desc: "Generate estimated LIB".to_string(),
+                key: TaskKey::GenerateEstimateLib,
+                progress_bar: ProgressBar::new_spinner(),
+                disabled: !tasks.contains(&TaskKey::GenerateEstimateLib),
+            },
             #[cfg(feature = "commercial")]
             Step {
                 desc: "Run DRC".to_string(),
@@ -88,7 +94,6 @@ impl StepContext {
                 progress_bar: ProgressBar::new_spinner(),
                 disabled: !tasks.contains(&TaskKey::RunPex) && !tasks.contains(&TaskKey::All),
             },
-            #[cfg(feature = "commercial")]
             Step {
                 desc: "Generate LIB".to_string(),
                 key: TaskKey::GenerateLib,
@@ -162,12 +167,21 @@ impl StepContext {
     }
 
     pub fn finish(&mut self, key: TaskKey) {
+        self.complete(key, StepStatus::Done);
+    }
+
+    /// Marks a step that does not apply to the current plan as skipped.
+    pub fn skip(&mut self, key: TaskKey) {
+        self.complete(key, StepStatus::Skipped);
+    }
+
+    fn complete(&mut self, key: TaskKey, status: StepStatus) {
         if let Some(current_step) = self.current_step() {
             if current_step.key != key {
                 panic!("A step was completed out of order");
             }
 
-            current_step.set_status(StepStatus::Done, None);
+            current_step.set_status(status, None);
 
             self.advance();
 
@@ -28,10 +28,12 @@ use substrate::verification::simulation::{Simulator, SimulatorOpts};
 use tera::Tera;
 
 pub mod abs;
+pub mod antenna;
 pub mod blocks;
 pub mod cli;
 #[cfg(feature = "commercial")]
 pub mod liberate;
+pub mod liberty;
 pub mod measure;
 pub mod paths;
 pub mod pex;
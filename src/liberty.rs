@@ -0,0 +1,1059 @@
+//! A typed model of Liberty timing libraries.
+//!
+//! All quantities are stored in SI units (seconds, farads, square microns for area)
+//! and converted to library units when written.
+
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+
+use crate::{Result, SKY130_OPEN_PDK_ROOT};
+
+/// Library time unit in seconds.
+const TIME_UNIT: f64 = 1e-9;
+/// Library capacitance unit in farads.
+const CAP_UNIT: f64 = 1e-12;
+/// Library leakage power unit in watts.
+const POWER_UNIT: f64 = 1e-9;
+/// Library internal energy unit in joules, derived from the voltage and capacitance units.
+const ENERGY_UNIT: f64 = 1e-12;
+
+/// Default input transition times at which tables are evaluated, in seconds.
+pub const DEFAULT_SLEWS: [f64; 3] = [40e-12, 160e-12, 640e-12];
+/// Default output loads at which tables are evaluated, in farads.
+pub const DEFAULT_LOADS: [f64; 3] = [2e-15, 10e-15, 50e-15];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Library {
+    pub name: String,
+    /// Nominal supply voltage.
+    pub voltage: f64,
+    /// Nominal temperature in degrees Celsius.
+    pub temperature: f64,
+    pub templates: Vec<TableTemplate>,
+    /// Templates of [`InternalPower`] tables.
+    pub power_templates: Vec<TableTemplate>,
+    pub cells: Vec<Cell>,
+}
+
+/// A lookup table template, referenced by name from [`Table`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableTemplate {
+    pub name: String,
+    /// Each variable along with its default index values.
+    pub variables: Vec<(TableVariable, Vec<f64>)>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TableVariable {
+    InputNetTransition,
+    TotalOutputNetCapacitance,
+    RelatedPinTransition,
+    ConstrainedPinTransition,
+    InputTransitionTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub name: String,
+    /// Area in square microns.
+    pub area: f64,
+    /// Leakage power drawn from `vdd` in watts.
+    pub leakage_power: Option<f64>,
+    pub pins: Vec<Pin>,
+    pub buses: Vec<Bus>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Direction {
+    Input,
+    Output,
+    Inout,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pin {
+    pub name: String,
+    pub direction: Direction,
+    /// Whether the pin is a clock pin.
+    pub clock: bool,
+    /// Input capacitance in farads.
+    pub capacitance: Option<f64>,
+    /// Maximum transition time in seconds.
+    pub max_transition: Option<f64>,
+    /// Minimum period of a clock pin in seconds.
+    pub min_period: Option<f64>,
+    pub timing: Vec<Timing>,
+    pub internal_power: Vec<InternalPower>,
+}
+
+impl Pin {
+    pub fn new(name: impl Into<String>, direction: Direction) -> Self {
+        Self {
+            name: name.into(),
+            direction,
+            clock: false,
+            capacitance: None,
+            max_transition: None,
+            min_period: None,
+            timing: Vec::new(),
+            internal_power: Vec::new(),
+        }
+    }
+}
+
+/// A bus of pins that share the same attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bus {
+    pub width: usize,
+    /// The attributes of every bit of the bus.
+    ///
+    /// The name of the pin is used as the name of the bus.
+    pub pin: Pin,
+    /// Capacitance of each bit in farads, overriding the capacitance of [`Bus::pin`].
+    ///
+    /// Empty if every bit has the capacitance of [`Bus::pin`].
+    pub bit_capacitance: Vec<f64>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TimingType {
+    Combinational,
+    RisingEdge,
+    /// An asynchronous clear, such as an active low reset.
+    Clear,
+    SetupRising,
+    HoldRising,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TimingSense {
+    PositiveUnate,
+    NegativeUnate,
+    NonUnate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub related_pin: String,
+    pub timing_type: TimingType,
+    pub timing_sense: Option<TimingSense>,
+    pub tables: Vec<(TableKind, Table)>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TableKind {
+    CellRise,
+    CellFall,
+    RiseTransition,
+    FallTransition,
+    RiseConstraint,
+    FallConstraint,
+}
+
+/// Energy dissipated inside a cell when a pin transitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InternalPower {
+    /// The input pin whose transition causes the dissipation, if any.
+    pub related_pin: Option<String>,
+    /// A boolean condition on the cell's pins under which the tables apply, if any.
+    pub when: Option<String>,
+    pub tables: Vec<(PowerKind, Table)>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PowerKind {
+    RisePower,
+    FallPower,
+}
+
+/// A table of times in seconds, or of energies in joules for [`InternalPower`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// The name of the [`TableTemplate`] of this table.
+    pub template: String,
+    /// One index per template variable.
+    pub indices: Vec<Vec<f64>>,
+    /// Table values, with one row per value of the first index.
+    ///
+    /// One-dimensional tables have a single row.
+    pub values: Vec<Vec<f64>>,
+}
+
+impl TableVariable {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::InputNetTransition => "input_net_transition",
+            Self::TotalOutputNetCapacitance => "total_output_net_capacitance",
+            Self::RelatedPinTransition => "related_pin_transition",
+            Self::ConstrainedPinTransition => "constrained_pin_transition",
+            Self::InputTransitionTime => "input_transition_time",
+        }
+    }
+
+    fn unit(&self) -> f64 {
+        match self {
+            Self::TotalOutputNetCapacitance => CAP_UNIT,
+            _ => TIME_UNIT,
+        }
+    }
+}
+
+impl Direction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Output => "output",
+            Self::Inout => "inout",
+        }
+    }
+}
+
+impl TimingType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Combinational => "combinational",
+            Self::RisingEdge => "rising_edge",
+            Self::Clear => "clear",
+            Self::SetupRising => "setup_rising",
+            Self::HoldRising => "hold_rising",
+        }
+    }
+}
+
+impl TimingSense {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::PositiveUnate => "positive_unate",
+            Self::NegativeUnate => "negative_unate",
+            Self::NonUnate => "non_unate",
+        }
+    }
+}
+
+impl PowerKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::RisePower => "rise_power",
+            Self::FallPower => "fall_power",
+        }
+    }
+}
+
+impl TableKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::CellRise => "cell_rise",
+            Self::CellFall => "cell_fall",
+            Self::RiseTransition => "rise_transition",
+            Self::FallTransition => "fall_transition",
+            Self::RiseConstraint => "rise_constraint",
+            Self::FallConstraint => "fall_constraint",
+        }
+    }
+}
+
+impl TableTemplate {
+    /// A delay template indexed by input transition and output load.
+    pub fn delay(slews: &[f64], loads: &[f64]) -> Self {
+        Self {
+            name: format!("delay_template_{}x{}", slews.len(), loads.len()),
+            variables: vec![
+                (TableVariable::InputNetTransition, slews.to_vec()),
+                (TableVariable::TotalOutputNetCapacitance, loads.to_vec()),
+            ],
+        }
+    }
+
+    /// A constraint template indexed by the transition of the constrained pin.
+    pub fn constraint(slews: &[f64]) -> Self {
+        Self {
+            name: format!("constraint_template_{}", slews.len()),
+            variables: vec![(TableVariable::ConstrainedPinTransition, slews.to_vec())],
+        }
+    }
+
+    /// A power template indexed by input transition.
+    pub fn power(slews: &[f64]) -> Self {
+        Self {
+            name: format!("power_template_{}", slews.len()),
+            variables: vec![(TableVariable::InputTransitionTime, slews.to_vec())],
+        }
+    }
+
+    /// A table using this template and its default indices.
+    pub fn table(&self, values: Vec<Vec<f64>>) -> Table {
+        Table {
+            template: self.name.clone(),
+            indices: self.variables.iter().map(|(_, i)| i.clone()).collect(),
+            values,
+        }
+    }
+}
+
+/// A first-order RC model of the path from an input pin to an output pin,
+/// used to estimate timing tables without simulation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DriverModel {
+    /// Delay through the path with no load, in seconds.
+    pub intrinsic: f64,
+    /// Resistance of the output driver in ohms.
+    pub res: f64,
+    /// Parasitic capacitance at the output in farads.
+    pub cout: f64,
+}
+
+impl DriverModel {
+    /// The estimated delay given the input transition time and output load.
+    pub fn delay(&self, slew: f64, load: f64) -> f64 {
+        self.intrinsic + slew / 2. + self.res * (self.cout + load)
+    }
+
+    /// The estimated 10-90% output transition time given the output load.
+    pub fn transition(&self, load: f64) -> f64 {
+        2.2 * self.res * (self.cout + load)
+    }
+
+    /// Delay and transition tables for a template created by [`TableTemplate::delay`].
+    ///
+    /// Rising and falling outputs are assumed to be symmetric.
+    pub fn delay_tables(&self, template: &TableTemplate) -> Vec<(TableKind, Table)> {
+        let slews = &template.variables[0].1;
+        let loads = &template.variables[1].1;
+        let delay = template.table(
+            slews
+                .iter()
+                .map(|&slew| loads.iter().map(|&load| self.delay(slew, load)).collect())
+                .collect(),
+        );
+        let transition = template.table(
+            slews
+                .iter()
+                .map(|_| loads.iter().map(|&load| self.transition(load)).collect())
+                .collect(),
+        );
+        vec![
+            (TableKind::CellRise, delay.clone()),
+            (TableKind::CellFall, delay),
+            (TableKind::RiseTransition, transition.clone()),
+            (TableKind::FallTransition, transition),
+        ]
+    }
+}
+
+impl InternalPower {
+    /// Internal power for charging and discharging a capacitance `cap` to `vdd`,
+    /// using a template created by [`TableTemplate::power`].
+    pub fn switching(
+        related_pin: Option<String>,
+        template: &TableTemplate,
+        cap: f64,
+        vdd: f64,
+    ) -> Self {
+        let energy = 0.5 * cap * vdd * vdd;
+        Self::constant(related_pin, None, template, energy, energy)
+    }
+
+    /// Internal power that dissipates `rise` and `fall` joules per transition
+    /// regardless of slew, using a template created by [`TableTemplate::power`].
+    pub fn constant(
+        related_pin: Option<String>,
+        when: Option<String>,
+        template: &TableTemplate,
+        rise: f64,
+        fall: f64,
+    ) -> Self {
+        let table = |energy| template.table(vec![vec![energy; template.variables[0].1.len()]]);
+        Self {
+            related_pin,
+            when,
+            tables: vec![
+                (PowerKind::RisePower, table(rise)),
+                (PowerKind::FallPower, table(fall)),
+            ],
+        }
+    }
+}
+
+impl Library {
+    fn template(&self, name: &str) -> Option<&TableTemplate> {
+        self.templates
+            .iter()
+            .chain(self.power_templates.iter())
+            .find(|t| t.name == name)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_string())?;
+
+        Ok(())
+    }
+}
+
+fn bus_type_name(cell: &Cell, bus: &Bus) -> String {
+    format!("bus_{}_{}", cell.name, bus.pin.name)
+}
+
+fn fmt_values(values: &[f64], unit: f64) -> String {
+    values
+        .iter()
+        .map(|v| format!("{:.6}", v / unit))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Writes Liberty groups with consistent indentation.
+struct LibWriter<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    depth: usize,
+}
+
+impl<'a, 'b> LibWriter<'a, 'b> {
+    fn line(&mut self, line: impl Display) -> fmt::Result {
+        writeln!(self.f, "{:indent$}{line}", "", indent = 2 * self.depth)
+    }
+
+    fn attr(&mut self, name: &str, value: impl Display) -> fmt::Result {
+        self.line(format_args!("{name} : {value};"))
+    }
+
+    fn open(&mut self, group: &str, name: impl Display) -> fmt::Result {
+        self.line(format_args!("{group} ({name}) {{"))?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn close(&mut self) -> fmt::Result {
+        self.depth -= 1;
+        self.line("}")
+    }
+
+    fn pin(&mut self, lib: &Library, pin: &Pin, name: &str, bus: bool) -> fmt::Result {
+        self.open("pin", name)?;
+        if !bus {
+            self.attr("direction", pin.direction.as_str())?;
+        }
+        if pin.clock {
+            self.attr("clock", "true")?;
+        }
+        self.attr("related_power_pin", "vdd")?;
+        self.attr("related_ground_pin", "vss")?;
+        if let Some(cap) = pin.capacitance {
+            self.attr("capacitance", format_args!("{:.6}", cap / CAP_UNIT))?;
+        }
+        if let Some(max_transition) = pin.max_transition {
+            self.attr(
+                "max_transition",
+                format_args!("{:.6}", max_transition / TIME_UNIT),
+            )?;
+        }
+        if let Some(min_period) = pin.min_period {
+            self.attr("min_period", format_args!("{:.6}", min_period / TIME_UNIT))?;
+        }
+        for timing in pin.timing.iter() {
+            self.timing(lib, timing)?;
+        }
+        for power in pin.internal_power.iter() {
+            self.open("internal_power", "")?;
+            if let Some(related_pin) = &power.related_pin {
+                self.attr("related_pin", format_args!("\"{related_pin}\""))?;
+            }
+            if let Some(when) = &power.when {
+                self.attr("when", format_args!("\"{when}\""))?;
+            }
+            self.attr("related_pg_pin", "vdd")?;
+            for (kind, table) in power.tables.iter() {
+                self.table(lib, kind.as_str(), table, ENERGY_UNIT)?;
+            }
+            self.close()?;
+        }
+        self.close()
+    }
+
+    fn timing(&mut self, lib: &Library, timing: &Timing) -> fmt::Result {
+        self.open("timing", "")?;
+        self.attr("related_pin", format_args!("\"{}\"", timing.related_pin))?;
+        self.attr("timing_type", timing.timing_type.as_str())?;
+        if let Some(sense) = timing.timing_sense {
+            self.attr("timing_sense", sense.as_str())?;
+        }
+        for (kind, table) in timing.tables.iter() {
+            self.table(lib, kind.as_str(), table, TIME_UNIT)?;
+        }
+        self.close()
+    }
+
+    fn table(&mut self, lib: &Library, group: &str, table: &Table, unit: f64) -> fmt::Result {
+        self.open(group, &table.template)?;
+        let variables = lib
+            .template(&table.template)
+            .map(|t| t.variables.iter().map(|(v, _)| *v).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for (i, index) in table.indices.iter().enumerate() {
+            let unit = variables.get(i).map(|v| v.unit()).unwrap_or(TIME_UNIT);
+            self.line(format_args!(
+                "index_{} (\"{}\");",
+                i + 1,
+                fmt_values(index, unit)
+            ))?;
+        }
+        let rows = table
+            .values
+            .iter()
+            .map(|row| format!("\"{}\"", fmt_values(row, unit)))
+            .collect::<Vec<_>>();
+        if let [row] = rows.as_slice() {
+            self.line(format_args!("values ({row});"))?;
+        } else {
+            self.line("values ( \\")?;
+            for (i, row) in rows.iter().enumerate() {
+                let sep = if i + 1 < rows.len() { "," } else { "" };
+                self.line(format_args!("  {row}{sep} \\"))?;
+            }
+            self.line(");")?;
+        }
+        self.close()
+    }
+}
+
+impl Display for Library {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut w = LibWriter { f, depth: 0 };
+        let voltage = format!("{:.2}", self.voltage);
+
+        w.open("library", &self.name)?;
+        w.attr("delay_model", "table_lookup")?;
+        w.line("capacitive_load_unit (1,pf);")?;
+        w.attr("time_unit", "\"1ns\"")?;
+        w.attr("voltage_unit", "\"1V\"")?;
+        w.attr("current_unit", "\"1mA\"")?;
+        w.attr("leakage_power_unit", "\"1nW\"")?;
+        w.attr("pulling_resistance_unit", "\"1kohm\"")?;
+        w.attr("nom_process", 1)?;
+        w.attr("nom_temperature", self.temperature)?;
+        w.attr("nom_voltage", &voltage)?;
+        w.attr("input_threshold_pct_rise", 50)?;
+        w.attr("input_threshold_pct_fall", 50)?;
+        w.attr("output_threshold_pct_rise", 50)?;
+        w.attr("output_threshold_pct_fall", 50)?;
+        w.attr("slew_lower_threshold_pct_rise", 10)?;
+        w.attr("slew_lower_threshold_pct_fall", 10)?;
+        w.attr("slew_upper_threshold_pct_rise", 90)?;
+        w.attr("slew_upper_threshold_pct_fall", 90)?;
+        w.attr("slew_derate_from_library", 1)?;
+        w.attr("bus_naming_style", "\"%s[%d]\"")?;
+        w.line(format_args!("voltage_map (vdd, {voltage});"))?;
+        w.line("voltage_map (vss, 0);")?;
+        w.open("operating_conditions", "nominal")?;
+        w.attr("process", 1)?;
+        w.attr("temperature", self.temperature)?;
+        w.attr("voltage", &voltage)?;
+        w.close()?;
+        w.attr("default_operating_conditions", "nominal")?;
+
+        for (group, template) in self
+            .templates
+            .iter()
+            .map(|t| ("lu_table_template", t))
+            .chain(
+                self.power_templates
+                    .iter()
+                    .map(|t| ("power_lut_template", t)),
+            )
+        {
+            w.open(group, &template.name)?;
+            for (i, (variable, _)) in template.variables.iter().enumerate() {
+                w.attr(&format!("variable_{}", i + 1), variable.as_str())?;
+            }
+            for (i, (variable, index)) in template.variables.iter().enumerate() {
+                w.line(format_args!(
+                    "index_{} (\"{}\");",
+                    i + 1,
+                    fmt_values(index, variable.unit())
+                ))?;
+            }
+            w.close()?;
+        }
+
+        for cell in self.cells.iter() {
+            for bus in cell.buses.iter() {
+                w.open("type", bus_type_name(cell, bus))?;
+                w.attr("base_type", "array")?;
+                w.attr("data_type", "bit")?;
+                w.attr("bit_width", bus.width)?;
+                w.attr("bit_from", bus.width - 1)?;
+                w.attr("bit_to", 0)?;
+                w.attr("downto", "true")?;
+                w.close()?;
+            }
+        }
+
+        for cell in self.cells.iter() {
+            w.open("cell", &cell.name)?;
+            w.attr("area", format_args!("{:.3}", cell.area))?;
+            w.attr("dont_use", "true")?;
+            w.attr("interface_timing", "true")?;
+            for (name, pg_type) in [("vdd", "primary_power"), ("vss", "primary_ground")] {
+                w.open("pg_pin", name)?;
+                w.attr("pg_type", pg_type)?;
+                w.attr("voltage_name", name)?;
+                w.close()?;
+            }
+            if let Some(leakage) = cell.leakage_power {
+                w.attr(
+                    "cell_leakage_power",
+                    format_args!("{:.6}", leakage / POWER_UNIT),
+                )?;
+                w.open("leakage_power", "")?;
+                w.attr("value", format_args!("{:.6}", leakage / POWER_UNIT))?;
+                w.attr("related_pg_pin", "vdd")?;
+                w.close()?;
+            }
+            for pin in cell.pins.iter() {
+                w.pin(self, pin, &pin.name, false)?;
+            }
+            for bus in cell.buses.iter() {
+                w.open("bus", &bus.pin.name)?;
+                w.attr("bus_type", bus_type_name(cell, bus))?;
+                w.attr("direction", bus.pin.direction.as_str())?;
+                for i in 0..bus.width {
+                    let name = format!("{}[{i}]", bus.pin.name);
+                    match bus.bit_capacitance.get(i) {
+                        Some(&cap) => {
+                            let mut pin = bus.pin.clone();
+                            pin.capacitance = Some(cap);
+                            w.pin(self, &pin, &name, true)?;
+                        }
+                        None => w.pin(self, &bus.pin, &name, true)?,
+                    }
+                }
+                w.close()?;
+            }
+            w.close()?;
+        }
+
+        w.close()
+    }
+}
+
+/// Finds `name (` in `text` at or after `from`, where `name` is not part of a longer identifier.
+///
+/// Returns the positions of `name` and of the opening parenthesis.
+fn find_call(text: &str, name: &str, from: usize) -> Option<(usize, usize)> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut start = from;
+    while let Some(i) = text[start..].find(name) {
+        let i = start + i;
+        let end = i + name.len();
+        let paren = end + text[end..].len() - text[end..].trim_start().len();
+        if !text[..i].ends_with(is_ident) && text[paren..].starts_with('(') {
+            return Some((i, paren));
+        }
+        start = end;
+    }
+    None
+}
+
+/// The arguments between the parenthesis at `paren` and the next closing parenthesis,
+/// and the position just after the closing parenthesis.
+fn call_args(text: &str, paren: usize) -> Option<(&str, usize)> {
+    let close = paren + text[paren..].find(')')?;
+    Some((&text[paren + 1..close], close + 1))
+}
+
+/// The names and bodies of the groups `group (name) { ... }` in Liberty source `text`, in order.
+///
+/// Names are returned without quotes. Groups nested in a returned group are skipped.
+pub fn groups<'a>(text: &'a str, group: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+    let mut from = 0;
+    std::iter::from_fn(move || loop {
+        let (_, paren) = find_call(text, group, from)?;
+        let (args, after) = call_args(text, paren)?;
+        let open = after + text[after..].len() - text[after..].trim_start().len();
+        if !text[open..].starts_with('{') {
+            // A complex attribute with the same name as the group.
+            from = after;
+            continue;
+        }
+        let mut depth = 0;
+        for (i, c) in text[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        from = open + i + 1;
+                        return Some((args.trim().trim_matches('"'), &text[open + 1..open + i]));
+                    }
+                }
+                _ => {}
+            }
+        }
+        return None;
+    })
+}
+
+/// The body of the first group `group (name) { ... }` in Liberty source `text`.
+pub fn find_group<'a>(text: &'a str, group: &'a str, name: &str) -> Option<&'a str> {
+    groups(text, group)
+        .find(|&(n, _)| n == name)
+        .map(|(_, body)| body)
+}
+
+/// The value of the first simple attribute `name : value;` in Liberty source `text`,
+/// without quotes.
+pub fn simple_attr<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    text.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == name).then(|| value.trim().trim_end_matches(';').trim().trim_matches('"'))
+    })
+}
+
+/// The numbers in the quoted strings of the first complex attribute `name ("...", ...);`
+/// in Liberty source `text`, with one vector per string.
+pub fn complex_attr(text: &str, name: &str) -> Option<Vec<Vec<f64>>> {
+    let (_, paren) = find_call(text, name, 0)?;
+    let (args, _) = call_args(text, paren)?;
+    args.split('"')
+        .skip(1)
+        .step_by(2)
+        .map(|values| {
+            values
+                .split(',')
+                .map(|value| value.trim().parse().ok())
+                .collect()
+        })
+        .collect()
+}
+
+/// The Liberty file of the typical corner of the standard cell library `lib`.
+pub fn stdcell_lib_path(lib: &str) -> PathBuf {
+    PathBuf::from(SKY130_OPEN_PDK_ROOT).join(format!(
+        "libraries/{lib}/latest/timing/{lib}__tt_025C_1v80.lib"
+    ))
+}
+
+/// A model of a standard cell flip-flop with an active-low reset.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DffModel {
+    /// Clock pin capacitance in farads.
+    pub clk_cap: f64,
+    /// Data pin capacitance in farads.
+    pub d_cap: f64,
+    /// Reset pin capacitance in farads.
+    pub reset_b_cap: f64,
+    /// Clock to output and reset to output delay.
+    pub driver: DriverModel,
+    /// Setup time of the data pin in seconds.
+    pub setup: f64,
+    /// Hold time of the data pin in seconds.
+    pub hold: f64,
+}
+
+impl DffModel {
+    /// Reads the model of `cell` from the Liberty file of `lib` at [`stdcell_lib_path`].
+    pub fn load(lib: &str, cell: &str) -> Result<Self> {
+        let path = stdcell_lib_path(lib);
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("failed to read standard cell library {path:?}: {e}"))?;
+        Self::from_liberty(&text, cell)
+    }
+
+    /// Reads the model of `cell` from the source of a standard cell Liberty file
+    /// with time and capacitance units of 1ns and 1pF.
+    ///
+    /// The driver is a least squares fit of the worse of the rising and falling
+    /// clock to output delays at the smallest clock slew. The setup and hold times
+    /// are the worse of the rising and falling constraints at the smallest slews.
+    pub fn from_liberty(text: &str, cell: &str) -> Result<Self> {
+        if simple_attr(text, "time_unit") != Some("1ns") {
+            bail!("expected a time unit of 1ns");
+        }
+        let name = cell;
+        let cell =
+            find_group(text, "cell", name).ok_or_else(|| anyhow!("cell `{name}` not found"))?;
+        let pin_cap = |name: &str| -> Result<f64> {
+            let cap = find_group(cell, "pin", name)
+                .and_then(|pin| simple_attr(pin, "capacitance"))
+                .ok_or_else(|| anyhow!("capacitance of pin `{name}` not found"))?;
+            Ok(cap.parse::<f64>()? * 1e-12)
+        };
+
+        let timing = clk_timing(cell, "Q", None)?;
+        let slew = table_attr(timing, "cell_rise", "index_1")?[0][0] * 1e-9;
+        let loads = table_attr(timing, "cell_rise", "index_2")?[0]
+            .iter()
+            .map(|load| load * 1e-12)
+            .collect::<Vec<_>>();
+        let rise = table_attr(timing, "cell_rise", "values")?;
+        let fall = table_attr(timing, "cell_fall", "values")?;
+        let delays = rise[0]
+            .iter()
+            .zip(fall[0].iter())
+            .map(|(rise, fall)| rise.max(*fall) * 1e-9)
+            .collect::<Vec<_>>();
+        if loads.len() < 2 || loads.len() != delays.len() {
+            bail!("clock to output delay table of `{name}` is malformed");
+        }
+
+        let n = loads.len() as f64;
+        let mean_load = loads.iter().sum::<f64>() / n;
+        let mean_delay = delays.iter().sum::<f64>() / n;
+        let res = loads
+            .iter()
+            .zip(delays.iter())
+            .map(|(load, delay)| (load - mean_load) * (delay - mean_delay))
+            .sum::<f64>()
+            / loads
+                .iter()
+                .map(|load| (load - mean_load).powi(2))
+                .sum::<f64>();
+
+        let constraint = |timing_type: &str| -> Result<f64> {
+            let timing = clk_timing(cell, "D", Some(timing_type))?;
+            let rise = table_attr(timing, "rise_constraint", "values")?;
+            let fall = table_attr(timing, "fall_constraint", "values")?;
+            Ok(rise[0][0].max(fall[0][0]) * 1e-9)
+        };
+
+        Ok(Self {
+            clk_cap: pin_cap("CLK")?,
+            d_cap: pin_cap("D")?,
+            reset_b_cap: pin_cap("RESET_B")?,
+            driver: DriverModel {
+                intrinsic: mean_delay - res * mean_load - slew / 2.,
+                res,
+                cout: 0.,
+            },
+            setup: constraint("setup_rising")?,
+            hold: constraint("hold_rising")?,
+        })
+    }
+}
+
+/// The first timing group of `pin` of a Liberty `cell` relative to `CLK`,
+/// restricted to the given timing type if any.
+fn clk_timing<'a>(cell: &'a str, pin: &str, timing_type: Option<&str>) -> Result<&'a str> {
+    let body = find_group(cell, "pin", pin).ok_or_else(|| anyhow!("pin `{pin}` not found"))?;
+    groups(body, "timing")
+        .map(|(_, body)| body)
+        .find(|body| {
+            simple_attr(body, "related_pin") == Some("CLK")
+                && timing_type.map_or(true, |t| simple_attr(body, "timing_type") == Some(t))
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "{} timing of pin `{pin}` not found",
+                timing_type.unwrap_or("clock")
+            )
+        })
+}
+
+/// The attribute `attr` of the first `kind` table of a Liberty timing group.
+fn table_attr(timing: &str, kind: &str, attr: &str) -> Result<Vec<Vec<f64>>> {
+    let (_, body) = groups(timing, kind)
+        .next()
+        .ok_or_else(|| anyhow!("`{kind}` table not found"))?;
+    complex_attr(body, attr).ok_or_else(|| anyhow!("`{kind}` has no `{attr}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_liberty() {
+        let template = TableTemplate::delay(&[1e-11, 1e-10], &[1e-15, 1e-14]);
+        let power_template = TableTemplate::power(&[1e-11, 1e-10]);
+        let table = template.table(vec![vec![1e-10, 2e-10], vec![3e-10, 4e-10]]);
+        let mut clk = Pin::new("clk", Direction::Input);
+        clk.clock = true;
+        clk.capacitance = Some(5e-15);
+        clk.internal_power
+            .push(InternalPower::switching(None, &power_template, 10e-15, 1.8));
+        clk.internal_power.push(InternalPower::constant(
+            None,
+            Some("ce & !we".to_string()),
+            &power_template,
+            2e-12,
+            0.,
+        ));
+        let mut dout = Pin::new("dout", Direction::Output);
+        dout.timing.push(Timing {
+            related_pin: "clk".to_string(),
+            timing_type: TimingType::RisingEdge,
+            timing_sense: Some(TimingSense::NonUnate),
+            tables: vec![(TableKind::CellRise, table)],
+        });
+        let lib = Library {
+            name: "test_lib".to_string(),
+            voltage: 1.8,
+            temperature: 25.,
+            templates: vec![template],
+            power_templates: vec![power_template],
+            cells: vec![Cell {
+                name: "test_cell".to_string(),
+                area: 100.,
+                leakage_power: Some(1e-9),
+                pins: vec![clk],
+                buses: vec![Bus {
+                    width: 2,
+                    pin: dout,
+                    bit_capacitance: vec![1e-15, 3e-15],
+                }],
+            }],
+        };
+
+        let lib = lib.to_string();
+        assert!(lib.starts_with("library (test_lib) {\n"));
+        assert!(lib.contains("  type (bus_test_cell_dout) {\n"));
+        assert!(lib.contains("      capacitance : 0.005000;\n"));
+        assert!(lib.contains("      pin (dout[1]) {\n"));
+        assert!(lib.contains("        capacitance : 0.003000;\n"));
+        assert!(lib.contains("        when : \"ce & !we\";\n"));
+        assert!(lib.contains("values (\"2.000000, 2.000000\");"));
+        assert!(lib.contains("index_2 (\"0.001000, 0.010000\");"));
+        assert!(lib.contains("  \"0.100000, 0.200000\", \\\n"));
+        assert!(lib.contains("  power_lut_template (power_template_2) {\n"));
+        assert!(lib.contains("values (\"0.016200, 0.016200\");"));
+        assert!(lib.contains("    cell_leakage_power : 1.000000;\n"));
+        assert_eq!(lib.matches('{').count(), lib.matches('}').count());
+    }
+
+    #[test]
+    fn test_read_liberty() {
+        let lib = r#"
+library (test_lib) {
+  time_unit : "1ns";
+  cell ("inv_1") {
+    pin ("A") {
+      capacitance : 0.0020000000;
+    }
+  }
+  cell ("buf_1") {
+    pin ("A") {
+      capacitance : 0.0010000000;
+    }
+    pin ("X") {
+      timing () {
+        related_pin : "A";
+        cell_rise ("del_2_2") {
+          index_1 ("0.01, 0.1");
+          index_2("0.001, 0.01");
+          values ("0.10, 0.20", \
+                  "0.15, 0.25");
+        }
+      }
+    }
+  }
+}
+"#;
+        assert_eq!(simple_attr(lib, "time_unit"), Some("1ns"));
+        let cells = groups(lib, "cell")
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(cells, ["inv_1", "buf_1"]);
+
+        let cell = find_group(lib, "cell", "buf_1").unwrap();
+        let a = find_group(cell, "pin", "A").unwrap();
+        assert_eq!(simple_attr(a, "capacitance"), Some("0.0010000000"));
+        let x = find_group(cell, "pin", "X").unwrap();
+        let (_, timing) = groups(x, "timing").next().unwrap();
+        assert_eq!(simple_attr(timing, "related_pin"), Some("A"));
+        let table = find_group(timing, "cell_rise", "del_2_2").unwrap();
+        assert_eq!(
+            complex_attr(table, "index_2"),
+            Some(vec![vec![0.001, 0.01]])
+        );
+        assert_eq!(
+            complex_attr(table, "values"),
+            Some(vec![vec![0.10, 0.20], vec![0.15, 0.25]])
+        );
+        assert!(find_group(lib, "cell", "nand2_1").is_none());
+    }
+
+    #[test]
+    fn test_dff_model_from_liberty() {
+        let lib = r#"
+library (stdcells) {
+  time_unit : "1ns";
+  cell ("sky130_fd_sc_hd__dfrtp_2") {
+    pin ("CLK") {
+      capacitance : 0.0018000000;
+    }
+    pin ("Q") {
+      timing () {
+        related_pin : "RESET_B";
+        cell_fall ("del_1_3") {
+          index_1 ("0.01");
+          index_2 ("0.0005, 0.01, 0.1");
+          values ("0.9, 0.9, 0.9");
+        }
+      }
+      timing () {
+        related_pin : "CLK";
+        cell_rise ("del_1_3") {
+          index_1 ("0.01");
+          index_2 ("0.0005, 0.01, 0.1");
+          values ("0.302, 0.34, 0.7");
+        }
+        cell_fall ("del_1_3") {
+          index_1 ("0.01");
+          index_2 ("0.0005, 0.01, 0.1");
+          values ("0.2, 0.25, 0.6");
+        }
+      }
+    }
+    pin ("RESET_B") {
+      capacitance : 0.0035000000;
+    }
+    pin ("D") {
+      capacitance : 0.0017000000;
+      timing () {
+        related_pin : "CLK";
+        timing_type : "hold_rising";
+        rise_constraint ("vio_3_3_1") {
+          values ("-0.05, -0.04, -0.02", "-0.03, -0.02, 0.0", "0.0, 0.01, 0.03");
+        }
+        fall_constraint ("vio_3_3_1") {
+          values ("-0.06, -0.05, -0.03", "-0.04, -0.03, -0.01", "-0.01, 0.0, 0.02");
+        }
+      }
+      timing () {
+        related_pin : "CLK";
+        timing_type : "setup_rising";
+        rise_constraint ("vio_3_3_1") {
+          values ("0.12, 0.15, 0.25", "0.1, 0.13, 0.23", "0.05, 0.08, 0.18");
+        }
+        fall_constraint ("vio_3_3_1") {
+          values ("0.2, 0.24, 0.36", "0.18, 0.22, 0.34", "0.13, 0.17, 0.29");
+        }
+      }
+    }
+  }
+}
+"#;
+        let dff = DffModel::from_liberty(lib, "sky130_fd_sc_hd__dfrtp_2").unwrap();
+        assert!((dff.clk_cap - 1.8e-15).abs() < 1e-20);
+        assert!((dff.d_cap - 1.7e-15).abs() < 1e-20);
+        assert!((dff.reset_b_cap - 3.5e-15).abs() < 1e-20);
+        // The rising delay is 300 ps plus 4 ns/pF, less half of the 10 ps clock slew.
+        assert!((dff.driver.res - 4e3).abs() < 1e-6);
+        assert!((dff.driver.intrinsic - 295e-12).abs() < 1e-15);
+        // Falling data needs the longer setup time, and rising data the longer hold time.
+        assert!((dff.setup - 200e-12).abs() < 1e-15);
+        assert!((dff.hold + 50e-12).abs() < 1e-15);
+
+        assert!(DffModel::from_liberty(lib, "sky130_fd_sc_hd__dfrtp_1").is_err());
+        assert!(DffModel::from_liberty(
+            &lib.replace("setup_rising", "setup_falling"),
+            "sky130_fd_sc_hd__dfrtp_2"
+        )
+        .is_err());
+    }
+}
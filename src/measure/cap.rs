@@ -39,6 +39,9 @@ pub struct TbParams<T> {
     pub dut: T,
     pub pex_netlist: Option<PathBuf>,
     pub connections: HashMap<ArcStr, Vec<TbNode>>,
+    /// SPICE files to include in the simulation, such as simulator options.
+    #[builder(default)]
+    pub includes: Vec<PathBuf>,
 }
 
 impl<T: Clone> TbParams<T> {
@@ -132,6 +135,9 @@ impl<P: Clone + Serialize, T: Component<Params = P>> Testbench for CapTestbench<
         if let Some(ref netlist) = self.params.pex_netlist {
             ctx.include(netlist);
         }
+        for path in self.params.includes.iter() {
+            ctx.include(path);
+        }
         ctx.add_analysis(Analysis::Tran(
             TranAnalysis::builder()
                 .stop(6e-6)
@@ -0,0 +1,266 @@
+//! Estimated dimensions of SRAM organizations, used to meet aspect ratio and size targets.
+//!
+//! Estimates are computed from the bitcell pitch and fixed periphery overheads,
+//! so they take no layout generation. They are meant for comparing organizations
+//! of the same SRAM and for the area of estimated Liberty libraries,
+//! rather than as exact macro dimensions.
+
+use anyhow::bail;
+
+use crate::blocks::bitcell_array::BitcellType;
+use crate::blocks::sram::{MuxRatio, SramParams, MIN_ROWS};
+use crate::Result;
+
+/// Width of the row decoders, wordline drivers and address flip-flops, in nm.
+const ROW_PERIPHERY_WIDTH: i64 = 60_000;
+/// Height of the column multiplexers, sense amplifiers, write drivers
+/// and data flip-flops, in nm.
+const COL_PERIPHERY_HEIGHT: i64 = 50_000;
+/// Additional height of the column periphery per doubling of the mux ratio, in nm.
+const MUX_LEVEL_HEIGHT: i64 = 4_000;
+/// Width of the control logic placed beside the column periphery, in nm.
+const CONTROL_WIDTH: i64 = 20_000;
+/// Width of the power rings and guard rings on each side of the macro, in nm.
+const RING_WIDTH: i64 = 10_000;
+/// Width of a column of wordline drivers of a split array, including the
+/// spacing to the half-arrays on either side of it, in nm.
+const SPLIT_DRIVER_WIDTH: i64 = 30_000;
+
+/// The mux ratios the planner may choose from.
+pub const MUX_RATIOS: [MuxRatio; 4] = [MuxRatio::M4, MuxRatio::M8, MuxRatio::M16, MuxRatio::M32];
+
+/// A target shape for an SRAM macro.
+///
+/// The planner chooses the organization whose estimated aspect ratio is closest
+/// to `aspect_ratio` among those that fit within `max_width` and `max_height`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct SizeTarget {
+    /// Desired ratio of width to height. Defaults to a square macro.
+    pub aspect_ratio: Option<f64>,
+    /// Maximum width of the macro in microns.
+    pub max_width: Option<f64>,
+    /// Maximum height of the macro in microns.
+    pub max_height: Option<f64>,
+}
+
+impl SizeTarget {
+    /// Returns `true` if no target was specified.
+    pub fn is_empty(&self) -> bool {
+        self.aspect_ratio.is_none() && self.max_width.is_none() && self.max_height.is_none()
+    }
+
+    /// Returns `true` if `floorplan` fits within the maximum dimensions.
+    pub fn fits(&self, floorplan: &Floorplan) -> bool {
+        floorplan.width_um() <= self.max_width.unwrap_or(f64::INFINITY)
+            && floorplan.height_um() <= self.max_height.unwrap_or(f64::INFINITY)
+    }
+
+    /// How far the aspect ratio of `floorplan` is from the target,
+    /// as the magnitude of the log of their ratio.
+    fn aspect_error(&self, floorplan: &Floorplan) -> f64 {
+        (floorplan.aspect_ratio() / self.aspect_ratio.unwrap_or(1.))
+            .ln()
+            .abs()
+    }
+}
+
+/// An organization of an SRAM and its estimated dimensions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Floorplan {
+    pub mux_ratio: MuxRatio,
+    /// Whether the array is folded into two half-arrays sharing a row decoder.
+    pub split: bool,
+    /// Estimated width in nm.
+    pub width: i64,
+    /// Estimated height in nm.
+    pub height: i64,
+}
+
+impl Floorplan {
+    /// Estimates the dimensions of an SRAM, optionally split into two half-arrays.
+    pub fn estimate(
+        num_words: usize,
+        data_width: usize,
+        mux_ratio: MuxRatio,
+        split: bool,
+        bitcell: BitcellType,
+    ) -> Self {
+        let (width, height) = dimensions(num_words, data_width, mux_ratio as usize, split, bitcell);
+        Self {
+            mux_ratio,
+            split,
+            width,
+            height,
+        }
+    }
+
+    /// The ratio of width to height.
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+
+    #[inline]
+    pub fn width_um(&self) -> f64 {
+        self.width as f64 / 1_000.
+    }
+
+    #[inline]
+    pub fn height_um(&self) -> f64 {
+        self.height as f64 / 1_000.
+    }
+}
+
+/// Estimates the width and height in nm of an SRAM with the given organization.
+fn dimensions(
+    num_words: usize,
+    data_width: usize,
+    mux: usize,
+    split: bool,
+    bitcell: BitcellType,
+) -> (i64, i64) {
+    let rows = (num_words / mux).max(MIN_ROWS) as i64;
+    let cols = (data_width * mux) as i64;
+    let mux_levels = mux.ilog2() as i64 - 2;
+
+    let width = ROW_PERIPHERY_WIDTH
+        + CONTROL_WIDTH.max(cols * bitcell.width() as i64)
+        + if split { SPLIT_DRIVER_WIDTH } else { 0 }
+        + 2 * RING_WIDTH;
+    let height = rows * bitcell.height() as i64
+        + COL_PERIPHERY_HEIGHT
+        + mux_levels * MUX_LEVEL_HEIGHT
+        + 2 * RING_WIDTH;
+    (width, height)
+}
+
+/// Estimates the area of the SRAM described by `params` in square microns.
+///
+/// Banks are placed side by side like the halves of a split array,
+/// each with its own column of wordline drivers.
+pub fn estimate_area(params: &SramParams) -> f64 {
+    let banks = params.banks();
+    let (width, height) = dimensions(
+        params.num_words() / banks,
+        params.data_width() * banks,
+        params.mux_ratio(),
+        params.split_array() || banks > 1,
+        params.bitcell(),
+    );
+    let width = width + banks.saturating_sub(2) as i64 * SPLIT_DRIVER_WIDTH;
+    width as f64 * height as f64 / 1e6
+}
+
+/// Returns `true` if an SRAM with the given mux ratio can be generated.
+fn is_valid(num_words: usize, data_width: usize, mux_ratio: MuxRatio) -> bool {
+    let mux = mux_ratio as usize;
+    let rows = num_words / mux;
+    num_words % mux == 0 && rows % 4 == 0 && rows > MIN_ROWS / 2 && data_width * mux >= 16
+}
+
+/// Chooses the organization of an SRAM that best meets `target`.
+///
+/// Only the given mux ratios and array splits are considered. A split array
+/// is wider than an unsplit one with the same mux ratio, so it is chosen
+/// when it brings a tall array closer to the target aspect ratio.
+///
+/// Returns an error describing the smallest organization if none fit.
+pub fn choose_floorplan(
+    num_words: usize,
+    data_width: usize,
+    mux_ratios: &[MuxRatio],
+    splits: &[bool],
+    bitcell: BitcellType,
+    target: &SizeTarget,
+) -> Result<Floorplan> {
+    let candidates = splits
+        .iter()
+        .flat_map(|&split| mux_ratios.iter().map(move |&mux| (mux, split)))
+        .filter(|&(mux, _)| is_valid(num_words, data_width, mux))
+        .map(|(mux, split)| Floorplan::estimate(num_words, data_width, mux, split, bitcell))
+        .collect::<Vec<_>>();
+
+    let cost = |f: &Floorplan| target.aspect_error(f);
+    if let Some(floorplan) = candidates
+        .iter()
+        .filter(|f| target.fits(f))
+        .min_by(|a, b| cost(a).partial_cmp(&cost(b)).unwrap())
+    {
+        return Ok(*floorplan);
+    }
+
+    let Some(smallest) = candidates.iter().min_by_key(|f| f.width.max(f.height)) else {
+        bail!("No valid mux ratio exists for this number of words and data width");
+    };
+    bail!(
+        "No organization fits within the maximum dimensions; the smallest is an estimated {:.1} x {:.1} um (mux ratio {}{})",
+        smallest.width_um(),
+        smallest.height_um(),
+        smallest.mux_ratio as usize,
+        if smallest.split { ", split array" } else { "" },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPLITS: [bool; 2] = [false, true];
+
+    #[test]
+    fn test_choose_floorplan() {
+        let square = SizeTarget::default();
+        let floorplan =
+            choose_floorplan(4096, 8, &MUX_RATIOS, &SPLITS, BitcellType::Sp, &square).unwrap();
+        assert!(!floorplan.split);
+        assert_eq!(floorplan.mux_ratio, MuxRatio::M32);
+
+        let wide = SizeTarget {
+            aspect_ratio: Some(4.),
+            ..Default::default()
+        };
+        let floorplan =
+            choose_floorplan(4096, 32, &MUX_RATIOS, &SPLITS, BitcellType::Sp, &wide).unwrap();
+        assert!(floorplan.aspect_ratio() > 1.);
+
+        // 4096 words at mux 8 is 512 rows, over 800 um tall and under 200 um wide,
+        // so splitting the array brings it closer to square.
+        let floorplan =
+            choose_floorplan(4096, 8, &[MuxRatio::M8], &SPLITS, BitcellType::Sp, &square).unwrap();
+        assert!(floorplan.split);
+        let floorplan = choose_floorplan(
+            4096,
+            8,
+            &[MuxRatio::M8],
+            &SPLITS[..1],
+            BitcellType::Sp,
+            &square,
+        )
+        .unwrap();
+        assert!(!floorplan.split);
+
+        // Splitting never reduces the height.
+        let short = SizeTarget {
+            max_height: Some(400.),
+            ..Default::default()
+        };
+        assert!(
+            choose_floorplan(4096, 8, &[MuxRatio::M8], &SPLITS, BitcellType::Sp, &short).is_err()
+        );
+
+        // The drivers of a split array do not fit in a narrow macro.
+        let narrow = SizeTarget {
+            max_width: Some(170.),
+            ..Default::default()
+        };
+        let floorplan =
+            choose_floorplan(4096, 8, &[MuxRatio::M8], &SPLITS, BitcellType::Sp, &narrow).unwrap();
+        assert!(!floorplan.split);
+        assert!(floorplan.width_um() <= 170.);
+
+        let tiny = SizeTarget {
+            max_width: Some(10.),
+            ..Default::default()
+        };
+        assert!(choose_floorplan(64, 32, &MUX_RATIOS, &SPLITS, BitcellType::Sp, &tiny).is_err());
+    }
+}
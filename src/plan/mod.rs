@@ -1,17 +1,54 @@
-use crate::blocks::sram::{Sram, SramConfig, SramParams};
+use crate::blocks::bist::{SramBist, SramBistParams};
+use crate::blocks::bitcell_array::BitcellType;
+use crate::blocks::ecc::{EccMode, SramEcc, SramEccParams};
+use crate::blocks::sram::dual_port::{Sram1r1w, Sram1r1wParams};
+use crate::blocks::sram::pins::MIN_PIN_PITCH;
+use crate::blocks::sram::{
+    MuxRatio, OutputMode, PortStyle, Ports, Sram, SramConfig, SramParams, WriteMode, MIN_ROWS,
+};
 use crate::cli::progress::StepContext;
 use crate::paths::{out_gds, out_spice, out_verilog};
-use crate::verilog::save_1rw_verilog;
+use crate::verilog::{save_1r1w_verilog, save_1rw_verilog, save_bist_verilog, save_ecc_verilog};
 use crate::{setup_ctx, Result};
 use anyhow::bail;
+use floorplan::{choose_floorplan, Floorplan, SizeTarget, MUX_RATIOS};
 use std::collections::HashSet;
 use std::path::Path;
+use substrate::component::Component;
+use substrate::data::SubstrateCtx;
+
+pub mod floorplan;
 
 /// A concrete plan for an SRAM.
 ///
 /// Has a 1-1 mapping with a schematic.
 pub struct SramPlan {
+    /// Parameters of the SRAM array and periphery.
+    ///
+    /// With ECC enabled, the data width includes the check bits.
     pub sram_params: SramParams,
+    pub ecc_params: Option<SramEccParams>,
+    /// Parameters of an SRAM with BIST, which wraps an SRAM with `sram_params`.
+    pub bist_params: Option<SramBistParams>,
+    /// Parameters of a 1R1W SRAM, whose ports each use `sram_params`.
+    pub dual_port: Option<Sram1r1wParams>,
+    /// The organization chosen to meet the configured size target, if any.
+    pub floorplan: Option<Floorplan>,
+}
+
+impl SramPlan {
+    /// The name of the generated macro.
+    pub fn name(&self) -> arcstr::ArcStr {
+        if let Some(ecc_params) = &self.ecc_params {
+            ecc_params.name()
+        } else if let Some(bist_params) = &self.bist_params {
+            bist_params.name()
+        } else if let Some(dual_port) = &self.dual_port {
+            dual_port.name()
+        } else {
+            self.sram_params.name()
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
@@ -21,13 +58,13 @@ pub enum TaskKey {
     GenerateLayout,
     GenerateVerilog,
     GenerateLef,
+    GenerateEstimateLib,
     #[cfg(feature = "commercial")]
     RunDrc,
     #[cfg(feature = "commercial")]
     RunLvs,
     #[cfg(feature = "commercial")]
     RunPex,
-    #[cfg(feature = "commercial")]
     GenerateLib,
     #[cfg(feature = "commercial")]
     All,
@@ -37,36 +74,440 @@ pub struct ExecutePlanParams<'a> {
     pub work_dir: &'a Path,
     pub plan: &'a SramPlan,
     pub tasks: &'a HashSet<TaskKey>,
+    /// Skip the netlist, layout, Verilog and LEF, only generating the requested LIBs.
+    pub estimate_only: bool,
     pub ctx: Option<&'a mut StepContext>,
     #[cfg(feature = "commercial")]
     pub pex_level: Option<calibre::pex::PexLevel>,
 }
 
+/// An optional feature of an SRAM that may not be combinable with others.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Feature {
+    Ecc,
+    Bist,
+    DualPort,
+    Redundancy,
+    SizeTarget,
+    PowerStraps,
+    Pins,
+    SplitArray,
+    DividedWordlines,
+    Banks,
+    Opt1a,
+    PortStyle,
+    WriteThrough,
+    RegisteredOutput,
+    PowerGating,
+    WordlineUnderdrive,
+    TimingTrim,
+}
+
+impl Feature {
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Ecc => "ECC",
+            Self::Bist => "BIST",
+            Self::DualPort => "a 1R1W SRAM",
+            Self::Redundancy => "spare rows or columns",
+            Self::SizeTarget => "an aspect ratio or maximum dimension",
+            Self::PowerStraps => "power straps",
+            Self::Pins => "pin placement",
+            Self::SplitArray => "a split array",
+            Self::DividedWordlines => "divided wordlines",
+            Self::Banks => "banks",
+            Self::Opt1a => "the opt1a bitcell",
+            Self::PortStyle => "port styles other than ce/we",
+            Self::WriteThrough => "write-through",
+            Self::RegisteredOutput => "a registered output",
+            Self::PowerGating => "power gating",
+            Self::WordlineUnderdrive => "wordline underdrive",
+            Self::TimingTrim => "timing trim",
+        }
+    }
+
+    /// The features declared after this one that cannot be combined with it.
+    fn incompatible(&self) -> &'static [Feature] {
+        use Feature::*;
+        match self {
+            // The ECC and BIST logic wrap a plain SRAM, and a 1R1W SRAM only shares
+            // the periphery of one.
+            Ecc => &[
+                Bist,
+                DualPort,
+                Redundancy,
+                SizeTarget,
+                PowerStraps,
+                Pins,
+                SplitArray,
+                DividedWordlines,
+                Banks,
+                Opt1a,
+                PortStyle,
+                WriteThrough,
+                RegisteredOutput,
+                PowerGating,
+                WordlineUnderdrive,
+                TimingTrim,
+            ],
+            Bist => &[
+                DualPort,
+                Redundancy,
+                SizeTarget,
+                PowerStraps,
+                Pins,
+                SplitArray,
+                DividedWordlines,
+                Banks,
+                Opt1a,
+                PortStyle,
+                WriteThrough,
+                RegisteredOutput,
+                PowerGating,
+                TimingTrim,
+            ],
+            DualPort => &[
+                Redundancy,
+                SizeTarget,
+                PowerStraps,
+                Pins,
+                SplitArray,
+                DividedWordlines,
+                Banks,
+                Opt1a,
+                PortStyle,
+                WriteThrough,
+                RegisteredOutput,
+                PowerGating,
+                TimingTrim,
+            ],
+            // The repair logic and the bank output mux sit beside the guard ring,
+            // where the power switches, pin routing and power straps would otherwise go,
+            // and neither is included in the size estimates.
+            Redundancy => &[
+                SizeTarget,
+                PowerStraps,
+                Pins,
+                SplitArray,
+                DividedWordlines,
+                Banks,
+                PowerGating,
+            ],
+            SizeTarget => &[DividedWordlines, Banks],
+            PowerStraps | Pins => &[SplitArray, DividedWordlines, Banks, PowerGating],
+            SplitArray => &[Banks],
+            // The halves of a divided-wordline array are organized like two banks.
+            DividedWordlines => &[Banks, PowerGating],
+            Banks => &[PowerGating],
+            _ => &[],
+        }
+    }
+}
+
+/// The optional features enabled by `config`.
+fn features(config: &SramConfig) -> Vec<Feature> {
+    [
+        (config.ecc != EccMode::None, Feature::Ecc),
+        (config.bist.is_some(), Feature::Bist),
+        (config.ports != Ports::OneRw, Feature::DualPort),
+        (
+            config.spare_rows > 0 || config.spare_cols > 0,
+            Feature::Redundancy,
+        ),
+        (
+            config.aspect_ratio.is_some()
+                || config.max_width.is_some()
+                || config.max_height.is_some(),
+            Feature::SizeTarget,
+        ),
+        (config.power_straps.is_some(), Feature::PowerStraps),
+        (config.pins.is_some(), Feature::Pins),
+        (config.split_array, Feature::SplitArray),
+        (config.divided_wordlines, Feature::DividedWordlines),
+        (config.banks.is_some(), Feature::Banks),
+        (config.bitcell != BitcellType::Sp, Feature::Opt1a),
+        (config.port_style != PortStyle::CeWe, Feature::PortStyle),
+        (
+            config.write_mode != WriteMode::NoChange,
+            Feature::WriteThrough,
+        ),
+        (
+            config.output != OutputMode::Latched,
+            Feature::RegisteredOutput,
+        ),
+        (config.power_gating, Feature::PowerGating),
+        (config.wordline_underdrive, Feature::WordlineUnderdrive),
+        (config.timing_trim.is_some(), Feature::TimingTrim),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, feature)| enabled.then_some(feature))
+    .collect()
+}
+
+/// Checks that no two of `features`, given in declaration order, are incompatible.
+fn check_compatibility(features: &[Feature]) -> Result<()> {
+    for (i, a) in features.iter().enumerate() {
+        if let Some(b) = features[i + 1..]
+            .iter()
+            .find(|b| a.incompatible().contains(b))
+        {
+            bail!(
+                "Cannot combine {} with {}",
+                a.description(),
+                b.description()
+            );
+        }
+    }
+    Ok(())
+}
+
 pub fn generate_plan(config: &SramConfig) -> Result<SramPlan> {
     let &SramConfig {
         num_words,
         data_width,
         mux_ratio,
         write_size,
+        ports,
+        ecc,
+        bist,
+        spare_rows,
+        spare_cols,
+        power_straps,
+        pins,
+        split_array,
+        divided_wordlines,
+        banks,
+        bitcell,
+        port_style,
+        write_mode,
+        output,
+        power_gating,
+        wordline_underdrive,
+        timing_trim,
+        aspect_ratio,
+        max_width,
+        max_height,
         ..
     } = config;
 
     if data_width % write_size != 0 {
         bail!("Data width must be a multiple of write size");
     }
+    check_compatibility(&features(config))?;
 
-    let params = SramParams::new(write_size, mux_ratio, num_words, data_width);
+    let target = SizeTarget {
+        aspect_ratio,
+        max_width,
+        max_height,
+    };
+    let floorplan = if target.is_empty() {
+        None
+    } else {
+        if target.aspect_ratio.is_some_and(|r| r <= 0.)
+            || target.max_width.is_some_and(|w| w <= 0.)
+            || target.max_height.is_some_and(|h| h <= 0.)
+        {
+            bail!("The aspect ratio and maximum dimensions must be positive");
+        }
+        let mux_ratios = match mux_ratio {
+            Some(mux_ratio) => vec![mux_ratio],
+            None => MUX_RATIOS.to_vec(),
+        };
+        // The array is only split if requested, or if a split array can be generated
+        // with the rest of the configuration.
+        let splits = if split_array {
+            &[true][..]
+        } else if power_straps.is_none() && pins.is_none() && (data_width / write_size) % 2 == 0 {
+            &[false, true][..]
+        } else {
+            &[false][..]
+        };
+        Some(choose_floorplan(
+            num_words,
+            data_width,
+            &mux_ratios,
+            splits,
+            bitcell,
+            &target,
+        )?)
+    };
 
-    if 2usize.pow(params.row_bits().try_into().unwrap()) != params.rows() || params.rows() < 16 {
-        bail!("The number of rows (num words / mux ratio) must be a power of 2 greater than or equal to 16");
+    let Some(mux_ratio) = floorplan.map(|f| f.mux_ratio).or(mux_ratio) else {
+        bail!("A mux ratio is required unless an aspect ratio or maximum dimension is given");
+    };
+    let split_array = floorplan.map_or(split_array, |f| f.split);
+
+    if num_words % mux_ratio as usize != 0 {
+        bail!("The number of words must be a multiple of the mux ratio");
+    }
+
+    // Each half of a divided-wordline array holds half of the columns of every data bit.
+    let core_mux_ratio = if divided_wordlines {
+        if !split_array {
+            bail!("Divided wordlines require a split array");
+        }
+        match mux_ratio {
+            MuxRatio::M4 => bail!("Divided wordlines require a mux ratio of at least 8"),
+            MuxRatio::M8 => MuxRatio::M4,
+            MuxRatio::M16 => MuxRatio::M8,
+            MuxRatio::M32 => MuxRatio::M16,
+        }
+    } else {
+        mux_ratio
+    };
+
+    let ecc_params = match ecc {
+        EccMode::None => None,
+        EccMode::Secded => {
+            if write_size != data_width {
+                bail!("ECC requires the write size to equal the data width");
+            }
+            Some(SramEccParams::new(mux_ratio, num_words, data_width))
+        }
+    };
+
+    let redundant = spare_rows > 0 || spare_cols > 0;
+    let params = if let Some(ecc_params) = &ecc_params {
+        ecc_params.sram()
+    } else {
+        SramParams::new(write_size, core_mux_ratio, num_words, data_width).with_bitcell(bitcell)
+    };
+
+    let params = if redundant {
+        if spare_rows % 4 != 0 {
+            bail!("The number of spare rows must be a multiple of 4");
+        }
+        params.with_redundancy(spare_rows, spare_cols)
+    } else {
+        params
+    };
+
+    let params = if let Some(power_straps) = power_straps {
+        if !(3..=4).contains(&power_straps.metal) {
+            bail!("Power straps must be drawn on metal 3 or 4");
+        }
+        if power_straps.width <= 0 || power_straps.pitch < 4 * power_straps.width {
+            bail!("The power strap pitch must be at least 4 times the strap width");
+        }
+        params.with_power_straps(power_straps)
+    } else {
+        params
+    };
+
+    let params = if let Some(pins) = pins {
+        for group in pins.groups() {
+            if !(2..=4).contains(&group.layer) {
+                bail!("Pins must be placed on metal 2, 3 or 4");
+            }
+            if group.pitch < MIN_PIN_PITCH {
+                bail!("The pin pitch must be at least {MIN_PIN_PITCH} nm");
+            }
+        }
+        params.with_pin_placement(pins)
+    } else {
+        params
+    };
+
+    let params = if port_style != PortStyle::CeWe {
+        params.with_port_style(port_style)
+    } else {
+        params
+    };
+
+    let params = if write_mode != WriteMode::NoChange {
+        params.with_write_mode(write_mode)
+    } else {
+        params
+    };
+
+    let params = if output != OutputMode::Latched {
+        params.with_output(output)
+    } else {
+        params
+    };
+
+    let params = if power_gating {
+        params.with_power_gating()
+    } else {
+        params
+    };
+
+    let params = if wordline_underdrive {
+        params.with_wordline_underdrive()
+    } else {
+        params
+    };
+
+    let params = if let Some(bits) = timing_trim {
+        if !(2..=4).contains(&bits) {
+            bail!("Timing trim pins must have between 2 and 4 bits");
+        }
+        params.with_timing_trim(bits)
+    } else {
+        params
+    };
+
+    let params = if divided_wordlines {
+        // The halves share the row decoder like two banks, none of whose rows may be padding.
+        let rows = num_words / mux_ratio as usize;
+        if !rows.is_power_of_two() || rows < MIN_ROWS {
+            bail!(
+                "Divided wordlines require the number of rows (num words / mux ratio) to be a power of two of at least {MIN_ROWS}"
+            );
+        }
+        params.with_divided_wordlines()
+    } else if split_array {
+        if params.wmask_width() % 2 != 0 {
+            bail!("A split array requires an even number of write mask bits");
+        }
+        params.with_split_array()
+    } else {
+        params
+    };
+
+    let params = if let Some(banks) = banks {
+        if banks != 2 && banks != 4 {
+            bail!("The number of banks must be 2 or 4");
+        }
+        // The row decoder is shared by all banks, so each must have the same number of rows,
+        // none of which may be padding.
+        let bank_rows = num_words / mux_ratio as usize / banks;
+        if !bank_rows.is_power_of_two() || bank_rows < MIN_ROWS {
+            bail!(
+                "The number of rows of each bank (num words / mux ratio / banks) must be a power of two of at least {MIN_ROWS}"
+            );
+        }
+        params.with_banks(banks)
+    } else {
+        params
+    };
+
+    // Arrays with fewer than `MIN_ROWS` rows are padded, which must not widen the address.
+    let rows = num_words / mux_ratio as usize;
+    if rows % 4 != 0 || rows <= MIN_ROWS / 2 {
+        bail!(
+            "The number of rows (num words / mux ratio) must be a multiple of 4 greater than {}",
+            MIN_ROWS / 2
+        );
     }
 
     if params.cols() < 16 {
         bail!("The number of columns (data width * mux ratio) must be at least 16");
     }
 
+    let dual_port = match ports {
+        Ports::OneRw => None,
+        Ports::OneROneW => Some(Sram1r1wParams::new(params)),
+    };
+
+    let bist_params = bist.map(|algorithm| SramBistParams::new(params, algorithm));
+
     Ok(SramPlan {
         sram_params: params,
+        ecc_params,
+        bist_params,
+        dual_port,
+        floorplan,
     })
 }
 
@@ -78,6 +519,14 @@ macro_rules! try_finish_task {
     };
 }
 
+macro_rules! try_skip_task {
+    ( $ctx:expr, $task:expr ) => {
+        if let Some(ctx) = $ctx.as_mut() {
+            ctx.skip($task);
+        }
+    };
+}
+
 #[cfg(feature = "commercial")]
 macro_rules! try_execute_task {
     ( $tasks:expr, $task:expr, $body:expr, $ctx:expr) => {
@@ -92,36 +541,173 @@ pub fn execute_plan(params: ExecutePlanParams) -> Result<()> {
     let ExecutePlanParams {
         work_dir,
         plan,
+        estimate_only,
         mut ctx,
         ..
     } = params;
 
     std::fs::create_dir_all(work_dir)?;
 
-    let name = &plan.sram_params.name();
     let sctx = setup_ctx();
 
-    let spice_path = out_spice(work_dir, name);
-    sctx.write_schematic_to_file::<Sram>(&plan.sram_params, &spice_path)
-        .expect("failed to write schematic");
-    try_finish_task!(ctx, TaskKey::GenerateNetlist);
+    if let Some(ecc_params) = &plan.ecc_params {
+        return execute_variant_plan::<SramEcc>(
+            &sctx,
+            work_dir,
+            ecc_params,
+            &ecc_params.name(),
+            |path| save_ecc_verilog(path, ecc_params),
+            |path| crate::abs::write_component_abstract::<SramEcc>(&sctx, ecc_params, path),
+            Some(|| {
+                use crate::blocks::sram::estimate::{ecc_lib_name, generate_ecc_estimate_lib};
 
-    let gds_path = out_gds(work_dir, name);
-    sctx.write_layout::<Sram>(&plan.sram_params, &gds_path)
-        .expect("failed to write layout");
-    try_finish_task!(ctx, TaskKey::GenerateLayout);
+                let lib_params = estimate_lib_params(&sctx, work_dir, ecc_params.sram())?;
+                generate_ecc_estimate_lib(
+                    &lib_params,
+                    ecc_params,
+                    crate::paths::out_lib(
+                        work_dir,
+                        &format!("{}_estimate", ecc_lib_name(&lib_params, ecc_params)),
+                    ),
+                )
+            }),
+            estimate_only,
+            ctx,
+            params.tasks,
+        );
+    }
+    if let Some(bist_params) = &plan.bist_params {
+        return execute_variant_plan::<SramBist>(
+            &sctx,
+            work_dir,
+            bist_params,
+            &bist_params.name(),
+            |path| save_bist_verilog(path, bist_params),
+            |path| crate::abs::write_component_abstract::<SramBist>(&sctx, bist_params, path),
+            None::<fn() -> Result<()>>,
+            estimate_only,
+            ctx,
+            params.tasks,
+        );
+    }
+    if let Some(dual_port) = &plan.dual_port {
+        return execute_variant_plan::<Sram1r1w>(
+            &sctx,
+            work_dir,
+            dual_port,
+            &dual_port.name(),
+            |path| save_1r1w_verilog(path, dual_port),
+            |path| crate::abs::write_component_abstract::<Sram1r1w>(&sctx, dual_port, path),
+            None::<fn() -> Result<()>>,
+            estimate_only,
+            ctx,
+            params.tasks,
+        );
+    }
+    if plan.sram_params.redundancy().is_some()
+        && params
+            .tasks
+            .iter()
+            .any(|task| *task != TaskKey::GenerateEstimateLib)
+    {
+        bail!("Only estimated LIB generation is supported with spare rows or columns");
+    }
+    // The Liberate flow characterizes the pins and timing of the default SRAM.
+    #[cfg(feature = "commercial")]
+    if params.tasks.contains(&TaskKey::GenerateLib) || params.tasks.contains(&TaskKey::All) {
+        let unsupported = [
+            (
+                plan.sram_params.port_style() != PortStyle::CeWe,
+                Feature::PortStyle,
+            ),
+            (
+                plan.sram_params.write_mode() != WriteMode::NoChange,
+                Feature::WriteThrough,
+            ),
+            (
+                plan.sram_params.output() != OutputMode::Latched,
+                Feature::RegisteredOutput,
+            ),
+        ];
+        if let Some((_, feature)) = unsupported.iter().find(|(enabled, _)| *enabled) {
+            bail!(
+                "Cannot generate a LIB for {} with commercial plugins",
+                feature.description()
+            );
+        }
+    }
 
+    let name = &plan.sram_params.name();
+    let spice_path = out_spice(work_dir, name);
+    let gds_path = out_gds(work_dir, name);
     let verilog_path = out_verilog(work_dir, name);
-    save_1rw_verilog(&verilog_path, &plan.sram_params).expect("failed to write behavioral model");
-    try_finish_task!(ctx, TaskKey::GenerateVerilog);
 
-    crate::abs::write_abstract(
-        &sctx,
-        &plan.sram_params,
-        crate::paths::out_lef(work_dir, name),
-    )
-    .expect("failed to write abstract");
-    try_finish_task!(ctx, TaskKey::GenerateLef);
+    if estimate_only {
+        for task in [
+            TaskKey::GenerateNetlist,
+            TaskKey::GenerateLayout,
+            TaskKey::GenerateVerilog,
+            TaskKey::GenerateLef,
+        ] {
+            try_skip_task!(ctx, task);
+        }
+    } else {
+        sctx.write_schematic_to_file::<Sram>(&plan.sram_params, &spice_path)
+            .expect("failed to write schematic");
+        try_finish_task!(ctx, TaskKey::GenerateNetlist);
+
+        sctx.write_layout::<Sram>(&plan.sram_params, &gds_path)
+            .expect("failed to write layout");
+        try_finish_task!(ctx, TaskKey::GenerateLayout);
+
+        save_1rw_verilog(&verilog_path, &plan.sram_params)
+            .expect("failed to write behavioral model");
+        try_finish_task!(ctx, TaskKey::GenerateVerilog);
+
+        crate::abs::write_abstract(
+            &sctx,
+            &plan.sram_params,
+            crate::paths::out_lef(work_dir, name),
+        )
+        .expect("failed to write abstract");
+        try_finish_task!(ctx, TaskKey::GenerateLef);
+    }
+
+    if params.tasks.contains(&TaskKey::GenerateEstimateLib) {
+        use crate::blocks::sram::estimate::generate_estimate_lib;
+
+        let lib_params = estimate_lib_params(&sctx, work_dir, plan.sram_params)?;
+        generate_estimate_lib(
+            &lib_params,
+            crate::paths::out_lib(work_dir, &format!("{}_estimate", lib_params.lib_name())),
+        )?;
+        try_finish_task!(ctx, TaskKey::GenerateEstimateLib);
+    }
+
+    #[cfg(not(feature = "commercial"))]
+    if params.tasks.contains(&TaskKey::GenerateLib) {
+        use crate::blocks::sram::testbench::characterize::{generate_sram_lib, CharacterizeParams};
+        use crate::blocks::sram::SramPhysicalDesignScript;
+        use subgeom::bbox::BoundBox;
+
+        let brect = sctx.instantiate_layout::<Sram>(&plan.sram_params)?.brect();
+        let dsn = sctx.run_script::<SramPhysicalDesignScript>(&plan.sram_params)?;
+        let lib_params = CharacterizeParams::builder()
+            .work_dir(work_dir.join("lib"))
+            .sram(plan.sram_params)
+            .dsn(dsn)
+            .corner("tt")
+            .vdd(1.8)
+            .area(brect.width() as f64 * brect.height() as f64 / 1e6)
+            .build()
+            .unwrap();
+        generate_sram_lib(
+            &sctx,
+            &lib_params,
+            crate::paths::out_lib(work_dir, &lib_params.lib_name()),
+        )?;
+        try_finish_task!(ctx, TaskKey::GenerateLib);
+    }
 
     #[cfg(feature = "commercial")]
     {
@@ -285,3 +871,258 @@ pub fn execute_plan(params: ExecutePlanParams) -> Result<()> {
     }
     Ok(())
 }
+
+/// The parameters of the estimated LIB of `sram`.
+///
+/// The area is estimated as well, so that no layout is needed.
+fn estimate_lib_params(
+    sctx: &SubstrateCtx,
+    work_dir: &Path,
+    sram: SramParams,
+) -> Result<crate::blocks::sram::testbench::characterize::CharacterizeParams> {
+    use crate::blocks::sram::testbench::characterize::CharacterizeParams;
+    use crate::blocks::sram::SramPhysicalDesignScript;
+
+    let dsn = sctx.run_script::<SramPhysicalDesignScript>(&sram)?;
+    Ok(CharacterizeParams::builder()
+        .work_dir(work_dir.join("lib"))
+        .sram(sram)
+        .dsn(dsn)
+        .corner("tt")
+        .vdd(1.8)
+        .area(floorplan::estimate_area(&sram))
+        .build()
+        .unwrap())
+}
+
+/// Generates the netlist, layout, behavioral model and LEF of an SRAM variant
+/// built around an [`Sram`], such as an SRAM with ECC or BIST.
+///
+/// `write_estimate_lib` writes the estimated LIB, if the variant supports it.
+#[allow(clippy::too_many_arguments)]
+fn execute_variant_plan<T: Component>(
+    sctx: &SubstrateCtx,
+    work_dir: &Path,
+    params: &T::Params,
+    name: &str,
+    save_verilog: impl FnOnce(&Path) -> Result<()>,
+    write_lef: impl FnOnce(&Path) -> Result<()>,
+    write_estimate_lib: Option<impl FnOnce() -> Result<()>>,
+    estimate_only: bool,
+    mut ctx: Option<&mut StepContext>,
+    tasks: &HashSet<TaskKey>,
+) -> Result<()> {
+    let supported =
+        |task: &TaskKey| *task == TaskKey::GenerateEstimateLib && write_estimate_lib.is_some();
+    if !tasks.iter().all(supported) {
+        bail!("DRC, LVS, PEX and LIB generation are not yet supported for this configuration");
+    }
+
+    if estimate_only {
+        for task in [
+            TaskKey::GenerateNetlist,
+            TaskKey::GenerateLayout,
+            TaskKey::GenerateVerilog,
+            TaskKey::GenerateLef,
+        ] {
+            try_skip_task!(ctx, task);
+        }
+    } else {
+        let spice_path = out_spice(work_dir, name);
+        sctx.write_schematic_to_file::<T>(params, &spice_path)
+            .expect("failed to write schematic");
+        try_finish_task!(ctx, TaskKey::GenerateNetlist);
+
+        let gds_path = out_gds(work_dir, name);
+        sctx.write_layout::<T>(params, &gds_path)
+            .expect("failed to write layout");
+        try_finish_task!(ctx, TaskKey::GenerateLayout);
+
+        let verilog_path = out_verilog(work_dir, name);
+        save_verilog(&verilog_path).expect("failed to write behavioral model");
+        try_finish_task!(ctx, TaskKey::GenerateVerilog);
+
+        write_lef(&crate::paths::out_lef(work_dir, name)).expect("failed to write abstract");
+        try_finish_task!(ctx, TaskKey::GenerateLef);
+    }
+
+    if let Some(write_estimate_lib) = write_estimate_lib {
+        if tasks.contains(&TaskKey::GenerateEstimateLib) {
+            write_estimate_lib()?;
+            try_finish_task!(ctx, TaskKey::GenerateEstimateLib);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::bist::march::MarchAlgorithm;
+
+    use super::*;
+
+    fn config(num_words: usize, data_width: usize, mux_ratio: usize) -> SramConfig {
+        toml::from_str(&format!(
+            "num_words = {num_words}\ndata_width = {data_width}\nmux_ratio = {mux_ratio}\nwrite_size = 8"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plan_padded_rows() {
+        let plan = generate_plan(&config(48, 32, 4)).unwrap();
+        assert_eq!(plan.sram_params.rows(), MIN_ROWS);
+        assert_eq!(plan.sram_params.addr_width(), 6);
+        assert_eq!(
+            plan.sram_params.row_bits() + plan.sram_params.col_select_bits(),
+            plan.sram_params.addr_width()
+        );
+
+        // Padding 8 rows to 16 would need another address bit.
+        assert!(generate_plan(&config(32, 32, 4)).is_err());
+        assert!(generate_plan(&config(40, 32, 4)).is_err());
+    }
+
+    #[test]
+    fn test_plan_split_floorplan() {
+        let config = |write_size: usize, extra: &str| -> SramConfig {
+            toml::from_str(&format!(
+                "num_words = 4096\ndata_width = 8\nmux_ratio = 8\nwrite_size = {write_size}\naspect_ratio = 1.0\n{extra}"
+            ))
+            .unwrap()
+        };
+
+        // 512 rows of 64 columns are folded into two half-arrays.
+        let plan = generate_plan(&config(4, "")).unwrap();
+        assert!(plan.floorplan.unwrap().split);
+        assert!(plan.sram_params.split_array());
+
+        // A single write mask bit cannot be divided between two halves.
+        let plan = generate_plan(&config(8, "")).unwrap();
+        assert!(!plan.floorplan.unwrap().split);
+        assert!(!plan.sram_params.split_array());
+
+        // The dimensions of the ECC logic are not estimated.
+        assert!(generate_plan(&config(8, "ecc = \"secded\"")).is_err());
+    }
+
+    #[test]
+    fn test_plan_mux_ratio_2() {
+        let err = toml::from_str::<SramConfig>(
+            "num_words = 64\ndata_width = 32\nmux_ratio = 2\nwrite_size = 8",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("mux ratio of 2 is not supported"));
+    }
+
+    #[test]
+    fn test_plan_banks() {
+        let mut config = config(256, 32, 4);
+        config.banks = Some(4);
+        let params = generate_plan(&config).unwrap().sram_params;
+
+        // Each of the 4 banks holds 64 words in 16 rows,
+        // and the two most significant address bits select the bank.
+        assert_eq!(params.banks(), 4);
+        assert_eq!(params.rows(), 16);
+        assert_eq!(params.addr_width(), 8);
+        assert_eq!(
+            params.col_select_bits() + params.row_bits() + params.bank_select_bits(),
+            params.addr_width()
+        );
+    }
+
+    #[test]
+    fn test_plan_divided_wordlines() {
+        let mut config = config(256, 32, 8);
+        config.split_array = true;
+        config.divided_wordlines = true;
+        let params = generate_plan(&config).unwrap().sram_params;
+
+        // Each half holds 4 of the 8 columns of every data bit in the same 32 rows.
+        assert!(params.divided_wordlines());
+        assert_eq!(params.mux_ratio(), MuxRatio::M4);
+        assert_eq!(params.banks(), 2);
+        assert_eq!(params.rows(), 32);
+        assert_eq!(params.addr_width(), 8);
+        assert!(params.name().contains("m8"));
+        assert!(params.name().ends_with("_dwl"));
+
+        // The halves need at least 4 columns per bit and no padding rows.
+        config.mux_ratio = Some(MuxRatio::M4);
+        assert!(generate_plan(&config).is_err());
+        config.mux_ratio = Some(MuxRatio::M8);
+        config.num_words = 192;
+        assert!(generate_plan(&config).is_err());
+
+        config.num_words = 256;
+        config.split_array = false;
+        assert!(generate_plan(&config).is_err());
+        config.split_array = true;
+        config.banks = Some(2);
+        assert!(generate_plan(&config).is_err());
+    }
+
+    #[test]
+    fn test_plan_dual_port() {
+        let mut config = config(64, 32, 4);
+        config.ports = Ports::OneROneW;
+        let plan = generate_plan(&config).unwrap();
+
+        // Both ports use the periphery of the single-port SRAM with the same organization.
+        let dual_port = plan.dual_port.unwrap();
+        assert_eq!(dual_port.port.rows(), 16);
+        assert_eq!(dual_port.addr_width(), 6);
+        assert_eq!(plan.name(), dual_port.name());
+        assert_eq!(
+            dual_port.name(),
+            format!("{}_1r1w", plan.sram_params.name())
+        );
+    }
+
+    #[test]
+    fn test_plan_bist() {
+        let mut config = config(64, 32, 4);
+        config.bist = Some(MarchAlgorithm::MatsPlus);
+        let plan = generate_plan(&config).unwrap();
+
+        // The BIST logic wraps the plain SRAM.
+        let bist_params = plan.bist_params.unwrap();
+        assert_eq!(bist_params.sram.name(), plan.sram_params.name());
+        assert_eq!(bist_params.algorithm, MarchAlgorithm::MatsPlus);
+        assert_eq!(plan.name(), bist_params.name());
+    }
+
+    #[test]
+    fn test_plan_compatibility() {
+        // The BIST logic checks each read on the following cycle.
+        let mut bist = config(64, 32, 4);
+        bist.bist = Some(MarchAlgorithm::MarchCm);
+        bist.output = OutputMode::Registered;
+        assert_eq!(
+            generate_plan(&bist).err().unwrap().to_string(),
+            "Cannot combine BIST with a registered output"
+        );
+
+        // Pairs are reported in the order of the features, whichever is set.
+        let mut banks = config(256, 32, 4);
+        banks.banks = Some(2);
+        banks.power_gating = true;
+        assert_eq!(
+            generate_plan(&banks).err().unwrap().to_string(),
+            "Cannot combine banks with power gating"
+        );
+
+        // Wordline underdrive only changes the periphery that both ports share.
+        let mut dual_port = config(64, 32, 4);
+        dual_port.ports = Ports::OneROneW;
+        dual_port.wordline_underdrive = true;
+        assert!(generate_plan(&dual_port)
+            .unwrap()
+            .dual_port
+            .unwrap()
+            .port
+            .wordline_underdrive());
+    }
+}
@@ -13,6 +13,8 @@ pub const CONTROL_LOGIC_INV: &str = "control_logic_inv";
 
 pub const BITCELL_HEIGHT: isize = 1580;
 pub const BITCELL_WIDTH: isize = 1200;
+pub const BITCELL_OPT1A_HEIGHT: isize = 1580;
+pub const BITCELL_OPT1A_WIDTH: isize = 1200;
 pub const TAPCELL_WIDTH: isize = 1300;
 pub const COLUMN_WIDTH: isize = BITCELL_WIDTH + TAPCELL_WIDTH;
 
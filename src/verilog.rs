@@ -1,6 +1,10 @@
 use std::path::Path;
 
-use crate::blocks::sram::SramParams;
+use crate::blocks::bist::march::AddrOrder;
+use crate::blocks::bist::{steps, SramBistParams};
+use crate::blocks::ecc::SramEccParams;
+use crate::blocks::sram::dual_port::Sram1r1wParams;
+use crate::blocks::sram::{OutputMode, PortStyle, SramParams, WriteMode};
 use crate::{Result, TEMPLATES};
 
 use serde::{Deserialize, Serialize};
@@ -13,6 +17,50 @@ pub struct Sram1RwParams {
     pub data_width: usize,
     pub addr_width: usize,
     pub wmask_width: usize,
+    /// Whether the SRAM has separate read and write ports.
+    pub dual_port: bool,
+    pub port_style: PortStyle,
+    pub write_mode: WriteMode,
+    pub output: OutputMode,
+    pub power_gating: bool,
+    pub timing_trim: Option<usize>,
+    /// The width of the repair register of an SRAM with spare rows and columns, if any.
+    pub repair_width: usize,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SramEccVerilogParams {
+    pub module_name: String,
+    pub num_words: usize,
+    pub data_width: usize,
+    pub addr_width: usize,
+    pub check_width: usize,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SramBistVerilogParams {
+    pub module_name: String,
+    pub num_words: usize,
+    pub data_width: usize,
+    pub addr_width: usize,
+    pub wmask_width: usize,
+    pub algorithm: String,
+    pub steps: Vec<MarchStepParams>,
+}
+
+/// A single operation of the march test run by the BIST controller.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MarchStepParams {
+    /// Whether addresses are visited from the last address to address 0.
+    pub down: bool,
+    pub write: bool,
+    /// The value read or written.
+    pub value: bool,
+    /// Whether this is the last step of its element,
+    /// after which the address advances.
+    pub last: bool,
+    /// The index of the first step of this step's element.
+    pub first: usize,
 }
 
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -27,23 +75,84 @@ pub struct DelayLineParams {
     pub control_width: usize,
 }
 
-pub fn generate_1rw_verilog(params: &SramParams) -> Result<String> {
-    assert_eq!(params.num_words(), 1 << params.addr_width());
-    let template = if params.wmask_width() > 1 {
-        "sram_1rw_wmask.v"
+fn render_1rw_verilog(params: &Sram1RwParams) -> Result<String> {
+    assert!(params.num_words <= 1 << params.addr_width);
+    let ports = if params.dual_port { "1r1w" } else { "1rw" };
+    let template = if params.wmask_width > 1 {
+        format!("sram_{ports}_wmask.v")
     } else {
-        "sram_1rw.v"
+        format!("sram_{ports}.v")
     };
 
-    let template_params = Sram1RwParams {
+    Ok(TEMPLATES.render(&template, &Context::from_serialize(params)?)?)
+}
+
+fn verilog_params(params: &SramParams) -> Sram1RwParams {
+    Sram1RwParams {
         module_name: params.name().to_string(),
         num_words: params.num_words(),
         data_width: params.data_width(),
         addr_width: params.addr_width(),
         wmask_width: params.wmask_width(),
+        dual_port: false,
+        port_style: params.port_style(),
+        write_mode: params.write_mode(),
+        output: params.output(),
+        power_gating: params.power_gating(),
+        timing_trim: params.timing_trim(),
+        repair_width: params.repair_width(),
+    }
+}
+
+pub fn generate_1rw_verilog(params: &SramParams) -> Result<String> {
+    render_1rw_verilog(&verilog_params(params))
+}
+
+pub fn generate_1r1w_verilog(params: &Sram1r1wParams) -> Result<String> {
+    render_1rw_verilog(&Sram1RwParams {
+        module_name: params.name().to_string(),
+        dual_port: true,
+        ..verilog_params(&params.port)
+    })
+}
+
+pub fn generate_ecc_verilog(params: &SramEccParams) -> Result<String> {
+    let params = SramEccVerilogParams {
+        module_name: params.name().to_string(),
+        num_words: params.num_words(),
+        data_width: params.data_width(),
+        addr_width: params.addr_width(),
+        check_width: params.check_bits(),
     };
+    assert!(params.num_words <= 1 << params.addr_width);
 
-    Ok(TEMPLATES.render(template, &Context::from_serialize(template_params)?)?)
+    Ok(TEMPLATES.render("sram_1rw_ecc.v", &Context::from_serialize(params)?)?)
+}
+
+pub fn generate_bist_verilog(params: &SramBistParams) -> Result<String> {
+    let steps = steps(params.algorithm)
+        .into_iter()
+        .enumerate()
+        .map(|(k, step)| MarchStepParams {
+            down: step.order == AddrOrder::Down,
+            write: step.op.is_write(),
+            value: step.op.value(),
+            last: step.last == k,
+            first: step.first,
+        })
+        .collect();
+    let params = SramBistVerilogParams {
+        module_name: params.name().to_string(),
+        num_words: params.sram.num_words(),
+        data_width: params.sram.data_width(),
+        addr_width: params.sram.addr_width(),
+        wmask_width: params.sram.wmask_width(),
+        algorithm: params.algorithm.name().to_string(),
+        steps,
+    };
+    assert!(params.num_words <= 1 << params.addr_width);
+
+    Ok(TEMPLATES.render("sram_1rw_bist.v", &Context::from_serialize(params)?)?)
 }
 
 pub fn save_1rw_verilog(path: impl AsRef<Path>, params: &SramParams) -> Result<()> {
@@ -58,6 +167,42 @@ pub fn save_1rw_verilog(path: impl AsRef<Path>, params: &SramParams) -> Result<(
     Ok(())
 }
 
+pub fn save_1r1w_verilog(path: impl AsRef<Path>, params: &Sram1r1wParams) -> Result<()> {
+    let verilog = generate_1r1w_verilog(params)?;
+
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, verilog)?;
+
+    Ok(())
+}
+
+pub fn save_ecc_verilog(path: impl AsRef<Path>, params: &SramEccParams) -> Result<()> {
+    let verilog = generate_ecc_verilog(params)?;
+
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, verilog)?;
+
+    Ok(())
+}
+
+pub fn save_bist_verilog(path: impl AsRef<Path>, params: &SramBistParams) -> Result<()> {
+    let verilog = generate_bist_verilog(params)?;
+
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, verilog)?;
+
+    Ok(())
+}
+
 pub fn generate_tdc_verilog(params: &TdcParams) -> Result<String> {
     assert!(
         params.data_width > 1,
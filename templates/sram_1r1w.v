@@ -0,0 +1,58 @@
+// SRAM22 1R1W SRAM model
+// Words: {{num_words}}
+// Word size: {{data_width}}
+// Write size: {{data_width}}
+//
+// A read from the address being written in the same cycle returns the old data.
+// Writes to addresses at or above RAM_DEPTH are ignored,
+// and reads from them return X.
+
+module {{module_name}}(
+`ifdef USE_POWER_PINS
+    vdd,
+    vss,
+`endif
+    wclk,rclk,rstb,wce,rce,waddr,raddr,din,dout
+  );
+
+  localparam DATA_WIDTH = {{data_width}};
+  localparam ADDR_WIDTH = {{addr_width}};
+  localparam RAM_DEPTH = {{num_words}};
+
+`ifdef USE_POWER_PINS
+    inout vdd; // power
+    inout vss; // ground
+`endif
+  input  wclk; // write clock
+  input  rclk; // read clock
+  input  rstb; // reset bar (active low reset)
+  input  wce; // write chip enable
+  input  rce; // read chip enable
+  input [ADDR_WIDTH-1:0]  waddr; // write address
+  input [ADDR_WIDTH-1:0]  raddr; // read address
+  input [DATA_WIDTH-1:0]  din; // data in
+  output reg [DATA_WIDTH-1:0] dout; // data out
+
+  reg [DATA_WIDTH-1:0] mem [0:RAM_DEPTH-1];
+
+  // Write
+  always @(posedge wclk)
+  begin
+    if (wce && rstb && waddr < RAM_DEPTH) begin
+      mem[waddr] <= din;
+    end
+  end
+
+  // Read
+  always @(posedge rclk)
+  begin
+    if (rce && rstb) begin
+      if (raddr < RAM_DEPTH) begin
+        dout <= mem[raddr];
+      end else begin
+        dout <= {DATA_WIDTH{1'bx}};
+      end
+    end
+  end
+
+endmodule
@@ -0,0 +1,67 @@
+{% set bits_per_mask = data_width / wmask_width -%}
+// SRAM22 1R1W SRAM model
+// Words: {{num_words}}
+// Word size: {{data_width}}
+// Write size: {{ bits_per_mask }}
+//
+// A read from the address being written in the same cycle returns the old data.
+// Writes to addresses at or above RAM_DEPTH are ignored,
+// and reads from them return X.
+
+module {{module_name}}(
+`ifdef USE_POWER_PINS
+  vdd,
+  vss,
+`endif
+  wclk,rclk,rstb,wce,rce,wmask,waddr,raddr,din,dout
+);
+
+  localparam DATA_WIDTH = {{data_width}};
+  localparam ADDR_WIDTH = {{addr_width}};
+  localparam WMASK_WIDTH = {{wmask_width}};
+  localparam RAM_DEPTH = {{num_words}};
+
+`ifdef USE_POWER_PINS
+  inout vdd; // power
+  inout vss; // ground
+`endif
+  input  wclk; // write clock
+  input  rclk; // read clock
+  input  rstb; // reset bar (active low reset)
+  input  wce; // write chip enable
+  input  rce; // read chip enable
+  input [WMASK_WIDTH-1:0] wmask; // write mask
+  input [ADDR_WIDTH-1:0]  waddr; // write address
+  input [ADDR_WIDTH-1:0]  raddr; // read address
+  input [DATA_WIDTH-1:0]  din; // data in
+  output reg [DATA_WIDTH-1:0] dout; // data out
+
+  reg [DATA_WIDTH-1:0] mem [0:RAM_DEPTH-1];
+
+  // Write
+  always @(posedge wclk)
+  begin
+    if (wce && rstb && waddr < RAM_DEPTH) begin
+      {%- for i in range(end=wmask_width) -%}
+        {% set lower = i * bits_per_mask %}
+        {% set upper = (i + 1) * bits_per_mask - 1 -%}
+        if (wmask[{{i}}]) begin
+          mem[waddr][{{upper}}:{{lower}}] <= din[{{upper}}:{{lower}}];
+        end
+      {%- endfor %}
+    end
+  end
+
+  // Read
+  always @(posedge rclk)
+  begin
+    if (rce && rstb) begin
+      if (raddr < RAM_DEPTH) begin
+        dout <= mem[raddr];
+      end else begin
+        dout <= {DATA_WIDTH{1'bx}};
+      end
+    end
+  end
+
+endmodule
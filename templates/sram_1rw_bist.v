@@ -0,0 +1,151 @@
+{% set bits_per_mask = data_width / wmask_width -%}
+// SRAM22 SRAM model with built-in self-test
+// Words: {{num_words}}
+// Word size: {{data_width}}
+// Write size: {{ bits_per_mask }}
+// March test: {{algorithm}}
+//
+// While bist_en is high, the SRAM is driven by the march test controller
+// instead of ce, we, wmask, addr and din. bist_done goes high once the
+// test has finished, and bist_fail goes high if any read returned
+// unexpected data.
+//
+// Writes to addresses at or above RAM_DEPTH are ignored,
+// and reads from them return X.
+
+module {{module_name}}(
+`ifdef USE_POWER_PINS
+  vdd,
+  vss,
+`endif
+  clk,rstb,ce,we,wmask,addr,din,dout,bist_en,bist_done,bist_fail
+);
+
+  localparam DATA_WIDTH = {{data_width}};
+  localparam ADDR_WIDTH = {{addr_width}};
+  localparam WMASK_WIDTH = {{wmask_width}};
+  localparam RAM_DEPTH = {{num_words}};
+  localparam NUM_STEPS = {{steps | length}};
+
+`ifdef USE_POWER_PINS
+  inout vdd; // power
+  inout vss; // ground
+`endif
+  input  clk; // clock
+  input  rstb; // reset bar (active low reset)
+  input  ce; // chip enable
+  input  we; // write enable
+  input [WMASK_WIDTH-1:0] wmask; // write mask
+  input [ADDR_WIDTH-1:0]  addr; // address
+  input [DATA_WIDTH-1:0]  din; // data in
+  output reg [DATA_WIDTH-1:0] dout; // data out
+  input  bist_en; // run the built-in self-test
+  output reg bist_done; // the self-test has finished
+  output reg bist_fail; // a read during the self-test returned unexpected data
+
+  reg [DATA_WIDTH-1:0] mem [0:RAM_DEPTH-1];
+
+  // BIST controller
+  //
+  // The controller is held in reset while bist_en is low. It issues one
+  // operation per cycle, and checks the data of each read on the next cycle.
+  wire bist_rstb = rstb && bist_en;
+  integer step; // the current step, or NUM_STEPS once finished
+  reg [ADDR_WIDTH-1:0] count;
+  reg chk; // dout holds read data to be checked
+  reg exp; // the expected value of every bit of dout
+  reg step_down;
+  reg step_write;
+  reg step_value;
+  reg step_last;
+  integer step_first;
+
+  always @(*)
+  begin
+    case (step)
+{%- for step in steps %}
+      {{loop.index0}}: begin
+        step_down = 1'b{% if step.down %}1{% else %}0{% endif %};
+        step_write = 1'b{% if step.write %}1{% else %}0{% endif %};
+        step_value = 1'b{% if step.value %}1{% else %}0{% endif %};
+        step_last = 1'b{% if step.last %}1{% else %}0{% endif %};
+        step_first = {{step.first}};
+      end
+{%- endfor %}
+      default: begin
+        step_down = 1'b0;
+        step_write = 1'b0;
+        step_value = 1'b0;
+        step_last = 1'b0;
+        step_first = NUM_STEPS;
+      end
+    endcase
+  end
+
+  wire bist_ce = step < NUM_STEPS;
+  wire [ADDR_WIDTH-1:0] bist_addr = step_down ? RAM_DEPTH - 1 - count : count;
+
+  always @(posedge clk or negedge bist_rstb)
+  begin
+    if (!bist_rstb) begin
+      step <= 0;
+      count <= {ADDR_WIDTH{1'b0}};
+      chk <= 1'b0;
+      exp <= 1'b0;
+      bist_done <= 1'b0;
+      bist_fail <= 1'b0;
+    end else begin
+      chk <= bist_ce && !step_write;
+      exp <= step_value;
+      bist_fail <= bist_fail || (chk && dout !== {DATA_WIDTH{exp}});
+      bist_done <= step == NUM_STEPS;
+      if (bist_ce) begin
+        if (!step_last) begin
+          step <= step + 1;
+        end else begin
+          // The address wraps to 0 after the last address of each element.
+          if (count == RAM_DEPTH - 1) begin
+            count <= {ADDR_WIDTH{1'b0}};
+            step <= step + 1;
+          end else begin
+            count <= count + 1;
+            step <= step_first;
+          end
+        end
+      end
+    end
+  end
+
+  // Input mux
+  wire sram_ce = bist_en ? bist_ce : ce;
+  wire sram_we = bist_en ? step_write : we;
+  wire [WMASK_WIDTH-1:0] sram_wmask = bist_en ? {WMASK_WIDTH{1'b1}} : wmask;
+  wire [ADDR_WIDTH-1:0] sram_addr = bist_en ? bist_addr : addr;
+  wire [DATA_WIDTH-1:0] sram_din = bist_en ? {DATA_WIDTH{step_value}} : din;
+
+  always @(posedge clk)
+  begin
+    if (sram_ce && rstb) begin
+      // Write
+      if (sram_we && sram_addr < RAM_DEPTH) begin
+        {%- for i in range(end=wmask_width) -%}
+          {% set lower = i * bits_per_mask %}
+          {% set upper = (i + 1) * bits_per_mask - 1 -%}
+          if (sram_wmask[{{i}}]) begin
+            mem[sram_addr][{{upper}}:{{lower}}] <= sram_din[{{upper}}:{{lower}}];
+          end
+        {%- endfor %}
+      end
+
+      // Read
+      if (!sram_we) begin
+        if (sram_addr < RAM_DEPTH) begin
+          dout <= mem[sram_addr];
+        end else begin
+          dout <= {DATA_WIDTH{1'bx}};
+        end
+      end
+    end
+  end
+
+endmodule
@@ -0,0 +1,63 @@
+// SRAM22 SRAM model with SECDED error correction
+// Words: {{num_words}}
+// Word size: {{data_width}}
+// Write size: {{data_width}}
+// Check bits: {{check_width}}
+//
+// Writes to addresses at or above RAM_DEPTH are ignored,
+// and reads from them return X.
+//
+// The model stores data without check bits, so reads never
+// report corrected or uncorrectable errors.
+
+module {{module_name}}(
+`ifdef USE_POWER_PINS
+    vdd,
+    vss,
+`endif
+    clk,rstb,ce,we,addr,din,dout,err_corrected,err_uncorrectable
+  );
+
+  localparam DATA_WIDTH = {{data_width}};
+  localparam ADDR_WIDTH = {{addr_width}};
+  localparam RAM_DEPTH = {{num_words}};
+
+`ifdef USE_POWER_PINS
+    inout vdd; // power
+    inout vss; // ground
+`endif
+  input  clk; // clock
+  input  rstb; // reset bar (active low reset)
+  input  ce; // chip enable
+  input  we; // write enable
+  input [ADDR_WIDTH-1:0]  addr; // address
+  input [DATA_WIDTH-1:0]  din; // data in
+  output reg [DATA_WIDTH-1:0] dout; // data out
+  output reg err_corrected; // a single-bit error was corrected in dout
+  output reg err_uncorrectable; // dout contains an uncorrectable error
+
+  reg [DATA_WIDTH-1:0] mem [0:RAM_DEPTH-1];
+
+  always @(posedge clk)
+  begin
+    if (ce && rstb) begin
+      // Write
+      if (we && addr < RAM_DEPTH) begin
+          mem[addr] <= din;
+      end
+
+      // Read
+      if (!we) begin
+        err_corrected <= 1'b0;
+        if (addr < RAM_DEPTH) begin
+          dout <= mem[addr];
+          err_uncorrectable <= 1'b0;
+        end else begin
+          dout <= {DATA_WIDTH{1'bx}};
+          err_uncorrectable <= 1'bx;
+        end
+      end
+    end
+  end
+
+endmodule
@@ -1,21 +1,63 @@
+{% if port_style == "ren_wen" -%}
+{% set en = "ren" -%}
+{% set wr = "wen" -%}
+{% elif port_style == "csb_web" -%}
+{% set en = "csb" -%}
+{% set wr = "web" -%}
+{% else -%}
+{% set en = "ce" -%}
+{% set wr = "we" -%}
+{% endif -%}
+{% if output == "registered" -%}
+{% set dout = "dout_latched" -%}
+{% else -%}
+{% set dout = "dout" -%}
+{% endif -%}
+{% if power_gating -%}
+{% set rstb = "rstb_gated" -%}
+{% else -%}
+{% set rstb = "rstb" -%}
+{% endif -%}
 {% set bits_per_mask = data_width / wmask_width -%}
 // SRAM22 SRAM model
 // Words: {{num_words}}
 // Word size: {{data_width}}
 // Write size: {{ bits_per_mask }}
+{%- if write_mode == "write_through" %}
+// Write mode: write-through
+{%- endif %}
+{%- if output == "registered" %}
+// Output: registered (read latency of 2 cycles)
+{%- endif %}
+{%- if power_gating %}
+// Power gating: sleep and retain pins
+{%- endif %}
+{%- if timing_trim %}
+// Timing trim: {{timing_trim}}-bit trim_saen and trim_wl pins, which do not affect this model
+{%- endif %}
+{%- if repair_width %}
+// Repair register bits: {{repair_width}}, which only model the register's shift behavior,
+// since repair is transparent at the macro boundary
+{%- endif %}
+//
+// Writes to addresses at or above RAM_DEPTH are ignored,
+// and reads from them return X.
 
 module {{module_name}}(
 `ifdef USE_POWER_PINS
   vdd,
   vss,
 `endif
-  clk,rstb,ce,we,wmask,addr,din,dout
+  clk,rstb,{% if power_gating %}sleep,retain,{% endif %}{% if timing_trim %}trim_saen,trim_wl,{% endif %}{{en}},{{wr}},wmask,addr,din,dout{% if repair_width %},repair_se,repair_si,repair_so{% endif %}
 );
 
   localparam DATA_WIDTH = {{data_width}};
   localparam ADDR_WIDTH = {{addr_width}};
   localparam WMASK_WIDTH = {{wmask_width}};
-  localparam RAM_DEPTH = 1 << ADDR_WIDTH;
+  localparam RAM_DEPTH = {{num_words}};
+{%- if repair_width %}
+  localparam REPAIR_WIDTH = {{repair_width}};
+{%- endif %}
 
 `ifdef USE_POWER_PINS
   inout vdd; // power
@@ -23,20 +65,108 @@ module {{module_name}}(
 `endif
   input  clk; // clock
   input  rstb; // reset bar (active low reset)
+{%- if power_gating %}
+  input  sleep; // power down the periphery
+  input  retain; // retain the array contents while asleep
+{%- endif %}
+{%- if timing_trim %}
+  input [{{timing_trim - 1}}:0] trim_saen; // sense amplifier enable delay trim
+  input [{{timing_trim - 1}}:0] trim_wl; // write wordline pulse width trim
+{%- endif %}
+{%- if port_style == "ren_wen" %}
+  input  ren; // read enable
+  input  wen; // write enable
+{%- elif port_style == "csb_web" %}
+  input  csb; // chip select bar (active low chip enable)
+  input  web; // write enable bar (active low write enable)
+{%- else %}
   input  ce; // chip enable
   input  we; // write enable
+{%- endif %}
   input [WMASK_WIDTH-1:0] wmask; // write mask
   input [ADDR_WIDTH-1:0]  addr; // address
   input [DATA_WIDTH-1:0]  din; // data in
   output reg [DATA_WIDTH-1:0] dout; // data out
+{%- if repair_width %}
+  input  repair_se; // repair register shift enable
+  input  repair_si; // repair register scan in
+  output repair_so; // repair register scan out
+{%- endif %}
 
   reg [DATA_WIDTH-1:0] mem [0:RAM_DEPTH-1];
+{%- if repair_width %}
+  reg [REPAIR_WIDTH-1:0] repair;
+{%- endif %}
+{%- if output == "registered" %}
+  reg [DATA_WIDTH-1:0] dout_latched;
+{%- endif %}
+{%- if port_style == "ren_wen" %}
 
-  always @(posedge clk)
+  // A cycle with both ren and wen high is a write.
+  wire ce = ren || wen;
+  wire we = wen;
+{%- elif port_style == "csb_web" %}
+
+  wire ce = !csb;
+  wire we = !web;
+{%- endif %}
+{%- if power_gating %}
+
+  // Power gating
+  //
+  // The periphery is held in reset until the first rising clock edge after sleep falls.
+  // dout is unknown after waking up until the next read, and the contents
+  // are lost if retain is low while asleep.
+  reg awake;
+  reg lost; // retain was low while asleep
+  wire rstb_gated = rstb && awake;
+  integer i;
+
+  always @(posedge clk or posedge sleep or negedge retain)
   begin
-    if (ce && rstb) begin
+    if (sleep) begin
+      awake <= 1'b0;
+      if (!retain) begin
+        lost <= 1'b1;
+      end
+    end else begin
+      awake <= 1'b1;
+      lost <= 1'b0;
+    end
+  end
+{%- endif %}
+
+{%- if repair_width %}
+
+  // Repair register
+  assign repair_so = repair[REPAIR_WIDTH-1];
+
+  always @(posedge clk or negedge {{rstb}})
+  begin
+    if (!{{rstb}}) begin
+      repair <= {REPAIR_WIDTH{1'b0}};
+    end else if (repair_se) begin
+      repair <= {% if repair_width > 1 %}{repair[REPAIR_WIDTH-2:0], repair_si}{% else %}repair_si{% endif %};
+    end
+  end
+{%- endif %}
+
+  always @(posedge clk{% if power_gating %} or posedge sleep{% endif %})
+  begin
+{%- if power_gating %}
+    if (sleep) begin
+      {{dout}} <= {DATA_WIDTH{1'bx}};
+    end else if (!awake && lost) begin
+      // The contents are lost on the first clock edge after waking up.
+      for (i = 0; i < RAM_DEPTH; i = i + 1) begin
+        mem[i] <= {DATA_WIDTH{1'bx}};
+      end
+    end else if (ce && {{rstb}}) begin
+{%- else %}
+    if (ce && {{rstb}}) begin
+{%- endif %}
       // Write
-      if (we) begin
+      if (we && addr < RAM_DEPTH) begin
         {%- for i in range(end=wmask_width) -%}
           {% set lower = i * bits_per_mask %}
           {% set upper = (i + 1) * bits_per_mask - 1 -%}
@@ -45,13 +175,42 @@ module {{module_name}}(
           end
         {%- endfor %}
       end
+{%- if write_mode == "write_through" %}
+
+      // Write-through
+      if (we) begin
+        {%- for i in range(end=wmask_width) -%}
+          {% set lower = i * bits_per_mask %}
+          {% set upper = (i + 1) * bits_per_mask - 1 -%}
+          if (wmask[{{i}}]) begin
+            {{dout}}[{{upper}}:{{lower}}] <= din[{{upper}}:{{lower}}];
+          end
+        {%- endfor %}
+      end
+{%- endif %}
 
       // Read
       if (!we) begin
-        dout <= mem[addr];
+        if (addr < RAM_DEPTH) begin
+          {{dout}} <= mem[addr];
+        end else begin
+          {{dout}} <= {DATA_WIDTH{1'bx}};
+        end
       end
     end
   end
+{%- if output == "registered" %}
+
+  // Output register
+  always @(posedge clk or negedge {{rstb}})
+  begin
+    if (!{{rstb}}) begin
+      dout <= {DATA_WIDTH{1'b0}};
+    end else begin
+      dout <= dout_latched;
+    end
+  end
+{%- endif %}
 
 endmodule
 